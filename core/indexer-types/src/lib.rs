@@ -1,6 +1,6 @@
 extern crate alloc;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use bitcoin::{
     BlockHash, FeeRate, OutPoint, ScriptBuf, TxOut, Txid, XOnlyPublicKey, taproot::LeafVersion,
 };
@@ -9,6 +9,7 @@ use indexmap::IndexMap;
 use macros::contract_address;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
+use std::collections::BTreeMap;
 use ts_rs::TS;
 pub use wit_bindgen;
 
@@ -22,6 +23,15 @@ pub struct InstructionQuery {
     pub chained_instruction: Option<Inst>,
 }
 
+/// An instruction to run as if `x_only_public_key` had signed it in a mined
+/// transaction, without broadcasting anything.
+#[derive(Serialize, Deserialize, Clone, Builder, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct InstructionSimulation {
+    pub x_only_public_key: String,
+    pub instruction: Inst,
+}
+
 #[derive(Serialize, Deserialize, Builder, TS)]
 #[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
 pub struct ComposeQuery {
@@ -206,6 +216,8 @@ pub struct Block {
     pub hash: BlockHash,
     #[ts(type = "string")]
     pub prev_hash: BlockHash,
+    #[ts(type = "number")]
+    pub time: u64,
     pub transactions: Vec<Transaction>,
 }
 
@@ -299,6 +311,32 @@ impl Op {
             Op::Issuance { metadata, .. } => metadata,
         }
     }
+
+    pub fn from_inst(inst: Inst, metadata: OpMetadata) -> Self {
+        match inst {
+            Inst::Publish {
+                gas_limit,
+                name,
+                bytes,
+            } => Op::Publish {
+                metadata,
+                gas_limit,
+                name,
+                bytes,
+            },
+            Inst::Call {
+                gas_limit,
+                contract,
+                expr,
+            } => Op::Call {
+                metadata,
+                gas_limit,
+                contract,
+                expr,
+            },
+            Inst::Issuance => Op::Issuance { metadata },
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder, TS)]
@@ -308,6 +346,11 @@ pub struct BlockRow {
     pub height: i64,
     #[ts(as = "String")]
     pub hash: BlockHash,
+    #[ts(as = "Option<String>")]
+    pub prev_hash: Option<BlockHash>,
+    #[ts(type = "number")]
+    #[builder(default = 0)]
+    pub time: i64,
     #[builder(default = false)]
     pub relevant: bool,
 }
@@ -317,6 +360,8 @@ impl From<&Block> for BlockRow {
         Self {
             height: b.height as i64,
             hash: b.hash,
+            prev_hash: Some(b.prev_hash),
+            time: b.time as i64,
             relevant: !b.transactions.is_empty(),
         }
     }
@@ -401,6 +446,10 @@ pub struct OpWithResult {
 #[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
 pub struct ViewExpr {
     pub expr: String,
+    /// Gas the view may spend, in place of the indexer's default.
+    #[ts(type = "number | null")]
+    #[serde(default)]
+    pub gas_limit: Option<u64>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, TS)]
@@ -417,6 +466,94 @@ pub struct ContractResponse {
     pub wit: String,
 }
 
+/// A contract's exported functions and the types it defines, parsed from its
+/// WIT so clients don't need a WIT parser of their own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct ContractSchema {
+    pub types: BTreeMap<String, WitTypeDef>,
+    /// Sorted by name.
+    pub functions: Vec<WitFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum WitTypeDef {
+    Record {
+        fields: Vec<WitNamedType>,
+    },
+    Variant {
+        cases: Vec<WitNamedType>,
+    },
+    Enum {
+        cases: Vec<String>,
+    },
+    Alias {
+        #[serde(rename = "type")]
+        ty: WitTypeRef,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct WitFunction {
+    pub name: String,
+    /// The context the function borrows, e.g. `proc-context`.
+    pub context: Option<String>,
+    /// Parameters after the context.
+    pub params: Vec<WitNamedType>,
+    pub result: Option<WitTypeRef>,
+}
+
+/// A parameter, record field or variant case; only cases may lack a type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct WitNamedType {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Option<WitTypeRef>,
+}
+
+/// Primitives and named types are referred to by name, anonymous types are
+/// spelled out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+#[serde(untagged)]
+pub enum WitTypeRef {
+    Named(String),
+    Option { option: Box<WitTypeRef> },
+    List { list: Box<WitTypeRef> },
+    Result { result: WitResultRef },
+    Tuple { tuple: Vec<WitTypeRef> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct WitResultRef {
+    pub ok: Option<Box<WitTypeRef>>,
+    pub err: Option<Box<WitTypeRef>>,
+}
+
+/// Whether a contract has written any storage, which it does once `init` has
+/// run.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct ContractStateResponse {
+    pub has_state: bool,
+}
+
+/// Gas burned by operations calling a contract directly, aggregated over
+/// their results.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
+pub struct GasStats {
+    #[ts(type = "number")]
+    pub total_consumed: i64,
+    #[ts(type = "number")]
+    pub call_count: i64,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../kontor-ts/src/bindings.d.ts")]
 pub struct ResultRow {
@@ -436,6 +573,8 @@ pub struct ResultRow {
     #[ts(type = "number")]
     pub gas: i64,
     pub value: Option<String>,
+    /// The calls that led to the failure, outermost first, if the call failed.
+    pub error_trace: Option<String>,
     pub contract: String,
 }
 
@@ -473,16 +612,60 @@ pub fn deserialize<T: for<'a> Deserialize<'a>>(buffer: &[u8]) -> Result<T> {
     Ok(postcard::from_bytes(buffer)?)
 }
 
+/// First byte of a versioned instruction or op-return envelope. Unversioned
+/// (v0) payloads never start with it: as the leading varint of a postcard
+/// enum tag or list length it would take 127 or more variants or entries.
+pub const ENVELOPE_TAG: u8 = 0xff;
+
+/// Schema version written after [`ENVELOPE_TAG`].
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Encodes an `Inst` or op-return payload for a transaction, tagged with
+/// [`ENVELOPE_VERSION`]. Storage values keep using [`serialize`].
+pub fn serialize_envelope<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    Ok(postcard::to_extend(
+        value,
+        vec![ENVELOPE_TAG, ENVELOPE_VERSION],
+    )?)
+}
+
+/// Decodes a payload written by [`serialize_envelope`]. Untagged payloads
+/// from before the envelope (v0) decode as they always have; tagged ones
+/// with an unknown version are rejected.
+pub fn deserialize_envelope<T: for<'a> Deserialize<'a>>(buffer: &[u8]) -> Result<T> {
+    match buffer {
+        [ENVELOPE_TAG, ENVELOPE_VERSION, payload @ ..] => from_bytes_exact(payload),
+        [ENVELOPE_TAG, version, ..] => bail!(
+            "Unsupported envelope version {} (expected {})",
+            version,
+            ENVELOPE_VERSION
+        ),
+        _ => deserialize(buffer),
+    }
+}
+
+fn from_bytes_exact<T: for<'a> Deserialize<'a>>(buffer: &[u8]) -> Result<T> {
+    let (value, rest) = postcard::take_from_bytes(buffer)?;
+    if !rest.is_empty() {
+        bail!("{} trailing bytes after payload", rest.len());
+    }
+    Ok(value)
+}
+
 pub fn json_to_bytes<T: for<'a> Deserialize<'a> + Serialize>(json: String) -> Vec<u8> {
     let inst = serde_json::from_str::<T>(&json).expect("Invalid JSON string");
-    serialize(&inst).expect("Failed to serialize to postcard")
+    serialize_envelope(&inst).expect("Failed to serialize to postcard")
 }
 
 pub fn bytes_to_json<T: for<'a> Deserialize<'a> + Serialize>(bytes: Vec<u8>) -> String {
-    let inst = deserialize::<T>(&bytes).expect("Failed to deserialize from postcard");
+    let inst = deserialize_envelope::<T>(&bytes).expect("Failed to deserialize from postcard");
     serde_json::to_string(&inst).expect("Failed to serialize to JSON")
 }
 
+/// Encodes an instruction given as JSON for a transaction. Fields are read by
+/// name and written in declaration order, and contract addresses are
+/// re-rendered from their parsed form, so equivalent JSON always yields the
+/// same bytes.
 pub fn inst_json_to_bytes(json: String) -> Vec<u8> {
     json_to_bytes::<Inst>(json)
 }
@@ -0,0 +1,119 @@
+//! A token `balance` view run with and without the fuel gauge, separating the
+//! gauge's bookkeeping from host and database latency.
+//!
+//! Run with `cargo bench -p indexer --features testlib --bench metering`. The
+//! token contract from `test-contracts` must be built first.
+
+use anyhow::Result;
+use criterion::{Criterion, criterion_group, criterion_main};
+use indexer::{
+    database::queries::insert_processed_block,
+    runtime::{ComponentCache, ContractAddress, Runtime, Storage, wit::Signer},
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_db},
+};
+use indexer_types::BlockRow;
+use tempfile::TempDir;
+use testlib::{ContractReader, Integer, to_wave_expr};
+
+const BALANCE_EXPR: &str = "balance(\"minter\")";
+
+struct Fixture {
+    runtime: Runtime,
+    token: ContractAddress,
+    _db: (TempDir, String),
+}
+
+/// Publishes the test token and mints a balance for `minter`.
+async fn setup() -> Result<Fixture> {
+    let (_, writer, db) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 0..=1 {
+        insert_processed_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let storage = Storage::builder().height(0).tx_index(0).conn(conn).build();
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime.publish_native_contracts().await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+
+    let minter = Signer::XOnlyPubKey("minter".to_string());
+    runtime.issuance(&minter).await?;
+    let bytes = ContractReader::new("../../test-contracts")
+        .await?
+        .read("token")
+        .await?
+        .expect("token contract has not been built");
+    runtime.publish(&minter, "token", &bytes).await?;
+    let token = ContractAddress {
+        name: "token".to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+    runtime
+        .execute(
+            Some(&minter),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(1_000))),
+        )
+        .await?;
+
+    // both modes must agree before their timings are worth comparing
+    let mut unmetered = runtime.clone();
+    assert_eq!(
+        runtime.execute(None, &token, BALANCE_EXPR).await?,
+        unmetered.execute_unmetered(&token, BALANCE_EXPR).await?
+    );
+
+    Ok(Fixture {
+        runtime,
+        token,
+        _db: db,
+    })
+}
+
+fn metering_benches(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to start tokio runtime");
+    let fixture = rt.block_on(setup()).expect("Failed to set up token");
+    let mut group = c.benchmark_group("token_balance");
+
+    group.bench_function("metered", |b| {
+        b.to_async(&rt).iter(|| {
+            let mut runtime = fixture.runtime.clone();
+            let token = fixture.token.clone();
+            async move {
+                runtime
+                    .execute(None, &token, BALANCE_EXPR)
+                    .await
+                    .expect("Failed to execute balance")
+            }
+        })
+    });
+
+    group.bench_function("unmetered", |b| {
+        b.to_async(&rt).iter(|| {
+            let mut runtime = fixture.runtime.clone();
+            let token = fixture.token.clone();
+            async move {
+                runtime
+                    .execute_unmetered(&token, BALANCE_EXPR)
+                    .await
+                    .expect("Failed to execute balance")
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, metering_benches);
+criterion_main!(benches);
@@ -0,0 +1,143 @@
+//! Storage-heavy contract operations at increasing map sizes.
+//!
+//! Run with `cargo bench -p indexer --bench storage`. The token contract from
+//! `test-contracts` must be built first.
+
+use anyhow::Result;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use futures_util::TryStreamExt;
+use indexer::{
+    database::queries::insert_processed_block,
+    runtime::{ComponentCache, ContractAddress, Runtime, Storage, wit::Signer},
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_db},
+};
+use indexer_types::BlockRow;
+use tempfile::TempDir;
+use testlib::{ContractReader, Integer, to_wave_expr};
+
+const SIZES: [usize; 2] = [100, 1_000];
+
+struct Fixture {
+    runtime: Runtime,
+    token: ContractAddress,
+    contract_id: i64,
+    _db: (TempDir, String),
+}
+
+/// Publishes the test token and spreads balances across `entries` accounts so
+/// its `ledger` map holds that many keys.
+async fn setup(entries: usize) -> Result<Fixture> {
+    let (_, writer, db) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 0..=1 {
+        insert_processed_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let storage = Storage::builder().height(0).tx_index(0).conn(conn).build();
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime.publish_native_contracts().await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+
+    let minter = Signer::XOnlyPubKey("minter".to_string());
+    runtime.issuance(&minter).await?;
+    let bytes = ContractReader::new("../../test-contracts")
+        .await?
+        .read("token")
+        .await?
+        .expect("token contract has not been built");
+    runtime.publish(&minter, "token", &bytes).await?;
+    let token = ContractAddress {
+        name: "token".to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+    let contract_id = runtime
+        .storage
+        .contract_id(&token)
+        .await?
+        .expect("token was just published");
+
+    runtime
+        .execute(
+            Some(&minter),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(entries as u64))),
+        )
+        .await?;
+    for i in 0..entries {
+        runtime
+            .execute(
+                Some(&minter),
+                &token,
+                &format!(
+                    "transfer(\"holder-{}\", {})",
+                    i,
+                    to_wave_expr(Integer::from(1))
+                ),
+            )
+            .await?;
+    }
+
+    Ok(Fixture {
+        runtime,
+        token,
+        contract_id,
+        _db: db,
+    })
+}
+
+fn storage_benches(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to start tokio runtime");
+    let mut group = c.benchmark_group("token_ledger");
+    group.sample_size(10);
+
+    for entries in SIZES {
+        let fixture = rt
+            .block_on(setup(entries))
+            .expect("Failed to set up token ledger");
+
+        // Host-side scan of every key under the map, no contract execution.
+        group.bench_with_input(BenchmarkId::new("keys", entries), &entries, |b, _| {
+            b.to_async(&rt).iter(|| async {
+                fixture
+                    .runtime
+                    .storage
+                    .keys(fixture.contract_id, "ledger".to_string())
+                    .await
+                    .expect("Failed to open key stream")
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .expect("Failed to scan keys")
+            })
+        });
+
+        // Full contract view: key scan plus one read per entry.
+        group.bench_with_input(BenchmarkId::new("balances", entries), &entries, |b, _| {
+            b.to_async(&rt).iter(|| {
+                let mut runtime = fixture.runtime.clone();
+                let token = fixture.token.clone();
+                async move {
+                    runtime
+                        .execute(None, &token, "balances()")
+                        .await
+                        .expect("Failed to execute balances")
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, storage_benches);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! Generates well-formed calls with assorted argument shapes and checks the
+//! parser accepts them and recovers the function name.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use indexer::runtime::parse_call_expr;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Arg {
+    U64(u64),
+    S64(i64),
+    Bool(bool),
+    Str(String),
+    Option(Option<u64>),
+    List(Vec<u64>),
+    Record(Vec<(u8, u64)>),
+    Case(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Call {
+    name: Vec<u8>,
+    args: Vec<Arg>,
+}
+
+/// A kebab-case identifier built from `seed`, never empty.
+fn ident(seed: &[u8]) -> String {
+    let mut ident = String::from("fn");
+    for b in seed.iter().take(16) {
+        let c = (b'a' + b % 26) as char;
+        if b % 7 == 0 && !ident.ends_with('-') {
+            ident.push('-');
+        }
+        ident.push(c);
+    }
+    ident
+}
+
+fn render(arg: &Arg) -> String {
+    match arg {
+        Arg::U64(n) => n.to_string(),
+        Arg::S64(n) => n.to_string(),
+        Arg::Bool(b) => b.to_string(),
+        Arg::Str(s) => format!(
+            "\"{}\"",
+            s.chars()
+                .filter(|c| !c.is_control() && !matches!(c, '\\' | '"'))
+                .collect::<String>()
+        ),
+        Arg::Option(None) => "none".to_string(),
+        Arg::Option(Some(n)) => format!("some({})", n),
+        Arg::List(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Arg::Record(fields) if fields.is_empty() => "{}".to_string(),
+        Arg::Record(fields) => format!(
+            "{{{}}}",
+            fields
+                .iter()
+                .enumerate()
+                .map(|(i, (seed, n))| format!("{}{}: {}", ident(&[*seed]), i, n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Arg::Case(seed) => ident(&[*seed]),
+    }
+}
+
+fuzz_target!(|call: Call| {
+    let name = ident(&call.name);
+    let expr = format!(
+        "{}({})",
+        name,
+        call.args.iter().map(render).collect::<Vec<_>>().join(", ")
+    );
+    match parse_call_expr(&expr) {
+        Ok(parsed) => assert_eq!(parsed.name(), name, "{}", expr),
+        Err(e) => panic!("well-formed call {:?} was rejected: {}", expr, e),
+    }
+});
@@ -0,0 +1,11 @@
+//! Arbitrary strings fed to the call-expression parser used by
+//! `prepare_call` must be rejected with an error, never a panic.
+
+#![no_main]
+
+use indexer::runtime::parse_call_expr;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|expr: &str| {
+    let _ = parse_call_expr(expr);
+});
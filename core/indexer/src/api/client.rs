@@ -1,7 +1,8 @@
 use anyhow::{Result, anyhow};
 use indexer_types::{
-    ComposeOutputs, ComposeQuery, ContractResponse, ErrorResponse, Info, OpWithResult,
-    ResultResponse, ResultRow, RevealOutputs, RevealQuery, TransactionHex, ViewExpr, ViewResult,
+    ComposeOutputs, ComposeQuery, ContractResponse, ContractSchema, ContractStateResponse,
+    ErrorResponse, GasStats, Info, InstructionSimulation, OpWithResult, ResultResponse, ResultRow,
+    RevealOutputs, RevealQuery, TransactionHex, ViewExpr, ViewResult,
 };
 use reqwest::{Client as HttpClient, ClientBuilder, Response};
 use serde::{Deserialize, Serialize};
@@ -103,6 +104,20 @@ impl Client {
         .await
     }
 
+    pub async fn transaction_simulate_instruction(
+        &self,
+        simulation: InstructionSimulation,
+    ) -> Result<OpWithResult> {
+        Self::handle_response(
+            self.client
+                .post(format!("{}/transactions/simulate/instruction", &self.url))
+                .json(&simulation)
+                .send()
+                .await?,
+        )
+        .await
+    }
+
     pub async fn transaction_inspect(&self, txid: &bitcoin::Txid) -> Result<Vec<OpWithResult>> {
         Self::handle_response(
             self.client
@@ -114,8 +129,18 @@ impl Client {
     }
 
     pub async fn view(&self, contract_address: &ContractAddress, expr: &str) -> Result<ViewResult> {
+        self.view_with_gas_limit(contract_address, expr, None).await
+    }
+
+    pub async fn view_with_gas_limit(
+        &self,
+        contract_address: &ContractAddress,
+        expr: &str,
+        gas_limit: Option<u64>,
+    ) -> Result<ViewResult> {
         let view_expr = ViewExpr {
             expr: expr.to_string(),
+            gas_limit,
         };
         Self::handle_response(
             self.client
@@ -127,6 +152,29 @@ impl Client {
         .await
     }
 
+    /// Runs a view against confirmed state plus the mempool.
+    pub async fn view_pending(
+        &self,
+        contract_address: &ContractAddress,
+        expr: &str,
+    ) -> Result<ViewResult> {
+        let view_expr = ViewExpr {
+            expr: expr.to_string(),
+            gas_limit: None,
+        };
+        Self::handle_response(
+            self.client
+                .post(format!(
+                    "{}/contracts/{}/pending",
+                    &self.url, contract_address
+                ))
+                .json(&view_expr)
+                .send()
+                .await?,
+        )
+        .await
+    }
+
     pub async fn wit(&self, contract_address: &ContractAddress) -> Result<ContractResponse> {
         Self::handle_response(
             self.client
@@ -137,6 +185,48 @@ impl Client {
         .await
     }
 
+    pub async fn contract_schema(
+        &self,
+        contract_address: &ContractAddress,
+    ) -> Result<ContractSchema> {
+        Self::handle_response(
+            self.client
+                .get(format!(
+                    "{}/contracts/{}/schema",
+                    &self.url, contract_address
+                ))
+                .send()
+                .await?,
+        )
+        .await
+    }
+
+    pub async fn contract_state(
+        &self,
+        contract_address: &ContractAddress,
+    ) -> Result<ContractStateResponse> {
+        Self::handle_response(
+            self.client
+                .get(format!(
+                    "{}/contracts/{}/state",
+                    &self.url, contract_address
+                ))
+                .send()
+                .await?,
+        )
+        .await
+    }
+
+    pub async fn gas_stats(&self, contract_address: &ContractAddress) -> Result<GasStats> {
+        Self::handle_response(
+            self.client
+                .get(format!("{}/contracts/{}/gas", &self.url, contract_address))
+                .send()
+                .await?,
+        )
+        .await
+    }
+
     pub async fn result(&self, id: &OpResultId) -> Result<Option<ResultRow>> {
         Self::handle_response(
             self.client
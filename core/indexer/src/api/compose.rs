@@ -21,7 +21,7 @@ use bitcoin::hashes::Hash;
 use bitcoin::key::constants::SCHNORR_SIGNATURE_SIZE;
 use indexer_types::{
     CommitOutputs, ComposeOutputs, ComposeQuery, ParticipantScripts, RevealInputs, RevealOutputs,
-    RevealParticipantInputs, RevealQuery, TapLeafScript, serialize,
+    RevealParticipantInputs, RevealQuery, TapLeafScript, serialize_envelope,
 };
 use rand::rngs::StdRng;
 use rand::{SeedableRng, seq::SliceRandom};
@@ -108,7 +108,7 @@ impl ComposeInputs {
                         MAX_UTXOS_PER_PARTICIPANT
                     ));
                 }
-                let instruction = serialize(&instruction_query.instruction)?;
+                let instruction = serialize_envelope(&instruction_query.instruction)?;
                 if instruction.is_empty() || instruction.len() > MAX_SCRIPT_BYTES {
                     return Err(anyhow!("script data size invalid"));
                 }
@@ -116,7 +116,7 @@ impl ComposeInputs {
                 let chained_script_data_bytes = match instruction_query.chained_instruction.as_ref()
                 {
                     Some(inst) => {
-                        let bytes = serialize(inst)?;
+                        let bytes = serialize_envelope(inst)?;
                         if bytes.is_empty() || bytes.len() > MAX_SCRIPT_BYTES {
                             return Err(anyhow!("chained script data size invalid"));
                         }
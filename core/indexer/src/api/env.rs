@@ -6,7 +6,11 @@ use tokio::sync::{RwLock, mpsc::Sender};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    bitcoin_client::Client, config::Config, database, event::EventSubscriber, reactor::Simulation,
+    bitcoin_client::Client,
+    config::Config,
+    database,
+    event::EventSubscriber,
+    reactor::{PendingView, Simulation},
     runtime,
 };
 
@@ -20,6 +24,7 @@ pub struct Env {
     pub bitcoin: Client,
     pub runtime_pool: Pool<runtime::pool::Manager>,
     pub simulate_tx: Sender<Simulation>,
+    pub pending_view_tx: Sender<PendingView>,
 }
 
 impl Env {
@@ -29,15 +34,21 @@ impl Env {
         db_name: String,
     ) -> Result<Self> {
         let (simulate_tx, _) = tokio::sync::mpsc::channel(10);
+        let (pending_view_tx, _) = tokio::sync::mpsc::channel(10);
+        let config = Config::new_na();
+        let runtime_pool =
+            runtime::pool::new(db_path.to_path_buf(), db_name, config.runtime_pool_config())
+                .await?;
         Ok(Self {
             bitcoin: Client::new("".to_string(), "".to_string(), "".to_string())?,
-            config: Config::new_na(),
+            config,
             cancel_token: CancellationToken::new(),
             available: Arc::new(RwLock::new(true)),
             event_subscriber: EventSubscriber::new(),
-            runtime_pool: runtime::pool::new(db_path.to_path_buf(), db_name).await?,
+            runtime_pool,
             reader,
             simulate_tx,
+            pending_view_tx,
         })
     }
 }
@@ -9,6 +9,8 @@ pub enum HttpError {
     NotFound(String),
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
 }
@@ -18,6 +20,7 @@ impl HttpError {
         match self {
             HttpError::NotFound(_) => StatusCode::NOT_FOUND,
             HttpError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            HttpError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             HttpError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
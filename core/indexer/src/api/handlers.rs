@@ -4,26 +4,32 @@ use axum::{
     Json,
     extract::{Path, Query, State},
 };
-use bitcoin::consensus::encode;
+use bitcoin::{XOnlyPublicKey, consensus::encode};
 use indexer_types::{
-    BlockRow, CommitOutputs, ComposeOutputs, ComposeQuery, ContractListRow, ContractResponse, Info,
-    OpWithResult, PaginatedResponse, ResultRow, RevealOutputs, RevealQuery, TransactionHex,
+    BlockRow, CommitOutputs, ComposeOutputs, ComposeQuery, ContractListRow, ContractResponse,
+    ContractSchema, ContractStateResponse, GasStats, Info, InstructionSimulation, OpWithResult,
+    PaginatedResponse, ResultRow, RevealOutputs, RevealQuery, Transaction, TransactionHex,
     TransactionRow, ViewExpr, ViewResult,
 };
 
 use crate::{
     api::compose::reveal_inputs_from_query,
-    block::inspect,
+    block::{filter_map, inspect, instruction_transaction},
     built_info,
     database::{
         queries::{
-            self, get_blocks_paginated, get_checkpoint_latest, get_op_result,
-            get_results_paginated, get_transaction_by_txid, get_transactions_paginated,
-            select_block_latest, select_processed_block_by_height_or_hash,
+            self, get_blocks_paginated, get_checkpoint_latest, get_contracts_paginated,
+            get_op_result, get_results_paginated, get_transaction_by_txid,
+            get_transactions_paginated, select_block_latest,
+            select_processed_block_by_height_or_hash,
+        },
+        types::{
+            BlockQuery, ContractQuery, ContractResultPublicRow, OpResultId, ResultQuery,
+            TransactionQuery,
         },
-        types::{BlockQuery, ContractResultPublicRow, OpResultId, ResultQuery, TransactionQuery},
     },
-    runtime::ContractAddress,
+    height,
+    runtime::{ContractAddress, WitError},
 };
 
 use super::{
@@ -38,7 +44,9 @@ async fn get_info(env: &Env) -> anyhow::Result<Info> {
     let height = select_block_latest(&conn)
         .await?
         .map(|b| b.height)
-        .unwrap_or((env.config.starting_block_height - 1) as i64);
+        .unwrap_or(height::to_i64(
+            env.config.starting_block_height.saturating_sub(1),
+        ));
     let checkpoint = get_checkpoint_latest(&conn).await?.map(|c| c.hash);
     Ok(Info {
         version: built_info::PKG_VERSION.to_string(),
@@ -219,18 +227,40 @@ pub async fn post_simulate(
 ) -> Result<Vec<OpWithResult>> {
     let btx = encode::deserialize_hex::<bitcoin::Transaction>(&hex)
         .map_err(|e| HttpError::BadRequest(e.to_string()))?;
+    let tx = filter_map((0, btx))
+        .ok_or_else(|| HttpError::BadRequest("Invalid transaction".to_string()))?;
+    Ok(simulate(&env, tx).await?.into())
+}
+
+pub async fn post_simulate_instruction(
+    State(env): State<Env>,
+    Json(InstructionSimulation {
+        x_only_public_key,
+        instruction,
+    }): Json<InstructionSimulation>,
+) -> Result<OpWithResult> {
+    let signer = XOnlyPublicKey::from_str(&x_only_public_key)
+        .map_err(|e| HttpError::BadRequest(e.to_string()))?;
+    let tx = instruction_transaction(signer, instruction)
+        .map_err(|e| HttpError::BadRequest(e.to_string()))?;
+    let op = simulate(&env, tx).await?.pop().ok_or_else(|| {
+        HttpError::UnprocessableEntity("Instruction produced no operation".to_string())
+    })?;
+    Ok(op.into())
+}
+
+async fn simulate(env: &Env, tx: Transaction) -> anyhow::Result<Vec<OpWithResult>> {
     let (ret_tx, ret_rx) = tokio::sync::oneshot::channel();
-    env.simulate_tx.send((btx, ret_tx)).await?;
-    Ok(ret_rx
+    env.simulate_tx.send((tx, ret_tx)).await?;
+    ret_rx
         .await?
-        .map_err(|e| HttpError::BadRequest(e.to_string()))?
-        .into())
+        .map_err(|e| HttpError::BadRequest(e.to_string()).into())
 }
 
 pub async fn post_contract(
     Path(address): Path<String>,
     State(env): State<Env>,
-    Json(ViewExpr { expr }): Json<ViewExpr>,
+    Json(ViewExpr { expr, gas_limit }): Json<ViewExpr>,
 ) -> Result<ViewResult> {
     if !*env.available.read().await {
         return Err(HttpError::ServiceUnavailable("Indexer is not available".to_string()).into());
@@ -242,7 +272,11 @@ pub async fn post_contract(
         .runtime_pool
         .get()
         .await?
-        .execute(None, &contract_address, &expr)
+        .execute_view(
+            &contract_address,
+            &expr,
+            env.config.view_gas_limit(gas_limit),
+        )
         .await;
     Ok(match result {
         Ok(value) => ViewResult::Ok { value },
@@ -253,11 +287,57 @@ pub async fn post_contract(
     .into())
 }
 
+/// Like [`post_contract`], against confirmed state plus the transactions in
+/// the mempool. Only served when the indexer runs with pending views enabled.
+pub async fn post_contract_pending(
+    Path(address): Path<String>,
+    State(env): State<Env>,
+    Json(ViewExpr { expr, gas_limit }): Json<ViewExpr>,
+) -> Result<ViewResult> {
+    if !*env.available.read().await {
+        return Err(HttpError::ServiceUnavailable("Indexer is not available".to_string()).into());
+    }
+    let contract_address = address
+        .parse::<ContractAddress>()
+        .map_err(|_| HttpError::BadRequest("Invalid contract address".to_string()))?;
+    let (ret_tx, ret_rx) = tokio::sync::oneshot::channel();
+    env.pending_view_tx
+        .send((
+            contract_address,
+            expr,
+            env.config.view_gas_limit(gas_limit),
+            ret_tx,
+        ))
+        .await
+        .map_err(|_| HttpError::ServiceUnavailable("Pending views are not enabled".to_string()))?;
+    Ok(match ret_rx.await? {
+        Ok(value) => ViewResult::Ok { value },
+        Err(e) => ViewResult::Err {
+            message: format!("{:?}", e),
+        },
+    }
+    .into())
+}
+
 pub async fn get_contracts(State(env): State<Env>) -> Result<Vec<ContractListRow>> {
     let conn = env.reader.connection().await?;
     Ok(queries::get_contracts(&conn).await?.into())
 }
 
+pub async fn get_contracts_page(
+    Query(query): Query<ContractQuery>,
+    State(env): State<Env>,
+) -> Result<PaginatedResponse<ContractListRow>> {
+    validate_query(query.cursor, query.offset)?;
+    let (results, pagination) =
+        get_contracts_paginated(&*env.reader.connection().await?, query).await?;
+    Ok(PaginatedResponse {
+        results,
+        pagination,
+    }
+    .into())
+}
+
 pub async fn get_contract(
     Path(address): Path<String>,
     State(env): State<Env>,
@@ -275,10 +355,84 @@ pub async fn get_contract(
         .await?
         .ok_or(HttpError::NotFound("Contract not found".to_string()))?;
 
-    let wit = runtime.storage.component_wit(contract_id).await?;
+    let wit = runtime
+        .storage
+        .component_wit(contract_id)
+        .await
+        .map_err(|e| match e {
+            WitError::Other(e) => e,
+            e => HttpError::UnprocessableEntity(e.to_string()).into(),
+        })?;
     Ok(ContractResponse { wit }.into())
 }
 
+pub async fn get_contract_schema(
+    Path(address): Path<String>,
+    State(env): State<Env>,
+) -> Result<ContractSchema> {
+    if !*env.available.read().await {
+        return Err(HttpError::ServiceUnavailable("Indexer is not available".to_string()).into());
+    }
+    let contract_address = address
+        .parse::<ContractAddress>()
+        .map_err(|_| HttpError::BadRequest("Invalid contract address".to_string()))?;
+    let runtime = env.runtime_pool.get().await?;
+    let contract_id = runtime
+        .storage
+        .contract_id(&contract_address)
+        .await?
+        .ok_or(HttpError::NotFound("Contract not found".to_string()))?;
+
+    let schema = runtime
+        .storage
+        .component_schema(contract_id)
+        .await
+        .map_err(|e| match e {
+            WitError::Other(e) => e,
+            e => HttpError::UnprocessableEntity(e.to_string()).into(),
+        })?;
+    Ok(schema.into())
+}
+
+pub async fn get_contract_gas(
+    Path(address): Path<String>,
+    State(env): State<Env>,
+) -> Result<GasStats> {
+    if !*env.available.read().await {
+        return Err(HttpError::ServiceUnavailable("Indexer is not available".to_string()).into());
+    }
+    let contract_address = address
+        .parse::<ContractAddress>()
+        .map_err(|_| HttpError::BadRequest("Invalid contract address".to_string()))?;
+    let runtime = env.runtime_pool.get().await?;
+    let contract_id = runtime
+        .storage
+        .contract_id(&contract_address)
+        .await?
+        .ok_or(HttpError::NotFound("Contract not found".to_string()))?;
+    Ok(runtime.storage.gas_stats(contract_id).await?.into())
+}
+
+pub async fn get_contract_state(
+    Path(address): Path<String>,
+    State(env): State<Env>,
+) -> Result<ContractStateResponse> {
+    if !*env.available.read().await {
+        return Err(HttpError::ServiceUnavailable("Indexer is not available".to_string()).into());
+    }
+    let contract_address = address
+        .parse::<ContractAddress>()
+        .map_err(|_| HttpError::BadRequest("Invalid contract address".to_string()))?;
+    let runtime = env.runtime_pool.get().await?;
+    let contract_id = runtime
+        .storage
+        .contract_id(&contract_address)
+        .await?
+        .ok_or(HttpError::NotFound("Contract not found".to_string()))?;
+    let has_state = runtime.storage.has_state(contract_id).await?;
+    Ok(ContractStateResponse { has_state }.into())
+}
+
 impl From<ContractResultPublicRow> for ResultRow {
     fn from(row: ContractResultPublicRow) -> Self {
         ResultRow {
@@ -291,9 +445,10 @@ impl From<ContractResultPublicRow> for ResultRow {
             func: row.func,
             gas: row.gas,
             value: row.value,
+            error_trace: row.error_trace,
             contract: ContractAddress {
                 name: row.contract_name,
-                height: row.contract_height as u64,
+                height: height::to_u64(row.contract_height),
                 tx_index: row.contract_tx_index as u64,
             }
             .to_string(),
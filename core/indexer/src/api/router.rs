@@ -19,9 +19,11 @@ use tower_http::{
 use tracing::{Level, Span, error, field, info, span};
 
 use crate::api::handlers::{
-    get_block_transactions, get_blocks, get_contract, get_contracts, get_index, get_result,
-    get_results, get_transaction, get_transaction_inspect, get_transactions, post_compose,
-    post_contract, post_simulate, post_transaction_hex_inspect, stop,
+    get_block_transactions, get_blocks, get_contract, get_contract_gas, get_contract_schema,
+    get_contract_state, get_contracts, get_contracts_page, get_index, get_result, get_results,
+    get_transaction, get_transaction_inspect, get_transactions, post_compose, post_contract,
+    post_contract_pending, post_simulate, post_simulate_instruction, post_transaction_hex_inspect,
+    stop,
 };
 
 use super::{
@@ -111,6 +113,7 @@ pub fn new(context: Env) -> Router {
                         .route("/{txid}/inspect", get(get_transaction_inspect))
                         .route("/inspect", post(post_transaction_hex_inspect))
                         .route("/simulate", post(post_simulate))
+                        .route("/simulate/instruction", post(post_simulate_instruction))
                         .nest(
                             "/compose",
                             Router::new()
@@ -123,7 +126,12 @@ pub fn new(context: Env) -> Router {
                     "/contracts",
                     Router::new()
                         .route("/", get(get_contracts))
-                        .route("/{address}", get(get_contract).post(post_contract)),
+                        .route("/page", get(get_contracts_page))
+                        .route("/{address}", get(get_contract).post(post_contract))
+                        .route("/{address}/gas", get(get_contract_gas))
+                        .route("/{address}/pending", post(post_contract_pending))
+                        .route("/{address}/schema", get(get_contract_schema))
+                        .route("/{address}/state", get(get_contract_state)),
                 )
                 .nest(
                     "/results",
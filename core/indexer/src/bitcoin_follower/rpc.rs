@@ -186,6 +186,7 @@ pub fn run_processor(
                                                 height,
                                                 hash: block.block_hash(),
                                                 prev_hash: block.header.prev_blockhash,
+                                                time: block.header.time as u64,
                                                 transactions: tokio::task::spawn_blocking(move || {
                                                     block
                                                         .txdata
@@ -129,6 +129,7 @@ pub async fn process_data_message<C: BitcoinRpc>(
                     height,
                     hash: block.block_hash(),
                     prev_hash: block.header.prev_blockhash,
+                    time: block.header.time as u64,
                     transactions: task::spawn_blocking(move || {
                         block
                             .txdata
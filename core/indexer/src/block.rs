@@ -1,9 +1,14 @@
+use std::borrow::Borrow;
+
 use bitcoin::{
-    XOnlyPublicKey,
+    OutPoint, Txid, XOnlyPublicKey,
+    hashes::{Hash, sha256d},
     opcodes::all::{OP_CHECKSIG, OP_ENDIF, OP_IF, OP_RETURN},
     script::Instruction,
 };
-use indexer_types::{Inst, Op, OpMetadata, OpWithResult, Transaction, deserialize};
+use indexer_types::{
+    Inst, Op, OpMetadata, OpWithResult, Transaction, deserialize_envelope, serialize_envelope,
+};
 use indexmap::IndexMap;
 use libsql::Connection;
 
@@ -12,10 +17,16 @@ use crate::{
     runtime::wit::Signer,
 };
 
+/// Sorts ops into their canonical execution order, by input index. Ops
+/// sharing an input keep their relative order, which is their op index.
+pub fn sort_ops<T: Borrow<Op>>(ops: &mut [T]) {
+    ops.sort_by_key(|op| op.borrow().metadata().input_index);
+}
+
 pub type TransactionFilterMap = fn((usize, bitcoin::Transaction)) -> Option<Transaction>;
 
 pub fn filter_map((tx_index, tx): (usize, bitcoin::Transaction)) -> Option<Transaction> {
-    let ops = tx
+    let mut ops = tx
         .input
         .iter()
         .enumerate()
@@ -44,36 +55,14 @@ pub fn filter_map((tx_index, tx): (usize, bitcoin::Transaction)) -> Option<Trans
 
                     if inst == Some(Ok(Instruction::Op(OP_ENDIF)))
                         && insts.next().is_none()
-                        && let Ok(inst) = deserialize::<Inst>(&data)
+                        && let Ok(inst) = deserialize_envelope::<Inst>(&data)
                     {
                         let metadata = OpMetadata {
                             previous_output: input.previous_output,
                             input_index: input_index as i64,
                             signer: Signer::XOnlyPubKey(signer.to_string()),
                         };
-                        return Some(match inst {
-                            Inst::Publish {
-                                gas_limit,
-                                name,
-                                bytes,
-                            } => Op::Publish {
-                                metadata,
-                                gas_limit,
-                                name,
-                                bytes,
-                            },
-                            Inst::Call {
-                                gas_limit,
-                                contract,
-                                expr,
-                            } => Op::Call {
-                                metadata,
-                                gas_limit,
-                                contract,
-                                expr,
-                            },
-                            Inst::Issuance => Op::Issuance { metadata },
-                        });
+                        return Some(Op::from_inst(inst, metadata));
                     }
                 }
                 None
@@ -84,6 +73,7 @@ pub fn filter_map((tx_index, tx): (usize, bitcoin::Transaction)) -> Option<Trans
     if ops.is_empty() {
         return None;
     }
+    sort_ops(&mut ops);
 
     let op_return = tx.output.iter().find(|o| o.script_pubkey.is_op_return());
     let mut op_return_data = IndexMap::new();
@@ -93,7 +83,7 @@ pub fn filter_map((tx_index, tx): (usize, bitcoin::Transaction)) -> Option<Trans
         if let Some(Ok(Instruction::Op(OP_RETURN))) = op_return_instructions.next()
             && let Some(Ok(Instruction::PushBytes(data))) = op_return_instructions.next()
             && let Ok(entries) =
-                deserialize::<Vec<(u64, indexer_types::OpReturnData)>>(data.as_bytes())
+                deserialize_envelope::<Vec<(u64, indexer_types::OpReturnData)>>(data.as_bytes())
         {
             op_return_data = IndexMap::from_iter(entries);
         }
@@ -110,18 +100,47 @@ pub fn filter_map((tx_index, tx): (usize, bitcoin::Transaction)) -> Option<Trans
 pub async fn inspect(
     conn: &Connection,
     btx: bitcoin::Transaction,
+) -> anyhow::Result<Vec<OpWithResult>> {
+    match filter_map((0, btx)) {
+        Some(tx) => ops_with_results(conn, tx).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+pub async fn ops_with_results(
+    conn: &Connection,
+    tx: Transaction,
 ) -> anyhow::Result<Vec<OpWithResult>> {
     let mut ops = Vec::new();
-    if let Some(tx) = filter_map((0, btx)) {
-        for op in tx.ops {
-            let id = OpResultId::builder()
-                .txid(tx.txid.to_string())
-                .input_index(op.metadata().input_index)
-                .op_index(0)
-                .build();
-            let result = get_op_result(conn, &id).await?.map(Into::into);
-            ops.push(OpWithResult { op, result });
-        }
+    for op in tx.ops {
+        let id = OpResultId::builder()
+            .txid(tx.txid.to_string())
+            .input_index(op.metadata().input_index)
+            .op_index(0)
+            .build();
+        let result = get_op_result(conn, &id).await?.map(Into::into);
+        ops.push(OpWithResult { op, result });
     }
     Ok(ops)
 }
+
+/// A transaction carrying `inst` in its only input as if `signer` had
+/// revealed it, for simulating instructions that were never broadcast. The
+/// txid is derived from the signer and instruction so results can be looked
+/// up like any other transaction's.
+pub fn instruction_transaction(signer: XOnlyPublicKey, inst: Inst) -> anyhow::Result<Transaction> {
+    let txid = Txid::from_raw_hash(sha256d::Hash::hash(
+        &[signer.serialize().as_slice(), &serialize_envelope(&inst)?].concat(),
+    ));
+    let metadata = OpMetadata {
+        previous_output: OutPoint::null(),
+        input_index: 0,
+        signer: Signer::XOnlyPubKey(signer.to_string()),
+    };
+    Ok(Transaction {
+        txid,
+        index: 0,
+        ops: vec![Op::from_inst(inst, metadata)],
+        op_return_data: IndexMap::new(),
+    })
+}
@@ -1,10 +1,10 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use bitcoin::Network;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 
-use crate::logging;
+use crate::{logging, runtime::pool::PoolConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
 #[clap(
@@ -77,6 +77,35 @@ pub struct Config {
         default_value = "bitcoin"
     )]
     pub network: bitcoin::Network,
+
+    #[clap(
+        long,
+        env = "RUNTIME_POOL_SIZE",
+        help = "Maximum number of runtimes serving view calls (available parallelism if unset)"
+    )]
+    pub runtime_pool_size: Option<usize>,
+
+    #[clap(
+        long,
+        env = "RUNTIME_POOL_MAX_IDLE_SECS",
+        help = "Seconds a pooled runtime may sit idle before it is recreated (never if unset)"
+    )]
+    pub runtime_pool_max_idle_secs: Option<u64>,
+
+    #[clap(
+        long,
+        env = "MAX_VIEW_GAS_LIMIT",
+        help = "Largest gas limit a view request may ask for",
+        default_value = "10000000"
+    )]
+    pub max_view_gas_limit: u64,
+
+    #[clap(
+        long,
+        env = "PENDING_VIEWS",
+        help = "Serve view calls against confirmed state plus the mempool"
+    )]
+    pub pending_views: bool,
 }
 
 impl Config {
@@ -92,6 +121,27 @@ impl Config {
             api_port: 9333,
             data_dir: "will be set".into(),
             starting_block_height: 1,
+            runtime_pool_size: None,
+            runtime_pool_max_idle_secs: None,
+            max_view_gas_limit: 10_000_000,
+            pending_views: false,
+        }
+    }
+
+    /// The gas limit a view request gets: what it asked for, capped at
+    /// `max_view_gas_limit`.
+    pub fn view_gas_limit(&self, requested: Option<u64>) -> Option<u64> {
+        requested.map(|gas_limit| gas_limit.min(self.max_view_gas_limit))
+    }
+
+    pub fn runtime_pool_config(&self) -> PoolConfig {
+        let default = PoolConfig::default();
+        PoolConfig {
+            max_size: self.runtime_pool_size.unwrap_or(default.max_size),
+            max_idle: self
+                .runtime_pool_max_idle_secs
+                .map(Duration::from_secs)
+                .or(default.max_idle),
         }
     }
 }
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use libsql::Error;
+use libsql::{Error, params};
 use tokio::fs;
 
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
@@ -37,11 +37,89 @@ const LIB_FILE_EXT: &str = "dll";
 
 pub const CREATE_SCHEMA: &str = include_str!("sql/schema.sql");
 pub const CREATE_CONTRACT_STATE_TRIGGER: &str = include_str!("sql/checkpoint_trigger.sql");
+pub const CREATE_STATE_VERSION_TRIGGERS: &str = include_str!("sql/state_version_trigger.sql");
+
+// Databases created before a column existed need it added in place, `CREATE
+// TABLE IF NOT EXISTS` leaves existing tables untouched. Returns whether the
+// column was added, so the caller can backfill it.
+async fn add_column_if_missing(
+    conn: &libsql::Connection,
+    table: &str,
+    column: &str,
+    ddl: &str,
+) -> Result<bool, Error> {
+    let mut rows = conn
+        .query(
+            &format!(
+                "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?",
+                table
+            ),
+            params![column],
+        )
+        .await?;
+    if rows.next().await?.is_some() {
+        return Ok(false);
+    }
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, ddl),
+        (),
+    )
+    .await?;
+    Ok(true)
+}
+
+async fn migrate_columns(conn: &libsql::Connection) -> Result<(), Error> {
+    add_column_if_missing(conn, "blocks", "prev_hash", "TEXT").await?;
+    add_column_if_missing(conn, "blocks", "time", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(conn, "contracts", "deployer", "TEXT").await?;
+    // every contract in the table had its `init` succeed, a failed one rolls
+    // the publish back
+    if add_column_if_missing(
+        conn,
+        "contracts",
+        "initialized",
+        "BOOLEAN NOT NULL DEFAULT FALSE",
+    )
+    .await?
+    {
+        conn.execute("UPDATE contracts SET initialized = TRUE", ())
+            .await?;
+    }
+    add_column_if_missing(conn, "contract_results", "error_trace", "TEXT").await?;
+    if add_column_if_missing(
+        conn,
+        "contract_results",
+        "failed",
+        "BOOLEAN NOT NULL DEFAULT FALSE",
+    )
+    .await?
+    {
+        conn.execute(
+            "UPDATE contract_results SET failed = (value IS NULL OR value LIKE 'err(%')",
+            (),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+// Contracts published before content hashes were stored get theirs backfilled,
+// which needs the crypto extension loaded. The placeholder default only lets
+// the column be added as `NOT NULL`, like in the schema.
+async fn migrate_contract_hash_column(conn: &libsql::Connection) -> Result<(), Error> {
+    if add_column_if_missing(conn, "contracts", "hash", "BLOB NOT NULL DEFAULT x''").await? {
+        conn.execute("UPDATE contracts SET hash = crypto_sha256(bytes)", ())
+            .await?;
+    }
+    Ok(())
+}
 
 pub async fn initialize_database(data_dir: &Path, conn: &libsql::Connection) -> Result<(), Error> {
     conn.query("PRAGMA foreign_keys = ON;", ()).await?;
     conn.execute_batch(CREATE_SCHEMA).await?;
+    migrate_columns(conn).await?;
     conn.execute(CREATE_CONTRACT_STATE_TRIGGER, ()).await?;
+    conn.execute_batch(CREATE_STATE_VERSION_TRIGGERS).await?;
     conn.query("PRAGMA journal_mode = WAL;", ()).await?;
     conn.query("PRAGMA synchronous = NORMAL;", ()).await?;
     conn.load_extension_enable()?;
@@ -60,5 +138,6 @@ pub async fn initialize_database(data_dir: &Path, conn: &libsql::Connection) ->
         let extension_path = data_dir.join(name);
         conn.load_extension(extension_path, None)?;
     }
+    migrate_contract_hash_column(conn).await?;
     Ok(())
 }
@@ -1,16 +1,19 @@
+use std::collections::HashSet;
+
 use bitcoin::BlockHash;
 use futures_util::{Stream, stream};
-use indexer_types::{BlockRow, ContractListRow, PaginationMeta, TransactionRow};
+use indexer_types::{BlockRow, ContractListRow, GasStats, PaginationMeta, TransactionRow};
 use libsql::{Connection, Value, de::from_row, named_params, params};
 use serde::de::DeserializeOwned;
 use thiserror::Error as ThisError;
 
 use crate::{
     database::types::{
-        BlockQuery, CheckpointRow, ContractResultPublicRow, ContractResultRow, ContractRow,
-        FileMetadataRow, HasRowId, OpResultId, OrderDirection, ResultQuery, TransactionQuery,
+        BlockQuery, CheckpointRow, ContractQuery, ContractResultPublicRow, ContractResultRow,
+        ContractRow, FileMetadataRow, HasRowId, OpResultId, OrderDirection, ResultQuery,
+        TransactionQuery,
     },
-    runtime::ContractAddress,
+    runtime::{ContractAddress, hash_bytes},
 };
 
 use super::types::ContractStateRow;
@@ -33,8 +36,14 @@ pub enum Error {
 
 pub async fn insert_block(conn: &Connection, block: BlockRow) -> Result<i64, Error> {
     conn.execute(
-        "INSERT OR REPLACE INTO blocks (height, hash, relevant) VALUES (?, ?, ?)",
-        (block.height, block.hash.to_string(), block.relevant),
+        "INSERT OR REPLACE INTO blocks (height, hash, prev_hash, time, relevant) VALUES (?, ?, ?, ?, ?)",
+        (
+            block.height,
+            block.hash.to_string(),
+            block.prev_hash.map(|h| h.to_string()),
+            block.time,
+            block.relevant,
+        ),
     )
     .await?;
     Ok(conn.last_insert_rowid())
@@ -42,8 +51,14 @@ pub async fn insert_block(conn: &Connection, block: BlockRow) -> Result<i64, Err
 
 pub async fn insert_processed_block(conn: &Connection, block: BlockRow) -> Result<i64, Error> {
     conn.execute(
-        "INSERT OR REPLACE INTO blocks (height, hash, relevant, processed) VALUES (?, ?, ?, 1)",
-        (block.height, block.hash.to_string(), block.relevant),
+        "INSERT OR REPLACE INTO blocks (height, hash, prev_hash, time, relevant, processed) VALUES (?, ?, ?, ?, ?, 1)",
+        (
+            block.height,
+            block.hash.to_string(),
+            block.prev_hash.map(|h| h.to_string()),
+            block.time,
+            block.relevant,
+        ),
     )
     .await?;
     Ok(conn.last_insert_rowid())
@@ -60,7 +75,7 @@ pub async fn rollback_to_height(conn: &Connection, height: u64) -> Result<u64, E
 pub async fn select_block_latest(conn: &Connection) -> Result<Option<BlockRow>, Error> {
     let mut rows = conn
         .query(
-            "SELECT height, hash, relevant FROM blocks WHERE processed = 1 ORDER BY height DESC LIMIT 1",
+            "SELECT height, hash, prev_hash, time, relevant FROM blocks WHERE processed = 1 ORDER BY height DESC LIMIT 1",
             params![],
         )
         .await?;
@@ -88,7 +103,7 @@ pub async fn select_processed_block_by_height_or_hash(
 ) -> Result<Option<BlockRow>, Error> {
     let mut rows = conn
         .query(
-            "SELECT height, hash, relevant FROM blocks WHERE (height = ? OR hash = ?) AND processed = 1",
+            "SELECT height, hash, prev_hash, time, relevant FROM blocks WHERE (height = ? OR hash = ?) AND processed = 1",
             params![identifier, identifier],
         )
         .await?;
@@ -101,7 +116,7 @@ pub async fn select_block_at_height(
 ) -> Result<Option<BlockRow>, Error> {
     let mut rows = conn
         .query(
-            "SELECT height, hash, relevant FROM blocks WHERE height = ?",
+            "SELECT height, hash, prev_hash, time, relevant FROM blocks WHERE height = ?",
             params![height],
         )
         .await?;
@@ -114,7 +129,7 @@ pub async fn select_processed_block_at_height(
 ) -> Result<Option<BlockRow>, Error> {
     let mut rows = conn
         .query(
-            "SELECT height, hash, relevant FROM blocks WHERE height = ? AND processed = 1",
+            "SELECT height, hash, prev_hash, time, relevant FROM blocks WHERE height = ? AND processed = 1",
             params![height],
         )
         .await?;
@@ -127,7 +142,7 @@ pub async fn select_block_with_hash(
 ) -> Result<Option<BlockRow>, Error> {
     let mut rows = conn
         .query(
-            "SELECT height, hash, relevant FROM blocks WHERE hash = ?",
+            "SELECT height, hash, prev_hash, time, relevant FROM blocks WHERE hash = ?",
             params![hash.to_string()],
         )
         .await?;
@@ -148,7 +163,7 @@ pub async fn get_blocks_paginated(
     get_paginated(
         conn,
         var,
-        "b.height, b.hash, b.relevant",
+        "b.height, b.hash, b.prev_hash, b.time, b.relevant",
         &format!("blocks {}", var),
         where_clauses,
         params,
@@ -260,6 +275,37 @@ pub async fn get_latest_contract_state_value(
     Ok(None)
 }
 
+/// The value at `path` as of the end of block `height`, from the state rows
+/// retained for every height that wrote it.
+pub async fn get_contract_state_value_at_height(
+    conn: &Connection,
+    contract_id: i64,
+    path: &str,
+    height: i64,
+) -> Result<Option<Vec<u8>>, Error> {
+    let mut rows = conn
+        .query(
+            r#"
+            SELECT value, deleted
+            FROM contract_state
+            WHERE contract_id = :contract_id AND path = :path AND height <= :height
+            ORDER BY height DESC, tx_index DESC
+            LIMIT 1
+            "#,
+            (
+                (":contract_id", contract_id),
+                (":path", path),
+                (":height", height),
+            ),
+        )
+        .await?;
+
+    Ok(match rows.next().await? {
+        Some(row) if row.get::<i64>(1)? == 0 => Some(row.get::<Vec<u8>>(0)?),
+        _ => None,
+    })
+}
+
 pub async fn delete_contract_state(
     conn: &Connection,
     height: i64,
@@ -314,11 +360,26 @@ pub async fn path_prefix_filter_contract_state(
     conn: &Connection,
     contract_id: i64,
     path: String,
+) -> Result<impl Stream<Item = Result<String, libsql::Error>> + Send + 'static, Error> {
+    path_prefix_filter_contract_state_after(conn, contract_id, path, None).await
+}
+
+/// Like [`path_prefix_filter_contract_state`], starting with the first
+/// segment ordered after `after` when one is given.
+pub async fn path_prefix_filter_contract_state_after(
+    conn: &Connection,
+    contract_id: i64,
+    path: String,
+    after: Option<String>,
 ) -> Result<impl Stream<Item = Result<String, libsql::Error>> + Send + 'static, Error> {
     let rows = conn
         .query(
             PATH_PREFIX_FILTER_QUERY,
-            ((":contract_id", contract_id), (":path", path.clone())),
+            (
+                (":contract_id", contract_id),
+                (":path", path.clone()),
+                (":after", after),
+            ),
         )
         .await?;
     let stream = stream::unfold(rows, |mut rows| async move {
@@ -335,6 +396,36 @@ pub async fn path_prefix_filter_contract_state(
     Ok(stream)
 }
 
+const PATH_PREFIX_ENTRIES_QUERY: &str = include_str!("sql/path_prefix_entries_query.sql");
+
+/// Streams the direct children of `path` that hold a value, as
+/// `(segment, value)` pairs ordered like the keys of
+/// [`path_prefix_filter_contract_state`].
+pub async fn path_prefix_entries_contract_state(
+    conn: &Connection,
+    contract_id: i64,
+    path: String,
+) -> Result<impl Stream<Item = Result<(String, Vec<u8>), libsql::Error>> + Send + 'static, Error> {
+    let rows = conn
+        .query(
+            PATH_PREFIX_ENTRIES_QUERY,
+            ((":contract_id", contract_id), (":path", path)),
+        )
+        .await?;
+    let stream = stream::unfold(rows, |mut rows| async move {
+        match rows.next().await {
+            Ok(Some(row)) => match (row.get::<String>(0), row.get::<Vec<u8>>(1)) {
+                (Ok(segment), Ok(value)) => Some((Ok((segment, value)), rows)),
+                (Err(e), _) | (_, Err(e)) => Some((Err(e), rows)),
+            },
+            Ok(None) => None,
+            Err(e) => Some((Err(e), rows)),
+        }
+    });
+
+    Ok(stream)
+}
+
 const MATCHING_PATH_CONTRACT_STATE_QUERY: &str = include_str!("sql/matching_path_query.sql");
 
 pub async fn matching_path(
@@ -376,6 +467,82 @@ pub async fn delete_matching_paths(
         .await?)
 }
 
+const DELETE_PATH_PREFIX_QUERY: &str = include_str!("sql/delete_path_prefix.sql");
+
+/// Tombstones every live path strictly under `path` at `height`, returning the
+/// number of paths tombstoned and of distinct direct children removed.
+pub async fn delete_path_prefix(
+    conn: &Connection,
+    height: i64,
+    tx_index: i64,
+    contract_id: i64,
+    path: &str,
+) -> Result<(u64, u64), Error> {
+    let mut rows = conn
+        .query(
+            DELETE_PATH_PREFIX_QUERY,
+            (
+                (":contract_id", contract_id),
+                (":height", height),
+                (":tx_index", tx_index),
+                (":path", path),
+            ),
+        )
+        .await?;
+    let prefix = format!("{}.", path);
+    let mut deleted_paths = 0;
+    let mut keys = HashSet::new();
+    while let Some(row) = rows.next().await? {
+        deleted_paths += 1;
+        let deleted = row.get::<String>(0)?;
+        if let Some(rest) = deleted.strip_prefix(&prefix) {
+            keys.insert(rest.split('.').next().unwrap_or_default().to_string());
+        }
+    }
+    Ok((deleted_paths, keys.len() as u64))
+}
+
+const COUNT_PATH_PREFIX_QUERY: &str = include_str!("sql/count_path_prefix.sql");
+
+/// The number of live paths strictly under `path`, which is what
+/// [`delete_path_prefix`] would tombstone.
+pub async fn count_path_prefix(
+    conn: &Connection,
+    contract_id: i64,
+    path: &str,
+) -> Result<u64, Error> {
+    let mut rows = conn
+        .query(
+            COUNT_PATH_PREFIX_QUERY,
+            ((":contract_id", contract_id), (":path", path)),
+        )
+        .await?;
+    Ok(rows
+        .next()
+        .await?
+        .map(|r| r.get::<i64>(0))
+        .transpose()?
+        .unwrap_or_default() as u64)
+}
+
+pub async fn select_contract_state_version(
+    conn: &Connection,
+    contract_id: i64,
+) -> Result<i64, Error> {
+    let mut rows = conn
+        .query(
+            "SELECT version FROM contract_state_versions WHERE contract_id = ?",
+            params![contract_id],
+        )
+        .await?;
+    Ok(rows
+        .next()
+        .await?
+        .map(|r| r.get::<i64>(0))
+        .transpose()?
+        .unwrap_or_default())
+}
+
 pub async fn contract_has_state(conn: &Connection, contract_id: i64) -> Result<bool, Error> {
     let mut rows = conn
         .query(
@@ -400,12 +567,16 @@ pub async fn insert_contract(conn: &Connection, row: ContractRow) -> Result<i64,
                 height,
                 tx_index,
                 size,
-                bytes
+                bytes,
+                hash,
+                deployer
             ) VALUES (
                 ?,
                 ?,
                 ?,
                 ?,
+                ?,
+                ?,
                 ?
             )
             "#,
@@ -414,7 +585,9 @@ pub async fn insert_contract(conn: &Connection, row: ContractRow) -> Result<i64,
             row.height,
             row.tx_index,
             row.size(),
-            row.bytes
+            row.bytes.clone(),
+            row.hash().to_vec(),
+            row.deployer
         ],
     )
     .await?;
@@ -422,6 +595,52 @@ pub async fn insert_contract(conn: &Connection, row: ContractRow) -> Result<i64,
     Ok(conn.last_insert_rowid())
 }
 
+/// Records new code for a contract as of `height`/`tx_index`, leaving its id,
+/// address and deployer as they were. Versions are rows like contract state,
+/// so rolling back the block also restores the previous code.
+pub async fn insert_contract_code_version(
+    conn: &Connection,
+    contract_id: i64,
+    height: i64,
+    tx_index: i64,
+    bytes: &[u8],
+) -> Result<(), Error> {
+    conn.execute(
+        r#"
+        INSERT INTO contract_code_versions (contract_id, height, tx_index, size, bytes, hash)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT (contract_id, height, tx_index)
+        DO UPDATE SET size = excluded.size, bytes = excluded.bytes, hash = excluded.hash
+        "#,
+        params![
+            contract_id,
+            height,
+            tx_index,
+            bytes.len() as u64,
+            bytes.to_vec(),
+            hash_bytes(bytes).to_vec()
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+/// `column` of the contract's active code: its latest version by height, or
+/// what it was published with if it was never upgraded.
+fn active_contract_code_query(column: &str) -> String {
+    format!(
+        r#"
+        SELECT {column} FROM (
+          SELECT {column}, height, tx_index FROM contract_code_versions WHERE contract_id = :id
+          UNION ALL
+          SELECT {column}, height, tx_index FROM contracts WHERE id = :id
+        )
+        ORDER BY height DESC, tx_index DESC
+        LIMIT 1
+        "#
+    )
+}
+
 pub async fn get_contracts(conn: &Connection) -> Result<Vec<ContractListRow>, Error> {
     let mut rows = conn
         .query(
@@ -436,6 +655,25 @@ pub async fn get_contracts(conn: &Connection) -> Result<Vec<ContractListRow>, Er
     Ok(results)
 }
 
+pub async fn get_contracts_paginated(
+    conn: &Connection,
+    query: ContractQuery,
+) -> Result<(Vec<ContractListRow>, PaginationMeta), Error> {
+    get_paginated(
+        conn,
+        "c",
+        "c.id, c.name, c.height, c.tx_index, c.size",
+        "contracts c",
+        vec![],
+        vec![],
+        query.order,
+        query.cursor,
+        query.offset,
+        query.limit,
+    )
+    .await
+}
+
 pub async fn get_contract_bytes_by_address(
     conn: &Connection,
     address: &ContractAddress,
@@ -514,11 +752,103 @@ pub async fn get_contract_bytes_by_id(
     id: i64,
 ) -> Result<Option<Vec<u8>>, Error> {
     let mut rows = conn
-        .query("SELECT bytes FROM contracts WHERE id = ?", params![id])
+        .query(
+            &active_contract_code_query("bytes"),
+            named_params! { ":id": id },
+        )
         .await?;
     Ok(rows.next().await?.map(|r| r.get(0)).transpose()?)
 }
 
+pub async fn get_contract_hash_by_id(conn: &Connection, id: i64) -> Result<Option<Vec<u8>>, Error> {
+    let mut rows = conn
+        .query(
+            &active_contract_code_query("hash"),
+            named_params! { ":id": id },
+        )
+        .await?;
+    Ok(rows.next().await?.map(|r| r.get(0)).transpose()?)
+}
+
+pub async fn get_contract_deployer_by_id(
+    conn: &Connection,
+    id: i64,
+) -> Result<Option<String>, Error> {
+    let mut rows = conn
+        .query("SELECT deployer FROM contracts WHERE id = ?", params![id])
+        .await?;
+    Ok(rows
+        .next()
+        .await?
+        .map(|r| r.get::<Option<String>>(0))
+        .transpose()?
+        .flatten())
+}
+
+pub async fn insert_contract_deprecation(
+    conn: &Connection,
+    contract_id: i64,
+    height: i64,
+    tx_index: i64,
+) -> Result<(), Error> {
+    conn.execute(
+        "INSERT OR IGNORE INTO contract_deprecations (contract_id, height, tx_index) VALUES (?, ?, ?)",
+        params![contract_id, height, tx_index],
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn is_contract_deprecated(conn: &Connection, contract_id: i64) -> Result<bool, Error> {
+    let mut rows = conn
+        .query(
+            "SELECT 1 FROM contract_deprecations WHERE contract_id = ?",
+            params![contract_id],
+        )
+        .await?;
+    Ok(rows.next().await?.is_some())
+}
+
+pub async fn set_contract_initialized(conn: &Connection, contract_id: i64) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE contracts SET initialized = TRUE WHERE id = ?",
+        params![contract_id],
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn is_contract_initialized(conn: &Connection, contract_id: i64) -> Result<bool, Error> {
+    let mut rows = conn
+        .query(
+            "SELECT 1 FROM contracts WHERE id = ? AND initialized",
+            params![contract_id],
+        )
+        .await?;
+    Ok(rows.next().await?.is_some())
+}
+
+pub async fn get_contracts_by_hash(
+    conn: &Connection,
+    hash: &[u8],
+) -> Result<Vec<ContractListRow>, Error> {
+    let mut rows = conn
+        .query(
+            r#"
+        SELECT id, name, height, tx_index, size FROM contracts
+        WHERE hash = ?
+        ORDER BY height, tx_index, id
+        "#,
+            params![hash.to_vec()],
+        )
+        .await?;
+    let mut results = Vec::new();
+    while let Some(row) = rows.next().await? {
+        results.push(from_row(&row)?);
+    }
+    Ok(results)
+}
+
 pub async fn insert_transaction(conn: &Connection, row: TransactionRow) -> Result<(), Error> {
     conn.execute(
         "INSERT INTO transactions (height, txid, tx_index) VALUES (?, ?, ?)",
@@ -743,6 +1073,7 @@ pub async fn get_results_paginated(
             r.func,
             r.gas,
             r.value,
+            r.error_trace,
             c.name as contract_name,
             c.height as contract_height,
             c.tx_index as contract_tx_index
@@ -810,6 +1141,7 @@ pub async fn get_op_result(
                 r.result_index,
                 r.gas,
                 r.value,
+                r.error_trace,
                 c.name as contract_name,
                 c.height as contract_height,
                 c.tx_index as contract_tx_index
@@ -853,7 +1185,8 @@ pub async fn get_contract_result(
                 op_index,
                 result_index,
                 gas,
-                value
+                value,
+                error_trace
             FROM contract_results
             WHERE height = :height
               AND tx_index = :tx_index
@@ -873,6 +1206,150 @@ pub async fn get_contract_result(
     Ok(rows.next().await?.map(|r| from_row(&r)).transpose()?)
 }
 
+pub async fn get_contract_results_in_range(
+    conn: &Connection,
+    from_height: i64,
+    to_height: i64,
+) -> Result<Vec<ContractResultRow>, Error> {
+    let mut rows = conn
+        .query(
+            r#"
+            SELECT
+                id,
+                contract_id,
+                func,
+                height,
+                tx_index,
+                input_index,
+                op_index,
+                result_index,
+                gas,
+                value,
+                error_trace
+            FROM contract_results
+            WHERE height >= :from_height
+              AND height <= :to_height
+            ORDER BY height, tx_index, input_index, op_index, result_index
+            "#,
+            named_params! {
+                ":from_height": from_height,
+                ":to_height": to_height,
+            },
+        )
+        .await?;
+
+    let mut results = Vec::new();
+    while let Some(row) = rows.next().await? {
+        results.push(from_row(&row)?);
+    }
+    Ok(results)
+}
+
+/// Results of a contract's calls in `from_height..=to_height` that failed
+/// (`failed`) or succeeded, as [`ContractResultRow::failed`] recorded them.
+pub async fn get_contract_results_by_status(
+    conn: &Connection,
+    contract_id: i64,
+    failed: bool,
+    from_height: i64,
+    to_height: i64,
+) -> Result<Vec<ContractResultRow>, Error> {
+    let mut rows = conn
+        .query(
+            r#"
+            SELECT
+                id,
+                contract_id,
+                func,
+                height,
+                tx_index,
+                input_index,
+                op_index,
+                result_index,
+                gas,
+                value,
+                error_trace
+            FROM contract_results
+            WHERE contract_id = :contract_id
+              AND failed = :failed
+              AND height >= :from_height
+              AND height <= :to_height
+            ORDER BY height, tx_index, input_index, op_index, result_index
+            "#,
+            named_params! {
+                ":contract_id": contract_id,
+                ":failed": failed,
+                ":from_height": from_height,
+                ":to_height": to_height,
+            },
+        )
+        .await?;
+
+    let mut results = Vec::new();
+    while let Some(row) = rows.next().await? {
+        results.push(from_row(&row)?);
+    }
+    Ok(results)
+}
+
+pub async fn get_contract_gas_stats(
+    conn: &Connection,
+    contract_id: i64,
+) -> Result<GasStats, Error> {
+    let mut rows = conn
+        .query(
+            r#"
+            SELECT
+                COALESCE(SUM(r.gas), 0) AS total_consumed,
+                COUNT(*) AS call_count
+            FROM contract_results r
+            LEFT JOIN contract_results o
+              ON o.height = r.height
+              AND o.tx_index = r.tx_index
+              AND o.input_index = r.input_index
+              AND o.op_index = r.op_index
+              AND o.result_index > r.result_index
+            WHERE r.contract_id = ?
+              AND o.id IS NULL
+            "#,
+            params![contract_id],
+        )
+        .await?;
+    let row = rows.next().await?.ok_or(Error::InvalidData(
+        "gas stats query returned no row".to_string(),
+    ))?;
+    Ok(from_row(&row)?)
+}
+
+/// Gas consumed by the operations at `height` and how many there were. Only
+/// each operation's own result counts, not those of the calls it made, which
+/// are stored before it with lower result indices.
+pub async fn get_block_gas_usage(conn: &Connection, height: i64) -> Result<(u64, u64), Error> {
+    let mut rows = conn
+        .query(
+            r#"
+            SELECT
+                COALESCE(SUM(r.gas), 0),
+                COUNT(*)
+            FROM contract_results r
+            LEFT JOIN contract_results o
+              ON o.height = r.height
+              AND o.tx_index = r.tx_index
+              AND o.input_index = r.input_index
+              AND o.op_index = r.op_index
+              AND o.result_index > r.result_index
+            WHERE r.height = ?
+              AND o.id IS NULL
+            "#,
+            params![height],
+        )
+        .await?;
+    let row = rows.next().await?.ok_or(Error::InvalidData(
+        "block gas query returned no row".to_string(),
+    ))?;
+    Ok((row.get::<i64>(0)? as u64, row.get::<i64>(1)? as u64))
+}
+
 pub async fn insert_contract_result(
     conn: &Connection,
     row: ContractResultRow,
@@ -889,8 +1366,10 @@ pub async fn insert_contract_result(
                 op_index,
                 result_index,
                 gas,
-                value
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                value,
+                error_trace,
+                failed
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
         params![
             row.contract_id,
@@ -902,7 +1381,9 @@ pub async fn insert_contract_result(
             row.op_index,
             row.result_index,
             row.gas,
-            row.value
+            row.value,
+            row.error_trace,
+            row.failed()
         ],
     )
     .await?;
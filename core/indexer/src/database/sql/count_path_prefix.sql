@@ -0,0 +1,21 @@
+SELECT
+  COUNT(*)
+FROM
+  (
+    SELECT
+      deleted,
+      ROW_NUMBER() OVER (
+        PARTITION BY
+          path
+        ORDER BY
+          height DESC
+      ) AS rank
+    FROM
+      contract_state
+    WHERE
+      contract_id = :contract_id
+      AND substr (path, 1, length (:path) + 1) = :path || '.'
+  ) t
+WHERE
+  rank = 1
+  AND deleted = false;
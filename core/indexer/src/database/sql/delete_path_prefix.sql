@@ -0,0 +1,43 @@
+INSERT OR REPLACE INTO
+  contract_state (
+    contract_id,
+    height,
+    tx_index,
+    size,
+    path,
+    value,
+    deleted
+  )
+SELECT
+  contract_id,
+  :height,
+  :tx_index,
+  size,
+  path,
+  value,
+  true
+FROM
+  (
+    SELECT
+      contract_id,
+      size,
+      path,
+      value,
+      deleted,
+      ROW_NUMBER() OVER (
+        PARTITION BY
+          path
+        ORDER BY
+          height DESC
+      ) AS rank
+    FROM
+      contract_state
+    WHERE
+      contract_id = :contract_id
+      AND substr (path, 1, length (:path) + 1) = :path || '.'
+  ) t
+WHERE
+  rank = 1
+  AND deleted = false
+RETURNING
+  path;
@@ -0,0 +1,28 @@
+SELECT
+  substr(path, length(:path) + 2),
+  value
+FROM
+  (
+    SELECT
+      path,
+      value,
+      deleted,
+      ROW_NUMBER() OVER (
+        PARTITION BY
+          path
+        ORDER BY
+          height DESC
+      ) AS rank
+    FROM
+      contract_state
+    WHERE
+      contract_id = :contract_id
+      AND path > :path || '.'
+      AND path < :path || '/'
+      AND instr(substr(path, length(:path) + 2), '.') = 0
+  ) t
+WHERE
+  rank = 1
+  AND deleted = false
+ORDER BY
+  1;
@@ -1,22 +1,32 @@
-SELECT
-  regexp_capture (path, '^' || :path || '\.([^.]*)(\.|$)', 1)
+SELECT DISTINCT
+  key
 FROM
   (
     SELECT
-      path,
-      height,
-      ROW_NUMBER() OVER (
-        PARTITION BY
-          regexp_capture (path, '^(' || :path || '\.[^.]*)(\.|$)', 1)
-        ORDER BY
-          height DESC
-      ) AS rank
+      regexp_capture (path, '^' || :path || '\.([^.]*)(\.|$)', 1) AS key
     FROM
-      contract_state
+      (
+        SELECT
+          path,
+          deleted,
+          ROW_NUMBER() OVER (
+            PARTITION BY
+              path
+            ORDER BY
+              height DESC
+          ) AS rank
+        FROM
+          contract_state
+        WHERE
+          contract_id = :contract_id
+          AND path LIKE :path || '%'
+      ) t
     WHERE
-      contract_id = :contract_id
-      AND path LIKE :path || '%'
+      rank = 1
       AND deleted = false
-  ) t
+  ) k
 WHERE
-  rank = 1;
+  :after IS NULL
+  OR key > :after
+ORDER BY
+  1;
@@ -1,6 +1,8 @@
 CREATE TABLE IF NOT EXISTS blocks (
   height INTEGER PRIMARY KEY,
   hash TEXT NOT NULL UNIQUE,
+  prev_hash TEXT,
+  time INTEGER NOT NULL DEFAULT 0,
   relevant BOOLEAN NOT NULL,
   processed BOOLEAN NOT NULL DEFAULT 0
 );
@@ -27,10 +29,33 @@ CREATE TABLE IF NOT EXISTS contracts (
   tx_index INTEGER NOT NULL,
   size INTEGER NOT NULL,
   bytes BLOB NOT NULL,
+  hash BLOB NOT NULL,
+  deployer TEXT,
+  initialized BOOLEAN NOT NULL DEFAULT FALSE,
   UNIQUE (name, height, tx_index),
   FOREIGN KEY (height) REFERENCES blocks (height) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS contract_code_versions (
+  contract_id INTEGER NOT NULL,
+  height INTEGER NOT NULL,
+  tx_index INTEGER NOT NULL,
+  size INTEGER NOT NULL,
+  bytes BLOB NOT NULL,
+  hash BLOB NOT NULL,
+  UNIQUE (contract_id, height, tx_index),
+  FOREIGN KEY (contract_id) REFERENCES contracts (id) ON DELETE CASCADE,
+  FOREIGN KEY (height) REFERENCES blocks (height) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS contract_deprecations (
+  contract_id INTEGER PRIMARY KEY,
+  height INTEGER NOT NULL,
+  tx_index INTEGER NOT NULL,
+  FOREIGN KEY (contract_id) REFERENCES contracts (id) ON DELETE CASCADE,
+  FOREIGN KEY (height) REFERENCES blocks (height) ON DELETE CASCADE
+);
+
 CREATE TABLE IF NOT EXISTS contract_state (
   contract_id INTEGER NOT NULL,
   height INTEGER NOT NULL,
@@ -47,6 +72,11 @@ CREATE INDEX IF NOT EXISTS idx_contract_state_lookup ON contract_state (contract
 
 CREATE INDEX IF NOT EXISTS idx_contract_state_contract_tx ON contract_state (contract_id, height DESC, tx_index DESC);
 
+CREATE TABLE IF NOT EXISTS contract_state_versions (
+  contract_id INTEGER PRIMARY KEY,
+  version INTEGER NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS contract_results (
   id INTEGER PRIMARY KEY,
   contract_id INTEGER NOT NULL,
@@ -59,6 +89,8 @@ CREATE TABLE IF NOT EXISTS contract_results (
   gas INTEGER NOT NULL,
   size INTEGER NOT NULL,
   value TEXT,
+  error_trace TEXT,
+  failed BOOLEAN NOT NULL DEFAULT FALSE,
   UNIQUE (
     height,
     tx_index,
@@ -69,6 +101,8 @@ CREATE TABLE IF NOT EXISTS contract_results (
   FOREIGN KEY (height) REFERENCES blocks (height) ON DELETE CASCADE
 );
 
+CREATE INDEX IF NOT EXISTS idx_contract_results_contract_id ON contract_results (contract_id);
+
 CREATE TABLE IF NOT EXISTS file_metadata (
   id INTEGER PRIMARY KEY,
   file_id TEXT NOT NULL UNIQUE,
@@ -0,0 +1,21 @@
+CREATE TRIGGER IF NOT EXISTS trigger_state_version_on_contract_state_insert AFTER INSERT ON contract_state BEGIN
+INSERT INTO
+  contract_state_versions (contract_id, version)
+VALUES
+  (NEW.contract_id, 1)
+ON CONFLICT (contract_id) DO UPDATE
+SET
+  version = version + 1;
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_state_version_on_contract_state_delete AFTER DELETE ON contract_state BEGIN
+INSERT INTO
+  contract_state_versions (contract_id, version)
+VALUES
+  (OLD.contract_id, 1)
+ON CONFLICT (contract_id) DO UPDATE
+SET
+  version = version + 1;
+
+END;
@@ -7,7 +7,7 @@ use kontor_crypto::{FieldElement, FileDescriptor};
 use serde::{Deserialize, Serialize};
 use serde_with::{DefaultOnNull, DisplayFromStr, serde_as};
 
-use crate::runtime::ContractAddress;
+use crate::runtime::{ContractAddress, hash_bytes};
 
 // ─────────────────────────────────────────────────────────────────
 // FieldElement <-> [u8; 32] conversion utilities
@@ -104,6 +104,16 @@ impl HasRowId for TransactionRow {
     }
 }
 
+impl HasRowId for ContractListRow {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn id_name() -> String {
+        "id".to_string()
+    }
+}
+
 impl From<ContractRow> for ContractListRow {
     fn from(row: ContractRow) -> Self {
         ContractListRow {
@@ -124,12 +134,17 @@ pub struct ContractRow {
     pub height: i64,
     pub tx_index: i64,
     pub bytes: Vec<u8>,
+    pub deployer: Option<String>,
 }
 
 impl ContractRow {
     pub fn size(&self) -> u64 {
         self.bytes.len() as u64
     }
+
+    pub fn hash(&self) -> [u8; 32] {
+        hash_bytes(&self.bytes)
+    }
 }
 
 #[serde_as]
@@ -146,6 +161,19 @@ pub struct BlockQuery {
     pub relevant: Option<bool>,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, Builder, Eq, PartialEq)]
+pub struct ContractQuery {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub cursor: Option<i64>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+    #[builder(default)]
+    #[serde_as(as = "DefaultOnNull<DisplayFromStr>")]
+    #[serde(default)]
+    pub order: OrderDirection,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, Builder, Eq, PartialEq)]
 pub struct TransactionQuery {
@@ -200,12 +228,20 @@ pub struct ContractResultRow {
     pub func: String,
     pub gas: i64,
     pub value: Option<String>,
+    /// The calls that led to the failure, if the call failed.
+    pub error_trace: Option<String>,
 }
 
 impl ContractResultRow {
     pub fn size(&self) -> u64 {
         self.value.as_ref().map_or(0, |v| v.len() as u64)
     }
+
+    /// Whether the call trapped, leaving no value, or returned an `err`, the
+    /// same outcomes the runtime rolls back.
+    pub fn failed(&self) -> bool {
+        self.value.as_ref().is_none_or(|v| v.starts_with("err("))
+    }
 }
 
 // provide contract address instead of internal contract id
@@ -225,6 +261,7 @@ pub struct ContractResultPublicRow {
     pub func: String,
     pub gas: i64,
     pub value: Option<String>,
+    pub error_trace: Option<String>,
     pub contract_name: String,
     pub contract_height: i64,
     pub contract_tx_index: i64,
@@ -0,0 +1,22 @@
+//! Conversions between the `u64` heights of blocks and contract addresses and
+//! the `i64` heights the database and `Storage` use.
+//!
+//! Every valid height fits both types. Out of range values mean a bug, so
+//! they fail debug assertions and are clamped to the nearest valid height in
+//! release builds rather than wrapping around.
+
+/// Converts a block height to its stored form.
+pub fn to_i64(height: u64) -> i64 {
+    debug_assert!(
+        height <= i64::MAX as u64,
+        "height {} overflows the stored height",
+        height
+    );
+    i64::try_from(height).unwrap_or(i64::MAX)
+}
+
+/// Converts a stored height back to a block height.
+pub fn to_u64(height: i64) -> u64 {
+    debug_assert!(height >= 0, "stored height {} is negative", height);
+    u64::try_from(height).unwrap_or(0)
+}
@@ -52,6 +52,7 @@ async fn main() -> Result<()> {
     let (event_tx, event_rx) = mpsc::channel(10);
     let event_subscriber = EventSubscriber::new();
     let (simulate_tx, simulate_rx) = mpsc::channel(available_parallelism()?.into());
+    let (pending_view_tx, pending_view_rx) = mpsc::channel(available_parallelism()?.into());
     handles.push(event_subscriber.run(cancel_token.clone(), event_rx));
     handles.push(
         api::run(Env {
@@ -61,8 +62,14 @@ async fn main() -> Result<()> {
             reader: reader.clone(),
             event_subscriber: event_subscriber.clone(),
             bitcoin: bitcoin.clone(),
-            runtime_pool: runtime::pool::new(config.data_dir.clone(), filename.to_string()).await?,
+            runtime_pool: runtime::pool::new(
+                config.data_dir.clone(),
+                filename.to_string(),
+                config.runtime_pool_config(),
+            )
+            .await?,
             simulate_tx,
+            pending_view_tx,
         })
         .await?,
     );
@@ -75,9 +82,12 @@ async fn main() -> Result<()> {
         reader.clone(),
         writer,
         ctrl,
-        Some(init_tx),
-        Some(event_tx),
-        Some(simulate_rx),
+        reactor::ReactorChannels {
+            init_tx: Some(init_tx),
+            event_tx: Some(event_tx),
+            simulate_rx: Some(simulate_rx),
+            pending_rx: config.pending_views.then_some(pending_view_rx),
+        },
     ));
     init_rx.await?;
     let (init_tx, init_rx) = oneshot::channel();
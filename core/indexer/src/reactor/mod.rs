@@ -1,8 +1,11 @@
 pub mod types;
 
+use std::collections::HashMap;
+
 use anyhow::{Result, anyhow, bail};
 use futures_util::future::pending;
-use indexer_types::{Block, BlockRow, Event, Op, OpWithResult, TransactionRow};
+use indexer_types::{Block, BlockRow, Event, Op, OpWithResult, Transaction, TransactionRow};
+use indexmap::IndexMap;
 use tokio::{
     select,
     sync::{
@@ -13,7 +16,7 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
-use bitcoin::BlockHash;
+use bitcoin::{BlockHash, Txid, hashes::Hash};
 use tracing::{debug, error, info, warn};
 
 use crate::{
@@ -21,7 +24,7 @@ use crate::{
         ctrl::CtrlChannel,
         events::{BlockId, Event as FollowerEvent},
     },
-    block::{filter_map, inspect},
+    block::{ops_with_results, sort_ops},
     database::{
         self,
         queries::{
@@ -30,15 +33,62 @@ use crate::{
             set_block_processed,
         },
     },
-    runtime::{ComponentCache, Runtime, Storage},
+    height,
+    runtime::{ComponentCache, ContractAddress, Runtime, Storage, filestorage, wit::Signer},
     test_utils::new_mock_block_hash,
 };
 
-pub type Simulation = (
-    bitcoin::Transaction,
-    oneshot::Sender<Result<Vec<OpWithResult>>>,
+/// Gas available to each block hook invocation.
+pub const BLOCK_HOOK_GAS_LIMIT: u64 = 1_000_000;
+
+/// First block whose end runs filestorage's challenge hooks, so blocks before
+/// it replay as they always have. Not scheduled yet.
+pub const FILESTORAGE_HOOKS_ACTIVATION_HEIGHT: u64 = u64::MAX;
+
+/// A procedure the core calls with the block height at the end of every block
+/// from `activation_height` on, after the block's transactions.
+#[derive(Debug, Clone)]
+pub struct BlockHook {
+    pub contract: ContractAddress,
+    pub func: String,
+    pub activation_height: u64,
+}
+
+/// The hooks the reactor runs, in the order it runs them.
+pub fn native_block_hooks() -> Vec<BlockHook> {
+    ["expire-challenges", "generate-challenges-for-block"]
+        .into_iter()
+        .map(|func| BlockHook {
+            contract: filestorage::address(),
+            func: func.to_string(),
+            activation_height: FILESTORAGE_HOOKS_ACTIVATION_HEIGHT,
+        })
+        .collect()
+}
+
+/// A transaction processed on top of the latest block and rolled back
+/// afterwards, answered with each op's result.
+pub type Simulation = (Transaction, oneshot::Sender<Result<Vec<OpWithResult>>>);
+
+/// A view call, with an optional gas limit, evaluated against pending state:
+/// confirmed blocks plus the reactor's current mempool.
+pub type PendingView = (
+    ContractAddress,
+    String,
+    Option<u64>,
+    oneshot::Sender<Result<String>>,
 );
 
+/// The channels the reactor reports on and answers requests from, besides the
+/// follower's. Each is optional.
+#[derive(Default)]
+pub struct ReactorChannels {
+    pub init_tx: Option<oneshot::Sender<bool>>,
+    pub event_tx: Option<mpsc::Sender<Event>>,
+    pub simulate_rx: Option<Receiver<Simulation>>,
+    pub pending_rx: Option<Receiver<PendingView>>,
+}
+
 struct Reactor {
     reader: database::Reader,
     writer: database::Writer,
@@ -48,33 +98,47 @@ struct Reactor {
     init_tx: Option<oneshot::Sender<bool>>,
     event_tx: Option<mpsc::Sender<Event>>,
     runtime: Runtime,
+    block_hooks: Vec<BlockHook>,
     simulate_rx: Option<Receiver<Simulation>>,
+    pending_rx: Option<Receiver<PendingView>>,
 
+    mempool: IndexMap<Txid, Transaction>,
+    /// Answers to pending views against the current mempool, keyed by
+    /// contract, expression and gas limit. Dropped whenever the mempool or the
+    /// chain changes.
+    pending_results: HashMap<(String, String, Option<u64>), String>,
+    starting_block_height: u64,
     last_height: u64,
     option_last_hash: Option<BlockHash>,
 }
 
+/// Builds a block on top of the latest stored one, for transactions that are
+/// processed speculatively and rolled back afterwards.
+async fn speculative_block(runtime: &Runtime, transactions: Vec<Transaction>) -> Result<Block> {
+    let block_row = select_block_latest(&runtime.storage.conn).await?;
+    let height = block_row
+        .as_ref()
+        .map_or(1, |row| height::to_u64(row.height) + 1);
+    Ok(Block {
+        height,
+        hash: new_mock_block_hash(height as u32),
+        prev_hash: block_row
+            .as_ref()
+            .map_or(new_mock_block_hash(0), |row| row.hash),
+        time: block_row.as_ref().map_or(0, |row| row.time as u64),
+        transactions,
+    })
+}
+
 pub async fn simulate_handler(
     runtime: &mut Runtime,
-    btx: bitcoin::Transaction,
+    block_hooks: &[BlockHook],
+    tx: Transaction,
 ) -> Result<Vec<OpWithResult>> {
-    let tx = filter_map((0, btx.clone())).ok_or(anyhow!("Invalid transaction"))?;
     runtime.storage.savepoint().await?;
-    let block_row = select_block_latest(&runtime.storage.conn).await?;
-    let height = block_row.as_ref().map_or(1, |row| row.height as u64 + 1);
-    block_handler(
-        runtime,
-        &Block {
-            height,
-            hash: new_mock_block_hash(height as u32),
-            prev_hash: block_row
-                .as_ref()
-                .map_or(new_mock_block_hash(0), |row| row.hash),
-            transactions: vec![tx],
-        },
-    )
-    .await?;
-    let result = inspect(&runtime.storage.conn, btx).await;
+    let block = speculative_block(runtime, vec![tx.clone()]).await?;
+    block_handler(runtime, block_hooks, &block).await?;
+    let result = ops_with_results(&runtime.storage.conn, tx).await;
     runtime
         .storage
         .rollback()
@@ -83,31 +147,69 @@ pub async fn simulate_handler(
     result
 }
 
-pub async fn block_handler(runtime: &mut Runtime, block: &Block) -> Result<()> {
-    insert_block(&runtime.storage.conn, block.into()).await?;
+/// Applies `mempool` on top of the confirmed blocks in a savepoint, runs each
+/// view against it and rolls everything back, so the mempool is executed once
+/// for the batch and the write transaction only lives for this one batch.
+pub async fn pending_view_handler(
+    runtime: &mut Runtime,
+    block_hooks: &[BlockHook],
+    mempool: Vec<Transaction>,
+    views: &[(ContractAddress, String, Option<u64>)],
+) -> Result<Vec<Result<String>>> {
+    runtime.storage.savepoint().await?;
+    let result = async {
+        if !mempool.is_empty() {
+            let block = speculative_block(runtime, mempool).await?;
+            block_handler(runtime, block_hooks, &block).await?;
+        }
+        let mut results = Vec::with_capacity(views.len());
+        for (contract, expr, gas_limit) in views {
+            results.push(runtime.execute_view(contract, expr, *gas_limit).await);
+        }
+        Ok(results)
+    }
+    .await;
+    runtime
+        .storage
+        .rollback()
+        .await
+        .expect("Failed to rollback");
+    runtime
+        .file_ledger
+        .force_resync_from_db(&runtime.storage.conn)
+        .await?;
+    result
+}
 
-    // TODO: Challenge generation will be done via contract calls once reactor-to-contract
-    // infrastructure is in place. For now, challenges are managed entirely within
-    // the filestorage contract.
+pub async fn block_handler(
+    runtime: &mut Runtime,
+    block_hooks: &[BlockHook],
+    block: &Block,
+) -> Result<()> {
+    insert_block(&runtime.storage.conn, block.into()).await?;
 
     for t in &block.transactions {
         insert_transaction(
             &runtime.storage.conn,
             TransactionRow::builder()
-                .height(block.height as i64)
+                .height(height::to_i64(block.height))
                 .tx_index(t.index)
                 .txid(t.txid.to_string())
                 .build(),
         )
         .await?;
-        for op in &t.ops {
+        // transactions built elsewhere than `filter_map` may list their ops in
+        // any order, which must not change how they execute
+        let mut ops = t.ops.iter().collect::<Vec<_>>();
+        sort_ops(&mut ops);
+        for op in ops {
             let metadata = op.metadata();
             let input_index = metadata.input_index;
             let op_return_data = t.op_return_data.get(&(input_index as u64)).cloned();
             info!("Op return data: {:#?}", op_return_data);
             runtime
                 .set_context(
-                    block.height as i64,
+                    height::to_i64(block.height),
                     t.index,
                     input_index,
                     0,
@@ -154,11 +256,43 @@ pub async fn block_handler(runtime: &mut Runtime, block: &Block) -> Result<()> {
         }
     }
 
-    set_block_processed(&runtime.storage.conn, block.height as i64).await?;
+    // block hooks run as the ops of a pseudo-transaction following the
+    // block's last one, in the block's context
+    let tx_index = block.transactions.last().map_or(0, |t| t.index + 1);
+    let active_hooks = block_hooks
+        .iter()
+        .filter(|hook| block.height >= hook.activation_height);
+    for (op_index, hook) in active_hooks.enumerate() {
+        runtime
+            .set_context(
+                height::to_i64(block.height),
+                tx_index,
+                0,
+                op_index as i64,
+                Txid::all_zeros(),
+                None,
+                None,
+            )
+            .await;
+        runtime.set_gas_limit(BLOCK_HOOK_GAS_LIMIT);
+        // a failed hook is recorded with its result like any op; the block
+        // still completes so every node stays on the same state
+        if let Err(e) = runtime
+            .execute(
+                Some(&Signer::Core(Box::new(Signer::Nobody))),
+                &hook.contract,
+                &format!("{}({})", hook.func, block.height),
+            )
+            .await
+        {
+            error!(
+                "Block hook {} on {} failed at height {}: {}",
+                hook.func, hook.contract, block.height, e
+            );
+        }
+    }
 
-    // TODO: Challenge expiration will be done via contract calls once reactor-to-contract
-    // infrastructure is in place. For now, challenges are managed entirely within
-    // the filestorage contract via the expire_challenges function.
+    set_block_processed(&runtime.storage.conn, height::to_i64(block.height)).await?;
 
     Ok(())
 }
@@ -170,15 +304,19 @@ impl Reactor {
         writer: database::Writer,
         ctrl: CtrlChannel,
         cancel_token: CancellationToken,
-        init_tx: Option<oneshot::Sender<bool>>,
-        event_tx: Option<mpsc::Sender<Event>>,
-        simulate_rx: Option<Receiver<Simulation>>,
+        channels: ReactorChannels,
     ) -> Result<Self> {
+        let ReactorChannels {
+            init_tx,
+            event_tx,
+            simulate_rx,
+            pending_rx,
+        } = channels;
         let conn = &*reader.connection().await?;
         let (last_height, option_last_hash) = match select_block_latest(conn).await? {
             Some(block) => {
-                let block_height = block.height as u64;
-                if block_height < starting_block_height - 1 {
+                let block_height = height::to_u64(block.height);
+                if block_height < starting_block_height.saturating_sub(1) {
                     bail!(
                         "Latest block has height {}, less than start height {}",
                         block_height,
@@ -197,7 +335,7 @@ impl Reactor {
                     "No previous blocks found, starting from height {}",
                     starting_block_height
                 );
-                (starting_block_height - 1, None)
+                (starting_block_height.saturating_sub(1), None)
             }
         };
 
@@ -233,26 +371,146 @@ impl Reactor {
             ctrl,
             bitcoin_event_rx: None,
             simulate_rx,
+            pending_rx,
+            mempool: IndexMap::new(),
+            pending_results: HashMap::new(),
+            starting_block_height,
             last_height,
             option_last_hash,
             init_tx,
             event_tx,
             runtime,
+            block_hooks: native_block_hooks(),
         })
     }
 
+    /// The mempool as transactions of one block, in arrival order.
+    fn mempool_transactions(&self) -> Vec<Transaction> {
+        self.mempool
+            .values()
+            .enumerate()
+            .map(|(index, tx)| Transaction {
+                index: index as i64,
+                ..tx.clone()
+            })
+            .collect()
+    }
+
+    // The mempool is only kept when pending views are enabled, and any change
+    // to it drops the pending views answered against it.
+
+    fn mempool_set(&mut self, txs: Vec<Transaction>) {
+        info!("MempoolSet {}", txs.len());
+        if self.pending_rx.is_some() {
+            self.mempool = txs.into_iter().map(|tx| (tx.txid, tx)).collect();
+            self.pending_results.clear();
+        }
+    }
+
+    fn mempool_insert(&mut self, txs: Vec<Transaction>) {
+        debug!("MempoolInsert {}", txs.len());
+        if self.pending_rx.is_some() {
+            self.mempool.extend(txs.into_iter().map(|tx| (tx.txid, tx)));
+            self.pending_results.clear();
+        }
+    }
+
+    fn mempool_remove(&mut self, txids: &[Txid]) {
+        debug!("MempoolRemove {}", txids.len());
+        for txid in txids {
+            self.mempool.shift_remove(txid);
+        }
+        self.pending_results.clear();
+    }
+
+    fn mempool_clear(&mut self) {
+        info!("(implicit) MempoolClear");
+        self.mempool.clear();
+        self.pending_results.clear();
+    }
+
+    /// Answers `view` along with every other pending view already queued,
+    /// running the mempool once for those not answered since it last changed.
+    async fn handle_pending_views(&mut self, view: PendingView) {
+        let mut views = vec![view];
+        if let Some(rx) = self.pending_rx.as_mut() {
+            while let Ok(view) = rx.try_recv() {
+                views.push(view);
+            }
+        }
+        let (cached, missed): (Vec<_>, Vec<_>) = views.into_iter().partition(|view| {
+            self.pending_results
+                .contains_key(&(view.0.to_string(), view.1.clone(), view.2))
+        });
+        for (contract, expr, gas_limit, ret_tx) in cached {
+            let key = (contract.to_string(), expr, gas_limit);
+            let _ = ret_tx.send(Ok(self.pending_results[&key].clone()));
+        }
+        if missed.is_empty() {
+            return;
+        }
+
+        let (calls, senders): (Vec<_>, Vec<_>) = missed
+            .into_iter()
+            .map(|(contract, expr, gas_limit, ret_tx)| ((contract, expr, gas_limit), ret_tx))
+            .unzip();
+        let mempool = self.mempool_transactions();
+        match pending_view_handler(&mut self.runtime, &self.block_hooks, mempool, &calls).await {
+            Ok(results) => {
+                for ((call, result), ret_tx) in calls.into_iter().zip(results).zip(senders) {
+                    if let Ok(value) = &result {
+                        self.pending_results
+                            .insert((call.0.to_string(), call.1, call.2), value.clone());
+                    }
+                    let _ = ret_tx.send(result);
+                }
+            }
+            Err(e) => {
+                for ret_tx in senders {
+                    let _ = ret_tx.send(Err(anyhow!("{}", e)));
+                }
+            }
+        }
+    }
+
+    /// The height to roll back to for a bad block at `height` when the last
+    /// `depth` blocks up to it must go, never below the block preceding the
+    /// starting height.
+    fn rollback_height(&self, height: u64, depth: u64) -> u64 {
+        let floor = self.starting_block_height.saturating_sub(1);
+        match height.checked_sub(depth) {
+            Some(target) if target >= floor => target,
+            _ => {
+                warn!(
+                    "Rollback of {} blocks from height {} clamped to height {}",
+                    depth, height, floor
+                );
+                floor
+            }
+        }
+    }
+
     async fn rollback(&mut self, height: u64) -> Result<()> {
         rollback_to_height(&self.writer.connection(), height).await?;
         self.last_height = height;
 
+        // upgrades in the rolled back blocks are gone, and with them the code
+        // the cached components were compiled from
+        self.runtime.component_cache.clear();
+
         // Resync FileLedger after rollback (DB entries deleted via CASCADE)
         self.runtime
             .file_ledger
             .force_resync_from_db(&self.runtime.storage.conn)
             .await?;
 
+        // below the starting height only the native block remains, which is
+        // not part of the chain being followed
         let conn = &self.reader.connection().await?;
-        if let Some(block) = select_block_at_height(conn, height as i64).await? {
+        if height < self.starting_block_height {
+            self.option_last_hash = None;
+            info!("Rollback to height {}, before the starting height", height);
+        } else if let Some(block) = select_block_at_height(conn, height::to_i64(height)).await? {
             self.option_last_hash = Some(block.hash);
             info!("Rollback to height {} ({})", height, block.hash);
         } else {
@@ -289,7 +547,8 @@ impl Reactor {
         let conn = &self.writer.connection();
         let block_row = select_block_with_hash(conn, &hash).await?;
         if let Some(row) = block_row {
-            self.rollback((row.height as u64) - 1).await
+            self.rollback(self.rollback_height(height::to_u64(row.height), 1))
+                .await
         } else {
             error!("attemped rollback to hash {} failed, block not found", hash);
             Ok(())
@@ -308,7 +567,7 @@ impl Reactor {
                 self.last_height + 1,
             );
 
-            self.rollback(height - 1).await?;
+            self.rollback(self.rollback_height(height, 1)).await?;
             return Ok(());
         }
         if height > self.last_height + 1 {
@@ -329,7 +588,7 @@ impl Reactor {
 
                 // roll back 2 steps since we know both the received block and the
                 // last one stored must be bad.
-                self.rollback(height - 2).await?;
+                self.rollback(self.rollback_height(height, 2)).await?;
                 return Ok(());
             }
         } else {
@@ -344,7 +603,7 @@ impl Reactor {
 
         info!("# Block Kontor Transactions: {}", block.transactions.len());
 
-        block_handler(&mut self.runtime, &block).await?;
+        block_handler(&mut self.runtime, &self.block_hooks, &block).await?;
 
         if let Some(tx) = &self.event_tx {
             let _ = tx
@@ -390,6 +649,14 @@ impl Reactor {
                 }
             };
 
+            let pending_rx = async {
+                if let Some(rx) = self.pending_rx.as_mut() {
+                    rx.recv().await
+                } else {
+                    pending().await
+                }
+            };
+
             select! {
                 _ = self.cancel_token.cancelled() => {
                     info!("Cancelled");
@@ -402,26 +669,21 @@ impl Reactor {
                                 FollowerEvent::BlockInsert((target_height, block)) => {
                                     info!("Block {}/{} {}", block.height,
                                           target_height, block.hash);
-                                    debug!("(implicit) MempoolRemove {}", block.transactions.len());
+                                    let txids = block.transactions.iter().map(|tx| tx.txid).collect::<Vec<_>>();
+                                    self.mempool_remove(&txids);
                                     self.handle_block(block).await?;
                                 },
                                 FollowerEvent::BlockRemove(BlockId::Height(height)) => {
-                                    info!("(implicit) MempoolClear");
-                                    self.rollback(height).await?;
+                                    self.mempool_clear();
+                                    self.rollback(self.rollback_height(height, 0)).await?;
                                 },
                                 FollowerEvent::BlockRemove(BlockId::Hash(block_hash)) => {
-                                    info!("(implicit) MempoolClear");
+                                    self.mempool_clear();
                                     self.rollback_hash(block_hash).await?;
                                 },
-                                FollowerEvent::MempoolRemove(removed) => {
-                                    debug!("MempoolRemove {}", removed.len());
-                                },
-                                FollowerEvent::MempoolInsert(added) => {
-                                    debug!("MempoolInsert {}", added.len());
-                                },
-                                FollowerEvent::MempoolSet(txs) => {
-                                    info!("MempoolSet {}", txs.len());
-                                }
+                                FollowerEvent::MempoolSet(txs) => self.mempool_set(txs),
+                                FollowerEvent::MempoolInsert(txs) => self.mempool_insert(txs),
+                                FollowerEvent::MempoolRemove(txids) => self.mempool_remove(&txids),
                             }
                         },
                         None => {
@@ -431,8 +693,13 @@ impl Reactor {
                     }
                 }
                 option_event = simulate_rx => {
-                    if let Some((btx, ret_tx)) = option_event {
-                        let _ = ret_tx.send(simulate_handler(&mut self.runtime, btx).await);
+                    if let Some((tx, ret_tx)) = option_event {
+                        let _ = ret_tx.send(simulate_handler(&mut self.runtime, &self.block_hooks, tx).await);
+                    }
+                }
+                option_view = pending_rx => {
+                    if let Some(view) = option_view {
+                        self.handle_pending_views(view).await;
                     }
                 }
             }
@@ -458,9 +725,7 @@ pub fn run(
     reader: database::Reader,
     writer: database::Writer,
     ctrl: CtrlChannel,
-    init_tx: Option<oneshot::Sender<bool>>,
-    event_tx: Option<mpsc::Sender<Event>>,
-    simulate_rx: Option<Receiver<Simulation>>,
+    channels: ReactorChannels,
 ) -> JoinHandle<()> {
     tokio::spawn({
         async move {
@@ -470,9 +735,7 @@ pub fn run(
                 writer,
                 ctrl.clone(),
                 cancel_token.clone(),
-                init_tx,
-                event_tx,
-                simulate_rx,
+                channels,
             )
             .await
             {
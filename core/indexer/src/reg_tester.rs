@@ -24,8 +24,8 @@ use bitcoin::{
     transaction::Version,
 };
 use indexer_types::{
-    ComposeOutputs, ComposeQuery, Info, Inst, InstructionQuery, OpWithResult, ResultRow,
-    RevealOutputs, RevealQuery, TransactionHex, ViewResult,
+    ComposeOutputs, ComposeQuery, ContractSchema, Info, Inst, InstructionQuery, OpWithResult,
+    ResultRow, RevealOutputs, RevealQuery, TransactionHex, ViewResult,
 };
 use tempfile::TempDir;
 use tokio::{
@@ -482,6 +482,37 @@ impl RegTesterInner {
         Ok(next_funding_utxos)
     }
 
+    /// Mines `n` empty blocks and waits for the indexer to process them. The
+    /// coinbases go to the internal identity, since `identity` later spends
+    /// the coinbase 100 blocks back.
+    pub async fn mine(&mut self, n: u64) -> Result<Vec<BlockHash>> {
+        let block_hashes = self
+            .bitcoin_client
+            .generate_to_address(n, &self.identity.address.to_string())
+            .await?
+            .iter()
+            .map(|hash| BlockHash::from_str(hash))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.height += n as i64;
+
+        let target_height = self.height;
+        let kontor_client = self.kontor_client.clone();
+        retry_simple(async || {
+            let info = kontor_client.index().await?;
+            if info.height >= target_height {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "Indexer at height {}, waiting for {}",
+                    info.height,
+                    target_height
+                ))
+            }
+        })
+        .await?;
+        Ok(block_hashes)
+    }
+
     pub async fn view(&self, contract_address: &ContractAddress, expr: &str) -> Result<String> {
         let result = self.kontor_client.view(contract_address, expr).await?;
         match result {
@@ -495,6 +526,15 @@ impl RegTesterInner {
         Ok(response.wit)
     }
 
+    pub async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema> {
+        self.kontor_client.contract_schema(contract_address).await
+    }
+
+    pub async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool> {
+        let response = self.kontor_client.contract_state(contract_address).await?;
+        Ok(response.has_state)
+    }
+
     pub async fn checkpoint(&mut self) -> Result<Option<String>> {
         self.kontor_client
             .index()
@@ -677,6 +717,10 @@ impl RegTester {
         self.inner.lock().await.fund_address(address, count).await
     }
 
+    pub async fn mine(&mut self, n: u64) -> Result<Vec<BlockHash>> {
+        self.inner.lock().await.mine(n).await
+    }
+
     pub async fn view(&self, contract_address: &ContractAddress, expr: &str) -> Result<String> {
         self.inner.lock().await.view(contract_address, expr).await
     }
@@ -685,10 +729,22 @@ impl RegTester {
         self.inner.lock().await.wit(contract_address).await
     }
 
+    pub async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema> {
+        self.inner.lock().await.schema(contract_address).await
+    }
+
+    pub async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool> {
+        self.inner.lock().await.has_state(contract_address).await
+    }
+
     pub async fn height(&self) -> i64 {
         self.inner.lock().await.height
     }
 
+    pub async fn indexed_height(&self) -> Result<i64> {
+        Ok(self.inner.lock().await.kontor_client.index().await?.height)
+    }
+
     pub async fn checkpoint(&mut self) -> Result<Option<String>> {
         self.inner.lock().await.checkpoint().await
     }
@@ -0,0 +1,155 @@
+use thiserror::Error;
+use wasmtime::component::{Type, Val, wasm_wave};
+
+use crate::runtime::{ExecutionError, parse_call_expr};
+
+#[derive(Debug, Error)]
+pub enum ArgError {
+    #[error("Invalid expression: {0}")]
+    Syntax(String),
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+    #[error("expected {expected} argument(s), got {provided}")]
+    Arity { expected: usize, provided: usize },
+    #[error("arg {index}: expected {expected}, got {provided}")]
+    Type {
+        index: usize,
+        expected: String,
+        provided: String,
+    },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Splits `name(a, b, ...)` into the function name and its top-level
+/// argument sources.
+pub fn split_call(expr: &str) -> Result<(String, Vec<String>), ArgError> {
+    let call = parse_call_expr(expr).map_err(|e| match e {
+        ExecutionError::ParseExpr(message) => ArgError::Syntax(message),
+        e => ArgError::Other(e.into()),
+    })?;
+    let name = call.name().to_string();
+    let inner = expr
+        .find('(')
+        .zip(expr.rfind(')'))
+        .map(|(start, end)| &expr[start + 1..end])
+        .unwrap_or_default();
+
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in chars_outside_literals(inner) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(inner[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        args.push(last.to_string());
+    }
+    Ok((name, args))
+}
+
+/// The characters of `expr` outside its string and char literals, with their
+/// byte offsets. The quotes delimiting a literal are skipped as well.
+pub(crate) fn chars_outside_literals(expr: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    // the quote closing the string or char literal being scanned, if any
+    let mut in_literal = None;
+    let mut escaped = false;
+    expr.char_indices().filter(move |&(_, c)| {
+        if let Some(quote) = in_literal {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == quote => in_literal = None,
+                _ => {}
+            }
+            return false;
+        }
+        if matches!(c, '"' | '\'') {
+            in_literal = Some(c);
+            return false;
+        }
+        true
+    })
+}
+
+/// Checks each argument source against its parameter type, reporting the
+/// first mismatch. Trailing `option` parameters may be omitted.
+pub fn check_args(param_types: &[Type], args: &[String]) -> Result<(), ArgError> {
+    let required = param_types
+        .iter()
+        .rposition(|ty| !matches!(ty, Type::Option(_)))
+        .map_or(0, |i| i + 1);
+    if args.len() < required || args.len() > param_types.len() {
+        return Err(ArgError::Arity {
+            expected: param_types.len(),
+            provided: args.len(),
+        });
+    }
+    for (i, (ty, arg)) in param_types.iter().zip(args).enumerate() {
+        if wasm_wave::from_str::<Val>(ty, arg).is_err() {
+            return Err(ArgError::Type {
+                index: i + 1,
+                expected: type_name(ty),
+                provided: provided_name(arg).to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::S8 => "s8".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::S16 => "s16".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::S32 => "s32".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::S64 => "s64".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::Float32 => "f32".to_string(),
+        Type::Float64 => "f64".to_string(),
+        Type::Char => "char".to_string(),
+        Type::String => "string".to_string(),
+        Type::List(list) => format!("list<{}>", type_name(&list.ty())),
+        Type::Record(record) => format!(
+            "record {{ {} }}",
+            record
+                .fields()
+                .map(|field| field.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Tuple(_) => "tuple".to_string(),
+        Type::Variant(_) => "variant".to_string(),
+        Type::Enum(_) => "enum".to_string(),
+        Type::Option(option) => format!("option<{}>", type_name(&option.ty())),
+        Type::Result(_) => "result".to_string(),
+        Type::Flags(_) => "flags".to_string(),
+        _ => "resource".to_string(),
+    }
+}
+
+fn provided_name(arg: &str) -> &'static str {
+    match arg.chars().next() {
+        Some('"') => "string",
+        Some('\'') => "char",
+        Some('[') => "list",
+        Some('{') => "record",
+        Some('(') => "tuple",
+        Some(c) if c.is_ascii_digit() || c == '-' => "number",
+        _ if arg == "true" || arg == "false" => "bool",
+        _ if arg == "none" || arg.starts_with("some(") => "option",
+        _ if arg.starts_with("ok(") || arg.starts_with("err(") => "result",
+        _ => "case label",
+    }
+}
@@ -1,11 +1,28 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
 use moka::future::Cache;
 use wasmtime::component::Component;
 
+use super::signatures::FuncSig;
+
 const COMPONENT_CACHE_CAPACITY: u64 = 64;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComponentCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
 #[derive(Clone)]
 pub struct ComponentCache {
     inner: Cache<i64, Component>,
+    signatures: Cache<i64, Vec<FuncSig>>,
+    modules: Cache<i64, Arc<Vec<u8>>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
 }
 
 impl ComponentCache {
@@ -14,14 +31,69 @@ impl ComponentCache {
             inner: Cache::builder()
                 .max_capacity(COMPONENT_CACHE_CAPACITY)
                 .build(),
+            signatures: Cache::builder()
+                .max_capacity(COMPONENT_CACHE_CAPACITY)
+                .build(),
+            modules: Cache::builder()
+                .max_capacity(COMPONENT_CACHE_CAPACITY)
+                .build(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
         }
     }
 
     pub async fn get(&self, key: &i64) -> Option<Component> {
-        self.inner.get(key).await
+        let component = self.inner.get(key).await;
+        if component.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        component
     }
 
     pub async fn put(&self, key: i64, value: Component) {
         self.inner.insert(key, value).await
     }
+
+    /// Drops the compiled component, signatures and module bytes of a
+    /// contract whose code changed.
+    pub async fn invalidate(&self, key: &i64) {
+        self.inner.invalidate(key).await;
+        self.signatures.invalidate(key).await;
+        self.modules.invalidate(key).await;
+    }
+
+    /// Drops everything, for when code may have changed under any contract,
+    /// as after a rollback.
+    pub fn clear(&self) {
+        self.inner.invalidate_all();
+        self.signatures.invalidate_all();
+        self.modules.invalidate_all();
+    }
+
+    pub async fn get_signatures(&self, key: &i64) -> Option<Vec<FuncSig>> {
+        self.signatures.get(key).await
+    }
+
+    pub async fn put_signatures(&self, key: i64, value: Vec<FuncSig>) {
+        self.signatures.insert(key, value).await
+    }
+
+    /// Decompressed module bytes, so recompiling an evicted component skips
+    /// decompression.
+    pub async fn get_module(&self, key: &i64) -> Option<Arc<Vec<u8>>> {
+        self.modules.get(key).await
+    }
+
+    pub async fn put_module(&self, key: i64, value: Arc<Vec<u8>>) {
+        self.modules.insert(key, value).await
+    }
+
+    pub fn stats(&self) -> ComponentCacheStats {
+        ComponentCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
 }
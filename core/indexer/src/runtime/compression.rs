@@ -0,0 +1,62 @@
+use std::io::{Read, Write};
+
+const BUFFER_SIZE: usize = 4096;
+const LG_WINDOW_SIZE: u32 = 22;
+
+/// Brotli settings for contract bytes, which are stored and published as
+/// `.wasm.br`. Decompression doesn't depend on the level, so bytes produced at
+/// any level can be read back by any `Compression`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compression {
+    level: u32,
+}
+
+impl Compression {
+    /// Brotli's highest quality, matching the `brotli -Z` used by the
+    /// contract build scripts.
+    pub const MAX_LEVEL: u32 = 11;
+
+    /// Levels above [`Compression::MAX_LEVEL`] are clamped to it.
+    pub fn new(level: u32) -> Self {
+        Self {
+            level: level.min(Self::MAX_LEVEL),
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn compress(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(
+                &mut compressed,
+                BUFFER_SIZE,
+                self.level,
+                LG_WINDOW_SIZE,
+            );
+            writer
+                .write_all(bytes)
+                .expect("Writing to a Vec cannot fail");
+        }
+        compressed
+    }
+
+    pub fn decompress(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        self.decompressor(bytes).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    /// Streaming decompression, for callers that want to stop early.
+    pub fn decompressor<'a>(&self, bytes: &'a [u8]) -> impl Read + 'a {
+        brotli::Decompressor::new(bytes, BUFFER_SIZE)
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::new(Self::MAX_LEVEL)
+    }
+}
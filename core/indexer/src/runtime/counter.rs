@@ -22,8 +22,12 @@ impl Counter {
     }
 
     pub async fn increment(&self) -> u64 {
+        self.add(1).await
+    }
+
+    pub async fn add(&self, n: u64) -> u64 {
         let mut value = self.value.lock().await;
-        *value += 1;
+        *value += n;
         *value
     }
 
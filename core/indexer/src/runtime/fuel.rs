@@ -18,18 +18,25 @@ use crate::runtime::Runtime;
 pub enum Fuel {
     SignerToString,
     KeysNext(u64),
+    KeysTruncated,
+    EntriesNext(u64),
     Path(String),
     ExtendPathWithMatch(u64),
     GetKeys,
+    GetEntries,
     Exists,
     Get(usize),
     Set(u64),
     DeleteMatchingPaths(u64),
+    DeletePrefix(u64),
     ContractAddress,
+    ForeignViewStorage,
     ProcSigner,
     ProcContractSigner,
     ProcViewContext,
     ProcTransaction,
+    ProcPrevBlockHash,
+    ProcDeployer,
     ProcStorage,
     ViewStorage,
     FallSigner,
@@ -37,7 +44,10 @@ pub enum Fuel {
     FallViewContext,
     CoreProcContext,
     CryptoHash(u64),
+    CryptoHashMany(u64, u64),
+    CryptoCtEq(u64),
     CryptoGenerateId,
+    CryptoGenerateIdWith(u64),
     AddFile,
     GetFileId,
     GetFileDescriptor,
@@ -59,6 +69,8 @@ pub enum Fuel {
     NumbersSqrtInteger,
     NumbersIntegerToDecimal,
     NumbersDecimalToInteger,
+    NumbersDecimalToScaledInteger,
+    NumbersScaledIntegerToDecimal,
     NumbersU64ToDecimal,
     NumbersS64ToDecimal,
     NumbersF64ToDecimal,
@@ -74,63 +86,85 @@ pub enum Fuel {
     Result(u64),
 }
 
+/// Fuel charged for a single host operation. This is the only place costs are
+/// defined: `consume` charges exactly `fuel_cost(op)`, so tooling can sum these
+/// values to budget a sequence of host calls.
+pub fn fuel_cost(op: &Fuel) -> u64 {
+    match op {
+        Fuel::SignerToString => 50,
+        Fuel::KeysNext(key_len) => 100 + 10 * key_len,
+        Fuel::KeysTruncated => 100,
+        // a key and its value, priced as `KeysNext` and `Get` together
+        Fuel::EntriesNext(entry_len) => 100 + 10 * entry_len,
+        Fuel::Path(path) => 10 * DotPathBuf::from_str(path).unwrap().num_segments(),
+        Fuel::Get(value_len) => 10 * *value_len as u64,
+        Fuel::GetKeys | Fuel::GetEntries => 200,
+        Fuel::Exists => 50,
+        Fuel::ExtendPathWithMatch(regexp_len) => 500 + 10 * regexp_len,
+        Fuel::Set(value_len) | Fuel::Result(value_len) => 200 + 10 * value_len,
+        Fuel::DeleteMatchingPaths(regexp_len) => 1000 + 10 * regexp_len,
+        // a tombstone per path, priced like an empty `Set`
+        Fuel::DeletePrefix(paths) => 200 * paths,
+        Fuel::ContractAddress | Fuel::ProcDeployer => 100,
+        Fuel::ForeignViewStorage => 500,
+        Fuel::ProcSigner
+        | Fuel::ProcContractSigner
+        | Fuel::ProcTransaction
+        | Fuel::ProcPrevBlockHash => 500,
+        Fuel::ProcViewContext => 200,
+        Fuel::ProcStorage => 200,
+        Fuel::ViewStorage => 200,
+        Fuel::FallSigner
+        | Fuel::FallProcContext
+        | Fuel::FallViewContext
+        | Fuel::CoreProcContext => 100,
+        Fuel::CryptoHash(input_len) => 500 + 10 * input_len,
+        // one `CryptoHash` per digest
+        Fuel::CryptoHashMany(count, input_len) => 500 * count + 10 * input_len,
+        Fuel::CryptoCtEq(input_len) => 100 + 10 * input_len,
+        Fuel::CryptoGenerateId => 500,
+        Fuel::CryptoGenerateIdWith(domain_len) => 500 + 10 * domain_len,
+        Fuel::AddFile => 500,
+        Fuel::GetFileId => 100,
+        Fuel::GetFileDescriptor => 200,
+        Fuel::FromRawFileDescriptor => 500,
+        Fuel::ComputeChallengeId => 500,
+        Fuel::ProofFromBytes(bytes_len) => 1000 + 10 * bytes_len,
+        Fuel::ProofChallengeIds => 100,
+        Fuel::ProofVerify => 50_000,
+        Fuel::NumbersU64ToInteger
+        | Fuel::NumbersS64ToInteger
+        | Fuel::NumbersIntegerToDecimal
+        | Fuel::NumbersDecimalToInteger
+        | Fuel::NumbersDecimalToScaledInteger
+        | Fuel::NumbersScaledIntegerToDecimal
+        | Fuel::NumbersU64ToDecimal
+        | Fuel::NumbersS64ToDecimal
+        | Fuel::NumbersF64ToDecimal => 50,
+        Fuel::NumbersStringToInteger(s_len) | Fuel::NumbersStringToDecimal(s_len) => {
+            100 + 10 * s_len
+        }
+        Fuel::NumbersIntegerToString(output_len) | Fuel::NumbersDecimalToString(output_len) => {
+            100 + 10 * output_len
+        }
+        Fuel::NumbersEqInteger | Fuel::NumbersEqDecimal => 50,
+        Fuel::NumbersCmpInteger | Fuel::NumbersCmpDecimal => 75,
+        Fuel::NumbersAddInteger
+        | Fuel::NumbersSubInteger
+        | Fuel::NumbersMulInteger
+        | Fuel::NumbersDivInteger
+        | Fuel::NumbersAddDecimal
+        | Fuel::NumbersSubDecimal
+        | Fuel::NumbersMulDecimal
+        | Fuel::NumbersDivDecimal => 100,
+        Fuel::NumbersSqrtInteger => 500,
+        Fuel::NumbersLog10Decimal => 500,
+    }
+}
+
 impl Fuel {
     pub fn cost(&self) -> u64 {
-        match self {
-            Self::SignerToString => 50,
-            Self::KeysNext(key_len) => 100 + 10 * key_len,
-            Self::Path(path) => 10 * DotPathBuf::from_str(path).unwrap().num_segments(),
-            Self::Get(value_len) => 10 * *value_len as u64,
-            Self::GetKeys => 200,
-            Self::Exists => 50,
-            Self::ExtendPathWithMatch(regexp_len) => 500 + 10 * regexp_len,
-            Self::Set(value_len) | Self::Result(value_len) => 200 + 10 * value_len,
-            Self::DeleteMatchingPaths(regexp_len) => 1000 + 10 * regexp_len,
-            Self::ContractAddress => 100,
-            Self::ProcSigner | Self::ProcContractSigner | Self::ProcTransaction => 500,
-            Self::ProcViewContext => 200,
-            Self::ProcStorage => 200,
-            Self::ViewStorage => 200,
-            Self::FallSigner
-            | Self::FallProcContext
-            | Self::FallViewContext
-            | Self::CoreProcContext => 100,
-            Self::CryptoHash(input_len) => 500 + 10 * input_len,
-            Self::CryptoGenerateId => 500,
-            Self::AddFile => 500,
-            Self::GetFileId => 100,
-            Self::GetFileDescriptor => 200,
-            Self::FromRawFileDescriptor => 500,
-            Self::ComputeChallengeId => 500,
-            Self::ProofFromBytes(bytes_len) => 1000 + 10 * bytes_len,
-            Self::ProofChallengeIds => 100,
-            Self::ProofVerify => 50_000,
-            Self::NumbersU64ToInteger
-            | Self::NumbersS64ToInteger
-            | Self::NumbersIntegerToDecimal
-            | Self::NumbersDecimalToInteger
-            | Self::NumbersU64ToDecimal
-            | Self::NumbersS64ToDecimal
-            | Self::NumbersF64ToDecimal => 50,
-            Self::NumbersStringToInteger(s_len) | Self::NumbersStringToDecimal(s_len) => {
-                100 + 10 * s_len
-            }
-            Self::NumbersIntegerToString(output_len) | Self::NumbersDecimalToString(output_len) => {
-                100 + 10 * output_len
-            }
-            Self::NumbersEqInteger | Self::NumbersEqDecimal => 50,
-            Self::NumbersCmpInteger | Self::NumbersCmpDecimal => 75,
-            Self::NumbersAddInteger
-            | Self::NumbersSubInteger
-            | Self::NumbersMulInteger
-            | Self::NumbersDivInteger
-            | Self::NumbersAddDecimal
-            | Self::NumbersSubDecimal
-            | Self::NumbersMulDecimal
-            | Self::NumbersDivDecimal => 100,
-            Self::NumbersSqrtInteger => 500,
-            Self::NumbersLog10Decimal => 500,
-        }
+        fuel_cost(self)
     }
 
     pub async fn consume<T, R: HasData>(
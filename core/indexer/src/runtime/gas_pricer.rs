@@ -0,0 +1,36 @@
+use super::Decimal;
+
+/// The chain data a gas price may depend on: the height being priced and how
+/// full the block before it was.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockUsage {
+    pub height: i64,
+    /// Gas consumed by the operations of the previous block.
+    pub prev_gas: u64,
+    /// Number of operations in the previous block.
+    pub prev_ops: u64,
+}
+
+/// Prices gas in native token, both for the escrow held before a procedure
+/// runs and for the amount burned once its gas use is known. The price may
+/// only depend on chain data such as [`BlockUsage`], never on wall-clock time
+/// or local state, so that every indexer escrows the same amounts.
+pub trait GasPricer: Send + Sync {
+    fn token_per_gas(&self, usage: &BlockUsage) -> Decimal;
+}
+
+/// The same price at every height.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedGasPricer(pub Decimal);
+
+impl Default for FixedGasPricer {
+    fn default() -> Self {
+        Self(Decimal::from("1e-9"))
+    }
+}
+
+impl GasPricer for FixedGasPricer {
+    fn token_per_gas(&self, _usage: &BlockUsage) -> Decimal {
+        self.0
+    }
+}
@@ -1,37 +1,59 @@
 extern crate alloc;
 
+mod args;
 mod component_cache;
+mod compression;
 pub mod counter;
 pub mod file_ledger;
 pub mod filestorage;
 pub mod fuel;
+mod gas_pricer;
 pub mod numerics;
 pub mod pool;
+mod signatures;
 mod stack;
 mod storage;
+mod storage_backend;
 pub mod token;
 mod types;
+mod view_cache;
 pub mod wit;
 
+pub use args::{ArgError, split_call};
 use bitcoin::{Txid, hashes::Hash};
-pub use component_cache::ComponentCache;
+pub use component_cache::{ComponentCache, ComponentCacheStats};
+pub use compression::Compression;
 pub use file_ledger::FileLedger;
-use futures_util::{StreamExt, future::OptionFuture};
+use futures_util::{TryStreamExt, future::OptionFuture};
+pub use gas_pricer::{BlockUsage, FixedGasPricer, GasPricer};
 use hkdf::Hkdf;
 use libsql::Connection;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+pub use signatures::{FuncKind, FuncSig};
+pub use stack::CallTrace;
 pub use stdlib::{
     CheckedArithmetics, FromWaveValue, WaveType, from_wave_expr, from_wave_value, to_wave_expr,
     wave_type,
 };
 use stdlib::{contract_address, impls};
-pub use storage::Storage;
+pub use storage::{
+    DEFAULT_MAX_VALUE_SIZE, MAX_STORAGE_WRITES_PER_TX, STORAGE_WRITE_LIMIT_ACTIVATION_HEIGHT,
+    Storage, WitError,
+};
+pub use storage_backend::{EntryStream, KeyStream, MemoryStorage, StorageBackend};
 use tokio::sync::Mutex;
+use tracing::Instrument;
 pub use types::default_val_for_type;
+pub use view_cache::ViewCache;
 pub use wit::Root;
+use wit_component::DecodedWasm;
+use wit_parser::Resolve;
+use wit_validator::{ErrorCode, Validator};
 
-use std::sync::Arc;
+#[cfg(any(test, feature = "testlib"))]
+use std::collections::HashMap;
+use std::{io::Read, sync::Arc};
 
 use wit::kontor::*;
 
@@ -40,7 +62,7 @@ pub use wit::kontor::built_in::error::Error;
 pub use wit::kontor::built_in::file_registry::{ChallengeInput, RawFileDescriptor, VerifyResult};
 pub use wit::kontor::built_in::foreign::ContractAddress;
 pub use wit::kontor::built_in::numbers::{
-    Decimal, Integer, Ordering as NumericOrdering, Sign as NumericSign,
+    Decimal, Integer, Ordering as NumericOrdering, RoundingMode, ScaledInteger, Sign as NumericSign,
 };
 
 use anyhow::{Result, anyhow};
@@ -49,23 +71,27 @@ use wasmtime::{
     AsContext, AsContextMut, Engine, Store,
     component::{
         Accessor, Component, Func, HasData, Linker, Resource, ResourceTable, Val,
+        types::ComponentItem,
         wasm_wave::{
-            parser::Parser as WaveParser, to_string as to_wave_string, value::Value as WaveValue,
+            parser::Parser as WaveParser, to_string as to_wave_string, untyped::UntypedFuncCall,
+            value::Value as WaveValue,
         },
     },
 };
 
 use crate::database::native_contracts::{FILESTORAGE, FILESTORAGEMOCK, TOKEN};
-use crate::runtime::kontor::built_in::context::{OpReturnData, OutPoint};
+use crate::runtime::kontor::built_in::context::{EntryKind, EntryValue, OpReturnData, OutPoint};
 use crate::runtime::wit::{CoreContext, FileDescriptor, Transaction};
 use crate::{
+    height,
     runtime::{
         counter::Counter,
         fuel::{Fuel, FuelGauge},
         stack::Stack,
+        storage::encode_component,
         wit::{
-            FallContext, HasContractId, Keys, ProcContext, ProcStorage, Signer, ViewContext,
-            ViewStorage,
+            Entries, FallContext, HasContractId, Keys, ProcContext, ProcStorage, Signer,
+            ViewContext, ViewStorage,
         },
     },
     test_utils::new_mock_transaction,
@@ -80,6 +106,133 @@ pub fn hash_bytes(bytes: &[u8]) -> [u8; 32] {
     result.into()
 }
 
+/// The sha256 of each input, in order, as returned by `hash-many`.
+pub fn hash_many(inputs: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    inputs
+        .iter()
+        .map(|input| hash_bytes(input).to_vec())
+        .collect()
+}
+
+/// RFC 6962 Merkle tree hash: leaves are hashed as `sha256(0x00 || leaf)` and
+/// nodes as `sha256(0x01 || left || right)`, with an odd node carried up a
+/// level unchanged. No leaves gives `sha256("")`.
+pub fn merkle_root(leaves: &[Vec<u8>]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return hash_bytes(&[]);
+    }
+    let mut level = leaves
+        .iter()
+        .map(|leaf| hash_bytes(&[&[0u8][..], leaf].concat()))
+        .collect::<Vec<_>>();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_bytes(&[&[1u8][..], left, right].concat()),
+                [odd] => *odd,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// Compares `a` and `b` without short-circuiting: every byte up to the longer
+/// length is visited, so the time taken reveals only the lengths.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or_default();
+        let y = b.get(i).copied().unwrap_or_default();
+        diff |= usize::from(x ^ y);
+    }
+    std::hint::black_box(diff) == 0
+}
+
+/// Derives the id handed out by `generate-id`/`generate-id-with`: the first 8
+/// bytes of `sha256(txid || count || domain)`, hex encoded. An empty domain
+/// yields the same id as plain `generate-id`.
+pub fn derive_id(txid: &Txid, count: u64, domain: &str) -> String {
+    hex::encode(
+        &hash_bytes(
+            &[
+                txid.to_raw_hash().to_byte_array().to_vec(),
+                count.to_le_bytes().to_vec(),
+                domain.as_bytes().to_vec(),
+            ]
+            .concat(),
+        )[0..8],
+    )
+}
+
+/// How a call ended, as recorded in the `result_kind` field of the `execute`
+/// span: `trap` when the guest trapped, `err` when it returned an `err` result,
+/// `ok` otherwise. A fallback only hands back the WAVE text of the result it
+/// proxied, so that text is all there is to go on.
+fn result_kind(is_fallback: bool, result: &Result<()>, results: &[Val]) -> &'static str {
+    match (result, results.first()) {
+        (Err(_), _) => "trap",
+        (Ok(()), Some(Val::Result(Err(_)))) => "err",
+        (Ok(()), Some(Val::String(expr))) if is_fallback && expr.starts_with("err(") => "err",
+        _ => "ok",
+    }
+}
+
+/// Deepest nesting of lists, tuples, records and other compound values a call
+/// expression may have. The WAVE parser recurses once per level, so deeper
+/// input would overflow the stack rather than fail to parse.
+pub const MAX_EXPR_DEPTH: usize = 64;
+
+/// Path under which a contract's root `init` marks each `#[public]` field,
+/// e.g. `__public.ledger`.
+pub const PUBLIC_FIELDS_PATH: &str = "__public";
+
+/// Parses a call expression, reporting malformed input, including input
+/// nested deeper than [`MAX_EXPR_DEPTH`], as `ExecutionError::ParseExpr`.
+pub fn parse_call_expr(expr: &str) -> Result<UntypedFuncCall<'_>, ExecutionError> {
+    if nesting_depth(expr) > MAX_EXPR_DEPTH {
+        return Err(ExecutionError::ParseExpr(format!(
+            "expression nested deeper than {} levels",
+            MAX_EXPR_DEPTH
+        )));
+    }
+    WaveParser::new(expr)
+        .parse_raw_func_call()
+        .map_err(|e| ExecutionError::ParseExpr(e.to_string()))
+}
+
+/// Deepest bracket nesting in `expr`, ignoring brackets inside string and
+/// char literals.
+fn nesting_depth(expr: &str) -> usize {
+    let mut depth = 0usize;
+    let mut max_depth = 0;
+    for (_, c) in args::chars_outside_literals(expr) {
+        match c {
+            '(' | '[' | '{' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max_depth
+}
+
+/// Guards against signer impersonation across nested calls. A contract can
+/// only sign as itself, so a `Signer::ContractId` is accepted only when it
+/// names `caller`, the contract on top of the stack making the call. Any
+/// other contract id, including one presented with an empty stack, is forged.
+pub fn verify_contract_id_signer(signer: Option<&Signer>, caller: Option<i64>) -> Result<()> {
+    if let Some(Signer::ContractId { id, .. }) = signer
+        && caller != Some(*id)
+    {
+        return Err(anyhow!("Invalid contract id signer"));
+    }
+    Ok(())
+}
+
 impl PartialEq for RawFileDescriptor {
     fn eq(&self, other: &Self) -> bool {
         self.file_id == other.file_id
@@ -94,25 +247,184 @@ impl PartialEq for RawFileDescriptor {
 
 impl Eq for RawFileDescriptor {}
 
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExecutionError {
+    #[error("{0}")]
+    Trap(String),
+    #[error("Contract {0} is deprecated")]
+    Deprecated(ContractAddress),
+    #[error("Contract {0} is already initialized")]
+    AlreadyInitialized(ContractAddress),
+    #[error("Invalid expression: {0}")]
+    ParseExpr(String),
+    #[error("Contract exceeds the size limit of {0} bytes")]
+    ContractTooLarge(usize),
+    #[error("Contract exceeds the limit of {0} exported functions")]
+    TooManyExports(usize),
+    #[error("Invalid contract: {0}")]
+    InvalidContract(String),
+    #[error("Contract has test-only exports: {0}")]
+    TestOnlyExport(String),
+}
+
+impl ExecutionError {
+    fn from_trap(e: &anyhow::Error) -> Self {
+        match e.root_cause().downcast_ref::<ExecutionError>() {
+            Some(err) => err.clone(),
+            None => ExecutionError::Trap(e.root_cause().to_string()),
+        }
+    }
+}
+
+/// What [`Runtime::check_publish`] found wrong with a contract, if anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishReport {
+    /// WIT rule violations, each naming where in the WIT it occurred.
+    pub validation_errors: Vec<String>,
+    /// WIT findings that don't fail validation.
+    pub validation_warnings: Vec<String>,
+    /// Why `init` failed, if it did.
+    pub init_error: Option<String>,
+}
+
+impl PublishReport {
+    /// Whether the contract would publish and initialize cleanly.
+    pub fn is_ok(&self) -> bool {
+        self.validation_errors.is_empty() && self.init_error.is_none()
+    }
+}
+
+/// Largest contract, compressed or not, accepted by default.
+pub const DEFAULT_MAX_CONTRACT_SIZE: usize = 4 * 1024 * 1024;
+
+/// First block whose publishes and upgrades are held to the contract limits,
+/// so blocks before it replay as they always have. Not scheduled yet.
+pub const CONTRACT_LIMITS_ACTIVATION_HEIGHT: i64 = i64::MAX;
+
+/// First block whose calls to `init` on an initialized contract are rejected,
+/// so blocks before it replay as they always have. Not scheduled yet.
+pub const INIT_GUARD_ACTIVATION_HEIGHT: i64 = i64::MAX;
+
+/// Checks a brotli compressed module against the contract limits without
+/// compiling it. Decompression stops as soon as the size limit is passed.
+fn check_contract_limits(
+    bytes: &[u8],
+    max_size: Option<usize>,
+    max_exports: Option<usize>,
+) -> Result<(), ExecutionError> {
+    if let Some(max) = max_size
+        && bytes.len() > max
+    {
+        return Err(ExecutionError::ContractTooLarge(max));
+    }
+    if max_size.is_none() && max_exports.is_none() {
+        return Ok(());
+    }
+
+    let mut module_bytes = Vec::new();
+    Compression::default()
+        .decompressor(bytes)
+        .take(max_size.map_or(u64::MAX, |max| max as u64 + 1))
+        .read_to_end(&mut module_bytes)
+        .map_err(|e| ExecutionError::InvalidContract(e.to_string()))?;
+    if let Some(max) = max_size
+        && module_bytes.len() > max
+    {
+        return Err(ExecutionError::ContractTooLarge(max));
+    }
+
+    if let Some(max) = max_exports {
+        let component_bytes = encode_component(&module_bytes)
+            .map_err(|e| ExecutionError::InvalidContract(e.to_string()))?;
+        if count_component_exports(&component_bytes)? > max {
+            return Err(ExecutionError::TooManyExports(max));
+        }
+    }
+    Ok(())
+}
+
+/// Counts the functions and instances exported by the component itself,
+/// leaving out exports of the core modules and components nested in it.
+fn count_component_exports(component_bytes: &[u8]) -> Result<usize, ExecutionError> {
+    let mut depth = 0usize;
+    let mut count = 0;
+    for payload in wasmparser::Parser::new(0).parse_all(component_bytes) {
+        match payload.map_err(|e| ExecutionError::InvalidContract(e.to_string()))? {
+            wasmparser::Payload::ModuleSection { .. }
+            | wasmparser::Payload::ComponentSection { .. } => depth += 1,
+            wasmparser::Payload::End(_) => depth = depth.saturating_sub(1),
+            wasmparser::Payload::ComponentExportSection(reader) if depth == 0 => {
+                for export in reader {
+                    let export =
+                        export.map_err(|e| ExecutionError::InvalidContract(e.to_string()))?;
+                    if matches!(
+                        export.kind,
+                        wasmparser::ComponentExternalKind::Func
+                            | wasmparser::ComponentExternalKind::Instance
+                    ) {
+                        count += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(count)
+}
+
+/// The WIT a component was built from.
+fn decode_resolve(component_bytes: &[u8]) -> Result<Resolve> {
+    Ok(match wit_component::decode(component_bytes)? {
+        DecodedWasm::WitPackage(resolve, _) | DecodedWasm::Component(resolve, _) => resolve,
+    })
+}
+
+/// Produces the wave result of a call made to a mocked contract.
+pub type ContractMock = Arc<dyn Fn(&str) -> Result<String> + Send + Sync>;
+
 #[derive(Clone)]
 pub struct Runtime {
     pub engine: Engine,
     pub linker: Linker<Self>,
     pub table: Arc<Mutex<ResourceTable>>,
     pub component_cache: ComponentCache,
+    pub view_cache: Option<ViewCache>,
     pub storage: Storage,
+    /// Where contract state lives instead of `storage`, if set.
+    pub state_backend: Option<Arc<dyn StorageBackend>>,
     pub file_ledger: FileLedger,
     pub id_generation_counter: Counter,
     pub result_id_counter: Counter,
     pub stack: Stack<i64>,
+    /// Contract id and `contract.function` label of every call on `stack`.
+    pub call_frames: Stack<(i64, String)>,
+    /// Where the last failed execution failed, if it did.
+    error_trace: Arc<Mutex<Option<CallTrace>>>,
     pub gauge: Option<FuelGauge>,
     pub gas_limit: Option<u64>,
     pub gas_limit_for_non_procs: u64,
+    pub nested_call_budget_percent: Option<u64>,
+    /// Publishes and upgrades with larger code are rejected before compiling.
+    pub max_contract_size: Option<usize>,
+    /// Publishes and upgrades exporting more functions are rejected before
+    /// compiling.
+    pub max_contract_exports: Option<usize>,
+    /// Height from which `max_contract_size` and `max_contract_exports` apply.
+    pub contract_limits_activation_height: i64,
+    /// Height from which `init` can't be called again once it has succeeded.
+    pub init_guard_activation_height: i64,
+    pub allow_deprecated_views: bool,
+    /// Lets contracts built with their `test-only` exports be published. Only
+    /// local test runtimes set this; the reactor always rejects them.
+    pub allow_test_only_exports: bool,
     pub gas_to_fuel_multiplier: u64,
-    pub gas_to_token_multiplier: Decimal,
+    pub gas_pricer: Arc<dyn GasPricer>,
     pub txid: Option<Txid>,
     pub previous_output: Option<bitcoin::OutPoint>,
     pub op_return_data: Option<OpReturnData>,
+    /// Test stand-ins answering calls to contracts, keyed by address.
+    #[cfg(any(test, feature = "testlib"))]
+    pub mocks: HashMap<String, ContractMock>,
 }
 
 impl Runtime {
@@ -152,19 +464,32 @@ impl Runtime {
             linker,
             table: Arc::new(Mutex::new(ResourceTable::new())),
             component_cache,
+            view_cache: None,
             storage,
+            state_backend: None,
             file_ledger,
             id_generation_counter: Counter::new(),
             result_id_counter: Counter::new(),
             stack: Stack::new(),
+            call_frames: Stack::new(),
+            error_trace: Arc::new(Mutex::new(None)),
             gauge: Some(FuelGauge::new()),
             gas_limit: None,
             gas_limit_for_non_procs: 100_000,
+            nested_call_budget_percent: None,
+            max_contract_size: Some(DEFAULT_MAX_CONTRACT_SIZE),
+            max_contract_exports: None,
+            contract_limits_activation_height: CONTRACT_LIMITS_ACTIVATION_HEIGHT,
+            init_guard_activation_height: INIT_GUARD_ACTIVATION_HEIGHT,
+            allow_deprecated_views: true,
+            allow_test_only_exports: false,
             gas_to_fuel_multiplier: 1_000,
-            gas_to_token_multiplier: Decimal::from("1e-9"),
+            gas_pricer: Arc::new(FixedGasPricer::default()),
             txid: None,
             previous_output: None,
             op_return_data: None,
+            #[cfg(any(test, feature = "testlib"))]
+            mocks: HashMap::new(),
         })
     }
 
@@ -193,12 +518,18 @@ impl Runtime {
         previous_output: Option<bitcoin::OutPoint>,
         op_return_data: Option<OpReturnData>,
     ) {
+        if height != self.storage.height
+            && let Some(view_cache) = self.view_cache.as_ref()
+        {
+            view_cache.clear();
+        }
         self.storage.height = height;
         self.storage.tx_index = tx_index;
         self.storage.input_index = input_index;
         self.storage.op_index = op_index;
         self.id_generation_counter.reset().await;
         self.result_id_counter.reset().await;
+        self.storage.write_counter.reset().await;
         self.txid = Some(txid);
         self.previous_output = previous_output;
         self.op_return_data = op_return_data;
@@ -207,6 +538,28 @@ impl Runtime {
         }
     }
 
+    /// Answers every call `contract_address` receives from another contract
+    /// with `mock` instead of running the contract, which need not exist.
+    #[cfg(any(test, feature = "testlib"))]
+    pub fn mock(&mut self, contract_address: &ContractAddress, mock: ContractMock) {
+        self.mocks.insert(contract_address.to_string(), mock);
+    }
+
+    pub fn enable_view_cache(&mut self) {
+        self.view_cache = Some(ViewCache::new());
+    }
+
+    /// Moves a read-only runtime to the latest processed block, so the views
+    /// it serves run, and are cached, against that block.
+    pub async fn follow_latest_block(&mut self) -> Result<()> {
+        let block = self.storage.latest_block().await?;
+        if let Some(view_cache) = self.view_cache.as_ref() {
+            view_cache.move_to(block.as_ref().map(|block| block.hash));
+        }
+        self.storage.height = block.map_or(0, |block| block.height);
+        Ok(())
+    }
+
     pub fn get_storage_conn(&self) -> Connection {
         self.storage.conn.clone()
     }
@@ -215,18 +568,112 @@ impl Runtime {
         self.storage = storage;
     }
 
-    pub fn fuel_limit(&self) -> Option<u64> {
-        self.gas_limit.map(|l| l * self.gas_to_fuel_multiplier)
+    fn gas_to_fuel(&self, gas: u64) -> Result<u64> {
+        gas.checked_mul(self.gas_to_fuel_multiplier)
+            .ok_or_else(|| anyhow!("gas limit {} overflows the fuel limit", gas))
+    }
+
+    pub fn fuel_limit(&self) -> Result<Option<u64>> {
+        self.gas_limit.map(|l| self.gas_to_fuel(l)).transpose()
     }
 
-    pub fn fuel_limit_for_non_procs(&self) -> u64 {
-        self.gas_limit_for_non_procs * self.gas_to_fuel_multiplier
+    pub fn fuel_limit_for_non_procs(&self) -> Result<u64> {
+        self.gas_to_fuel(self.gas_limit_for_non_procs)
     }
 
     pub fn set_gas_limit(&mut self, gas_limit: u64) {
         self.gas_limit = Some(gas_limit);
     }
 
+    pub fn set_gas_pricer(&mut self, gas_pricer: Arc<dyn GasPricer>) {
+        self.gas_pricer = gas_pricer;
+    }
+
+    /// What the gas price of the current block is derived from.
+    async fn block_usage(&self) -> Result<BlockUsage> {
+        let height = self.storage.height;
+        let (prev_gas, prev_ops) = self.storage.block_gas_usage(height - 1).await?;
+        Ok(BlockUsage {
+            height,
+            prev_gas,
+            prev_ops,
+        })
+    }
+
+    /// Caps contracts entered through `foreign::call` at `percent` of the
+    /// caller's remaining fuel so one callee can't starve its siblings.
+    pub fn set_nested_call_budget_percent(&mut self, percent: u64) -> Result<()> {
+        if !(1..=100).contains(&percent) {
+            return Err(anyhow!(
+                "Nested call budget must be between 1 and 100 percent, got {}",
+                percent
+            ));
+        }
+        self.nested_call_budget_percent = Some(percent);
+        Ok(())
+    }
+
+    /// The contract state host functions read and write.
+    fn state(&self) -> &dyn StorageBackend {
+        self.state_backend.as_deref().unwrap_or(&self.storage)
+    }
+
+    /// Opens a savepoint on the database and, if it is separate, on the state
+    /// backend.
+    async fn state_savepoint(&self) -> Result<()> {
+        self.storage.savepoint().await?;
+        if let Some(backend) = &self.state_backend {
+            backend.savepoint().await?;
+        }
+        Ok(())
+    }
+
+    async fn state_commit(&self) -> Result<()> {
+        self.storage.commit().await?;
+        if let Some(backend) = &self.state_backend {
+            backend.commit().await?;
+        }
+        Ok(())
+    }
+
+    async fn state_rollback(&self) -> Result<()> {
+        self.storage.rollback().await?;
+        if let Some(backend) = &self.state_backend {
+            backend.rollback().await?;
+        }
+        Ok(())
+    }
+
+    fn contract_component(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        encode_component(&self.storage.compression.decompress(bytes)?)
+    }
+
+    /// Rejects contracts built with their `test-only` exports, which may only
+    /// be called in local tests and never published.
+    fn check_test_only_exports(&self, bytes: &[u8]) -> Result<()> {
+        if self.allow_test_only_exports {
+            return Ok(());
+        }
+        let resolve = decode_resolve(&self.contract_component(bytes)?)?;
+        let test_only = Validator::validate_resolve(&resolve)
+            .errors
+            .into_iter()
+            .filter(|e| e.code == Some(ErrorCode::TestOnlyExport))
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        if !test_only.is_empty() {
+            return Err(ExecutionError::TestOnlyExport(test_only.join("; ")).into());
+        }
+        Ok(())
+    }
+
+    fn check_contract_limits(&self, bytes: &[u8]) -> Result<(), ExecutionError> {
+        if self.storage.height < self.contract_limits_activation_height {
+            return Ok(());
+        }
+        check_contract_limits(bytes, self.max_contract_size, self.max_contract_exports)
+    }
+
     pub fn gas_consumed(&self, starting_fuel: u64, ending_fuel: u64) -> u64 {
         (starting_fuel - ending_fuel).div_ceil(self.gas_to_fuel_multiplier)
     }
@@ -235,6 +682,19 @@ impl Runtime {
         self.set_context(0, 0, 0, 0, new_mock_transaction(0).txid, None, None)
             .await;
         self.set_gas_limit(self.gas_limit_for_non_procs);
+        // native contracts are not held to the deployment's write or
+        // contract limits
+        let max_writes = std::mem::replace(&mut self.storage.max_writes_per_tx, u64::MAX);
+        let max_size = self.max_contract_size.take();
+        let max_exports = self.max_contract_exports.take();
+        let result = self.publish_native_contracts_unbounded().await;
+        self.storage.max_writes_per_tx = max_writes;
+        self.max_contract_size = max_size;
+        self.max_contract_exports = max_exports;
+        result
+    }
+
+    async fn publish_native_contracts_unbounded(&mut self) -> Result<()> {
         self.publish(&Signer::Core(Box::new(Signer::Nobody)), "token", TOKEN)
             .await?;
         self.publish(
@@ -255,7 +715,7 @@ impl Runtime {
     pub async fn publish(&mut self, signer: &Signer, name: &str, bytes: &[u8]) -> Result<String> {
         let address = ContractAddress {
             name: name.to_string(),
-            height: self.storage.height as u64,
+            height: height::to_u64(self.storage.height),
             tx_index: self.storage.tx_index as u64,
         };
         if self
@@ -267,25 +727,146 @@ impl Runtime {
         {
             return Ok("".to_string());
         }
+        self.check_contract_limits(bytes)?;
+        self.check_test_only_exports(bytes)?;
 
-        self.storage
-            .savepoint()
+        self.state_savepoint()
             .await
             .expect("Failed to create savepoint");
-        self.storage
-            .insert_contract(name, bytes)
+        let contract_id = self
+            .storage
+            .insert_contract_with_deployer(name, bytes, Some(&**signer))
             .await
             .expect("Failed to insert contract");
         let result = self.execute(Some(signer), &address, "init()").await;
         if result.is_err() {
-            self.storage.rollback().await.expect("Failed to rollback");
+            self.state_rollback().await.expect("Failed to rollback");
             result
         } else {
-            self.storage.commit().await.expect("Failed to commit");
+            self.storage
+                .set_initialized(contract_id)
+                .await
+                .expect("Failed to mark contract initialized");
+            self.state_commit().await.expect("Failed to commit");
             Ok(to_wave_expr(address.clone()))
         }
     }
 
+    /// Dry run of [`Runtime::publish`]: compiles the contract, validates its
+    /// WIT and runs `init` in a savepoint that is always rolled back, so
+    /// nothing is persisted. Contracts that fail the size limits or don't
+    /// compile are an error rather than a report.
+    pub async fn check_publish(
+        &mut self,
+        signer: &Signer,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<PublishReport> {
+        self.check_contract_limits(bytes)?;
+        let component_bytes = self.contract_component(bytes)?;
+        Component::from_binary(&self.engine, &component_bytes)?;
+        let validation = Validator::validate_resolve(&decode_resolve(&component_bytes)?);
+        let mut report = PublishReport {
+            validation_errors: validation.errors.iter().map(ToString::to_string).collect(),
+            validation_warnings: validation
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            init_error: None,
+        };
+
+        let address = ContractAddress {
+            name: name.to_string(),
+            height: height::to_u64(self.storage.height),
+            tx_index: self.storage.tx_index as u64,
+        };
+        self.state_savepoint().await?;
+        let contract_id = match self
+            .storage
+            .insert_contract_with_deployer(name, bytes, Some(&**signer))
+            .await
+        {
+            Ok(contract_id) => contract_id,
+            Err(e) => {
+                self.state_rollback().await?;
+                return Err(e);
+            }
+        };
+        report.init_error = self
+            .execute(Some(signer), &address, "init()")
+            .await
+            .err()
+            .map(|e| e.to_string());
+        self.state_rollback().await?;
+        // the id is free again, don't let a later publish pick up this build
+        self.component_cache.invalidate(&contract_id).await;
+        Ok(report)
+    }
+
+    /// Stops `contract_address` from accepting procedure calls, and views
+    /// too unless `allow_deprecated_views` is set. Only the original deployer
+    /// may do so.
+    pub async fn deprecate(
+        &mut self,
+        signer: &Signer,
+        contract_address: &ContractAddress,
+    ) -> Result<()> {
+        let contract_id = self
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found: {}", contract_address))?;
+        if self.storage.deployer(contract_id).await?.as_deref() != Some(&**signer) {
+            return Err(anyhow!(
+                "Only the deployer of {} can deprecate it",
+                contract_address
+            ));
+        }
+        self.storage.deprecate(contract_id).await
+    }
+
+    /// Replaces the code of `contract_address` with `bytes` while keeping its
+    /// storage. Only the original deployer may do so, and the new code must
+    /// compile.
+    pub async fn upgrade(
+        &mut self,
+        signer: &Signer,
+        contract_address: &ContractAddress,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let contract_id = self
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found: {}", contract_address))?;
+        if self.storage.deployer(contract_id).await?.as_deref() != Some(&**signer) {
+            return Err(anyhow!(
+                "Only the deployer of {} can upgrade it",
+                contract_address
+            ));
+        }
+        self.check_contract_limits(bytes)?;
+        self.check_test_only_exports(bytes)?;
+
+        self.storage.savepoint().await?;
+        self.storage
+            .replace_contract_bytes(contract_id, bytes)
+            .await?;
+        self.component_cache.invalidate(&contract_id).await;
+        if let Some(view_cache) = self.view_cache.as_ref() {
+            view_cache.clear();
+        }
+        match self.load_component(contract_id).await {
+            Ok(_) => self.storage.commit().await,
+            Err(e) => {
+                self.storage.rollback().await?;
+                self.component_cache.invalidate(&contract_id).await;
+                Err(e)
+            }
+        }
+    }
+
     pub async fn issuance(&mut self, signer: &Signer) -> Result<()> {
         token::api::issuance(self, &Signer::Core(Box::new(signer.clone())), 10.into())
             .await
@@ -294,11 +875,78 @@ impl Runtime {
         Ok(())
     }
 
+    /// Runs `expr` against a contract inside an `execute` span carrying the
+    /// contract, function, height and gas limit, plus the gas consumed and
+    /// whether the call returned `ok`, `err` or trapped. A call rejected
+    /// before it runs is recorded as `err`.
     pub async fn execute(
         &mut self,
         signer: Option<&Signer>,
         contract_address: &ContractAddress,
         expr: &str,
+    ) -> Result<String> {
+        let span = tracing::info_span!(
+            "execute",
+            contract = %contract_address,
+            func = tracing::field::Empty,
+            height = self.storage.height,
+            gas_limit = tracing::field::Empty,
+            gas_consumed = tracing::field::Empty,
+            result_kind = tracing::field::Empty,
+        );
+        let mut kind = "err";
+        let result = self
+            .execute_in_span(signer, contract_address, expr, &mut kind)
+            .instrument(span.clone())
+            .await;
+        span.record("result_kind", kind);
+        result
+    }
+
+    /// Runs the view `expr` with a budget of `gas_limit`, or
+    /// `gas_limit_for_non_procs` when none is given, so callers serving
+    /// views can bound each request on its own.
+    pub async fn execute_view(
+        &mut self,
+        contract_address: &ContractAddress,
+        expr: &str,
+        gas_limit: Option<u64>,
+    ) -> Result<String> {
+        let previous = self
+            .gas_limit
+            .replace(gas_limit.unwrap_or(self.gas_limit_for_non_procs));
+        let result = self.execute(None, contract_address, expr).await;
+        self.gas_limit = previous;
+        result
+    }
+
+    /// Runs the view `expr` with effectively unlimited fuel and no fuel
+    /// gauge, so benchmarks can time execution without the gauge's
+    /// bookkeeping. Wasmtime still counts fuel underneath. Never use it to
+    /// process chain data: results don't reflect gas limits.
+    #[cfg(any(test, feature = "testlib"))]
+    pub async fn execute_unmetered(
+        &mut self,
+        contract_address: &ContractAddress,
+        expr: &str,
+    ) -> Result<String> {
+        let gauge = self.gauge.take();
+        let gas_limit = self.gas_limit.take();
+        let gas_limit_for_non_procs = self.gas_limit_for_non_procs;
+        self.gas_limit_for_non_procs = i64::MAX as u64 / self.gas_to_fuel_multiplier;
+        let result = self.execute(None, contract_address, expr).await;
+        self.gauge = gauge;
+        self.gas_limit = gas_limit;
+        self.gas_limit_for_non_procs = gas_limit_for_non_procs;
+        result
+    }
+
+    async fn execute_in_span(
+        &mut self,
+        signer: Option<&Signer>,
+        contract_address: &ContractAddress,
+        expr: &str,
+        kind: &mut &'static str,
     ) -> Result<String> {
         tracing::info!(
             "Executing contract {} with expr {} at input index {}",
@@ -306,6 +954,24 @@ impl Runtime {
             expr,
             self.storage.input_index
         );
+        *self.error_trace.lock().await = None;
+        let view_cache_key = match (self.view_cache.as_ref(), signer) {
+            (Some(_), None) => match self.storage.contract_id(contract_address).await? {
+                Some(contract_id) => Some((
+                    contract_id,
+                    expr.to_string(),
+                    self.storage.state_version(contract_id).await?,
+                )),
+                None => None,
+            },
+            _ => None,
+        };
+        if let (Some(view_cache), Some(key)) = (self.view_cache.as_ref(), view_cache_key.as_ref())
+            && let Some(value) = view_cache.get(key).await
+        {
+            *kind = "ok";
+            return Ok(value);
+        }
         let (
             mut store,
             contract_id,
@@ -317,8 +983,11 @@ impl Runtime {
             is_proc,
             starting_fuel,
         ) = self
-            .prepare_call(contract_address, signer, expr, true, self.fuel_limit())
+            .prepare_call(contract_address, signer, expr, true, self.fuel_limit()?)
             .await?;
+        let span = tracing::Span::current();
+        span.record("func", func_name.as_str());
+        span.record("gas_limit", starting_fuel / self.gas_to_fuel_multiplier);
         OptionFuture::from(
             self.gauge
                 .as_ref()
@@ -334,6 +1003,7 @@ impl Runtime {
         })
         .await
         .expect("Failed to join execution");
+        *kind = result_kind(is_fallback, &result, &results);
         let mut result = self.handle_call(is_fallback, result, results).await;
         OptionFuture::from(
             self.gauge
@@ -355,6 +1025,24 @@ impl Runtime {
                     result,
                 )
                 .await;
+        } else {
+            span.record(
+                "gas_consumed",
+                self.gas_consumed(starting_fuel, store.get_fuel().unwrap()),
+            );
+        }
+        if result.is_err() && *kind == "ok" {
+            *kind = "err";
+        }
+        if result.is_err()
+            && let Some(trace) = self.error_trace().await
+        {
+            result = result.map_err(|e| e.context(trace));
+        }
+        if let (Some(view_cache), Some(key), Ok(value)) =
+            (self.view_cache.as_ref(), view_cache_key, result.as_ref())
+        {
+            view_cache.put(key, value.clone()).await;
         }
         result
     }
@@ -363,7 +1051,17 @@ impl Runtime {
         Ok(match self.component_cache.get(&contract_id).await {
             Some(component) => component,
             None => {
-                let component_bytes = self.storage.component_bytes(contract_id).await?;
+                let module_bytes = match self.component_cache.get_module(&contract_id).await {
+                    Some(module_bytes) => module_bytes,
+                    None => {
+                        let module_bytes = Arc::new(self.storage.module_bytes(contract_id).await?);
+                        self.component_cache
+                            .put_module(contract_id, module_bytes.clone())
+                            .await;
+                        module_bytes
+                    }
+                };
+                let component_bytes = encode_component(&module_bytes)?;
                 let component = Component::from_binary(&self.engine, &component_bytes)?;
                 self.component_cache
                     .put(contract_id, component.clone())
@@ -379,6 +1077,82 @@ impl Runtime {
         Ok(s)
     }
 
+    /// Checks `expr`'s arguments against the target function's parameter
+    /// types without executing it.
+    pub async fn validate_args(
+        &self,
+        expr: &str,
+        contract_address: &ContractAddress,
+    ) -> Result<(), ArgError> {
+        let (name, args) = args::split_call(expr)?;
+        let contract_id = self
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found: {}", contract_address))?;
+        let component = self.load_component(contract_id).await?;
+        let mut store = self.make_store(self.fuel_limit_for_non_procs()?)?;
+        let instance = self
+            .linker
+            .instantiate_async(&mut store, &component)
+            .await
+            .map_err(anyhow::Error::from)?;
+        let Some(func) = instance.get_func(&mut store, &name) else {
+            // anything goes when a fallback will receive the raw expression
+            return if instance.get_func(&mut store, "fallback").is_some() {
+                Ok(())
+            } else {
+                Err(ArgError::UnknownFunction(name))
+            };
+        };
+        let param_types = func
+            .ty(&store)
+            .params()
+            .skip(1)
+            .map(|(_, t)| t)
+            .collect::<Vec<_>>();
+        args::check_args(&param_types, &args)
+    }
+
+    /// Every exported function callable with a context, with its kind and
+    /// parameter and result types. Cached per contract alongside its component.
+    pub async fn function_signatures(
+        &self,
+        contract_address: &ContractAddress,
+    ) -> Result<Vec<FuncSig>> {
+        let contract_id = self
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found: {}", contract_address))?;
+        if let Some(signatures) = self.component_cache.get_signatures(&contract_id).await {
+            return Ok(signatures);
+        }
+        let component = self.load_component(contract_id).await?;
+        let mut store = self.make_store(self.fuel_limit_for_non_procs()?)?;
+        let instance = self
+            .linker
+            .instantiate_async(&mut store, &component)
+            .await?;
+        let mut signatures = Vec::new();
+        for (name, item) in component.component_type().exports(&self.engine) {
+            if !matches!(item, ComponentItem::ComponentFunc(_)) {
+                continue;
+            }
+            let Some(func) = instance.get_func(&mut store, name) else {
+                continue;
+            };
+            let ty = func.ty(&store);
+            if let Some(signature) = FuncSig::new(name, ty.params(), ty.results()) {
+                signatures.push(signature);
+            }
+        }
+        self.component_cache
+            .put_signatures(contract_id, signatures.clone())
+            .await;
+        Ok(signatures)
+    }
+
     async fn prepare_call(
         &self,
         contract_address: &ContractAddress,
@@ -403,7 +1177,10 @@ impl Runtime {
             .await?
             .ok_or(anyhow!("Contract not found: {}", contract_address))?;
         let component = self.load_component(contract_id).await?;
-        let mut fuel_limit = fuel.unwrap_or(self.fuel_limit_for_non_procs());
+        let mut fuel_limit = match fuel {
+            Some(fuel) => fuel,
+            None => self.fuel_limit_for_non_procs()?,
+        };
         let mut store = self.make_store(fuel_limit)?;
         let instance = self
             .linker
@@ -416,11 +1193,11 @@ impl Runtime {
             to_wave_string(&WaveValue::from(expr))?
         );
 
-        let call = WaveParser::new(expr).parse_raw_func_call()?;
+        let call = parse_call_expr(expr)?;
         let (call, func) = if let Some(func) = instance.get_func(&mut store, call.name()) {
             (call, func)
         } else if let Some(func) = instance.get_func(&mut store, fallback_name) {
-            (WaveParser::new(&fallback_expr).parse_raw_func_call()?, func)
+            (parse_call_expr(&fallback_expr)?, func)
         } else {
             return Err(anyhow!("Expression does not refer to any known function"));
         };
@@ -433,26 +1210,18 @@ impl Runtime {
             .split_first()
             .ok_or(anyhow!("Context/signer parameter not found"))?;
         let mut params = call.to_wasm_params(func_param_types)?;
-        let resource_type = match func_ctx_param_type {
-            wasmtime::component::Type::Borrow(t) => Ok(*t),
-            _ => Err(anyhow!("Unsupported context type")),
-        }?;
+        let func_kind = FuncKind::from_context_type(func_ctx_param_type)
+            .ok_or(anyhow!("Unsupported context type"))?;
 
-        if let Some(Signer::ContractId { id, .. }) = signer
-            && self.stack.peek().await != Some(*id)
-        {
-            return Err(anyhow!("Invalid contract id signer"));
-        }
+        verify_contract_id_signer(signer, self.stack.peek().await)?;
 
         let mut is_proc = false;
         {
             let mut table = self.table.lock().await;
-            match (resource_type, signer) {
-                (t, Some(Signer::Core(signer)))
-                    if t.eq(&wasmtime::component::ResourceType::host::<CoreContext>()) =>
-                {
+            match (func_kind, signer) {
+                (FuncKind::Core, Some(Signer::Core(signer))) => {
                     is_proc = true;
-                    fuel_limit = self.fuel_limit_for_non_procs();
+                    fuel_limit = self.fuel_limit_for_non_procs()?;
                     store
                         .set_fuel(fuel_limit)
                         .expect("Failed to set fuel for core context procedure");
@@ -468,18 +1237,15 @@ impl Runtime {
                         ),
                     )
                 }
-                (t, _) if t.eq(&wasmtime::component::ResourceType::host::<ViewContext>()) => params
-                    .insert(
-                        0,
-                        wasmtime::component::Val::Resource(
-                            table
-                                .push(ViewContext { contract_id })?
-                                .try_into_resource_any(&mut store)?,
-                        ),
+                (FuncKind::View, _) => params.insert(
+                    0,
+                    wasmtime::component::Val::Resource(
+                        table
+                            .push(ViewContext { contract_id })?
+                            .try_into_resource_any(&mut store)?,
                     ),
-                (t, Some(signer))
-                    if t.eq(&wasmtime::component::ResourceType::host::<ProcContext>()) =>
-                {
+                ),
+                (FuncKind::Proc, Some(signer)) => {
                     is_proc = true;
                     params.insert(
                         0,
@@ -494,7 +1260,7 @@ impl Runtime {
                     )
                 }
 
-                (t, signer) if t.eq(&wasmtime::component::ResourceType::host::<FallContext>()) => {
+                (FuncKind::Fallback, signer) => {
                     is_proc = signer.is_some();
                     params.insert(
                         0,
@@ -508,10 +1274,10 @@ impl Runtime {
                         ),
                     )
                 }
-                (t, signer) => {
+                (kind, signer) => {
                     return Err(anyhow!(
                         "Unsupported context/signer type: {:?} {:?}",
-                        t,
+                        kind,
                         signer
                     ));
                 }
@@ -522,6 +1288,22 @@ impl Runtime {
             return Err(anyhow!("Missing fuel for procedure"));
         }
 
+        if (is_proc || !self.allow_deprecated_views)
+            && self.storage.is_deprecated(contract_id).await?
+        {
+            return Err(ExecutionError::Deprecated(contract_address.clone()).into());
+        }
+
+        // `init` runs once at publish; calling it again would overwrite the
+        // contract's storage root. Contracts wanting to reset state must
+        // export their own entry point for it
+        if func_name == "init"
+            && self.storage.height >= self.init_guard_activation_height
+            && self.storage.is_initialized(contract_id).await?
+        {
+            return Err(ExecutionError::AlreadyInitialized(contract_address.clone()).into());
+        }
+
         let results = component_func
             .results()
             .map(default_val_for_type)
@@ -532,17 +1314,19 @@ impl Runtime {
             && let Some(signer) = signer
             && !signer.is_core()
         {
+            let token_per_gas = self.gas_pricer.token_per_gas(&self.block_usage().await?);
+            let escrow = Decimal::from(fuel_limit)
+                .div(Decimal::from(self.gas_to_fuel_multiplier))
+                .and_then(|gas_limit| gas_limit.mul(token_per_gas))
+                .map_err(|e| anyhow!("Failed to price gas limit in token: {:?}", e))?;
             Box::pin({
                 let mut runtime = self.clone();
+                runtime.storage.write_counter = Counter::new();
                 async move {
                     token::api::hold(
                         &mut runtime,
                         &Signer::Core(Box::new(signer.clone())),
-                        Decimal::from(fuel_limit)
-                            .div(Decimal::from(self.gas_to_fuel_multiplier))
-                            .expect("Failed to convert fuel limit into gas limit")
-                            .mul(self.gas_to_token_multiplier)
-                            .expect("Failed to convert gas limit into token limit"),
+                        escrow,
                     )
                     .await
                 }
@@ -559,7 +1343,13 @@ impl Runtime {
         }
 
         self.stack.push(contract_id).await?;
-        self.storage.savepoint().await?;
+        self.call_frames
+            .push((
+                contract_id,
+                format!("{}.{}", contract_address.name, func_name),
+            ))
+            .await?;
+        self.state_savepoint().await?;
         self.file_ledger.clear_dirty().await;
 
         Ok((
@@ -582,9 +1372,10 @@ impl Runtime {
         mut results: Vec<Val>,
     ) -> Result<String> {
         self.stack.pop().await;
+        let frame = self.call_frames.pop().await;
 
         let result = if let Err(e) = result {
-            Err(anyhow!(format!("{}", e.root_cause())))
+            Err(anyhow!(ExecutionError::from_trap(&e)))
         } else if results.is_empty() {
             Ok("".to_string())
         } else if results.len() != 1 {
@@ -604,9 +1395,12 @@ impl Runtime {
             }
         };
 
+        if let Some(frame) = frame {
+            self.trace_call(frame, &result).await;
+        }
+
         if result.is_err() || result.as_ref().is_ok_and(|val| val.starts_with("err(")) {
-            self.storage
-                .rollback()
+            self.state_rollback()
                 .await
                 .expect("Failed to rollback storage after failure to extract expression");
             self.file_ledger
@@ -614,8 +1408,7 @@ impl Runtime {
                 .await
                 .expect("Failed to resync file ledger after rollback");
         } else {
-            self.storage
-                .commit()
+            self.state_commit()
                 .await
                 .expect("Failed to commit storage after successful call");
         }
@@ -623,6 +1416,45 @@ impl Runtime {
         result
     }
 
+    /// Records where a failed call failed, keeping the trace of a deeper call
+    /// that already failed on the same path, and forgets it once a call on
+    /// that path succeeds.
+    async fn trace_call(&self, frame: (i64, String), result: &Result<String>) {
+        let message = match result {
+            Err(e) => Some(e.to_string()),
+            Ok(val) if val.starts_with("err(") => {
+                Some(val["err(".len()..val.len() - 1].to_string())
+            }
+            Ok(_) => None,
+        };
+        if message.is_none() && self.error_trace.lock().await.is_none() {
+            return;
+        }
+        let frames = self
+            .call_frames
+            .items()
+            .await
+            .into_iter()
+            .chain(std::iter::once(frame))
+            .map(|(_, label)| label)
+            .collect::<Vec<_>>();
+        let mut error_trace = self.error_trace.lock().await;
+        let on_path = error_trace
+            .as_ref()
+            .is_some_and(|trace| trace.frames.starts_with(&frames));
+        match message {
+            None if on_path => *error_trace = None,
+            Some(message) if !on_path => *error_trace = Some(CallTrace { frames, message }),
+            _ => {}
+        }
+    }
+
+    /// The chain of calls behind the last failed execution, cleared when the
+    /// next execution starts.
+    pub async fn error_trace(&self) -> Option<CallTrace> {
+        self.error_trace.lock().await.clone()
+    }
+
     pub async fn handle_procedure(
         &mut self,
         signer: &Signer,
@@ -647,6 +1479,9 @@ impl Runtime {
                 store.get_fuel().expect("Fuel should be available"),
             )
             .max(1);
+        if is_op_result {
+            tracing::Span::current().record("gas_consumed", gas);
+        }
 
         if is_op_result && !signer.is_core() {
             tracing::info!(
@@ -655,19 +1490,19 @@ impl Runtime {
                 starting_fuel,
                 store.get_fuel().unwrap()
             );
+            let token_per_gas = self.gas_pricer.token_per_gas(&self.block_usage().await?);
+            let fee = Decimal::from(gas)
+                .mul(token_per_gas)
+                .map_err(|e| anyhow!("Failed to price gas consumed in token: {:?}", e))?;
             Box::pin({
                 let mut runtime = self.clone();
                 runtime.stack = Stack::new();
-                let gas_to_token_multiplier = self.gas_to_token_multiplier;
+                runtime.call_frames = Stack::new();
+                runtime.error_trace = Arc::new(Mutex::new(None));
+                runtime.storage.write_counter = Counter::new();
                 async move {
-                    token::api::release(
-                        &mut runtime,
-                        &Signer::Core(Box::new(signer.clone())),
-                        Decimal::from(gas)
-                            .mul(gas_to_token_multiplier)
-                            .expect("Failed to convert gas consumed to token amount"),
-                    )
-                    .await
+                    token::api::release(&mut runtime, &Signer::Core(Box::new(signer.clone())), fee)
+                        .await
                 }
             })
             .await
@@ -679,6 +1514,12 @@ impl Runtime {
             return result;
         }
         let value = result.as_ref().map(|v| v.clone()).ok();
+        let error_trace =
+            if result.is_err() || value.as_ref().is_some_and(|v| v.starts_with("err(")) {
+                self.error_trace().await.map(|trace| trace.to_string())
+            } else {
+                None
+            };
         let result_index = self.result_id_counter.get().await as i64;
         self.storage
             .insert_contract_result(
@@ -687,6 +1528,7 @@ impl Runtime {
                 func_name.to_string(),
                 gas as i64,
                 value,
+                error_trace,
             )
             .await
             .expect("Failed to insert contract result");
@@ -701,7 +1543,14 @@ impl Runtime {
         contract_address: &ContractAddress,
         expr: &str,
     ) -> Result<String> {
-        let starting_fuel = accessor.with(|access| access.as_context().get_fuel())?;
+        #[cfg(any(test, feature = "testlib"))]
+        if let Some(mock) = self.mocks.get(&contract_address.to_string()) {
+            return mock(expr);
+        }
+        let parent_fuel = accessor.with(|access| access.as_context().get_fuel())?;
+        let starting_fuel = self
+            .nested_call_budget_percent
+            .map_or(parent_fuel, |percent| parent_fuel / 100 * percent);
 
         let signer =
             OptionFuture::from(signer.map(async |s| self.table.lock().await.get(&s).cloned()))
@@ -738,7 +1587,7 @@ impl Runtime {
         .await
         .expect("Failed to join call");
         let mut result = self.handle_call(is_fallback, result, results).await;
-        let fuel = store.get_fuel().unwrap();
+        let fuel = parent_fuel - (starting_fuel - store.get_fuel().unwrap());
         accessor
             .with(|mut access| access.as_context_mut().set_fuel(fuel))
             .expect("Failed to set remaining fuel on parent store");
@@ -966,9 +1815,11 @@ impl Runtime {
     ) -> Result<Option<R>> {
         let fuel = accessor.with(|access| access.as_context().get_fuel())?;
         let table = self.table.lock().await;
-        let contract_id = table.get(&self_)?.get_contract_id();
+        let resource = table.get(&self_)?;
+        self._ensure_public(accessor, resource, &path).await?;
+        let contract_id = resource.get_contract_id();
         OptionFuture::from(
-            self.storage
+            self.state()
                 .get(fuel, contract_id, &path)
                 .await?
                 .map(async |bs| {
@@ -987,12 +1838,51 @@ impl Runtime {
         accessor: &Accessor<S, Self>,
         resource: Resource<T>,
         path: String,
+        after: Option<String>,
+        max_keys: Option<u64>,
     ) -> Result<Resource<Keys>> {
         let mut table = self.table.lock().await;
-        let contract_id = table.get(&resource)?.get_contract_id();
+        let _self = table.get(&resource)?;
+        self._ensure_public(accessor, _self, &path).await?;
+        let contract_id = _self.get_contract_id();
         Fuel::GetKeys.consume(accessor, self.gauge.as_ref()).await?;
-        let stream = Box::pin(self.storage.keys(contract_id, path.clone()).await?);
-        Ok(table.push(Keys { stream })?)
+        let stream = self.state().keys_after(contract_id, &path, after).await?;
+        Ok(table.push(Keys {
+            stream,
+            remaining: max_keys,
+            truncated: None,
+        })?)
+    }
+
+    async fn _get_entries<S, T: HasContractId>(
+        &self,
+        accessor: &Accessor<S, Self>,
+        resource: Resource<T>,
+        path: String,
+        kind: EntryKind,
+    ) -> Result<Resource<Entries>> {
+        let mut table = self.table.lock().await;
+        let _self = table.get(&resource)?;
+        self._ensure_public(accessor, _self, &path).await?;
+        let contract_id = _self.get_contract_id();
+        Fuel::GetEntries
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let stream: EntryStream = match kind {
+            EntryKind::Integer | EntryKind::Decimal => Box::pin(
+                self.state()
+                    .keys_after(contract_id, &path, None)
+                    .await?
+                    .map_ok(|k| (k, Vec::new())),
+            ),
+            _ => self.state().entries(contract_id, &path).await?,
+        };
+        Ok(table.push(Entries {
+            stream,
+            kind,
+            contract_id,
+            path,
+        })?)
     }
 
     async fn _exists<S, T: HasContractId>(
@@ -1003,8 +1893,9 @@ impl Runtime {
     ) -> Result<bool> {
         let table = self.table.lock().await;
         let _self = table.get(&resource)?;
+        self._ensure_public(accessor, _self, &path).await?;
         Fuel::Exists.consume(accessor, self.gauge.as_ref()).await?;
-        self.storage.exists(_self.get_contract_id(), &path).await
+        self.state().exists(_self.get_contract_id(), &path).await
     }
 
     async fn _extend_path_with_match<S, T: HasContractId>(
@@ -1016,10 +1907,11 @@ impl Runtime {
     ) -> Result<Option<String>> {
         let table = self.table.lock().await;
         let _self = table.get(&resource)?;
+        self._ensure_public(accessor, _self, &path).await?;
         Fuel::ExtendPathWithMatch(variants.len() as u64)
             .consume(accessor, self.gauge.as_ref())
             .await?;
-        self.storage
+        self.state()
             .extend_path_with_match(
                 _self.get_contract_id(),
                 &path,
@@ -1028,6 +1920,35 @@ impl Runtime {
             .await
     }
 
+    /// Storage opened on another contract may only be read under the fields
+    /// that contract marked `#[public]`. The markers are written by the
+    /// contract's `init`, so contracts published before `#[public]` existed
+    /// expose nothing until they are republished.
+    async fn _ensure_public<S, T: HasContractId>(
+        &self,
+        accessor: &Accessor<S, Self>,
+        resource: &T,
+        path: &str,
+    ) -> Result<()> {
+        if !resource.public_only() {
+            return Ok(());
+        }
+        Fuel::Exists.consume(accessor, self.gauge.as_ref()).await?;
+        let field = path.split('.').next().unwrap_or_default();
+        if field.is_empty()
+            || !self
+                .state()
+                .exists(
+                    resource.get_contract_id(),
+                    &format!("{}.{}", PUBLIC_FIELDS_PATH, field),
+                )
+                .await?
+        {
+            return Err(anyhow!("Path is not public: {}", path));
+        }
+        Ok(())
+    }
+
     async fn _delete_matching_paths<S, T: HasContractId>(
         &self,
         accessor: &Accessor<S, Self>,
@@ -1038,11 +1959,31 @@ impl Runtime {
             .consume(accessor, self.gauge.as_ref())
             .await?;
         let contract_id = self.table.lock().await.get(&self_)?.get_contract_id();
-        self.storage
+        self.state()
             .delete_matching_paths(contract_id, &regexp)
             .await
     }
 
+    async fn _delete_prefix<S, T: HasContractId>(
+        &self,
+        accessor: &Accessor<S, Self>,
+        self_: Resource<T>,
+        base_path: String,
+    ) -> Result<u64> {
+        Fuel::Path(base_path.clone())
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let contract_id = self.table.lock().await.get(&self_)?.get_contract_id();
+        // charged for every path before any is tombstoned, so a call that
+        // can't pay for the delete doesn't do it
+        let paths = self.state().count_prefix(contract_id, &base_path).await?;
+        Fuel::DeletePrefix(paths)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let (_, keys) = self.state().delete_prefix(contract_id, &base_path).await?;
+        Ok(keys)
+    }
+
     async fn _set_primitive<S, T: HasContractId, V: Serialize>(
         &self,
         accessor: &Accessor<S, Self>,
@@ -1058,20 +1999,46 @@ impl Runtime {
         Fuel::Set(bs.len() as u64)
             .consume(accessor, self.gauge.as_ref())
             .await?;
-        self.storage.set(contract_id, &path, bs).await
+        self.state().set(contract_id, &path, bs).await
+    }
+
+    async fn _hash<T>(
+        &self,
+        accessor: &Accessor<T, Runtime>,
+        input: String,
+    ) -> Result<(String, Vec<u8>)> {
+        Fuel::CryptoHash(input.len() as u64)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let bs = hash_bytes(input.as_bytes());
+        let s = hex::encode(bs);
+        Ok((s, bs.to_vec()))
+    }
+
+    async fn _hash_many<T>(
+        &self,
+        accessor: &Accessor<T, Runtime>,
+        inputs: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>> {
+        let total = inputs.iter().map(Vec::len).sum::<usize>();
+        Fuel::CryptoHashMany(inputs.len() as u64, total as u64)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        Ok(hash_many(&inputs))
     }
 
-    async fn _hash<T>(
+    async fn _merkle_root<T>(
         &self,
         accessor: &Accessor<T, Runtime>,
-        input: String,
-    ) -> Result<(String, Vec<u8>)> {
-        Fuel::CryptoHash(input.len() as u64)
+        leaves: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        // one hash per leaf and one 65-byte node hash per inner node
+        let nodes = leaves.len().saturating_sub(1);
+        let total = leaves.iter().map(|leaf| leaf.len() + 1).sum::<usize>() + 65 * nodes;
+        Fuel::CryptoHashMany((leaves.len() + nodes).max(1) as u64, total as u64)
             .consume(accessor, self.gauge.as_ref())
             .await?;
-        let bs = hash_bytes(input.as_bytes());
-        let s = hex::encode(bs);
-        Ok((s, bs.to_vec()))
+        Ok(merkle_root(&leaves).to_vec())
     }
 
     async fn _hkdf_derive<T>(
@@ -1099,24 +2066,43 @@ impl Runtime {
         Ok(okm.to_vec())
     }
 
+    async fn _ct_eq<T>(
+        &self,
+        accessor: &Accessor<T, Runtime>,
+        a: Vec<u8>,
+        b: Vec<u8>,
+    ) -> Result<bool> {
+        Fuel::CryptoCtEq(a.len().max(b.len()) as u64)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        Ok(ct_eq(&a, &b))
+    }
+
     async fn _generate_id<T>(&self, accessor: &Accessor<T, Self>) -> Result<String> {
         Fuel::CryptoGenerateId
             .consume(accessor, self.gauge.as_ref())
             .await?;
+        self._next_id("").await
+    }
+
+    async fn _generate_id_with<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        domain: String,
+    ) -> Result<String> {
+        Fuel::CryptoGenerateIdWith(domain.len() as u64)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        self._next_id(&domain).await
+    }
+
+    async fn _next_id(&self, domain: &str) -> Result<String> {
         let count = self.id_generation_counter.get().await;
         self.id_generation_counter.increment().await;
-        Ok(hex::encode(
-            &hash_bytes(
-                &[
-                    self.txid
-                        .expect("txid is not set")
-                        .to_raw_hash()
-                        .to_byte_array()
-                        .to_vec(),
-                    count.to_le_bytes().to_vec(),
-                ]
-                .concat(),
-            )[0..8],
+        Ok(derive_id(
+            &self.txid.expect("txid is not set"),
+            count,
+            domain,
         ))
     }
 
@@ -1169,6 +2155,50 @@ impl Runtime {
         Ok(table.push(Transaction {})?)
     }
 
+    async fn _proc_prev_block_hash<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        _: Resource<ProcContext>,
+    ) -> Result<Vec<u8>> {
+        Fuel::ProcPrevBlockHash
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let prev_hash = self.storage.prev_block_hash().await?.ok_or(anyhow!(
+            "Previous block hash not found for height {}",
+            self.storage.height
+        ))?;
+        Ok(prev_hash.to_byte_array().to_vec())
+    }
+
+    /// The contract this context belongs to, which stays fixed even while a
+    /// nested call it made is on top of the stack.
+    async fn _proc_contract_address<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcContext>,
+    ) -> Result<ContractAddress> {
+        Fuel::ContractAddress
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let contract_id = self.table.lock().await.get(&self_)?.contract_id;
+        self.storage
+            .contract_address(contract_id)
+            .await?
+            .ok_or(anyhow!("Contract not found: {}", contract_id))
+    }
+
+    async fn _proc_deployer<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcContext>,
+    ) -> Result<Option<String>> {
+        Fuel::ProcDeployer
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let contract_id = self.table.lock().await.get(&self_)?.contract_id;
+        self.storage.deployer(contract_id).await
+    }
+
     async fn _proc_view_context<T>(
         &self,
         accessor: &Accessor<T, Self>,
@@ -1192,7 +2222,10 @@ impl Runtime {
             .await?;
         let mut table = self.table.lock().await;
         let contract_id = table.get(&self_)?.contract_id;
-        Ok(table.push(ViewStorage { contract_id })?)
+        Ok(table.push(ViewStorage {
+            contract_id,
+            public_only: false,
+        })?)
     }
 
     async fn _view_storage<T>(
@@ -1205,7 +2238,10 @@ impl Runtime {
             .await?;
         let mut table = self.table.lock().await;
         let contract_id = table.get(&self_)?.contract_id;
-        Ok(table.push(ViewStorage { contract_id })?)
+        Ok(table.push(ViewStorage {
+            contract_id,
+            public_only: false,
+        })?)
     }
 
     async fn _proc_storage<T>(
@@ -1226,15 +2262,7 @@ impl Runtime {
         accessor: &Accessor<T, Self>,
         self_: Resource<Keys>,
     ) -> Result<Option<String>> {
-        let k = self
-            .table
-            .lock()
-            .await
-            .get_mut(&self_)?
-            .stream
-            .next()
-            .await
-            .transpose()?;
+        let k = self.table.lock().await.get_mut(&self_)?.next().await?;
         if let Some(k) = &k {
             Fuel::KeysNext(k.len() as u64)
                 .consume(accessor, self.gauge.as_ref())
@@ -1243,6 +2271,98 @@ impl Runtime {
         Ok(k)
     }
 
+    async fn _truncated<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        self_: Resource<Keys>,
+    ) -> Result<bool> {
+        Fuel::KeysTruncated
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        Ok(self.table.lock().await.get_mut(&self_)?.truncated().await?)
+    }
+
+    async fn _entries_next<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        self_: Resource<Entries>,
+    ) -> Result<Option<(String, EntryValue)>> {
+        let fuel = accessor.with(|access| access.as_context().get_fuel())?;
+        let mut table = self.table.lock().await;
+        let entries = table.get_mut(&self_)?;
+        let Some((k, bs)) = entries.next().await? else {
+            return Ok(None);
+        };
+        let (size, number) = match entries.kind {
+            EntryKind::Integer | EntryKind::Decimal => {
+                let path = format!("{}.{}", entries.path, k);
+                let (number, size) = self.load_number(fuel, entries.contract_id, &path).await?;
+                (size, Some(number))
+            }
+            _ => (bs.len(), None),
+        };
+        Fuel::EntriesNext((k.len() + size) as u64)
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let value = match (entries.kind, number) {
+            (EntryKind::Str, _) => EntryValue::Str(deserialize(&bs)?),
+            (EntryKind::U64, _) => EntryValue::U64(deserialize(&bs)?),
+            (EntryKind::S64, _) => EntryValue::S64(deserialize(&bs)?),
+            (EntryKind::Bool, _) => EntryValue::Bool(deserialize(&bs)?),
+            (EntryKind::ListU8, _) => EntryValue::ListU8(deserialize(&bs)?),
+            (EntryKind::Integer, Some(n)) => EntryValue::Integer(n),
+            (EntryKind::Decimal, Some(n)) => EntryValue::Decimal(Decimal {
+                r0: n.r0,
+                r1: n.r1,
+                r2: n.r2,
+                r3: n.r3,
+                sign: n.sign,
+            }),
+            (_, None) => unreachable!("numbers are loaded above"),
+        };
+        Ok(Some((k, value)))
+    }
+
+    /// Reads the limbs and sign an `integer` or `decimal` record stores under
+    /// `path`, with the number of bytes read.
+    async fn load_number(
+        &self,
+        fuel: u64,
+        contract_id: i64,
+        path: &str,
+    ) -> Result<(Integer, usize)> {
+        let mut limbs = [0u64; 4];
+        let mut size = 0;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let bs = self
+                .state()
+                .get(fuel, contract_id, &format!("{}.r{}", path, i))
+                .await?
+                .ok_or_else(|| anyhow!("Number at {} is missing limb r{}", path, i))?;
+            size += bs.len();
+            *limb = deserialize(&bs)?;
+        }
+        let sign = if self
+            .state()
+            .exists(contract_id, &format!("{}.sign.minus", path))
+            .await?
+        {
+            NumericSign::Minus
+        } else {
+            NumericSign::Plus
+        };
+        Ok((
+            Integer {
+                r0: limbs[0],
+                r1: limbs[1],
+                r2: limbs[2],
+                r3: limbs[3],
+                sign,
+            },
+            size,
+        ))
+    }
+
     async fn _fall_signer<T>(
         &self,
         accessor: &Accessor<T, Self>,
@@ -1347,6 +2467,23 @@ impl Runtime {
             .expect("Failed to get contract address"))
     }
 
+    async fn _foreign_view_storage<T>(
+        &self,
+        accessor: &Accessor<T, Self>,
+        contract_address: &ContractAddress,
+    ) -> Result<Option<Resource<ViewStorage>>> {
+        Fuel::ForeignViewStorage
+            .consume(accessor, self.gauge.as_ref())
+            .await?;
+        let Some(contract_id) = self.storage.contract_id(contract_address).await? else {
+            return Ok(None);
+        };
+        Ok(Some(self.table.lock().await.push(ViewStorage {
+            contract_id,
+            public_only: true,
+        })?))
+    }
+
     async fn _drop<T: 'static>(&self, rep: Resource<T>) -> Result<()> {
         self.table.lock().await.delete(rep)?;
         Ok(())
@@ -1359,6 +2496,12 @@ impl HasData for Runtime {
 
 impl built_in::error::Host for Runtime {}
 
+impl built_in::error::HostWithStore for Runtime {
+    async fn trap<T>(_accessor: &Accessor<T, Self>, message: String) -> Result<()> {
+        Err(ExecutionError::Trap(message).into())
+    }
+}
+
 impl built_in::file_registry::Host for Runtime {}
 
 impl built_in::file_registry::HostFileDescriptor for Runtime {}
@@ -1491,6 +2634,23 @@ impl built_in::crypto::HostWithStore for Runtime {
             .await
     }
 
+    async fn hash_many<T>(
+        accessor: &Accessor<T, Self>,
+        inputs: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._hash_many(accessor, inputs)
+            .await
+    }
+
+    async fn merkle_root<T>(accessor: &Accessor<T, Self>, leaves: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._merkle_root(accessor, leaves)
+            .await
+    }
+
     async fn hkdf_derive<T>(
         accessor: &Accessor<T, Self>,
         ikm: Vec<u8>,
@@ -1502,6 +2662,13 @@ impl built_in::crypto::HostWithStore for Runtime {
             ._hkdf_derive(accessor, ikm, salt, info)
             .await
     }
+
+    async fn ct_eq<T>(accessor: &Accessor<T, Self>, a: Vec<u8>, b: Vec<u8>) -> Result<bool> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._ct_eq(accessor, a, b)
+            .await
+    }
 }
 
 impl built_in::foreign::Host for Runtime {}
@@ -1525,6 +2692,16 @@ impl built_in::foreign::HostWithStore for Runtime {
             ._get_contract_address(accessor)
             .await
     }
+
+    async fn view_storage<T>(
+        accessor: &Accessor<T, Self>,
+        contract_address: ContractAddress,
+    ) -> Result<Option<Resource<ViewStorage>>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._foreign_view_storage(accessor, &contract_address)
+            .await
+    }
 }
 
 impl built_in::context::Host for Runtime {}
@@ -1598,10 +2775,36 @@ impl built_in::context::HostViewStorageWithStore for Runtime {
         accessor: &Accessor<T, Self>,
         self_: Resource<ViewStorage>,
         path: String,
+        max_keys: Option<u64>,
+    ) -> Result<Resource<Keys>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._get_keys(accessor, self_, path, None, max_keys)
+            .await
+    }
+
+    async fn get_keys_after<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ViewStorage>,
+        path: String,
+        after: String,
+        max_keys: Option<u64>,
     ) -> Result<Resource<Keys>> {
         accessor
             .with(|mut access| access.get().clone())
-            ._get_keys(accessor, self_, path)
+            ._get_keys(accessor, self_, path, Some(after), max_keys)
+            .await
+    }
+
+    async fn get_entries<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ViewStorage>,
+        path: String,
+        kind: EntryKind,
+    ) -> Result<Resource<Entries>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._get_entries(accessor, self_, path, kind)
             .await
     }
 
@@ -1737,10 +2940,36 @@ impl built_in::context::HostProcStorageWithStore for Runtime {
         accessor: &Accessor<T, Self>,
         self_: Resource<ProcStorage>,
         path: String,
+        max_keys: Option<u64>,
+    ) -> Result<Resource<Keys>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._get_keys(accessor, self_, path, None, max_keys)
+            .await
+    }
+
+    async fn get_keys_after<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcStorage>,
+        path: String,
+        after: String,
+        max_keys: Option<u64>,
     ) -> Result<Resource<Keys>> {
         accessor
             .with(|mut access| access.get().clone())
-            ._get_keys(accessor, self_, path)
+            ._get_keys(accessor, self_, path, Some(after), max_keys)
+            .await
+    }
+
+    async fn get_entries<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcStorage>,
+        path: String,
+        kind: EntryKind,
+    ) -> Result<Resource<Entries>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._get_entries(accessor, self_, path, kind)
             .await
     }
 
@@ -1854,6 +3083,17 @@ impl built_in::context::HostProcStorageWithStore for Runtime {
             .await
     }
 
+    async fn delete_prefix<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcStorage>,
+        base_path: String,
+    ) -> Result<u64> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._delete_prefix(accessor, self_, base_path)
+            .await
+    }
+
     async fn view_storage<T>(
         accessor: &Accessor<T, Self>,
         self_: Resource<ProcStorage>,
@@ -1905,6 +3145,36 @@ impl built_in::context::HostProcContextWithStore for Runtime {
             .await
     }
 
+    async fn prev_block_hash<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcContext>,
+    ) -> Result<Vec<u8>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._proc_prev_block_hash(accessor, self_)
+            .await
+    }
+
+    async fn contract_address<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcContext>,
+    ) -> Result<ContractAddress> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._proc_contract_address(accessor, self_)
+            .await
+    }
+
+    async fn deployer<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<ProcContext>,
+    ) -> Result<Option<String>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._proc_deployer(accessor, self_)
+            .await
+    }
+
     async fn view_context<T>(
         accessor: &Accessor<T, Self>,
         self_: Resource<ProcContext>,
@@ -1925,6 +3195,17 @@ impl built_in::context::HostProcContextWithStore for Runtime {
             .await
     }
 
+    async fn generate_id_with<T>(
+        accessor: &Accessor<T, Self>,
+        _self: Resource<ProcContext>,
+        domain: String,
+    ) -> Result<String> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._generate_id_with(accessor, domain)
+            .await
+    }
+
     async fn storage<T>(
         accessor: &Accessor<T, Self>,
         self_: Resource<ProcContext>,
@@ -1955,6 +3236,34 @@ impl built_in::context::HostKeysWithStore for Runtime {
             ._next(accessor, self_)
             .await
     }
+
+    async fn truncated<T>(accessor: &Accessor<T, Self>, self_: Resource<Keys>) -> Result<bool> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._truncated(accessor, self_)
+            .await
+    }
+}
+
+impl built_in::context::HostEntries for Runtime {}
+
+impl built_in::context::HostEntriesWithStore for Runtime {
+    async fn drop<T>(accessor: &Accessor<T, Self>, rep: Resource<Entries>) -> Result<()> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._drop(rep)
+            .await
+    }
+
+    async fn next<T>(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<Entries>,
+    ) -> Result<Option<(String, EntryValue)>> {
+        accessor
+            .with(|mut access| access.get().clone())
+            ._entries_next(accessor, self_)
+            .await
+    }
 }
 
 impl built_in::context::HostFallContext for Runtime {}
@@ -2250,6 +3559,36 @@ impl built_in::numbers::HostWithStore for Runtime {
         Ok(numerics::decimal_to_integer(d))
     }
 
+    async fn decimal_to_scaled_integer<T>(
+        accessor: &Accessor<T, Self>,
+        d: Decimal,
+    ) -> Result<ScaledInteger> {
+        Fuel::NumbersDecimalToScaledInteger
+            .consume(
+                accessor,
+                accessor
+                    .with(|mut access| access.get().gauge.clone())
+                    .as_ref(),
+            )
+            .await?;
+        Ok(numerics::decimal_to_scaled_integer(d))
+    }
+
+    async fn scaled_integer_to_decimal<T>(
+        accessor: &Accessor<T, Self>,
+        s: ScaledInteger,
+    ) -> Result<Result<Decimal, Error>> {
+        Fuel::NumbersScaledIntegerToDecimal
+            .consume(
+                accessor,
+                accessor
+                    .with(|mut access| access.get().gauge.clone())
+                    .as_ref(),
+            )
+            .await?;
+        Ok(numerics::scaled_integer_to_decimal(s))
+    }
+
     async fn u64_to_decimal<T>(accessor: &Accessor<T, Self>, i: u64) -> Result<Decimal> {
         Fuel::NumbersU64ToDecimal
             .consume(
@@ -2406,6 +3745,23 @@ impl built_in::numbers::HostWithStore for Runtime {
         Ok(numerics::div_decimal(a, b))
     }
 
+    async fn div_decimal_rounded<T>(
+        accessor: &Accessor<T, Self>,
+        a: Decimal,
+        b: Decimal,
+        mode: RoundingMode,
+    ) -> Result<Result<Decimal, Error>> {
+        Fuel::NumbersDivDecimal
+            .consume(
+                accessor,
+                accessor
+                    .with(|mut access| access.get().gauge.clone())
+                    .as_ref(),
+            )
+            .await?;
+        Ok(numerics::div_decimal_rounded(a, b, mode))
+    }
+
     async fn log10_decimal<T>(
         accessor: &Accessor<T, Self>,
         a: Decimal,
@@ -10,7 +10,7 @@ use fastnum::{
 };
 use num::{BigInt, bigint::Sign};
 
-use super::{Decimal, Error, Integer, NumericOrdering, NumericSign};
+use super::{Decimal, Error, Integer, NumericOrdering, NumericSign, RoundingMode, ScaledInteger};
 
 const DECIMAL_18_DECS: D256 = dec256!(1_000_000_000_000_000_000);
 const MIN_DECIMAL: D256 = dec256!(0.000_000_000_000_000_001);
@@ -133,14 +133,37 @@ pub fn s64_to_integer(i: i64) -> Integer {
     }
 }
 
+/// Parses an `Integer` from `[+|-] digits` where `digits` is either decimal
+/// (`1_000`) or hex with a `0x`/`0X` prefix (`0xff`). Underscores may separate
+/// digits but may not lead, trail or repeat, and hex digits require the prefix.
 pub fn string_to_integer(s: &str) -> Result<Integer, Error> {
     let max_int = MAX_INT.clone();
-    let i = match s.parse::<BigInt>() {
-        Ok(i) => i,
-        Err(e) => {
-            return Err(Error::Syntax(e.to_string()));
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (radix, digits) = match unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+    {
+        Some(hex) => (16, hex),
+        None => (10, unsigned),
+    };
+    if digits.is_empty()
+        || digits.starts_with('_')
+        || digits.ends_with('_')
+        || digits.contains("__")
+        || !digits.chars().all(|c| c == '_' || c.is_digit(radix))
+    {
+        return Err(Error::Syntax(format!("invalid integer: {:?}", s)));
+    }
+    let magnitude = match BigInt::parse_bytes(digits.replace('_', "").as_bytes(), radix) {
+        Some(i) => i,
+        None => {
+            return Err(Error::Syntax(format!("invalid integer: {:?}", s)));
         }
     };
+    let i = if negative { -magnitude } else { magnitude };
     if i > max_int || i < -max_int {
         return Err(Error::Overflow("result overflows Integer".to_string()));
     }
@@ -213,8 +236,15 @@ pub fn div_integer(a: Integer, b: Integer) -> Result<Integer, Error> {
     Ok((big_a / big_b).into())
 }
 
+/// Floor of the square root. Negative inputs are rejected rather than left to
+/// trap the host inside `BigInt::sqrt`.
 pub fn sqrt_integer(i: Integer) -> Result<Integer, Error> {
     let big_i: BigInt = i.into();
+    if big_i.sign() == Sign::Minus {
+        return Err(Error::Validation(
+            "square root of negative integer".to_string(),
+        ));
+    }
     Ok(big_i.sqrt().into())
 }
 
@@ -234,6 +264,68 @@ pub fn decimal_to_integer(d: Decimal) -> Integer {
     big.into()
 }
 
+/// Number of decimal places a `Decimal` carries.
+pub const DECIMAL_SCALE: u32 = 18;
+
+/// The exact value of `d` as `mantissa * 10^-scale`, with `scale` always
+/// `DECIMAL_SCALE`.
+pub fn decimal_to_scaled_integer(d: Decimal) -> ScaledInteger {
+    ScaledInteger {
+        mantissa: Integer {
+            r0: d.r0,
+            r1: d.r1,
+            r2: d.r2,
+            r3: d.r3,
+            sign: d.sign,
+        },
+        scale: DECIMAL_SCALE,
+    }
+}
+
+/// Largest scale `scaled_integer_to_decimal` accepts. No 78-digit mantissa
+/// can drop more than 78 zeros, so anything above this overflows.
+pub const MAX_SCALE: u32 = DECIMAL_SCALE + 78;
+
+/// Inverse of `decimal_to_scaled_integer`. Any scale up to `MAX_SCALE` is
+/// accepted as long as the value fits in 18 decimal places without rounding.
+pub fn scaled_integer_to_decimal(s: ScaledInteger) -> Result<Decimal, Error> {
+    if s.scale > MAX_SCALE {
+        return Err(Error::Overflow(format!(
+            "scale {} exceeds the maximum of {}",
+            s.scale, MAX_SCALE
+        )));
+    }
+    let mantissa: BigInt = s.mantissa.into();
+    let units = if s.scale <= DECIMAL_SCALE {
+        mantissa * BigInt::from(10u8).pow(DECIMAL_SCALE - s.scale)
+    } else {
+        let divisor = BigInt::from(10u8).pow(s.scale - DECIMAL_SCALE);
+        if &mantissa % &divisor != BigInt::ZERO {
+            return Err(Error::Validation(format!(
+                "scaled integer has more than {} decimal places",
+                DECIMAL_SCALE
+            )));
+        }
+        mantissa / divisor
+    };
+    units_to_decimal(units)
+}
+
+/// The `Decimal` whose value is `units * 10^-DECIMAL_SCALE`.
+fn units_to_decimal(units: BigInt) -> Result<Decimal, Error> {
+    if units > *MAX_INT || units < -MAX_INT.clone() {
+        return Err(Error::Overflow("result overflows Decimal".to_string()));
+    }
+    let i: Integer = units.into();
+    Ok(Decimal {
+        r0: i.r0,
+        r1: i.r1,
+        r2: i.r2,
+        r3: i.r3,
+        sign: i.sign,
+    })
+}
+
 fn num_to_decimal(n: impl Into<D256>) -> Decimal {
     let dec: D256 = n.into();
     let res = dec.with_ctx(CTX).quantize(MIN_DECIMAL);
@@ -344,8 +436,48 @@ pub fn div_decimal(a: Decimal, b: Decimal) -> Result<Decimal, Error> {
     Ok(res.into())
 }
 
+/// Divides exactly in units of the last decimal place and rounds the
+/// quotient once, from the remainder, to `DECIMAL_SCALE` places. `Down` and
+/// `Up` round toward and away from zero.
+pub fn div_decimal_rounded(a: Decimal, b: Decimal, mode: RoundingMode) -> Result<Decimal, Error> {
+    let big_a: BigInt = decimal_to_scaled_integer(a).mantissa.into();
+    let big_b: BigInt = decimal_to_scaled_integer(b).mantissa.into();
+    if big_b == BigInt::ZERO {
+        return Err(Error::DivByZero("decimal divide by zero".to_string()));
+    }
+    let numerator = big_a * BigInt::from(10u8).pow(DECIMAL_SCALE);
+    // `/` truncates toward zero and `%` takes the sign of the numerator
+    let quotient = &numerator / &big_b;
+    let remainder = &numerator % &big_b;
+    let away_from_zero = remainder != BigInt::ZERO
+        && match mode {
+            RoundingMode::Down => false,
+            RoundingMode::Up => true,
+            RoundingMode::HalfUp => remainder.magnitude() * 2u8 >= *big_b.magnitude(),
+            RoundingMode::HalfEven => match (remainder.magnitude() * 2u8).cmp(big_b.magnitude()) {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => &quotient % 2u8 != BigInt::ZERO,
+            },
+        };
+    let units = if !away_from_zero {
+        quotient
+    } else if (numerator.sign() == Sign::Minus) != (big_b.sign() == Sign::Minus) {
+        quotient - 1
+    } else {
+        quotient + 1
+    };
+    units_to_decimal(units)
+}
+
+/// Base-10 logarithm. Only defined for positive inputs.
 pub fn log10_decimal(a: Decimal) -> Result<Decimal, Error> {
     let dec_a: D256 = a.into();
+    if dec_a <= D256::ZERO {
+        return Err(Error::Validation(
+            "logarithm of non-positive decimal".to_string(),
+        ));
+    }
     let res = (dec_a.log10()).with_ctx(CTX).quantize(MIN_DECIMAL);
     if res.is_op_invalid() {
         return Err(Error::Overflow("invalid decimal number".to_string()));
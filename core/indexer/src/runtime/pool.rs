@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use anyhow::Context;
-use deadpool::managed::{self, Pool, RecycleResult};
+use deadpool::managed::{self, Pool, RecycleError, RecycleResult};
 use thiserror::Error;
 use wasmtime::{Engine, component::Linker};
 
@@ -18,21 +18,46 @@ pub enum RuntimeError {
     DatabaseConnection(String),
 }
 
+/// Sizing of the pool of read-only runtimes serving view calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Maximum number of runtimes, each holding its own connection.
+    pub max_size: usize,
+    /// Runtimes left idle for longer are dropped and recreated on their next
+    /// checkout rather than reused.
+    pub max_idle: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            max_idle: None,
+        }
+    }
+}
+
 pub struct Manager {
     data_dir: PathBuf,
     filename: String,
+    max_idle: Option<Duration>,
     engine: Engine,
     linker: Linker<Runtime>,
     component_cache: ComponentCache,
 }
 
 impl Manager {
-    pub fn new(data_dir: PathBuf, filename: String) -> anyhow::Result<Self> {
+    pub fn new(
+        data_dir: PathBuf,
+        filename: String,
+        max_idle: Option<Duration>,
+    ) -> anyhow::Result<Self> {
         let engine = Runtime::new_engine()?;
         let linker = Runtime::new_linker(&engine)?;
         Ok(Self {
             data_dir,
             filename,
+            max_idle,
             engine,
             linker,
             component_cache: ComponentCache::new(),
@@ -45,7 +70,7 @@ impl managed::Manager for Manager {
     type Error = RuntimeError;
 
     async fn create(&self) -> Result<Self::Type, Self::Error> {
-        Runtime::new_read_only(
+        let mut runtime = Runtime::new_read_only(
             self.engine.clone(),
             self.linker.clone(),
             self.component_cache.clone(),
@@ -54,21 +79,40 @@ impl managed::Manager for Manager {
                 .map_err(|e| RuntimeError::DatabaseConnection(e.to_string()))?,
         )
         .await
-        .map_err(|e| RuntimeError::CreationFailed(e.to_string()))
+        .map_err(|e| RuntimeError::CreationFailed(e.to_string()))?;
+        runtime.enable_view_cache();
+        runtime
+            .follow_latest_block()
+            .await
+            .map_err(|e| RuntimeError::CreationFailed(e.to_string()))?;
+        Ok(runtime)
     }
 
+    /// Also moves the runtime to the latest processed block, so cached views
+    /// never outlive the block they were computed against.
     async fn recycle(
         &self,
-        _obj: &mut Self::Type,
-        _metrics: &deadpool::managed::Metrics,
+        obj: &mut Self::Type,
+        metrics: &deadpool::managed::Metrics,
     ) -> RecycleResult<Self::Error> {
-        Ok(())
+        if let Some(max_idle) = self.max_idle
+            && metrics.last_used() > max_idle
+        {
+            return Err(RecycleError::Message("Runtime idle for too long".into()));
+        }
+        obj.follow_latest_block()
+            .await
+            .map_err(|e| RecycleError::Message(format!("Failed to follow the chain: {}", e).into()))
     }
 }
 
-pub async fn new(data_dir: PathBuf, filename: String) -> anyhow::Result<Pool<Manager>> {
-    Pool::builder(Manager::new(data_dir, filename)?)
-        .max_size(std::thread::available_parallelism()?.into())
+pub async fn new(
+    data_dir: PathBuf,
+    filename: String,
+    config: PoolConfig,
+) -> anyhow::Result<Pool<Manager>> {
+    Pool::builder(Manager::new(data_dir, filename, config.max_idle)?)
+        .max_size(config.max_size)
         .build()
         .context("Failed to build runtime pool")
 }
@@ -0,0 +1,62 @@
+use wasmtime::component::{ResourceType, Type};
+
+use super::{
+    args::type_name,
+    wit::{CoreContext, FallContext, ProcContext, ViewContext},
+};
+
+/// How an exported function is called, decided by its context parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuncKind {
+    Proc,
+    View,
+    Fallback,
+    Core,
+}
+
+impl FuncKind {
+    pub fn from_context_type(ty: &Type) -> Option<Self> {
+        let Type::Borrow(t) = ty else {
+            return None;
+        };
+        if t.eq(&ResourceType::host::<ProcContext>()) {
+            Some(FuncKind::Proc)
+        } else if t.eq(&ResourceType::host::<ViewContext>()) {
+            Some(FuncKind::View)
+        } else if t.eq(&ResourceType::host::<FallContext>()) {
+            Some(FuncKind::Fallback)
+        } else if t.eq(&ResourceType::host::<CoreContext>()) {
+            Some(FuncKind::Core)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncSig {
+    pub name: String,
+    pub kind: FuncKind,
+    /// Parameter names and types, excluding the context parameter.
+    pub params: Vec<(String, String)>,
+    pub result: Option<String>,
+}
+
+impl FuncSig {
+    pub fn new<'a>(
+        name: &str,
+        params: impl IntoIterator<Item = (&'a str, Type)>,
+        mut results: impl Iterator<Item = Type>,
+    ) -> Option<Self> {
+        let mut params = params.into_iter();
+        let (_, ctx) = params.next()?;
+        Some(FuncSig {
+            name: name.to_string(),
+            kind: FuncKind::from_context_type(&ctx)?,
+            params: params
+                .map(|(name, ty)| (name.to_string(), type_name(&ty)))
+                .collect(),
+            result: results.next().map(|ty| type_name(&ty)),
+        })
+    }
+}
@@ -10,6 +10,15 @@ pub enum StackError {
     CycleDetected(String),
 }
 
+/// The chain of contract calls that led to a failure, outermost first, with
+/// each frame written as `contract.function`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("in {}: {message}", .frames.join(" → "))]
+pub struct CallTrace {
+    pub frames: Vec<String>,
+    pub message: String,
+}
+
 #[derive(Clone, Debug, Builder)]
 pub struct Stack<T> {
     #[builder(default = Arc::new(Mutex::new(Vec::new())))]
@@ -49,6 +58,12 @@ impl<T: Send + PartialEq + Debug + Clone> Stack<T> {
         stack.last().cloned()
     }
 
+    /// Items from the bottom of the stack to the top.
+    pub async fn items(&self) -> Vec<T> {
+        let stack = self.inner.lock().await;
+        stack.clone()
+    }
+
     pub async fn is_empty(&self) -> bool {
         let stack = self.inner.lock().await;
         stack.is_empty()
@@ -1,24 +1,73 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Result, anyhow, bail};
+use bitcoin::BlockHash;
 use bon::Builder;
 use futures_util::Stream;
+use indexer_types::{BlockRow, ContractSchema, GasStats};
 use libsql::Connection;
 use regex::bytes::RegexBuilder;
-use std::io::Read;
-use wit_component::{ComponentEncoder, WitPrinter};
+use wit_component::{ComponentEncoder, DecodedWasm, WitPrinter};
+use wit_parser::Resolve;
+use wit_validator::Validator;
 
 use crate::{
     database::{
         queries::{
-            delete_contract_state, delete_matching_paths, exists_contract_state,
-            get_contract_address_from_id, get_contract_bytes_by_id, get_contract_id_from_address,
-            get_latest_contract_state_value, insert_contract, insert_contract_result,
-            insert_contract_state, matching_path, path_prefix_filter_contract_state,
+            contract_has_state, count_path_prefix, delete_contract_state, delete_matching_paths,
+            delete_path_prefix, exists_contract_state, get_block_gas_usage,
+            get_contract_address_from_id, get_contract_bytes_by_id, get_contract_deployer_by_id,
+            get_contract_gas_stats, get_contract_hash_by_id, get_contract_id_from_address,
+            get_contract_results_by_status, get_contract_results_in_range,
+            get_contract_state_value_at_height, get_contracts, get_contracts_by_hash,
+            get_latest_contract_state_value, insert_contract, insert_contract_deprecation,
+            insert_contract_result, insert_contract_state, is_contract_deprecated,
+            is_contract_initialized, matching_path, path_prefix_filter_contract_state_after,
+            select_block_at_height, select_block_latest, select_contract_state_version,
+            set_contract_initialized,
         },
         types::{ContractResultRow, ContractRow, ContractStateRow},
     },
-    runtime::{ContractAddress, counter::Counter, stack::Stack},
+    runtime::{Compression, ContractAddress, counter::Counter, stack::Stack},
 };
 
+pub const DEFAULT_MAX_VALUE_SIZE: usize = 64 * 1024;
+
+/// Most writes and deletes a single operation may perform. Every node must
+/// agree on it, so it is a protocol constant rather than node configuration.
+pub const MAX_STORAGE_WRITES_PER_TX: u64 = 10_000;
+
+/// First block whose operations are held to [`MAX_STORAGE_WRITES_PER_TX`], so
+/// blocks before it replay as they always have.
+pub const STORAGE_WRITE_LIMIT_ACTIVATION_HEIGHT: i64 = 980_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WitError {
+    #[error("Contract has no WIT section")]
+    Missing,
+    #[error("Contract has a malformed WIT section: {0}")]
+    Malformed(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+fn has_wit_section(module_bytes: &[u8]) -> Result<bool, WitError> {
+    for payload in wasmparser::Parser::new(0).parse_all(module_bytes) {
+        if let wasmparser::Payload::CustomSection(reader) =
+            payload.map_err(|e| WitError::Malformed(e.to_string()))?
+            && reader.name().starts_with("component-type")
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub(crate) fn encode_component(module_bytes: &[u8]) -> Result<Vec<u8>> {
+    ComponentEncoder::default()
+        .module(module_bytes)?
+        .validate(true)
+        .encode()
+}
+
 #[derive(Builder, Clone)]
 pub struct Storage {
     pub conn: Connection,
@@ -34,6 +83,20 @@ pub struct Storage {
     pub input_index: i64,
     #[builder(default = 0)]
     pub op_index: i64,
+    #[builder(default = DEFAULT_MAX_VALUE_SIZE)]
+    pub max_value_size: usize,
+    /// Caps the number of writes and deletes a single operation may perform,
+    /// independent of fuel.
+    #[builder(default = MAX_STORAGE_WRITES_PER_TX)]
+    pub max_writes_per_tx: u64,
+    /// Height from which `max_writes_per_tx` applies.
+    #[builder(default = STORAGE_WRITE_LIMIT_ACTIVATION_HEIGHT)]
+    pub write_limit_activation_height: i64,
+    #[builder(default = Counter::builder().build())]
+    pub write_counter: Counter,
+    /// Used to decompress contract bytes when loading a contract.
+    #[builder(default)]
+    pub compression: Compression,
 }
 
 impl Storage {
@@ -41,7 +104,26 @@ impl Storage {
         Ok(get_latest_contract_state_value(&self.conn, fuel, contract_id, path).await?)
     }
 
+    /// Reads `path` as it stood after block `height` was processed.
+    pub async fn get_at_height(
+        &self,
+        contract_id: i64,
+        path: &str,
+        height: i64,
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(get_contract_state_value_at_height(&self.conn, contract_id, path, height).await?)
+    }
+
     pub async fn set(&self, contract_id: i64, path: &str, value: &[u8]) -> Result<()> {
+        if value.len() > self.max_value_size {
+            bail!(
+                "Storage value at {} is {} bytes, exceeding the maximum of {} bytes",
+                path,
+                value.len(),
+                self.max_value_size
+            );
+        }
+        self.count_write().await?;
         insert_contract_state(
             &self.conn,
             ContractStateRow::builder()
@@ -57,12 +139,28 @@ impl Storage {
     }
 
     pub async fn delete(&self, contract_id: i64, path: &str) -> Result<bool> {
+        self.count_write().await?;
         Ok(
             delete_contract_state(&self.conn, self.height, self.tx_index, contract_id, path)
                 .await?,
         )
     }
 
+    async fn count_write(&self) -> Result<()> {
+        self.count_writes(1).await
+    }
+
+    async fn count_writes(&self, n: u64) -> Result<()> {
+        let writes = self.write_counter.add(n).await;
+        if self.height >= self.write_limit_activation_height && writes > self.max_writes_per_tx {
+            bail!(
+                "Storage write limit of {} per transaction exceeded",
+                self.max_writes_per_tx
+            );
+        }
+        Ok(())
+    }
+
     pub async fn exists(&self, contract_id: i64, path: &str) -> Result<bool> {
         Ok(exists_contract_state(&self.conn, contract_id, path).await?)
     }
@@ -80,6 +178,37 @@ impl Storage {
         Ok(delete_matching_paths(&self.conn, contract_id, self.height, regexp).await?)
     }
 
+    /// The number of paths stored under `path`.
+    pub async fn count_prefix(&self, contract_id: i64, path: &str) -> Result<u64> {
+        Ok(count_path_prefix(&self.conn, contract_id, path).await?)
+    }
+
+    /// Tombstones everything stored under `path`, returning how many paths
+    /// were tombstoned and how many direct children (map keys) were removed.
+    /// Counts as a write per path, and at least one, checked before anything
+    /// is tombstoned.
+    pub async fn delete_prefix(&self, contract_id: i64, path: &str) -> Result<(u64, u64)> {
+        let paths = self.count_prefix(contract_id, path).await?;
+        self.count_writes(paths.max(1)).await?;
+        Ok(delete_path_prefix(&self.conn, self.height, self.tx_index, contract_id, path).await?)
+    }
+
+    /// Monotonic counter bumped whenever a committed write or delete touches
+    /// the contract's state, `0` if it has never been written.
+    pub async fn state_version(&self, contract_id: i64) -> Result<i64> {
+        Ok(select_contract_state_version(&self.conn, contract_id).await?)
+    }
+
+    pub async fn prev_block_hash(&self) -> Result<Option<BlockHash>> {
+        Ok(select_block_at_height(&self.conn, self.height)
+            .await?
+            .and_then(|block| block.prev_hash))
+    }
+
+    pub async fn latest_block(&self) -> Result<Option<BlockRow>> {
+        Ok(select_block_latest(&self.conn).await?)
+    }
+
     pub async fn contract_id(&self, contract_address: &ContractAddress) -> Result<Option<i64>> {
         Ok(get_contract_id_from_address(&self.conn, contract_address).await?)
     }
@@ -88,47 +217,128 @@ impl Storage {
         Ok(get_contract_address_from_id(&self.conn, contract_id).await?)
     }
 
+    /// Every deployed contract's address and id, in deployment order.
+    pub async fn list_contracts(&self) -> Result<Vec<(ContractAddress, i64)>> {
+        let mut contracts = get_contracts(&self.conn)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    ContractAddress {
+                        name: row.name,
+                        height: row.height,
+                        tx_index: row.tx_index,
+                    },
+                    row.id,
+                )
+            })
+            .collect::<Vec<_>>();
+        contracts.sort_by_key(|(address, id)| (address.height, address.tx_index, *id));
+        Ok(contracts)
+    }
+
+    /// The sha256 of a contract's published bytes.
+    pub async fn contract_hash(&self, contract_id: i64) -> Result<Option<[u8; 32]>> {
+        get_contract_hash_by_id(&self.conn, contract_id)
+            .await?
+            .map(|hash| {
+                hash.try_into()
+                    .map_err(|_| anyhow!("Contract hash is not 32 bytes"))
+            })
+            .transpose()
+    }
+
+    /// Every contract published with the given content hash, in deployment
+    /// order, so the first entry is the original deployment.
+    pub async fn contract_by_hash(&self, hash: &[u8; 32]) -> Result<Vec<(ContractAddress, i64)>> {
+        Ok(get_contracts_by_hash(&self.conn, hash)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    ContractAddress {
+                        name: row.name,
+                        height: row.height,
+                        tx_index: row.tx_index,
+                    },
+                    row.id,
+                )
+            })
+            .collect())
+    }
+
     pub async fn contract_bytes(&self, contract_id: i64) -> Result<Option<Vec<u8>>> {
         Ok(get_contract_bytes_by_id(&self.conn, contract_id).await?)
     }
 
-    pub async fn component_bytes(&self, contract_id: i64) -> Result<Vec<u8>> {
+    pub async fn module_bytes(&self, contract_id: i64) -> Result<Vec<u8>> {
         let compressed_bytes = self
             .contract_bytes(contract_id)
             .await?
             .ok_or(anyhow!("Contract not found when trying to load component"))?;
-        let module_bytes = tokio::task::spawn_blocking(move || {
-            let mut decompressor = brotli::Decompressor::new(&compressed_bytes[..], 4096);
-            let mut module_bytes = Vec::new();
-            decompressor.read_to_end(&mut module_bytes)?;
-            Ok::<_, std::io::Error>(module_bytes)
+        let compression = self.compression;
+        Ok(
+            tokio::task::spawn_blocking(move || compression.decompress(&compressed_bytes))
+                .await??,
+        )
+    }
+
+    pub async fn component_bytes(&self, contract_id: i64) -> Result<Vec<u8>> {
+        let module_bytes = self.module_bytes(contract_id).await?;
+        encode_component(&module_bytes)
+    }
+
+    async fn decode_component(&self, contract_id: i64) -> Result<DecodedWasm, WitError> {
+        let module_bytes = self.module_bytes(contract_id).await?;
+        if !has_wit_section(&module_bytes)? {
+            return Err(WitError::Missing);
+        }
+        let bs = encode_component(&module_bytes).map_err(|e| WitError::Malformed(e.to_string()))?;
+        wit_component::decode(&bs).map_err(|e| WitError::Malformed(e.to_string()))
+    }
+
+    /// The contract's parsed WIT, for callers that need more than
+    /// [`Storage::component_schema`] exposes.
+    pub async fn component_resolve(&self, contract_id: i64) -> Result<Resolve, WitError> {
+        Ok(match self.decode_component(contract_id).await? {
+            DecodedWasm::WitPackage(resolve, _) | DecodedWasm::Component(resolve, _) => resolve,
         })
-        .await??;
+    }
 
-        ComponentEncoder::default()
-            .module(&module_bytes)?
-            .validate(true)
-            .encode()
+    /// The contract's exported functions and the types it defines.
+    pub async fn component_schema(&self, contract_id: i64) -> Result<ContractSchema, WitError> {
+        let resolve = self.component_resolve(contract_id).await?;
+        serde_json::from_str(&Validator::schema_resolve(&resolve))
+            .map_err(|e| WitError::Malformed(e.to_string()))
     }
 
-    pub async fn component_wit(&self, contract_id: i64) -> Result<String> {
-        let bs = self.component_bytes(contract_id).await?;
-        let decoded = wit_component::decode(&bs).context("Failed to decode component")?;
+    pub async fn component_wit(&self, contract_id: i64) -> Result<String, WitError> {
+        let decoded = self.decode_component(contract_id).await?;
         let mut printer = WitPrinter::default();
         printer
             .print(decoded.resolve(), decoded.package(), &[])
-            .context("Failed to print component")?;
+            .map_err(|e| WitError::Malformed(e.to_string()))?;
         let wit = format!("{}", printer.output);
         // regexr.com/8i6dk
         let re = RegexBuilder::new(r"(\n^.*(borrow<core-context>|export init:|\{\s*core-context\s*\}).*$|[,]{0,1}\s*core-context[,]{0,1}\s*)")
             .multi_line(true)
-            .build()?;
+            .build()
+            .map_err(anyhow::Error::from)?;
         let wit =
             String::from_utf8_lossy(&re.replace_all(wit.as_bytes(), "".as_bytes())).into_owned();
         Ok(wit)
     }
 
     pub async fn insert_contract(&self, name: &str, bytes: &[u8]) -> Result<i64> {
+        self.insert_contract_with_deployer(name, bytes, None).await
+    }
+
+    pub async fn insert_contract_with_deployer(
+        &self,
+        name: &str,
+        bytes: &[u8],
+        deployer: Option<&str>,
+    ) -> Result<i64> {
         Ok(insert_contract(
             &self.conn,
             ContractRow::builder()
@@ -136,11 +346,59 @@ impl Storage {
                 .tx_index(self.tx_index)
                 .name(name.to_string())
                 .bytes(bytes.to_vec())
+                .maybe_deployer(deployer.map(str::to_string))
                 .build(),
         )
         .await?)
     }
 
+    /// Swaps in new code for the contract from the current height on; its
+    /// storage is keyed by id and so carries over unchanged.
+    pub async fn replace_contract_bytes(&self, contract_id: i64, bytes: &[u8]) -> Result<()> {
+        Ok(
+            insert_contract_code_version(
+                &self.conn,
+                contract_id,
+                self.height,
+                self.tx_index,
+                bytes,
+            )
+            .await?,
+        )
+    }
+
+    pub async fn deployer(&self, contract_id: i64) -> Result<Option<String>> {
+        Ok(get_contract_deployer_by_id(&self.conn, contract_id).await?)
+    }
+
+    /// Marks the contract deprecated as of the current height, so a rollback
+    /// past it restores the contract.
+    pub async fn deprecate(&self, contract_id: i64) -> Result<()> {
+        Ok(
+            insert_contract_deprecation(&self.conn, contract_id, self.height, self.tx_index)
+                .await?,
+        )
+    }
+
+    pub async fn is_deprecated(&self, contract_id: i64) -> Result<bool> {
+        Ok(is_contract_deprecated(&self.conn, contract_id).await?)
+    }
+
+    /// Records that the contract's `init` succeeded. The flag lives on the
+    /// contract's row, so it goes away with the publish on a rollback.
+    pub async fn set_initialized(&self, contract_id: i64) -> Result<()> {
+        Ok(set_contract_initialized(&self.conn, contract_id).await?)
+    }
+
+    pub async fn is_initialized(&self, contract_id: i64) -> Result<bool> {
+        Ok(is_contract_initialized(&self.conn, contract_id).await?)
+    }
+
+    /// Gas consumed by the operations at `height` and how many there were.
+    pub async fn block_gas_usage(&self, height: i64) -> Result<(u64, u64)> {
+        Ok(get_block_gas_usage(&self.conn, height).await?)
+    }
+
     pub fn build_contract_result_row(
         &self,
         result_index: i64,
@@ -148,6 +406,7 @@ impl Storage {
         func: String,
         gas: i64,
         value: Option<String>,
+        error_trace: Option<String>,
     ) -> ContractResultRow {
         ContractResultRow::builder()
             .contract_id(contract_id)
@@ -159,6 +418,7 @@ impl Storage {
             .func(func)
             .gas(gas)
             .maybe_value(value)
+            .maybe_error_trace(error_trace)
             .build()
     }
 
@@ -169,20 +429,91 @@ impl Storage {
         func: String,
         gas: i64,
         value: Option<String>,
+        error_trace: Option<String>,
     ) -> Result<i64> {
         Ok(insert_contract_result(
             &self.conn,
-            self.build_contract_result_row(result_index, contract_id, func, gas, value),
+            self.build_contract_result_row(
+                result_index,
+                contract_id,
+                func,
+                gas,
+                value,
+                error_trace,
+            ),
         )
         .await?)
     }
 
+    pub async fn results_in_range(
+        &self,
+        from_height: i64,
+        to_height: i64,
+    ) -> Result<Vec<ContractResultRow>> {
+        Ok(get_contract_results_in_range(&self.conn, from_height, to_height).await?)
+    }
+
+    /// Whether the contract has written any storage. Contracts set up their
+    /// state in `init`, so this tells whether it has run.
+    pub async fn has_state(&self, contract_id: i64) -> Result<bool> {
+        Ok(contract_has_state(&self.conn, contract_id).await?)
+    }
+
+    /// Recorded results of a contract's calls that failed, or succeeded when
+    /// `failed` is false.
+    pub async fn results_by_status(
+        &self,
+        contract_id: i64,
+        failed: bool,
+    ) -> Result<Vec<ContractResultRow>> {
+        self.results_by_status_in_range(contract_id, failed, 0, i64::MAX)
+            .await
+    }
+
+    pub async fn results_by_status_in_range(
+        &self,
+        contract_id: i64,
+        failed: bool,
+        from_height: i64,
+        to_height: i64,
+    ) -> Result<Vec<ContractResultRow>> {
+        Ok(
+            get_contract_results_by_status(&self.conn, contract_id, failed, from_height, to_height)
+                .await?,
+        )
+    }
+
+    /// Gas consumed by the operations that called a contract directly, not
+    /// counting the calls other contracts made to it. Results are deleted with
+    /// their block, so rolled back calls drop out of the totals.
+    pub async fn gas_stats(&self, contract_id: i64) -> Result<GasStats> {
+        Ok(get_contract_gas_stats(&self.conn, contract_id).await?)
+    }
+
     pub async fn keys(
         &self,
         contract_id: i64,
         path: String,
     ) -> Result<impl Stream<Item = Result<String, libsql::Error>> + Send + 'static> {
-        Ok(path_prefix_filter_contract_state(&self.conn, contract_id, path).await?)
+        self.keys_after(contract_id, path, None).await
+    }
+
+    /// The keys under `path` ordered after the (encoded) segment `after`.
+    pub async fn keys_after(
+        &self,
+        contract_id: i64,
+        path: String,
+        after: Option<String>,
+    ) -> Result<impl Stream<Item = Result<String, libsql::Error>> + Send + 'static> {
+        Ok(path_prefix_filter_contract_state_after(&self.conn, contract_id, path, after).await?)
+    }
+
+    pub async fn entries(
+        &self,
+        contract_id: i64,
+        path: String,
+    ) -> Result<impl Stream<Item = Result<(String, Vec<u8>), libsql::Error>> + Send + 'static> {
+        Ok(path_prefix_entries_contract_state(&self.conn, contract_id, path).await?)
     }
 
     pub async fn savepoint(&self) -> Result<()> {
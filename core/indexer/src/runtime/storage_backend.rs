@@ -0,0 +1,353 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    pin::Pin,
+    sync::Arc,
+};
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use futures_util::{Stream, TryStreamExt, stream};
+use regex::Regex;
+use tokio::sync::Mutex;
+
+use crate::{
+    database::queries::Error as QueryError,
+    runtime::{DEFAULT_MAX_VALUE_SIZE, Storage},
+};
+
+pub type KeyStream = Pin<Box<dyn Stream<Item = Result<String, libsql::Error>> + Send>>;
+
+pub type EntryStream = Pin<Box<dyn Stream<Item = Result<(String, Vec<u8>), libsql::Error>> + Send>>;
+
+/// The contract state operations host functions rely on, so they can run
+/// against SQLite in production or an in-memory map in unit tests.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// The value at `path`, failing if it is larger than `fuel` bytes.
+    async fn get(&self, fuel: u64, contract_id: i64, path: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn set(&self, contract_id: i64, path: &str, value: &[u8]) -> Result<()>;
+
+    /// The distinct path segments directly under `path` ordered after
+    /// `after`.
+    async fn keys_after(
+        &self,
+        contract_id: i64,
+        path: &str,
+        after: Option<String>,
+    ) -> Result<KeyStream>;
+
+    /// The distinct path segments directly under `path`, in order.
+    async fn keys(&self, contract_id: i64, path: &str) -> Result<Vec<String>> {
+        Ok(self
+            .keys_after(contract_id, path, None)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    /// The segments and values of the paths directly under `path`, in order.
+    async fn entries(&self, contract_id: i64, path: &str) -> Result<EntryStream>;
+
+    /// Whether anything is stored at or under `path`.
+    async fn exists(&self, contract_id: i64, path: &str) -> Result<bool>;
+
+    /// A path under `path` matching `regexp`, if there is one.
+    async fn extend_path_with_match(
+        &self,
+        contract_id: i64,
+        path: &str,
+        regexp: &str,
+    ) -> Result<Option<String>>;
+
+    /// Removes the value at `path`, returning whether there was one.
+    async fn delete(&self, contract_id: i64, path: &str) -> Result<bool>;
+
+    /// Removes the paths matching `regexp`, returning how many there were.
+    async fn delete_matching_paths(&self, contract_id: i64, regexp: &str) -> Result<u64>;
+
+    /// The number of paths under `path`.
+    async fn count_prefix(&self, contract_id: i64, path: &str) -> Result<u64>;
+
+    /// Removes everything under `path`, returning how many paths were removed
+    /// and how many direct children they had.
+    async fn delete_prefix(&self, contract_id: i64, path: &str) -> Result<(u64, u64)>;
+
+    /// Whether the contract has ever stored anything.
+    async fn has_state(&self, contract_id: i64) -> Result<bool>;
+
+    async fn savepoint(&self) -> Result<()>;
+
+    /// Keeps the writes made since the last savepoint.
+    async fn commit(&self) -> Result<()>;
+
+    /// Discards the writes made since the last savepoint.
+    async fn rollback(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl StorageBackend for Storage {
+    async fn get(&self, fuel: u64, contract_id: i64, path: &str) -> Result<Option<Vec<u8>>> {
+        Storage::get(self, fuel, contract_id, path).await
+    }
+
+    async fn set(&self, contract_id: i64, path: &str, value: &[u8]) -> Result<()> {
+        Storage::set(self, contract_id, path, value).await
+    }
+
+    async fn keys_after(
+        &self,
+        contract_id: i64,
+        path: &str,
+        after: Option<String>,
+    ) -> Result<KeyStream> {
+        Ok(Box::pin(
+            Storage::keys_after(self, contract_id, path.to_string(), after).await?,
+        ))
+    }
+
+    async fn entries(&self, contract_id: i64, path: &str) -> Result<EntryStream> {
+        Ok(Box::pin(
+            Storage::entries(self, contract_id, path.to_string()).await?,
+        ))
+    }
+
+    async fn exists(&self, contract_id: i64, path: &str) -> Result<bool> {
+        Storage::exists(self, contract_id, path).await
+    }
+
+    async fn extend_path_with_match(
+        &self,
+        contract_id: i64,
+        path: &str,
+        regexp: &str,
+    ) -> Result<Option<String>> {
+        Storage::extend_path_with_match(self, contract_id, path, regexp).await
+    }
+
+    async fn delete(&self, contract_id: i64, path: &str) -> Result<bool> {
+        Storage::delete(self, contract_id, path).await
+    }
+
+    async fn delete_matching_paths(&self, contract_id: i64, regexp: &str) -> Result<u64> {
+        Storage::delete_matching_paths(self, contract_id, regexp).await
+    }
+
+    async fn count_prefix(&self, contract_id: i64, path: &str) -> Result<u64> {
+        Storage::count_prefix(self, contract_id, path).await
+    }
+
+    async fn delete_prefix(&self, contract_id: i64, path: &str) -> Result<(u64, u64)> {
+        Storage::delete_prefix(self, contract_id, path).await
+    }
+
+    async fn has_state(&self, contract_id: i64) -> Result<bool> {
+        Storage::has_state(self, contract_id).await
+    }
+
+    async fn savepoint(&self) -> Result<()> {
+        Storage::savepoint(self).await
+    }
+
+    async fn commit(&self) -> Result<()> {
+        Storage::commit(self).await
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        Storage::rollback(self).await
+    }
+}
+
+type State = BTreeMap<(i64, String), Vec<u8>>;
+
+#[derive(Default)]
+struct MemoryState {
+    values: State,
+    /// Snapshot of `values` taken at each open savepoint, innermost last.
+    savepoints: Vec<State>,
+}
+
+/// Contract state held in a `BTreeMap`, without history, for tests that
+/// don't need a database.
+#[derive(Clone)]
+pub struct MemoryStorage {
+    state: Arc<Mutex<MemoryState>>,
+    pub max_value_size: usize,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemoryState::default())),
+            max_value_size: DEFAULT_MAX_VALUE_SIZE,
+        }
+    }
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The live paths of `contract_id` strictly under `path`, in order.
+    fn paths_under<'a>(
+        state: &'a MemoryState,
+        contract_id: i64,
+        path: &str,
+    ) -> impl Iterator<Item = (&'a str, &'a Vec<u8>)> {
+        let prefix = format!("{}.", path);
+        state
+            .values
+            .range((contract_id, prefix.clone())..)
+            .take_while(move |((id, p), _)| *id == contract_id && p.starts_with(&prefix))
+            .map(|((_, p), value)| (p.as_str(), value))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for MemoryStorage {
+    async fn get(&self, fuel: u64, contract_id: i64, path: &str) -> Result<Option<Vec<u8>>> {
+        let state = self.state.lock().await;
+        match state.values.get(&(contract_id, path.to_string())) {
+            Some(value) if value.len() as u64 > fuel => Err(QueryError::OutOfFuel.into()),
+            value => Ok(value.cloned()),
+        }
+    }
+
+    async fn set(&self, contract_id: i64, path: &str, value: &[u8]) -> Result<()> {
+        if value.len() > self.max_value_size {
+            bail!(
+                "Storage value at {} is {} bytes, exceeding the maximum of {} bytes",
+                path,
+                value.len(),
+                self.max_value_size
+            );
+        }
+        self.state
+            .lock()
+            .await
+            .values
+            .insert((contract_id, path.to_string()), value.to_vec());
+        Ok(())
+    }
+
+    async fn keys_after(
+        &self,
+        contract_id: i64,
+        path: &str,
+        after: Option<String>,
+    ) -> Result<KeyStream> {
+        let state = self.state.lock().await;
+        let keys = Self::paths_under(&state, contract_id, path)
+            .filter_map(|(p, _)| p[path.len() + 1..].split('.').next())
+            .filter(|key| after.as_deref().is_none_or(|after| *key > after))
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>();
+        Ok(Box::pin(stream::iter(keys.into_iter().map(Ok))))
+    }
+
+    async fn entries(&self, contract_id: i64, path: &str) -> Result<EntryStream> {
+        let state = self.state.lock().await;
+        let entries = Self::paths_under(&state, contract_id, path)
+            .map(|(p, value)| (p[path.len() + 1..].to_string(), value.clone()))
+            .filter(|(segment, _)| !segment.contains('.'))
+            .collect::<Vec<_>>();
+        Ok(Box::pin(stream::iter(entries.into_iter().map(Ok))))
+    }
+
+    async fn exists(&self, contract_id: i64, path: &str) -> Result<bool> {
+        let state = self.state.lock().await;
+        Ok(state
+            .values
+            .range((contract_id, path.to_string())..)
+            .next()
+            .is_some_and(|((id, p), _)| *id == contract_id && p.starts_with(path)))
+    }
+
+    async fn extend_path_with_match(
+        &self,
+        contract_id: i64,
+        path: &str,
+        regexp: &str,
+    ) -> Result<Option<String>> {
+        let regex = Regex::new(regexp)?;
+        let state = self.state.lock().await;
+        Ok(state
+            .values
+            .range((contract_id, path.to_string())..)
+            .take_while(|((id, p), _)| *id == contract_id && p.starts_with(path))
+            .map(|((_, p), _)| p)
+            .find(|p| regex.is_match(p))
+            .cloned())
+    }
+
+    async fn delete(&self, contract_id: i64, path: &str) -> Result<bool> {
+        Ok(self
+            .state
+            .lock()
+            .await
+            .values
+            .remove(&(contract_id, path.to_string()))
+            .is_some())
+    }
+
+    async fn delete_matching_paths(&self, contract_id: i64, regexp: &str) -> Result<u64> {
+        let regex = Regex::new(regexp)?;
+        let mut state = self.state.lock().await;
+        let before = state.values.len();
+        state
+            .values
+            .retain(|(id, p), _| *id != contract_id || !regex.is_match(p));
+        Ok((before - state.values.len()) as u64)
+    }
+
+    async fn count_prefix(&self, contract_id: i64, path: &str) -> Result<u64> {
+        let state = self.state.lock().await;
+        Ok(Self::paths_under(&state, contract_id, path).count() as u64)
+    }
+
+    async fn delete_prefix(&self, contract_id: i64, path: &str) -> Result<(u64, u64)> {
+        let mut state = self.state.lock().await;
+        let deleted = Self::paths_under(&state, contract_id, path)
+            .map(|(p, _)| p.to_string())
+            .collect::<Vec<_>>();
+        let keys = deleted
+            .iter()
+            .filter_map(|p| p[path.len() + 1..].split('.').next())
+            .collect::<BTreeSet<_>>()
+            .len();
+        for p in &deleted {
+            state.values.remove(&(contract_id, p.clone()));
+        }
+        Ok((deleted.len() as u64, keys as u64))
+    }
+
+    async fn has_state(&self, contract_id: i64) -> Result<bool> {
+        let state = self.state.lock().await;
+        Ok(state
+            .values
+            .range((contract_id, String::new())..)
+            .next()
+            .is_some_and(|((id, _), _)| *id == contract_id))
+    }
+
+    async fn savepoint(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        let snapshot = state.values.clone();
+        state.savepoints.push(snapshot);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        self.state.lock().await.savepoints.pop();
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if let Some(snapshot) = state.savepoints.pop() {
+            state.values = snapshot;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::{Arc, Mutex};
+
+use bitcoin::BlockHash;
+use moka::future::Cache;
+
+const VIEW_CACHE_CAPACITY: u64 = 1024;
+
+/// (contract_id, expr, contract state version)
+pub type ViewCacheKey = (i64, String, i64);
+
+/// Memoizes view results for a read-only runtime. Keys include the contract's
+/// state version, so a write to the contract misses the cache right away, and
+/// the whole cache is dropped whenever `set_context` moves to a different
+/// height or the runtime follows the chain to a different block.
+#[derive(Clone)]
+pub struct ViewCache {
+    inner: Cache<ViewCacheKey, String>,
+    block: Arc<Mutex<Option<BlockHash>>>,
+}
+
+impl ViewCache {
+    pub fn new() -> Self {
+        Self {
+            inner: Cache::builder().max_capacity(VIEW_CACHE_CAPACITY).build(),
+            block: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn get(&self, key: &ViewCacheKey) -> Option<String> {
+        self.inner.get(key).await
+    }
+
+    pub async fn put(&self, key: ViewCacheKey, value: String) {
+        self.inner.insert(key, value).await
+    }
+
+    pub fn clear(&self) {
+        self.inner.invalidate_all()
+    }
+
+    /// Clears the cache unless `block` is the block it last moved to, which
+    /// also catches a reorg replacing the block at the same height.
+    pub fn move_to(&self, block: Option<BlockHash>) {
+        let mut current = self.block.lock().expect("View cache lock poisoned");
+        if *current != block {
+            *current = block;
+            self.clear();
+        }
+    }
+}
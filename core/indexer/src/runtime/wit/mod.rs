@@ -1,8 +1,8 @@
 mod resources;
 
 pub use resources::{
-    CoreContext, FallContext, FileDescriptor, HasContractId, Keys, ProcContext, ProcStorage, Proof,
-    Signer, Transaction, ViewContext, ViewStorage,
+    CoreContext, Entries, FallContext, FileDescriptor, HasContractId, Keys, ProcContext,
+    ProcStorage, Proof, Signer, Transaction, ViewContext, ViewStorage,
 };
 
 wasmtime::component::bindgen!({
@@ -16,6 +16,7 @@ wasmtime::component::bindgen!({
         "kontor:built-in/context.view-storage": ViewStorage,
         "kontor:built-in/context.proc-storage": ProcStorage,
         "kontor:built-in/context.keys": Keys,
+        "kontor:built-in/context.entries": Entries,
         "kontor:built-in/context.transaction": Transaction,
         "kontor:built-in/file-registry.file-descriptor": FileDescriptor,
         "kontor:built-in/file-registry.proof": Proof,
@@ -1,15 +1,23 @@
 use std::pin::Pin;
 
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 pub use indexer_types::Signer;
 
 use crate::database::types::{FileMetadataRow, bytes_to_field_element};
-use crate::runtime::kontor::built_in::{error::Error, file_registry::RawFileDescriptor};
+use crate::runtime::kontor::built_in::{
+    context::EntryKind, error::Error, file_registry::RawFileDescriptor,
+};
 use kontor_crypto::Proof as CryptoProof;
 use kontor_crypto::api::{Challenge, FileMetadata as CryptoFileMetadata};
 
 pub trait HasContractId: 'static {
     fn get_contract_id(&self) -> i64;
+
+    /// Whether reads are limited to the contract's `#[public]` fields, as for
+    /// storage opened on another contract.
+    fn public_only(&self) -> bool {
+        false
+    }
 }
 
 pub struct ViewContext {
@@ -24,12 +32,17 @@ impl HasContractId for ViewContext {
 
 pub struct ViewStorage {
     pub contract_id: i64,
+    pub public_only: bool,
 }
 
 impl HasContractId for ViewStorage {
     fn get_contract_id(&self) -> i64 {
         self.contract_id
     }
+
+    fn public_only(&self) -> bool {
+        self.public_only
+    }
 }
 
 pub struct ProcStorage {
@@ -66,6 +79,62 @@ impl HasContractId for FallContext {
 
 pub struct Keys {
     pub stream: Pin<Box<dyn Stream<Item = Result<String, libsql::Error>> + Send>>,
+    /// Keys left before the stream stops early, if it was opened with a limit.
+    pub remaining: Option<u64>,
+    /// Whether the limit was reached with keys still left in the stream.
+    /// Only known once the limit has been reached.
+    pub truncated: Option<bool>,
+}
+
+impl Keys {
+    pub async fn next(&mut self) -> Result<Option<String>, libsql::Error> {
+        match self.remaining {
+            Some(0) => {
+                self.probe_truncated().await?;
+                Ok(None)
+            }
+            _ => {
+                let k = self.stream.next().await.transpose()?;
+                if k.is_some()
+                    && let Some(remaining) = self.remaining.as_mut()
+                {
+                    *remaining -= 1;
+                }
+                Ok(k)
+            }
+        }
+    }
+
+    pub async fn truncated(&mut self) -> Result<bool, libsql::Error> {
+        match self.remaining {
+            Some(0) => self.probe_truncated().await,
+            _ => Ok(false),
+        }
+    }
+
+    async fn probe_truncated(&mut self) -> Result<bool, libsql::Error> {
+        if self.truncated.is_none() {
+            self.truncated = Some(self.stream.next().await.transpose()?.is_some());
+        }
+        Ok(self.truncated.unwrap_or_default())
+    }
+}
+
+/// Key/value pairs of a map, read in the same scan. Values are decoded as
+/// `kind`, which the contract picks from the map's value type. Integers and
+/// decimals span several rows under their key, so for those kinds the stream
+/// yields only the keys and each value is loaded from `path` as it is read.
+pub struct Entries {
+    pub stream: Pin<Box<dyn Stream<Item = Result<(String, Vec<u8>), libsql::Error>> + Send>>,
+    pub kind: EntryKind,
+    pub contract_id: i64,
+    pub path: String,
+}
+
+impl Entries {
+    pub async fn next(&mut self) -> Result<Option<(String, Vec<u8>)>, libsql::Error> {
+        self.stream.next().await.transpose()
+    }
 }
 
 pub struct CoreContext {
@@ -1,4 +1,4 @@
-use anyhow::{Error, Result};
+use anyhow::{Context, Error, Result};
 
 use bitcoin::hashes::Hash;
 use bitcoin::key::TapTweak;
@@ -17,6 +17,7 @@ use indexer_types::{Block, BlockRow, Transaction};
 use indexmap::IndexMap;
 use libsql::Connection;
 use rand::prelude::*;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tempfile::TempDir;
@@ -27,7 +28,10 @@ use crate::bitcoin_follower::rpc;
 
 use crate::database::types::FileMetadataRow;
 use crate::database::{Reader, Writer, queries};
-use crate::runtime::RawFileDescriptor;
+use crate::height;
+use crate::runtime::{
+    ComponentCache, ContractAddress, RawFileDescriptor, Runtime, Storage, wit::Signer,
+};
 
 pub enum PublicKey<'a> {
     Segwit(&'a CompressedPublicKey),
@@ -312,6 +316,82 @@ pub async fn new_test_db() -> Result<(Reader, Writer, (TempDir, String))> {
     Ok((reader, writer, (temp_dir, db_name)))
 }
 
+/// Storage over a fresh database with blocks `0..=height` already processed.
+/// The writer and directory must outlive it.
+pub async fn new_test_storage(height: i64) -> Result<(Storage, Writer, (TempDir, String))> {
+    let (_, writer, db_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 0..=height {
+        queries::insert_processed_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+    let storage = Storage::builder().height(0).tx_index(0).conn(conn).build();
+    Ok((storage, writer, db_dir))
+}
+
+/// A runtime over [`new_test_storage`] with the native contracts published,
+/// positioned at the first transaction of height 1.
+pub async fn new_test_runtime(height: i64) -> Result<(Runtime, Writer, (TempDir, String))> {
+    let (storage, writer, db_dir) = new_test_storage(height).await?;
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime.publish_native_contracts().await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    Ok((runtime, writer, db_dir))
+}
+
+/// A [`new_test_runtime`] with a gas limit for procedures and a funded
+/// `test_signer`, for tests that drive the indexer runtime directly instead
+/// of going through the testlib harness.
+pub async fn new_test_runtime_with_signer(
+    height: i64,
+) -> Result<(Runtime, Signer, Writer, (TempDir, String))> {
+    let (mut runtime, writer, db_dir) = new_test_runtime(height).await?;
+    runtime.set_gas_limit(100_000);
+    let signer = new_test_signer(&mut runtime, "test_signer").await?;
+    Ok((runtime, signer, writer, db_dir))
+}
+
+/// A signer named `name` holding the issuance.
+pub async fn new_test_signer(runtime: &mut Runtime, name: &str) -> Result<Signer> {
+    let signer = Signer::XOnlyPubKey(name.to_string());
+    runtime.issuance(&signer).await?;
+    Ok(signer)
+}
+
+/// The test contract `name` as built by `test-contracts/build.sh`,
+/// compressed as it would be inscribed.
+pub async fn read_test_contract(name: &str) -> Result<Vec<u8>> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test-contracts/target/wasm32-unknown-unknown/kontor-test")
+        .join(format!("{}.wasm.br", name.replace('-', "_")));
+    tokio::fs::read(&path)
+        .await
+        .with_context(|| format!("{} contract has not been built", name))
+}
+
+/// Publishes the test contract `name` at the runtime's current position.
+pub async fn publish_test_contract(
+    runtime: &mut Runtime,
+    signer: &Signer,
+    name: &str,
+) -> Result<ContractAddress> {
+    let bytes = read_test_contract(name).await?;
+    runtime.publish(signer, name, &bytes).await?;
+    Ok(ContractAddress {
+        name: name.to_string(),
+        height: height::to_u64(runtime.storage.height),
+        tx_index: runtime.storage.tx_index as u64,
+    })
+}
+
 pub fn new_mock_block_hash(i: u32) -> BlockHash {
     let mut bytes = [0u8; 32];
     let i_bytes = i.to_le_bytes();
@@ -328,6 +408,7 @@ pub fn gen_numbered_block(height: u64, prev_hash: &BlockHash) -> Block {
         height,
         hash,
         prev_hash: *prev_hash,
+        time: height,
         transactions: vec![new_mock_transaction(height as u32)],
     }
 }
@@ -362,6 +443,7 @@ pub fn gen_random_block(height: u64, prev_hash: Option<BlockHash>) -> Block {
         height,
         hash: BlockHash::from_byte_array(hash),
         prev_hash: prev,
+        time: height,
         transactions: vec![],
     }
 }
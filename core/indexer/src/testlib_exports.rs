@@ -2,15 +2,15 @@ pub use crate::{
     logging,
     reg_tester::RegTester,
     runtime::{
-        CheckedArithmetics, FromWaveValue, WaveType, from_wave_expr, from_wave_value,
-        numerics as numbers, to_wave_expr, wave_type,
+        CallTrace, CheckedArithmetics, ContractMock, ExecutionError, FromWaveValue, WaveType,
+        from_wave_expr, from_wave_value, numerics as numbers, to_wave_expr, wave_type,
         wit::{
             Signer,
             kontor::built_in::{
-                error::Error,
+                error::{ContractError, Error},
                 file_registry::RawFileDescriptor,
                 foreign::ContractAddress,
-                numbers::{Decimal, Integer},
+                numbers::{Decimal, Integer, RoundingMode, ScaledInteger},
             },
         },
     },
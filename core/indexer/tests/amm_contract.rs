@@ -1,3 +1,5 @@
+use num::BigInt;
+use proptest::{prelude::*, strategy::ValueTree, test_runner::TestRunner};
 use testlib::*;
 
 interface!(name = "amm", path = "../../test-contracts/amm/wit");
@@ -376,6 +378,63 @@ async fn run_test_amm_deposit_withdraw(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn run_test_amm_balanced_deposit(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_balanced_deposit");
+    let admin = runtime.identity().await?;
+    let holder = runtime.identity().await?;
+
+    let amm = runtime.publish(&admin, "amm").await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+
+    token::mint(runtime, &token_a, &admin, 100.into()).await??;
+    token::mint(runtime, &token_b, &admin, 500.into()).await??;
+    token::mint(runtime, &token_a, &holder, 100.into()).await??;
+    token::mint(runtime, &token_b, &holder, 500.into()).await??;
+
+    let pair = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_b.clone(),
+    };
+    let lp_supply = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair.clone(),
+        100.into(),
+        500.into(),
+        0.into(),
+    )
+    .await??;
+    assert_eq!(lp_supply, 223.into());
+
+    // 20:100 matches the pool's 100:500 exactly, so both sides price the
+    // deposit the same and nothing is left over
+    let (amount_a, amount_b) = (Integer::from(20), Integer::from(100));
+    let shares_a = amount_a * lp_supply / 100.into();
+    let shares_b = amount_b * lp_supply / 500.into();
+    assert_eq!(shares_a, shares_b);
+
+    let quote = amm::quote_deposit(runtime, &amm, pair.clone(), amount_a, amount_b).await??;
+    assert_eq!(quote.lp_shares, shares_a);
+
+    // one unit of excess on either side leaves the shares unchanged, so the
+    // tie sits between two branches that agree
+    let more_a = amm::quote_deposit(runtime, &amm, pair.clone(), 21.into(), amount_b).await??;
+    let more_b = amm::quote_deposit(runtime, &amm, pair.clone(), amount_a, 101.into()).await??;
+    assert_eq!(more_a, quote);
+    assert_eq!(more_b, quote);
+
+    let res = amm::deposit(runtime, &amm, &holder, pair.clone(), amount_a, amount_b).await??;
+    assert_eq!(res, quote);
+    let bal = amm::balance(runtime, &amm, pair.clone(), &admin).await?;
+    assert_eq!(bal, Some(lp_supply));
+    let bal = amm::balance(runtime, &amm, pair.clone(), &holder).await?;
+    assert_eq!(bal, Some(shares_a));
+
+    Ok(())
+}
+
 async fn run_test_amm_limits(runtime: &mut Runtime) -> Result<()> {
     tracing::info!("test_amm_limits");
     let admin = runtime.identity().await?;
@@ -643,6 +702,380 @@ async fn run_test_amm_pools(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn run_test_amm_pool_isolation(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_pool_isolation");
+    let admin = runtime.identity().await?;
+    let minter = runtime.identity().await?;
+
+    let amm = runtime.publish(&admin, "amm").await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+    let token_c = runtime.publish_as(&admin, "token", "token-c").await?;
+
+    for token in [&token_a, &token_b, &token_c] {
+        token::mint(runtime, token, &minter, 1000.into()).await??;
+        token::transfer(runtime, token, &minter, &admin, 500.into()).await??;
+    }
+
+    // both pools hold token a in their `balance_a` field
+    let pair1 = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_b.clone(),
+    };
+    let pair2 = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_c.clone(),
+    };
+    let shares1 = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair1.clone(),
+        100.into(),
+        400.into(),
+        5.into(),
+    )
+    .await??;
+    let shares2 = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair2.clone(),
+        300.into(),
+        300.into(),
+        30.into(),
+    )
+    .await??;
+    assert_eq!(shares1, 200.into());
+    assert_eq!(shares2, 300.into());
+
+    let balances = async |runtime: &mut Runtime, pair: &amm::TokenPair| -> Result<_> {
+        Ok((
+            amm::token_balance(runtime, &amm, pair.clone(), pair.a.clone()).await??,
+            amm::token_balance(runtime, &amm, pair.clone(), pair.b.clone()).await??,
+            amm::fee(runtime, &amm, pair.clone()).await??,
+            amm::balance(runtime, &amm, pair.clone(), &admin).await?,
+        ))
+    };
+    assert_eq!(
+        balances(runtime, &pair1).await?,
+        (100.into(), 400.into(), 5.into(), Some(200.into()))
+    );
+    assert_eq!(
+        balances(runtime, &pair2).await?,
+        (300.into(), 300.into(), 30.into(), Some(300.into()))
+    );
+
+    amm::swap(
+        runtime,
+        &amm,
+        &minter,
+        pair1.clone(),
+        token_a.clone(),
+        10.into(),
+        0.into(),
+    )
+    .await??;
+    assert_eq!(
+        amm::token_balance(runtime, &amm, pair1.clone(), token_a.clone()).await?,
+        Ok(110.into())
+    );
+    assert_eq!(
+        balances(runtime, &pair2).await?,
+        (300.into(), 300.into(), 30.into(), Some(300.into()))
+    );
+
+    Ok(())
+}
+
+async fn run_test_amm_empty_pool(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_empty_pool");
+    let admin = runtime.identity().await?;
+    let amm = runtime.publish(&admin, "amm").await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+
+    token::mint(runtime, &token_a, &admin, 100.into()).await??;
+    token::mint(runtime, &token_b, &admin, 500.into()).await??;
+
+    let pair = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_b.clone(),
+    };
+    let shares = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair.clone(),
+        100.into(),
+        500.into(),
+        0.into(),
+    )
+    .await??;
+
+    // drain the pool to zero supply and zero balances
+    let res = amm::withdraw(runtime, &amm, &admin, pair.clone(), shares).await?;
+    assert_eq!(
+        res,
+        Ok(amm::WithdrawResult {
+            amount_a: 100.into(),
+            amount_b: 500.into(),
+        })
+    );
+
+    let res = amm::quote_deposit(runtime, &amm, pair.clone(), 10.into(), 10.into()).await?;
+    assert_eq!(res, Err(Error::contract(204, "empty pool")));
+    let res = amm::quote_withdraw(runtime, &amm, pair.clone(), 1.into()).await?;
+    assert_eq!(res, Err(Error::contract(204, "empty pool")));
+    let res = amm::quote_swap(runtime, &amm, pair.clone(), token_a.clone(), 10.into()).await?;
+    assert_eq!(res, Err(Error::contract(204, "empty pool")));
+
+    Ok(())
+}
+
+async fn run_test_amm_deposit_rollback(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_deposit_rollback");
+    let admin = runtime.identity().await?;
+    let holder = runtime.identity().await?;
+    let amm = runtime.publish(&admin, "amm").await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+
+    token::mint(runtime, &token_a, &admin, 100.into()).await??;
+    token::mint(runtime, &token_b, &admin, 500.into()).await??;
+    token::mint(runtime, &token_a, &holder, 200.into()).await??;
+    token::mint(runtime, &token_b, &holder, 10.into()).await??;
+
+    let pair = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_b.clone(),
+    };
+    amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair.clone(),
+        100.into(),
+        500.into(),
+        0.into(),
+    )
+    .await??;
+    let lp_total_supply = amm::lp_total_supply(runtime, &amm, pair.clone()).await?;
+    assert_eq!(lp_total_supply, Ok(223.into()));
+
+    // the deposit is booked and token A is transferred, then the token B
+    // transfer fails
+    let res = amm::deposit(runtime, &amm, &holder, pair.clone(), 50.into(), 100.into()).await?;
+    assert_eq!(res, Err(Error::contract(2, "insufficient funds")));
+
+    let bal = token::balance(runtime, &token_a, &holder).await?;
+    assert_eq!(bal, Some(200.into()));
+    let bal = token::balance(runtime, &token_b, &holder).await?;
+    assert_eq!(bal, Some(10.into()));
+
+    let bal_a = amm::token_balance(runtime, &amm, pair.clone(), token_a.clone()).await?;
+    assert_eq!(bal_a, Ok(100.into()));
+    let bal_b = amm::token_balance(runtime, &amm, pair.clone(), token_b.clone()).await?;
+    assert_eq!(bal_b, Ok(500.into()));
+    let bal = amm::balance(runtime, &amm, pair.clone(), &holder).await?;
+    assert_eq!(bal, None);
+    let res = amm::lp_total_supply(runtime, &amm, pair.clone()).await?;
+    assert_eq!(res, lp_total_supply);
+
+    Ok(())
+}
+
+async fn run_test_amm_create_mocked_transfer_failure(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_create_mocked_transfer_failure");
+    let admin = runtime.identity().await?;
+    let amm = runtime.publish(&admin, "amm").await?;
+
+    // neither token is deployed; both are answered by mocks
+    let token_a = ContractAddress {
+        name: "token-a".to_string(),
+        height: 100,
+        tx_index: 0,
+    };
+    let token_b = ContractAddress {
+        name: "token-b".to_string(),
+        height: 100,
+        tx_index: 0,
+    };
+    let mock_a = token::MockToken::new();
+    let mock_b = token::MockToken::new();
+    mock_a.install(runtime, &token_a).await?;
+    mock_b.install(runtime, &token_b).await?;
+    mock_a.transfer_returns(Ok(()));
+    mock_b.transfer_returns(Err(Error::Message("transfer rejected".to_string())));
+
+    let pair = amm::TokenPair {
+        a: token_a.clone(),
+        b: token_b.clone(),
+    };
+    let res = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair.clone(),
+        100.into(),
+        500.into(),
+        0.into(),
+    )
+    .await?;
+    assert_eq!(res, Err(Error::Message("transfer rejected".to_string())));
+    assert_eq!(mock_a.calls().len(), 1);
+    assert!(mock_a.calls()[0].starts_with("transfer("));
+    assert_eq!(mock_b.calls().len(), 1);
+    // the pool was not created
+    assert!(amm::fee(runtime, &amm, pair.clone()).await?.is_err());
+
+    mock_b.transfer_returns(Ok(()));
+    let res = amm::create(
+        runtime,
+        &amm,
+        &admin,
+        pair.clone(),
+        100.into(),
+        500.into(),
+        0.into(),
+    )
+    .await?;
+    assert_eq!(res, Ok(223.into()));
+    assert_eq!(mock_a.calls().len(), 2);
+    assert_eq!(mock_b.calls().len(), 2);
+    let bal_b = amm::token_balance(runtime, &amm, pair.clone(), token_b.clone()).await?;
+    assert_eq!(bal_b, Ok(500.into()));
+
+    Ok(())
+}
+
+/// Random pool balances, input amount and fee for a single swap.
+fn swap_case() -> impl Strategy<Value = (u64, u64, u64, u64)> {
+    (
+        1_000u64..1_000_000,
+        1_000u64..1_000_000,
+        1u64..100_000,
+        0u64..1_000,
+    )
+}
+
+async fn run_test_amm_swap_preserves_invariant(runtime: &mut Runtime) -> Result<()> {
+    tracing::info!("test_amm_swap_preserves_invariant");
+    let admin = runtime.identity().await?;
+    let trader = runtime.identity().await?;
+    let amm = runtime.publish(&admin, "amm").await?;
+
+    // cases are generated up front since the runtime is async; failures are
+    // reported with their inputs instead of being shrunk
+    let mut runner = TestRunner::new(ProptestConfig::with_cases(16));
+    for i in 0..16 {
+        let (bal_a, bal_b, amount_in, fee_bps) = swap_case()
+            .new_tree(&mut runner)
+            .map_err(|e| anyhow!(e))?
+            .current();
+        let case = format!(
+            "bal_a={} bal_b={} amount_in={} fee_bps={}",
+            bal_a, bal_b, amount_in, fee_bps
+        );
+
+        let token_a = runtime
+            .publish_as(&admin, "token", &format!("token-a-{}", i))
+            .await?;
+        let token_b = runtime
+            .publish_as(&admin, "token", &format!("token-b-{}", i))
+            .await?;
+        token::mint(runtime, &token_a, &admin, bal_a.into()).await??;
+        token::mint(runtime, &token_b, &admin, bal_b.into()).await??;
+        token::mint(runtime, &token_a, &trader, amount_in.into()).await??;
+
+        let pair = amm::TokenPair {
+            a: token_a.clone(),
+            b: token_b.clone(),
+        };
+        amm::create(
+            runtime,
+            &amm,
+            &admin,
+            pair.clone(),
+            bal_a.into(),
+            bal_b.into(),
+            fee_bps.into(),
+        )
+        .await??;
+
+        let quote = amm::quote_swap_exact(
+            runtime,
+            &amm,
+            pair.clone(),
+            token_a.clone(),
+            amount_in.into(),
+        )
+        .await??;
+        let amount_out = amm::quote_swap(
+            runtime,
+            &amm,
+            pair.clone(),
+            token_a.clone(),
+            amount_in.into(),
+        )
+        .await??;
+        assert_eq!(amount_out, quote.amount_out, "{}", case);
+
+        let res = amm::swap(
+            runtime,
+            &amm,
+            &trader,
+            pair.clone(),
+            token_a.clone(),
+            amount_in.into(),
+            0.into(),
+        )
+        .await?;
+        if quote.amount_out == 0.into() {
+            // nothing to pay out, so the output transfer is rejected
+            assert!(res.is_err(), "{}", case);
+            continue;
+        }
+        let amount_out = res.map_err(|e| anyhow!("{}: {:?}", case, e))?;
+        assert_eq!(amount_out, quote.amount_out, "{}", case);
+
+        // the output follows the constant-product formula, computed here
+        // independently of the contract
+        let in_less_fee = amount_in as u128 * (10_000 - fee_bps as u128) / 10_000;
+        let expected_out = bal_b as u128 * in_less_fee / (bal_a as u128 + in_less_fee);
+        assert_eq!(
+            BigInt::from(amount_out),
+            BigInt::from(expected_out),
+            "{}",
+            case
+        );
+
+        // reserves move by exactly what the trader paid and received
+        let after_a = amm::token_balance(runtime, &amm, pair.clone(), token_a.clone()).await??;
+        let after_b = amm::token_balance(runtime, &amm, pair.clone(), token_b.clone()).await??;
+        let after_a = BigInt::from(after_a);
+        let after_b = BigInt::from(after_b);
+        assert_eq!(after_a, BigInt::from(bal_a + amount_in), "{}", case);
+        assert_eq!(
+            after_b,
+            BigInt::from(bal_b) - BigInt::from(amount_out),
+            "{}",
+            case
+        );
+        let bal = token::balance(runtime, &token_b, &trader).await?;
+        assert_eq!(bal, Some(amount_out), "{}", case);
+
+        // the fee and rounding down never shrink the pool's product
+        assert!(
+            &after_a * &after_b >= BigInt::from(bal_a) * BigInt::from(bal_b),
+            "{}",
+            case
+        );
+    }
+
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_amm_swaps() -> Result<()> {
     run_test_amm_swaps(runtime).await
@@ -663,6 +1096,11 @@ async fn test_amm_deposit_withdraw() -> Result<()> {
     run_test_amm_deposit_withdraw(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_balanced_deposit() -> Result<()> {
+    run_test_amm_balanced_deposit(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_amm_limits() -> Result<()> {
     run_test_amm_limits(runtime).await
@@ -673,6 +1111,32 @@ async fn test_amm_pools() -> Result<()> {
     run_test_amm_pools(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_pool_isolation() -> Result<()> {
+    run_test_amm_pool_isolation(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_empty_pool() -> Result<()> {
+    run_test_amm_empty_pool(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_deposit_rollback() -> Result<()> {
+    run_test_amm_deposit_rollback(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_swap_preserves_invariant() -> Result<()> {
+    run_test_amm_swap_preserves_invariant(runtime).await
+}
+
+// mocks only exist in local mode
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_create_mocked_transfer_failure() -> Result<()> {
+    run_test_amm_create_mocked_transfer_failure(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
 async fn test_amm_contract_regtest() -> Result<()> {
     logging::setup();
@@ -680,7 +1144,10 @@ async fn test_amm_contract_regtest() -> Result<()> {
     run_test_amm_swap_fee(runtime).await?;
     run_test_amm_swap_low_slippage(runtime).await?;
     run_test_amm_deposit_withdraw(runtime).await?;
+    run_test_amm_balanced_deposit(runtime).await?;
     run_test_amm_limits(runtime).await?;
     run_test_amm_pools(runtime).await?;
+    run_test_amm_empty_pool(runtime).await?;
+    run_test_amm_deposit_rollback(runtime).await?;
     Ok(())
 }
@@ -221,6 +221,7 @@ async fn test_orderer() -> Result<()> {
                     height: 702,
                     hash: BlockHash::from_byte_array([0x44; 32]),
                     prev_hash: BlockHash::from_byte_array([0x33; 32]),
+                    time: 0,
                     transactions: vec![],
                 }
             ))
@@ -235,6 +236,7 @@ async fn test_orderer() -> Result<()> {
                     height: 700,
                     hash: BlockHash::from_byte_array([0x22; 32]),
                     prev_hash: BlockHash::from_byte_array([0x11; 32]),
+                    time: 0,
                     transactions: vec![],
                 }
             ))
@@ -249,6 +251,7 @@ async fn test_orderer() -> Result<()> {
                     height: 701,
                     hash: BlockHash::from_byte_array([0x33; 32]),
                     prev_hash: BlockHash::from_byte_array([0x22; 32]),
+                    time: 0,
                     transactions: vec![],
                 }
             ))
@@ -265,6 +268,7 @@ async fn test_orderer() -> Result<()> {
             height: 700,
             hash: BlockHash::from_byte_array([0x22; 32]),
             prev_hash: BlockHash::from_byte_array([0x11; 32]),
+            time: 0,
             transactions: vec![],
         }
     );
@@ -276,6 +280,7 @@ async fn test_orderer() -> Result<()> {
             height: 701,
             hash: BlockHash::from_byte_array([0x33; 32]),
             prev_hash: BlockHash::from_byte_array([0x22; 32]),
+            time: 0,
             transactions: vec![],
         }
     );
@@ -287,6 +292,7 @@ async fn test_orderer() -> Result<()> {
             height: 702,
             hash: BlockHash::from_byte_array([0x44; 32]),
             prev_hash: BlockHash::from_byte_array([0x33; 32]),
+            time: 0,
             transactions: vec![],
         }
     );
@@ -15,6 +15,7 @@ fn gen_block(height: u64) -> Block {
         // only height is relevant for orderer, using dummy values for the rest
         hash: BlockHash::from_byte_array([0x11; 32]),
         prev_hash: BlockHash::from_byte_array([0x11; 32]),
+        time: 0,
         transactions: vec![],
     }
 }
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use indexer::{
+    database::queries::get_contract_results_in_range,
+    reactor::{BlockHook, block_handler, native_block_hooks},
+    runtime::{
+        Runtime,
+        filestorage::{self, api},
+        wit::Signer,
+    },
+    test_utils::{make_descriptor, new_mock_block_hash, new_mock_transaction, new_test_runtime},
+};
+use indexer_types::Block;
+
+const DEADLINE_BLOCKS: u64 = 2016;
+
+fn empty_block(height: u64) -> Block {
+    Block {
+        height,
+        hash: new_mock_block_hash(height as u32),
+        prev_hash: new_mock_block_hash(height as u32 - 1),
+        time: 0,
+        transactions: vec![],
+    }
+}
+
+async fn challenge_status(
+    runtime: &mut Runtime,
+    challenge_id: &str,
+) -> Result<api::ChallengeStatus> {
+    Ok(api::get_challenge(runtime, challenge_id)
+        .await?
+        .expect("challenge exists")
+        .status)
+}
+
+#[tokio::test]
+async fn test_block_hooks_run_filestorage_per_block_functions() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(0).await?;
+    let hooks = native_block_hooks()
+        .into_iter()
+        .map(|hook| BlockHook {
+            activation_height: DEADLINE_BLOCKS,
+            ..hook
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        hooks
+            .iter()
+            .all(|hook| hook.contract == filestorage::address())
+    );
+
+    block_handler(&mut runtime, &hooks, &empty_block(1)).await?;
+
+    // an agreement challenged at height 1, due at the deadline
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    runtime.issuance(&signer).await?;
+    let descriptor = make_descriptor(
+        "file_0".to_string(),
+        vec![1u8; 32],
+        16,
+        100,
+        "file_0.txt".to_string(),
+    );
+    let created = api::create_agreement(&mut runtime, &signer, descriptor).await??;
+    for node in ["node_1", "node_2", "node_3"] {
+        api::join_agreement(&mut runtime, &signer, &created.agreement_id, node).await??;
+    }
+    let challenge = api::create_challenge_for_agreement(
+        &mut runtime,
+        &signer,
+        &created.agreement_id,
+        "node_1",
+        1,
+        vec![0u8; 32],
+    )
+    .await??;
+    assert_eq!(challenge.deadline_height, 1 + DEADLINE_BLOCKS);
+
+    block_handler(&mut runtime, &hooks, &empty_block(DEADLINE_BLOCKS)).await?;
+    assert_eq!(
+        challenge_status(&mut runtime, &challenge.challenge_id).await?,
+        api::ChallengeStatus::Active
+    );
+
+    block_handler(&mut runtime, &hooks, &empty_block(DEADLINE_BLOCKS + 1)).await?;
+    assert_eq!(
+        challenge_status(&mut runtime, &challenge.challenge_id).await?,
+        api::ChallengeStatus::Expired
+    );
+
+    // every block from the activation height on ran both hooks in order,
+    // after its transactions and at its own height
+    let results =
+        get_contract_results_in_range(&runtime.storage.conn, 1, (DEADLINE_BLOCKS + 1) as i64)
+            .await?
+            .into_iter()
+            .filter(|r| r.tx_index == 0 && r.result_index == 0)
+            .map(|r| (r.height, r.op_index, r.func))
+            .collect::<Vec<_>>();
+    let expected = [DEADLINE_BLOCKS, DEADLINE_BLOCKS + 1]
+        .into_iter()
+        .flat_map(|height| {
+            [
+                (height as i64, 0, "expire-challenges".to_string()),
+                (
+                    height as i64,
+                    1,
+                    "generate-challenges-for-block".to_string(),
+                ),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(results, expected);
+
+    Ok(())
+}
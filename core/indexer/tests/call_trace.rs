@@ -0,0 +1,119 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{CallTrace, ContractAddress},
+    test_utils::{new_mock_transaction, new_test_runtime, new_test_signer, publish_test_contract},
+};
+use testlib::{Integer, to_wave_expr};
+
+#[tokio::test]
+async fn test_nested_failure_traces_every_contract_on_the_path() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(1).await?;
+    runtime.set_gas_limit(1_000_000);
+
+    let admin = new_test_signer(&mut runtime, "admin").await?;
+    let minter = new_test_signer(&mut runtime, "minter").await?;
+
+    let mut publish = async |name: &str, tx_index: u64| -> Result<ContractAddress> {
+        runtime
+            .set_context(
+                1,
+                tx_index as i64,
+                0,
+                0,
+                new_mock_transaction(1).txid,
+                None,
+                None,
+            )
+            .await;
+        publish_test_contract(&mut runtime, &admin, name).await
+    };
+    let amm = publish("amm", 1).await?;
+    let token_a = publish("token", 2).await?;
+    let token_b = publish("token", 3).await?;
+
+    for (token, amount) in [(&token_a, 100), (&token_b, 500)] {
+        runtime
+            .execute(
+                Some(&minter),
+                token,
+                &format!("mint({})", to_wave_expr(Integer::from(1000))),
+            )
+            .await?;
+        runtime
+            .execute(
+                Some(&minter),
+                token,
+                &format!(
+                    "transfer(\"admin\", {})",
+                    to_wave_expr(Integer::from(amount))
+                ),
+            )
+            .await?;
+    }
+    let pair = format!(
+        "{{a: {}, b: {}}}",
+        to_wave_expr(token_a.clone()),
+        to_wave_expr(token_b.clone())
+    );
+    runtime
+        .execute(
+            Some(&admin),
+            &amm,
+            &format!(
+                "create({}, {}, {}, {})",
+                pair,
+                to_wave_expr(Integer::from(100)),
+                to_wave_expr(Integer::from(500)),
+                to_wave_expr(Integer::from(0))
+            ),
+        )
+        .await?;
+    assert_eq!(runtime.error_trace().await, None);
+
+    // The minter holds 900 of token a, so the amm's transfer of 950 into its
+    // custody fails
+    let result = runtime
+        .execute(
+            Some(&minter),
+            &amm,
+            &format!(
+                "swap({}, {}, {}, {})",
+                pair,
+                to_wave_expr(token_a.clone()),
+                to_wave_expr(Integer::from(950)),
+                to_wave_expr(Integer::from(0))
+            ),
+        )
+        .await?;
+    assert!(result.starts_with("err("), "{}", result);
+    let trace = runtime
+        .error_trace()
+        .await
+        .expect("swap failure was traced");
+    assert_eq!(
+        trace,
+        CallTrace {
+            frames: vec!["amm.swap".to_string(), "token.transfer".to_string()],
+            message: "contract({code: 2, message: \"insufficient funds\"})".to_string(),
+        }
+    );
+    assert_eq!(
+        trace.to_string(),
+        "in amm.swap → token.transfer: contract({code: 2, message: \"insufficient funds\"})"
+    );
+    let swap = runtime
+        .storage
+        .results_in_range(1, 1)
+        .await?
+        .into_iter()
+        .rfind(|row| row.func == "swap")
+        .expect("swap result was stored");
+    assert_eq!(swap.error_trace, Some(trace.to_string()));
+
+    runtime
+        .execute(None, &token_a, "balance(\"minter\")")
+        .await?;
+    assert_eq!(runtime.error_trace().await, None);
+
+    Ok(())
+}
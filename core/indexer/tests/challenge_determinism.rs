@@ -0,0 +1,139 @@
+use anyhow::Result;
+use bitcoin::{BlockHash, hashes::Hash};
+use hkdf::Hkdf;
+use indexer::{
+    database::{Writer, queries::insert_processed_block},
+    runtime::{ComponentCache, Runtime, Storage, filestorage::api, wit::Signer},
+    test_utils::{make_descriptor, new_mock_block_hash, new_mock_transaction, new_test_storage},
+};
+use indexer_types::BlockRow;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+const AGREEMENTS: u64 = 10;
+const BLOCK_HEIGHT: u64 = 1000;
+
+/// Mirrors filestorage's agreement roll: with the default parameters a block
+/// challenges one of `AGREEMENTS` agreements only when the roll is below
+/// `12 * AGREEMENTS`.
+fn agreement_roll(prev_hash: &BlockHash) -> u64 {
+    let derive = |ikm: &[u8], salt: Option<&[u8]>, info: &[u8]| {
+        let mut okm = [0u8; 32];
+        Hkdf::<Sha256>::new(salt, ikm)
+            .expand(info, &mut okm)
+            .unwrap();
+        okm
+    };
+    let seed = derive(
+        &prev_hash.to_byte_array(),
+        None,
+        b"kontor/hkdf/agreement_selection",
+    );
+    let blocks_per_year = 52_560u64;
+    let limit = u64::MAX - (u64::MAX % blocks_per_year);
+    for counter in 0u64.. {
+        let bytes = derive(&seed, Some(&counter.to_le_bytes()), b"kontor/rng/roll");
+        let value = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        if value < limit {
+            return value % blocks_per_year;
+        }
+    }
+    unreachable!()
+}
+
+/// A previous block hash for which `generate-challenges-for-block` is
+/// guaranteed to select an agreement.
+fn challenging_prev_hash() -> BlockHash {
+    (0u64..)
+        .map(|i| BlockHash::from_byte_array(Sha256::digest(i.to_le_bytes()).into()))
+        .find(|hash| agreement_roll(hash) < 12 * AGREEMENTS)
+        .unwrap()
+}
+
+async fn runtime_at_height_one(writer: &Writer) -> Result<Runtime> {
+    let storage = Storage::builder()
+        .height(1)
+        .tx_index(1)
+        .conn(writer.connection())
+        .build();
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+    Ok(runtime)
+}
+
+/// Publishes the native contracts and creates `AGREEMENTS` active agreements,
+/// all on a chain whose block 1 follows `prev_hash`.
+async fn setup(prev_hash: BlockHash) -> Result<(Runtime, Writer, (TempDir, String))> {
+    let (storage, writer, db_dir) = new_test_storage(0).await?;
+    insert_processed_block(
+        &storage.conn,
+        BlockRow::builder()
+            .height(1)
+            .hash(new_mock_block_hash(1))
+            .prev_hash(prev_hash)
+            .build(),
+    )
+    .await?;
+
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime.publish_native_contracts().await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    runtime.issuance(&signer).await?;
+    for i in 0..AGREEMENTS {
+        let file_id = format!("file_{}", i);
+        let descriptor = make_descriptor(
+            file_id.clone(),
+            vec![1u8; 32],
+            16,
+            100,
+            format!("{}.txt", file_id),
+        );
+        let created = api::create_agreement(&mut runtime, &signer, descriptor).await??;
+        for node in ["node_1", "node_2", "node_3"] {
+            api::join_agreement(&mut runtime, &signer, &created.agreement_id, node).await??;
+        }
+    }
+    Ok((runtime, writer, db_dir))
+}
+
+async fn challenge_ids(runtime: &mut Runtime) -> Result<Vec<String>> {
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    Ok(
+        api::generate_challenges_for_block(runtime, &signer, BLOCK_HEIGHT)
+            .await?
+            .into_iter()
+            .map(|c| c.challenge_id)
+            .collect(),
+    )
+}
+
+#[tokio::test]
+async fn test_challenge_generation_is_deterministic() -> Result<()> {
+    let prev_hash = challenging_prev_hash();
+
+    let mut runs = Vec::new();
+    for _ in 0..2 {
+        let (mut runtime, _writer, _temp_dir) = setup(prev_hash).await?;
+        runs.push(challenge_ids(&mut runtime).await?);
+    }
+    assert!(!runs[0].is_empty(), "expected at least one challenge");
+    assert_eq!(runs[0], runs[1]);
+
+    // same chain state, but challenges are generated by a runtime rebuilt
+    // from the database as after a restart
+    let (runtime, writer, (temp_dir, db_name)) = setup(prev_hash).await?;
+    drop((runtime, writer));
+    let writer = Writer::new(temp_dir.path(), &db_name).await?;
+    let mut runtime = runtime_at_height_one(&writer).await?;
+    assert_eq!(challenge_ids(&mut runtime).await?, runs[0]);
+
+    Ok(())
+}
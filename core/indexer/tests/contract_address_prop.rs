@@ -0,0 +1,83 @@
+use indexer::runtime::ContractAddress;
+use proptest::prelude::*;
+
+/// Contract names as published: kebab-case, since underscores separate the
+/// address fields.
+fn arb_name() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9-]{0,31}"
+}
+
+fn arb_address() -> impl Strategy<Value = ContractAddress> {
+    (arb_name(), any::<u64>(), any::<u64>()).prop_map(|(name, height, tx_index)| ContractAddress {
+        name,
+        height,
+        tx_index,
+    })
+}
+
+proptest! {
+    #[test]
+    fn contract_address_round_trips(address in arb_address()) {
+        let s = address.to_string();
+        prop_assert_eq!(
+            &s,
+            &format!("{}_{}_{}", address.name, address.height, address.tx_index)
+        );
+        prop_assert_eq!(s.parse::<ContractAddress>(), Ok(address));
+    }
+
+    #[test]
+    fn contract_address_matches_wire_type(address in arb_address()) {
+        let wire = indexer_types::ContractAddress::from(address.clone());
+        prop_assert_eq!(wire.to_string(), address.to_string());
+        prop_assert_eq!(
+            address.to_string().parse::<indexer_types::ContractAddress>(),
+            Ok(wire)
+        );
+    }
+
+    #[test]
+    fn contract_address_rejects_non_numeric_fields(
+        name in arb_name(),
+        field in "[a-z-]{1,8}",
+        n in any::<u64>(),
+    ) {
+        prop_assert!(format!("{}_{}_{}", name, field, n).parse::<ContractAddress>().is_err());
+        prop_assert!(format!("{}_{}_{}", name, n, field).parse::<ContractAddress>().is_err());
+    }
+}
+
+#[test]
+fn test_contract_address_rejects_malformed_strings() {
+    for (s, err) in [
+        ("token", "expected 3 parts separated by '_', got: token"),
+        ("token_1", "expected 3 parts separated by '_', got: token_1"),
+        (
+            "token_1_2_3",
+            "expected 3 parts separated by '_', got: token_1_2_3",
+        ),
+        (
+            "token:1:2",
+            "expected 3 parts separated by '_', got: token:1:2",
+        ),
+        (
+            "token__2",
+            "invalid height: cannot parse integer from empty string",
+        ),
+        ("token_x_2", "invalid height: invalid digit found in string"),
+        (
+            "token_-1_2",
+            "invalid height: invalid digit found in string",
+        ),
+        (
+            "token_1_y",
+            "invalid tx_index: invalid digit found in string",
+        ),
+        (
+            "token_1_18446744073709551616",
+            "invalid tx_index: number too large to fit in target type",
+        ),
+    ] {
+        assert_eq!(s.parse::<ContractAddress>(), Err(err.to_string()), "{}", s);
+    }
+}
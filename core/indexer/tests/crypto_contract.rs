@@ -1,3 +1,11 @@
+use indexer::{
+    runtime::{
+        ct_eq, derive_id,
+        fuel::{Fuel, fuel_cost},
+        hash_bytes, hash_many, merkle_root,
+    },
+    test_utils::new_mock_transaction,
+};
 use testlib::*;
 
 interface!(name = "crypto", path = "../../test-contracts/crypto/wit");
@@ -43,6 +51,159 @@ async fn test_crypto_contract() -> Result<()> {
     Ok(())
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_crypto_generate_id_with() -> Result<()> {
+    let (alice, crypto) = run_test_crypto_contract(runtime).await?;
+
+    let result = crypto::generate_id_with(runtime, &crypto, &alice, "account").await?;
+    assert_eq!(result, "48af11cb6c2388a3");
+
+    let result = crypto::generate_id_with(runtime, &crypto, &alice, "order").await?;
+    assert_eq!(result, "48714cb3aedbb906");
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_crypto_ct_eq() -> Result<()> {
+    let alice = runtime.identity().await?;
+    let crypto = runtime.publish(&alice, "crypto").await?;
+    let hash = crypto::set_hash(runtime, &crypto, &alice, "foo").await?;
+
+    let mut flipped = hash.clone();
+    flipped[31] ^= 1;
+    let cases = [
+        (hash.clone(), hash.clone(), true),
+        (vec![], vec![], true),
+        (hash.clone(), flipped, false),
+        (hash.clone(), hash[..31].to_vec(), false),
+        (hash[..31].to_vec(), hash.clone(), false),
+        (vec![], hash.clone(), false),
+        // a shorter input isn't zero-padded to match
+        (vec![1, 0], vec![1], false),
+    ];
+    for (a, b, expected) in cases {
+        assert_eq!(ct_eq(&a, &b), expected, "{:?} == {:?}", a, b);
+        let result = crypto::ct_eq(runtime, &crypto, a.clone(), b.clone()).await?;
+        assert_eq!(result, expected, "{:?} == {:?}", a, b);
+    }
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_crypto_hash_many_and_merkle_root() -> Result<()> {
+    let alice = runtime.identity().await?;
+    let crypto = runtime.publish(&alice, "crypto").await?;
+
+    let inputs = vec!["foo", "bar", "", "baz"];
+    let batched = crypto::hash_many(runtime, &crypto, inputs.clone()).await?;
+    let looped = crypto::hash_each(runtime, &crypto, inputs.clone()).await?;
+    assert_eq!(batched, looped);
+    assert_eq!(
+        hex::encode(&batched[0].bytes),
+        "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+    );
+    assert_eq!(
+        hex::encode(&batched[2].bytes),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert!(
+        crypto::hash_many(runtime, &crypto, vec![])
+            .await?
+            .is_empty()
+    );
+
+    let root = crypto::merkle_root(runtime, &crypto, vec!["foo", "bar", "baz"]).await?;
+    assert_eq!(
+        hex::encode(root),
+        "5ac8333ed2f88046fe6397205b2bf26afabd252b62e910198e00c8399d10d8f5"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_hash_many_and_merkle_root_vectors() {
+    let leaves = |items: &[&str]| {
+        items
+            .iter()
+            .map(|item| item.as_bytes().to_vec())
+            .collect::<Vec<_>>()
+    };
+
+    let inputs = leaves(&["foo", "bar", ""]);
+    let looped = inputs
+        .iter()
+        .map(|input| hash_bytes(input).to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(hash_many(&inputs), looped);
+    assert_eq!(
+        hex::encode(&looped[1]),
+        "fcde2b2edba56bf408601fb721fe9b5c338d10ee429ea04fae5511b68fbf8fb9"
+    );
+
+    for (items, expected) in [
+        (
+            &[][..],
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+        (
+            &["foo"][..],
+            "1d2039fa7971f4bf01a1c20cb2a3fe7af46865ca9cd9b840c2063df8fec4ff75",
+        ),
+        (
+            &["foo", "bar"][..],
+            "39286a4a5531622751d6845bb8efb4cf33bec2c5f3f8430d7584874371a35bda",
+        ),
+        (
+            &["foo", "bar", "baz"][..],
+            "5ac8333ed2f88046fe6397205b2bf26afabd252b62e910198e00c8399d10d8f5",
+        ),
+        (
+            &["a", "b", "c", "d", "e"][..],
+            "fe14a5426fbd70c0fa73f52342afed0da0bd23c4838662ccf6b88a3070ead97b",
+        ),
+    ] {
+        assert_eq!(
+            hex::encode(merkle_root(&leaves(items))),
+            expected,
+            "{:?}",
+            items
+        );
+    }
+}
+
+#[test]
+fn test_hash_many_fuel_matches_looped_hashes() {
+    let inputs = [3u64, 0, 10];
+    let looped = inputs
+        .iter()
+        .map(|len| fuel_cost(&Fuel::CryptoHash(*len)))
+        .sum::<u64>();
+    assert_eq!(fuel_cost(&Fuel::CryptoHashMany(3, 13)), looped);
+
+    // a batch of empty inputs still pays for every digest
+    assert_eq!(
+        fuel_cost(&Fuel::CryptoHashMany(1000, 0)),
+        1000 * fuel_cost(&Fuel::CryptoHash(0))
+    );
+}
+
+#[test]
+fn test_derive_id_domains() {
+    let txid = new_mock_transaction(0).txid;
+
+    // an empty domain matches plain generate_id
+    assert_eq!(derive_id(&txid, 0, ""), "2c34ce1df23b838c");
+    assert_eq!(derive_id(&txid, 0, "account"), "48af11cb6c2388a3");
+    assert_eq!(derive_id(&txid, 0, "order"), "67c6320243078a61");
+    assert_ne!(
+        derive_id(&txid, 0, "account"),
+        derive_id(&txid, 1, "account")
+    );
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
 async fn test_crypto_contract_regtest() -> Result<()> {
     let (alice, crypto) = run_test_crypto_contract(runtime).await?;
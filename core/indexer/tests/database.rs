@@ -8,17 +8,20 @@ use indexer::{
             contract_has_state, delete_contract_state, delete_matching_paths,
             exists_contract_state, get_contract_bytes_by_address, get_contract_bytes_by_id,
             get_contract_id_from_address, get_contract_result, get_contracts,
-            get_latest_contract_state, get_latest_contract_state_value, get_op_result,
-            get_transaction_by_txid, get_transactions_at_height, insert_block, insert_contract,
-            insert_contract_result, insert_contract_state, insert_file_metadata,
+            get_contracts_paginated, get_latest_contract_state, get_latest_contract_state_value,
+            get_op_result, get_transaction_by_txid, get_transactions_at_height, insert_block,
+            insert_contract, insert_contract_result, insert_contract_state, insert_file_metadata,
             insert_processed_block, insert_transaction, matching_path,
             path_prefix_filter_contract_state, rollback_to_height, select_all_file_metadata,
             select_block_at_height, select_block_latest, select_processed_block_by_height_or_hash,
         },
-        types::{ContractResultRow, ContractRow, ContractStateRow, FileMetadataRow, OpResultId},
+        types::{
+            ContractQuery, ContractResultRow, ContractRow, ContractStateRow, FileMetadataRow,
+            OpResultId, OrderDirection,
+        },
     },
-    runtime::ContractAddress,
-    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_db},
+    runtime::{ContractAddress, Storage, hash_bytes},
+    test_utils::{gen_numbered_blocks, new_mock_block_hash, new_mock_transaction, new_test_db},
 };
 use indexer_types::{BlockRow, ContractListRow, TransactionRow};
 use libsql::{Connection, params};
@@ -487,6 +490,37 @@ async fn test_contracts_gapless() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_block_header_fields_survive_rollback() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    let blocks = gen_numbered_blocks(0, 3, new_mock_block_hash(0));
+    for block in &blocks {
+        insert_processed_block(&conn, block.into()).await?;
+    }
+
+    let row = select_block_at_height(&conn, 2).await?.unwrap();
+    assert_eq!(row.prev_hash, Some(blocks[0].hash));
+    assert_eq!(row.time, blocks[1].time as i64);
+
+    rollback_to_height(&conn, 1).await?;
+    assert!(select_block_at_height(&conn, 2).await?.is_none());
+    let row = select_block_at_height(&conn, 1).await?.unwrap();
+    assert_eq!(row.prev_hash, Some(new_mock_block_hash(0)));
+    assert_eq!(row.time, blocks[0].time as i64);
+
+    for block in &blocks[1..] {
+        insert_processed_block(&conn, block.into()).await?;
+    }
+    for block in &blocks {
+        let row = select_block_at_height(&conn, block.height as i64)
+            .await?
+            .unwrap();
+        assert_eq!(row, BlockRow::from(block));
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_map_keys() -> Result<()> {
     let (_reader, writer, _temp_dir) = new_test_db().await?;
@@ -737,3 +771,315 @@ async fn test_file_metadata_operations() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_storage_max_value_size() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    let height = 800000;
+    insert_block(
+        &conn,
+        BlockRow::builder()
+            .height(height)
+            .hash(new_mock_block_hash(height as u32))
+            .build(),
+    )
+    .await?;
+
+    let storage = Storage::builder()
+        .conn(conn)
+        .height(height)
+        .max_value_size(16)
+        .build();
+    let contract_id = 1;
+
+    storage.set(contract_id, "under", &[1; 15]).await?;
+    storage.set(contract_id, "at", &[1; 16]).await?;
+    let err = storage
+        .set(contract_id, "over", &[1; 17])
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("exceeding the maximum of 16 bytes")
+    );
+
+    assert!(storage.exists(contract_id, "under").await?);
+    assert!(storage.exists(contract_id, "at").await?);
+    assert!(!storage.exists(contract_id, "over").await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_storage_state_version() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    let height = 800000;
+    insert_block(
+        &conn,
+        BlockRow::builder()
+            .height(height)
+            .hash(new_mock_block_hash(height as u32))
+            .build(),
+    )
+    .await?;
+
+    let storage = Storage::builder().conn(conn).height(height).build();
+    let contract_id = 1;
+    let other_contract_id = 2;
+    assert_eq!(storage.state_version(contract_id).await?, 0);
+
+    storage.savepoint().await?;
+    storage.set(contract_id, "key", &[1]).await?;
+    storage.commit().await?;
+    let version = storage.state_version(contract_id).await?;
+    assert_eq!(version, 1);
+
+    // a rolled back call leaves the version unchanged
+    storage.savepoint().await?;
+    storage.set(contract_id, "key", &[2]).await?;
+    storage.set(contract_id, "other", &[3]).await?;
+    storage.rollback().await?;
+    assert_eq!(storage.state_version(contract_id).await?, version);
+
+    // deleting a missing path writes nothing
+    assert!(!storage.delete(contract_id, "missing").await?);
+    assert_eq!(storage.state_version(contract_id).await?, version);
+
+    assert!(storage.delete(contract_id, "key").await?);
+    assert!(storage.state_version(contract_id).await? > version);
+
+    assert_eq!(storage.state_version(other_contract_id).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_contracts() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in [1, 2] {
+        insert_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let early = ContractAddress {
+        name: "early".to_string(),
+        height: 1,
+        tx_index: 3,
+    };
+    let late = ContractAddress {
+        name: "late".to_string(),
+        height: 2,
+        tx_index: 0,
+    };
+    for address in [&early, &late] {
+        insert_contract(
+            &conn,
+            ContractRow::builder()
+                .bytes(address.name.as_bytes().to_vec())
+                .height(address.height)
+                .tx_index(address.tx_index)
+                .name(address.name.clone())
+                .build(),
+        )
+        .await?;
+    }
+    let early_id = get_contract_id_from_address(&conn, &early).await?.unwrap();
+    let late_id = get_contract_id_from_address(&conn, &late).await?.unwrap();
+
+    let storage = Storage::builder().conn(conn.clone()).build();
+    assert_eq!(
+        storage.list_contracts().await?,
+        vec![(early.clone(), early_id), (late.clone(), late_id)]
+    );
+
+    let (rows, meta) = get_contracts_paginated(
+        &conn,
+        ContractQuery::builder()
+            .order(OrderDirection::Asc)
+            .limit(1)
+            .build(),
+    )
+    .await?;
+    assert_eq!(meta.total_count, 2);
+    assert!(meta.has_more);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, early_id);
+    assert_eq!(rows[0].name, early.name);
+    assert_eq!(rows[0].height, early.height);
+    assert_eq!(rows[0].tx_index, early.tx_index);
+
+    let (rows, meta) = get_contracts_paginated(
+        &conn,
+        ContractQuery::builder()
+            .order(OrderDirection::Asc)
+            .maybe_cursor(meta.next_cursor)
+            .build(),
+    )
+    .await?;
+    assert!(!meta.has_more);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, late_id);
+    assert_eq!(rows[0].name, late.name);
+    assert_eq!(rows[0].height, late.height);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_contract_by_hash() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    insert_block(
+        &conn,
+        BlockRow::builder()
+            .height(1)
+            .hash(new_mock_block_hash(1))
+            .build(),
+    )
+    .await?;
+
+    let bytes = b"same component".to_vec();
+    let mut ids = Vec::new();
+    for (tx_index, name) in ["first", "second"].into_iter().enumerate() {
+        let storage = Storage::builder()
+            .conn(conn.clone())
+            .height(1)
+            .tx_index(tx_index as i64)
+            .build();
+        ids.push(storage.insert_contract(name, &bytes).await?);
+    }
+    assert_ne!(ids[0], ids[1]);
+
+    let storage = Storage::builder().conn(conn.clone()).build();
+    let hash = storage.contract_hash(ids[0]).await?.unwrap();
+    assert_eq!(hash, hash_bytes(&bytes));
+    assert_eq!(storage.contract_hash(ids[1]).await?, Some(hash));
+
+    let contracts = storage.contract_by_hash(&hash).await?;
+    assert_eq!(
+        contracts
+            .iter()
+            .map(|(address, id)| (address.name.as_str(), *id))
+            .collect::<Vec<_>>(),
+        vec![("first", ids[0]), ("second", ids[1])]
+    );
+
+    assert!(
+        storage
+            .contract_by_hash(&hash_bytes(b"other"))
+            .await?
+            .is_empty()
+    );
+    assert_eq!(storage.contract_hash(ids[1] + 1).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_at_height() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 1..=4 {
+        insert_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let contract_id = Storage::builder()
+        .conn(conn.clone())
+        .height(1)
+        .build()
+        .insert_contract("token", b"component")
+        .await?;
+    let path = "ledger.alice";
+    for (height, balance) in [(1, 10u64), (2, 25), (3, 7)] {
+        let storage = Storage::builder().conn(conn.clone()).height(height).build();
+        storage
+            .set(contract_id, path, &balance.to_le_bytes())
+            .await?;
+    }
+    Storage::builder()
+        .conn(conn.clone())
+        .height(4)
+        .build()
+        .delete(contract_id, path)
+        .await?;
+
+    let storage = Storage::builder().conn(conn.clone()).build();
+    for (height, expected) in [
+        (0, None),
+        (1, Some(10u64)),
+        (2, Some(25)),
+        (3, Some(7)),
+        (4, None),
+    ] {
+        let value = storage
+            .get_at_height(contract_id, path, height)
+            .await?
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+        assert_eq!(value, expected, "height {}", height);
+    }
+    assert_eq!(
+        storage.get_at_height(contract_id, "ledger.bob", 3).await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_map_keys_skip_deleted_keys() -> Result<()> {
+    let (_reader, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 1..=3 {
+        insert_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let contract_id = 123;
+    let state = |height: i64, path: &str| {
+        ContractStateRow::builder()
+            .contract_id(contract_id)
+            .tx_index(1)
+            .height(height)
+            .path(format!("test.path.{}", path))
+            .value(vec![1, 2, 3, 4])
+            .build()
+    };
+    for path in ["gone", "split.a", "split.b", "back"] {
+        insert_contract_state(&conn, state(1, path)).await?;
+    }
+    for path in ["gone", "split.a", "back"] {
+        assert!(
+            delete_contract_state(&conn, 2, 1, contract_id, &format!("test.path.{}", path)).await?
+        );
+    }
+    insert_contract_state(&conn, state(3, "back")).await?;
+
+    // a key's older value must not outlive its tombstone, and a key with
+    // several live paths is listed once
+    let stream =
+        path_prefix_filter_contract_state(&conn, contract_id, "test.path".to_string()).await?;
+    let paths = stream.try_collect::<Vec<String>>().await?;
+    assert_eq!(paths, vec!["back", "split"]);
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Result, anyhow};
+use indexer::{runtime::token, test_utils::new_test_runtime_with_signer};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{
+    Layer,
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+};
+
+#[derive(Default)]
+struct Fields(HashMap<String, String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+/// Collects the fields of every closed `execute` span.
+#[derive(Clone, Default)]
+struct ExecuteSpans(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ExecuteSpans {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "execute" {
+            return;
+        }
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(fields) = span.extensions_mut().get_mut::<Fields>()
+        {
+            values.record(fields);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id)
+            && let Some(fields) = span.extensions_mut().remove::<Fields>()
+        {
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_execute_span_records_gas() -> Result<()> {
+    let spans = ExecuteSpans::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    token::api::transfer(&mut runtime, &signer, "recipient", 1.into()).await??;
+
+    let gas = runtime
+        .storage
+        .results_in_range(1, 1)
+        .await?
+        .into_iter()
+        .rfind(|row| row.func == "transfer")
+        .ok_or(anyhow!("transfer result not found"))?
+        .gas;
+
+    let spans = spans.0.lock().unwrap();
+    let span = spans
+        .iter()
+        .rfind(|fields| fields.get("func").map(String::as_str) == Some("transfer"))
+        .ok_or(anyhow!("transfer span not found"))?;
+    assert_eq!(span["contract"], token::address().to_string());
+    assert_eq!(span["height"], "1");
+    assert_eq!(span["gas_limit"], "100000");
+    assert_eq!(span["gas_consumed"], gas.to_string());
+    assert_eq!(span["result_kind"], "ok");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_span_classifies_results() -> Result<()> {
+    let spans = ExecuteSpans::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+
+    // more than was issued: the contract returns an error
+    token::api::transfer(&mut runtime, &signer, "recipient", 1_000.into())
+        .await?
+        .unwrap_err();
+    // too little gas to finish: the guest runs out of fuel and traps
+    runtime.set_gas_limit(10);
+    token::api::transfer(&mut runtime, &signer, "recipient", 1.into())
+        .await
+        .unwrap_err();
+    // never runs
+    runtime.set_gas_limit(100_000);
+    runtime
+        .execute(Some(&signer), &token::address(), "transfer(")
+        .await
+        .unwrap_err();
+
+    let spans = spans.0.lock().unwrap();
+    let transfers = spans
+        .iter()
+        .filter(|fields| fields.get("func").map(String::as_str) == Some("transfer"))
+        .map(|fields| fields["result_kind"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(transfers, vec!["err", "trap"]);
+    let rejected = spans
+        .last()
+        .ok_or(anyhow!("rejected call span not found"))?;
+    assert!(!rejected.contains_key("func"));
+    assert_eq!(rejected["result_kind"], "err");
+
+    Ok(())
+}
@@ -1,3 +1,10 @@
+use indexer::{
+    runtime::{
+        Compression,
+        fuel::{Fuel, FuelDiscriminants, FuelGauge, fuel_cost},
+    },
+    test_utils::{new_test_runtime_with_signer, publish_test_contract, read_test_contract},
+};
 use testlib::*;
 
 interface!(name = "arith", path = "../../test-contracts/arith/wit",);
@@ -23,6 +30,12 @@ async fn run_test_fib_contract(runtime: &mut Runtime) -> Result<()> {
     let result = arith::last_op(runtime, &arith).await?;
     assert_eq!(result, last_op);
 
+    let result = proxy::get_deployer(runtime, &proxy).await?;
+    assert_eq!(result, Some(signer.to_string()));
+
+    let result = proxy::self_address(runtime, &proxy, &signer).await?;
+    assert_eq!(result, proxy);
+
     proxy::set_contract_address(runtime, &proxy, &signer, fib.clone()).await?;
 
     let result = proxy::get_contract_address(runtime, &proxy).await?;
@@ -45,6 +58,33 @@ async fn run_test_fib_contract(runtime: &mut Runtime) -> Result<()> {
     let result = arith::checked_sub(runtime, &arith, y, x).await?;
     assert_eq!(result, Err(Error::Message("less than 0".to_string())));
 
+    // `?` on a failed parse surfaces the parser's own description
+    let result = arith::checked_sub(runtime, &arith, "five", y).await?;
+    assert_eq!(
+        result,
+        Err(Error::Message(
+            "Parse integer error: invalid digit found in string".to_string()
+        ))
+    );
+
+    let result = arith::checked_sub(runtime, &arith, x, "").await?;
+    assert_eq!(
+        result,
+        Err(Error::Message(
+            "Parse integer error: cannot parse integer from empty string".to_string()
+        ))
+    );
+
+    // multiple return values come back as a tuple
+    let result = arith::divmod(runtime, &arith, 17, 5).await?;
+    assert_eq!(result, Ok((3, 2)));
+
+    let result = arith::divmod(runtime, &arith, 17, 0).await?;
+    assert_eq!(
+        result,
+        Err(Error::DivByZero("division by zero".to_string()))
+    );
+
     // result through import
     let x = "18";
     let y = "10";
@@ -64,6 +104,272 @@ async fn run_test_fib_contract(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_arith_checked_ops() -> Result<()> {
+    let signer = runtime.identity().await?;
+    let arith = runtime.publish(&signer, "arith").await?;
+
+    let result = arith::eval(
+        runtime,
+        &arith,
+        &signer,
+        10,
+        arith::Op::Div(arith::Operand { y: 2 }),
+    )
+    .await?;
+    assert_eq!(result, Ok(arith::ArithReturn { value: 5 }));
+
+    let result = arith::eval(
+        runtime,
+        &arith,
+        &signer,
+        10,
+        arith::Op::Div(arith::Operand { y: 0 }),
+    )
+    .await?;
+    assert_eq!(result, Err(Error::Message("division by zero".to_string())));
+
+    let result = arith::eval(
+        runtime,
+        &arith,
+        &signer,
+        u64::MAX,
+        arith::Op::Mul(arith::Operand { y: 2 }),
+    )
+    .await?;
+    assert_eq!(result, Err(Error::Message("overflow".to_string())));
+
+    // failed evals roll back the stored op
+    let result = arith::last_op(runtime, &arith).await?;
+    assert_eq!(result, Some(arith::Op::Div(arith::Operand { y: 2 })));
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_arith_nested_storage() -> Result<()> {
+    let signer = runtime.identity().await?;
+    let arith = runtime.publish(&signer, "arith").await?;
+
+    // empty list and none round-trip from init
+    let result = arith::history(runtime, &arith).await?;
+    assert_eq!(result, vec![]);
+    let result = arith::last_operand(runtime, &arith).await?;
+    assert_eq!(result, None);
+
+    let sum = arith::Op::Sum(arith::Operand { y: 2 });
+    arith::eval(runtime, &arith, &signer, 1, sum.clone()).await??;
+    let result = arith::last_operand(runtime, &arith).await?;
+    assert_eq!(result, Some(arith::Operand { y: 2 }));
+
+    arith::eval(runtime, &arith, &signer, 1, arith::Op::Id).await??;
+    let result = arith::last_operand(runtime, &arith).await?;
+    assert_eq!(result, None);
+
+    // a failed eval rolls back its history entry
+    let div = arith::Op::Div(arith::Operand { y: 0 });
+    let result = arith::eval(runtime, &arith, &signer, 1, div).await?;
+    assert!(result.is_err());
+
+    let result = arith::history(runtime, &arith).await?;
+    assert_eq!(result, vec![sum, arith::Op::Id]);
+
+    // overwriting with a shorter list drops the elements past its end
+    arith::set_history(runtime, &arith, &signer, vec![arith::Op::Id]).await?;
+    let result = arith::history(runtime, &arith).await?;
+    assert_eq!(result, vec![arith::Op::Id]);
+    assert!(arith::history_stored(runtime, &arith, 0).await?);
+    assert!(!arith::history_stored(runtime, &arith, 1).await?);
+
+    arith::set_history(runtime, &arith, &signer, vec![]).await?;
+    let result = arith::history(runtime, &arith).await?;
+    assert_eq!(result, vec![]);
+    assert!(!arith::history_stored(runtime, &arith, 0).await?);
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_panic_message_preserved() -> Result<()> {
+    let signer = runtime.identity().await?;
+    let fib = runtime.publish(&signer, "fib").await?;
+    let arith = runtime.publish(&signer, "arith").await?;
+
+    // fib(94) overflows u64, and fib panics on the failed addition
+    let err = fib::fib(runtime, &fib, &signer, arith.clone(), 94)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::Trap(message)) if message.contains("fib addition failed")
+    ));
+    // the calls that led to the panic travel with the error
+    let trace = err
+        .downcast_ref::<CallTrace>()
+        .expect("failure carries its call trace");
+    assert_eq!(trace.frames[0], "fib.fib");
+    assert!(err.to_string().starts_with("in fib.fib"), "{}", err);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuel_cost_matches_tracked_fuel() -> Result<()> {
+    let gauge = FuelGauge::new();
+    let ops = [Fuel::Get(8), Fuel::Set(8), Fuel::NumbersAddInteger];
+    for op in &ops {
+        gauge.track(op).await;
+    }
+
+    let expected = ops.iter().map(fuel_cost).sum::<u64>();
+    assert_eq!(expected, 80 + 280 + 100);
+    assert_eq!(gauge.total_host_fuel().await, expected);
+    assert_eq!(
+        gauge
+            .history()
+            .await
+            .iter()
+            .map(|(_, cost)| cost)
+            .sum::<u64>(),
+        expected
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuel_cost_matches_a_contract_call() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let arith = publish_test_contract(&mut runtime, &signer, "arith").await?;
+
+    let gauge = FuelGauge::new();
+    runtime.gauge = Some(gauge.clone());
+    let result = runtime
+        .execute(
+            None,
+            &arith,
+            &format!(
+                "compare({}, {})",
+                to_wave_expr(Integer::from(1u64)),
+                to_wave_expr(Integer::from(2u64))
+            ),
+        )
+        .await?;
+    runtime.gauge = None;
+    assert_eq!(result, "\"less\"");
+
+    // the comparison is the call's only host operation, charged at its
+    // published cost
+    let cost = fuel_cost(&Fuel::NumbersCmpInteger);
+    assert_eq!(
+        gauge.history().await,
+        vec![(FuelDiscriminants::NumbersCmpInteger, cost)]
+    );
+    assert_eq!(gauge.total_host_fuel().await, cost);
+
+    // and taken out of the call's fuel on top of the wasm it ran
+    let consumed = gauge.starting_fuel().await - gauge.ending_fuel().await;
+    assert!(
+        consumed > cost,
+        "call consumed {} fuel, less than its host cost {}",
+        consumed,
+        cost
+    );
+
+    Ok(())
+}
+
+fn rejection(result: Result<String>) -> ExecutionError {
+    result
+        .expect_err("publish should be rejected")
+        .downcast::<ExecutionError>()
+        .expect("rejected with an execution error")
+}
+
+#[tokio::test]
+async fn test_publish_rejects_contracts_over_limits() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let address = |name: &str| ContractAddress {
+        name: name.to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+
+    // limits only apply from their activation height on
+    runtime.max_contract_size = Some(1024);
+    runtime.contract_limits_activation_height = 2;
+    let err = runtime
+        .publish(&signer, "raw", &[0xff; 2048])
+        .await
+        .expect_err("raw bytes are not a contract");
+    assert!(!matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::ContractTooLarge(_))
+    ));
+    runtime.contract_limits_activation_height = 1;
+
+    // neither blob is wasm, so only the limits can have rejected them
+    let err = rejection(runtime.publish(&signer, "raw", &[0xff; 2048]).await);
+    assert!(matches!(err, ExecutionError::ContractTooLarge(1024)));
+    let bomb = Compression::default().compress(&[0; 1 << 20]);
+    assert!(bomb.len() < 1024);
+    let err = rejection(runtime.publish(&signer, "bomb", &bomb).await);
+    assert!(matches!(err, ExecutionError::ContractTooLarge(1024)));
+    for name in ["raw", "bomb"] {
+        assert_eq!(runtime.storage.contract_id(&address(name)).await?, None);
+    }
+
+    runtime.max_contract_size = None;
+    runtime.max_contract_exports = Some(1);
+    let bytes = read_test_contract("fib").await?;
+    let err = rejection(runtime.publish(&signer, "fib", &bytes).await);
+    assert!(matches!(err, ExecutionError::TooManyExports(1)));
+    assert_eq!(runtime.storage.contract_id(&address("fib")).await?, None);
+
+    runtime.max_contract_exports = None;
+    let fib = publish_test_contract(&mut runtime, &signer, "fib").await?;
+    assert!(runtime.storage.contract_id(&fib).await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_has_state_once_init_has_run() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let bytes = read_test_contract("arith").await?;
+    // stored without running `init`, as publishing would
+    let contract_id = runtime
+        .storage
+        .insert_contract_with_deployer("arith", &bytes, Some(&*signer))
+        .await?;
+    assert!(!runtime.storage.has_state(contract_id).await?);
+
+    let arith = ContractAddress {
+        name: "arith".to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+    runtime.execute(Some(&signer), &arith, "init()").await?;
+    assert!(runtime.storage.has_state(contract_id).await?);
+
+    Ok(())
+}
+
+async fn run_test_published_contract_has_state(runtime: &mut Runtime) -> Result<()> {
+    let signer = runtime.identity().await?;
+    let arith = runtime.publish(&signer, "arith").await?;
+    assert!(runtime.has_state(&arith).await?);
+    Ok(())
+}
+
+#[test]
+fn test_parse_bool_error_converts_to_message() {
+    let err: Error = "yes".parse::<bool>().unwrap_err().into();
+    assert_eq!(
+        err,
+        Error::Message("Parse bool error: provided string was not `true` or `false`".to_string())
+    );
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_fib_contract() -> Result<()> {
     run_test_fib_contract(runtime).await
@@ -73,3 +379,13 @@ async fn test_fib_contract() -> Result<()> {
 async fn test_fib_contract_regtest() -> Result<()> {
     run_test_fib_contract(runtime).await
 }
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_published_contract_has_state() -> Result<()> {
+    run_test_published_contract_has_state(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
+async fn test_published_contract_has_state_regtest() -> Result<()> {
+    run_test_published_contract_has_state(runtime).await
+}
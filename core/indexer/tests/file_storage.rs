@@ -13,3 +13,14 @@ async fn test_file_storage_regtest() -> Result<()> {
 async fn test_file_storage_e2e_regtest() -> Result<()> {
     file_storage_tests::proof_verification_e2e::run(runtime).await
 }
+
+#[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
+async fn test_file_storage_split_outcomes_regtest() -> Result<()> {
+    file_storage_tests::proof_verification_e2e::e2e_expired_challenge_rejected_in_aggregate(runtime)
+        .await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_file_storage_challenge_selector() -> Result<()> {
+    file_storage_tests::native_filestorage_contract::challenge_selector(runtime).await
+}
@@ -58,6 +58,42 @@ async fn filestorage_defaults(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn filestorage_config(runtime: &mut Runtime) -> Result<()> {
+    // One call returns every protocol parameter.
+    let config = filestorage::config(runtime).await?;
+    assert_eq!(config.min_nodes, 3);
+    assert_eq!(config.challenge_deadline_blocks, 2016);
+    assert_eq!(config.c_target, 12);
+    assert_eq!(config.s_chal, 100);
+    assert_eq!(config.blocks_per_year, 52560);
+    assert_eq!(config.challenge_selector, Some(0));
+
+    Ok(())
+}
+
+pub async fn challenge_selector(runtime: &mut Runtime) -> Result<()> {
+    let signer = runtime.identity().await?;
+    let core = Signer::Core(Box::new(signer.clone()));
+
+    filestorage::set_challenge_selector(runtime, &core, 1).await??;
+    let config = filestorage::config(runtime).await?;
+    assert_eq!(config.challenge_selector, Some(1));
+
+    let err = filestorage::set_challenge_selector(runtime, &core, 7).await?;
+    assert!(matches!(err, Err(Error::Message(_))));
+    let config = filestorage::config(runtime).await?;
+    assert_eq!(config.challenge_selector, Some(1));
+
+    // only the node core may change it
+    assert!(
+        filestorage::set_challenge_selector(runtime, &signer, 0)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
 async fn filestorage_empty_file_id_fails(runtime: &mut Runtime) -> Result<()> {
     let signer = runtime.identity().await?;
     let descriptor = make_descriptor(
@@ -580,9 +616,7 @@ async fn challenge_gen_smoke_test(runtime: &mut Runtime) -> Result<()> {
     filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_1").await??;
     filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_2").await??;
 
-    let block_hash = vec![1u8; 32];
-    let challenges =
-        filestorage::generate_challenges_for_block(runtime, &signer, 1000, block_hash).await?;
+    let challenges = filestorage::generate_challenges_for_block(runtime, &signer, 1000).await?;
 
     // Verify the return type is correct (list of challenges, possibly empty)
     assert!(challenges.len() <= 1, "Should have 0 or 1 challenges");
@@ -599,6 +633,7 @@ async fn challenge_gen_smoke_test(runtime: &mut Runtime) -> Result<()> {
 
 pub async fn run(runtime: &mut Runtime) -> Result<()> {
     filestorage_defaults(runtime).await?;
+    filestorage_config(runtime).await?;
     filestorage_empty_file_id_fails(runtime).await?;
     filestorage_get_all_active_agreements(runtime).await?;
     filestorage_expire_challenges_noop(runtime).await?;
@@ -32,10 +32,8 @@ async fn setup_active_agreement_with_challenge(
     filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_3").await??;
 
     // Generate a challenge
-    let block_hash = vec![42u8; 32];
     let challenges =
-        filestorage::generate_challenges_for_block(runtime, &signer, block_height, block_hash)
-            .await?;
+        filestorage::generate_challenges_for_block(runtime, &signer, block_height).await?;
 
     Ok((created.agreement_id, challenges))
 }
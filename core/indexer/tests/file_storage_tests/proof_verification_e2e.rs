@@ -11,7 +11,10 @@
 
 use ff::PrimeField;
 use indexer::database::types::field_element_to_bytes;
-use kontor_crypto::api::{self, FieldElement};
+use kontor_crypto::{
+    FileLedger, PorSystem,
+    api::{self, Challenge, FieldElement},
+};
 use serde::Deserialize;
 use testlib::*;
 
@@ -186,6 +189,27 @@ async fn e2e_cross_block_aggregation_with_new_agreement(runtime: &mut Runtime) -
         result.verified_count, 2,
         "Should verify both challenges even after new agreement was created"
     );
+    let mut outcomes = result
+        .outcomes
+        .iter()
+        .map(|o| (o.challenge_id.clone(), o.status))
+        .collect::<Vec<_>>();
+    outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = vec![
+        (
+            challenge_a.challenge_id.clone(),
+            filestorage::ChallengeStatus::Proven,
+        ),
+        (
+            challenge_b.challenge_id.clone(),
+            filestorage::ChallengeStatus::Proven,
+        ),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        outcomes, expected,
+        "Each challenge should report its outcome"
+    );
 
     // Verify challenge statuses
     let challenge_a_after = filestorage::get_challenge(runtime, &challenge_a.challenge_id)
@@ -209,11 +233,216 @@ async fn e2e_cross_block_aggregation_with_new_agreement(runtime: &mut Runtime) -
     Ok(())
 }
 
+// ─────────────────────────────────────────────────────────────────
+// Aggregated Proof Mixing Provers Is Rejected
+// ─────────────────────────────────────────────────────────────────
+
+/// A proof covering challenges issued to two different provers is refused
+/// before verification, leaving both challenges active.
+async fn e2e_mixed_prover_proof_rejected(runtime: &mut Runtime) -> Result<()> {
+    let signer = runtime.identity().await?;
+
+    let (prepared_d, metadata_d) =
+        prepare_test_file(b"Content of file D for mixed provers", "mixed_d.txt");
+    let (prepared_e, metadata_e) =
+        prepare_test_file(b"Content of file E for mixed provers", "mixed_e.txt");
+
+    let mut agreement_ids = Vec::new();
+    for metadata in [&metadata_d, &metadata_e] {
+        let created =
+            filestorage::create_agreement(runtime, &signer, metadata_to_descriptor(metadata))
+                .await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_1").await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_2").await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_3").await??;
+        agreement_ids.push(created.agreement_id);
+    }
+
+    let block_height = 50000u64;
+    let challenge_d = filestorage::create_challenge_for_agreement(
+        runtime,
+        &signer,
+        &agreement_ids[0],
+        "node_1",
+        block_height,
+        valid_seed_bytes(300),
+    )
+    .await??;
+    let challenge_e = filestorage::create_challenge_for_agreement(
+        runtime,
+        &signer,
+        &agreement_ids[1],
+        "node_2",
+        block_height,
+        valid_seed_bytes(301),
+    )
+    .await??;
+
+    // The contract rejects the proof before checking it against the ledger,
+    // so a local ledger holding just these files is enough to produce it.
+    let mut ledger = FileLedger::new();
+    ledger.add_file(&metadata_d)?;
+    ledger.add_file(&metadata_e)?;
+    let challenges = vec![
+        Challenge::new(
+            metadata_d.clone(),
+            block_height,
+            100,
+            FieldElement::from(300u64),
+            "node_1".to_string(),
+        ),
+        Challenge::new(
+            metadata_e.clone(),
+            block_height,
+            100,
+            FieldElement::from(301u64),
+            "node_2".to_string(),
+        ),
+    ];
+    let proof_bytes = PorSystem::new(&ledger)
+        .prove(vec![&prepared_d, &prepared_e], &challenges)
+        .map_err(|e| anyhow!("Failed to generate proof: {e}"))?
+        .to_bytes()
+        .map_err(|e| anyhow!("Failed to serialize proof: {e}"))?;
+
+    let result = filestorage::verify_proof(runtime, &signer, proof_bytes).await?;
+    assert!(
+        matches!(&result, Err(Error::Message(m)) if m.contains("belongs to prover")),
+        "Proof mixing provers should be rejected, got: {:?}",
+        result
+    );
+
+    for challenge in [&challenge_d, &challenge_e] {
+        let after = filestorage::get_challenge(runtime, &challenge.challenge_id)
+            .await?
+            .expect("Challenge should exist");
+        assert_eq!(
+            after.status,
+            filestorage::ChallengeStatus::Active,
+            "Challenge {} should still be Active",
+            challenge.challenge_id
+        );
+    }
+
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────
+// Aggregated Proof With An Expired Challenge
+// ─────────────────────────────────────────────────────────────────
+
+/// A proof covering two challenges where one expired before the proof landed:
+/// the live challenge is proven and the expired one is reported as rejected.
+pub async fn e2e_expired_challenge_rejected_in_aggregate(runtime: &mut Runtime) -> Result<()> {
+    let signer = runtime.identity().await?;
+
+    let (prepared_x, metadata_x) =
+        prepare_test_file(b"Content of file X for split outcomes", "split_x.txt");
+    let (prepared_y, metadata_y) =
+        prepare_test_file(b"Content of file Y for split outcomes", "split_y.txt");
+
+    let mut agreement_ids = Vec::new();
+    for metadata in [&metadata_x, &metadata_y] {
+        let created =
+            filestorage::create_agreement(runtime, &signer, metadata_to_descriptor(metadata))
+                .await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_1").await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_2").await??;
+        filestorage::join_agreement(runtime, &signer, &created.agreement_id, "node_3").await??;
+        agreement_ids.push(created.agreement_id);
+    }
+
+    // Challenge X expires before challenge Y is issued, so only X is past
+    // its deadline when the proof arrives.
+    let block_height = 60000u64;
+    let challenge_x = filestorage::create_challenge_for_agreement(
+        runtime,
+        &signer,
+        &agreement_ids[0],
+        "node_1",
+        block_height,
+        valid_seed_bytes(400),
+    )
+    .await??;
+    filestorage::expire_challenges(runtime, &signer, challenge_x.deadline_height).await?;
+    let challenge_y = filestorage::create_challenge_for_agreement(
+        runtime,
+        &signer,
+        &agreement_ids[1],
+        "node_1",
+        block_height,
+        valid_seed_bytes(401),
+    )
+    .await??;
+
+    let s_chal = filestorage::get_s_chal(runtime).await?;
+    let mut ledger = FileLedger::new();
+    ledger.add_file(&metadata_x)?;
+    ledger.add_file(&metadata_y)?;
+    let challenges = vec![
+        Challenge::new(
+            metadata_x.clone(),
+            block_height,
+            s_chal as usize,
+            FieldElement::from(400u64),
+            "node_1".to_string(),
+        ),
+        Challenge::new(
+            metadata_y.clone(),
+            block_height,
+            s_chal as usize,
+            FieldElement::from(401u64),
+            "node_1".to_string(),
+        ),
+    ];
+    let proof_bytes = PorSystem::new(&ledger)
+        .prove(vec![&prepared_x, &prepared_y], &challenges)
+        .map_err(|e| anyhow!("Failed to generate proof: {e}"))?
+        .to_bytes()
+        .map_err(|e| anyhow!("Failed to serialize proof: {e}"))?;
+
+    let result = filestorage::verify_proof(runtime, &signer, proof_bytes).await??;
+    assert_eq!(
+        result.verified_count, 1,
+        "Only the live challenge should count as verified"
+    );
+    let outcome_of = |id: &str| {
+        result
+            .outcomes
+            .iter()
+            .find(|o| o.challenge_id == id)
+            .map(|o| o.status)
+    };
+    assert_eq!(result.outcomes.len(), 2);
+    assert_eq!(
+        outcome_of(&challenge_x.challenge_id),
+        Some(filestorage::ChallengeStatus::Expired),
+        "Expired challenge should be reported as rejected"
+    );
+    assert_eq!(
+        outcome_of(&challenge_y.challenge_id),
+        Some(filestorage::ChallengeStatus::Proven),
+        "Live challenge should be reported as proven"
+    );
+
+    let x_after = filestorage::get_challenge(runtime, &challenge_x.challenge_id)
+        .await?
+        .expect("Challenge X should exist");
+    assert_eq!(x_after.status, filestorage::ChallengeStatus::Expired);
+    let y_after = filestorage::get_challenge(runtime, &challenge_y.challenge_id)
+        .await?
+        .expect("Challenge Y should exist");
+    assert_eq!(y_after.status, filestorage::ChallengeStatus::Proven);
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────
 // Test Runner
 // ─────────────────────────────────────────────────────────────────
 pub async fn run(runtime: &mut Runtime) -> Result<()> {
     e2e_cross_block_aggregation_with_new_agreement(runtime).await?;
+    e2e_mixed_prover_proof_rejected(runtime).await?;
     e2e_invalid_proof_rejected(runtime).await?;
     Ok(())
 }
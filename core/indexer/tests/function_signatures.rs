@@ -0,0 +1,48 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{FuncKind, token},
+    test_utils::new_test_runtime,
+};
+
+#[tokio::test]
+async fn test_function_signatures() -> Result<()> {
+    let (runtime, _writer, _temp_dir) = new_test_runtime(0).await?;
+    let token = token::address();
+
+    let signatures = runtime.function_signatures(&token).await?;
+    let find = |name: &str| {
+        signatures
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("{} not exported", name))
+    };
+
+    let transfer = find("transfer");
+    assert_eq!(transfer.kind, FuncKind::Proc);
+    assert_eq!(
+        transfer
+            .params
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["dst", "amt"]
+    );
+    assert_eq!(transfer.params[0].1, "string");
+    assert!(transfer.params[1].1.starts_with("record"));
+    assert_eq!(transfer.result.as_deref(), Some("result"));
+
+    let balance = find("balance");
+    assert_eq!(balance.kind, FuncKind::View);
+    assert_eq!(
+        balance.result.as_deref(),
+        Some("option<record { r0, r1, r2, r3, sign }>")
+    );
+
+    assert_eq!(find("issuance").kind, FuncKind::Core);
+    assert_eq!(find("init").result, None);
+
+    // served from the cache the second time
+    assert_eq!(runtime.function_signatures(&token).await?, signatures);
+
+    Ok(())
+}
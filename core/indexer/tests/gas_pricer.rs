@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use indexer::{
+    database::Writer,
+    runtime::{
+        BlockUsage, CheckedArithmetics, Decimal, FixedGasPricer, GasPricer, Runtime, token::api,
+        wit::Signer,
+    },
+    test_utils::{new_mock_transaction, new_test_runtime, new_test_signer},
+};
+use tempfile::TempDir;
+
+/// Twice the default price at every height.
+struct DoubledGasPricer;
+
+impl GasPricer for DoubledGasPricer {
+    fn token_per_gas(&self, usage: &BlockUsage) -> Decimal {
+        FixedGasPricer::default()
+            .token_per_gas(usage)
+            .mul(Decimal::from(2))
+            .unwrap()
+    }
+}
+
+/// Twice the default price after a block that consumed gas.
+struct CongestionGasPricer;
+
+impl GasPricer for CongestionGasPricer {
+    fn token_per_gas(&self, usage: &BlockUsage) -> Decimal {
+        let price = FixedGasPricer::default().token_per_gas(usage);
+        if usage.prev_gas > 0 {
+            price.mul(Decimal::from(2)).unwrap()
+        } else {
+            price
+        }
+    }
+}
+
+async fn runtime_with(pricer: Arc<dyn GasPricer>) -> Result<(Runtime, Signer, Writer, TempDir)> {
+    let (mut runtime, writer, (temp_dir, _)) = new_test_runtime(4).await?;
+    runtime.set_gas_pricer(pricer);
+    let signer = new_test_signer(&mut runtime, "test_signer").await?;
+    Ok((runtime, signer, writer, temp_dir))
+}
+
+/// The fee charged for a transfer of 1, from the signer's balance change.
+async fn transfer_fee(runtime: &mut Runtime, signer: &Signer) -> Result<Decimal> {
+    let before = api::balance(runtime, signer).await?.unwrap();
+    api::transfer(runtime, signer, "recipient", Decimal::from(1)).await??;
+    let after = api::balance(runtime, signer).await?.unwrap();
+    Ok(before.sub(after)?.sub(Decimal::from(1))?)
+}
+
+#[tokio::test]
+async fn test_gas_pricer_scales_escrow_and_fee() -> Result<()> {
+    let (mut fixed, fixed_signer, _fixed_writer, _fixed_dir) =
+        runtime_with(Arc::new(FixedGasPricer::default())).await?;
+    let (mut doubled, doubled_signer, _doubled_writer, _doubled_dir) =
+        runtime_with(Arc::new(DoubledGasPricer)).await?;
+
+    // the same call burns the same gas, charged at twice the price
+    fixed.set_gas_limit(100_000);
+    doubled.set_gas_limit(100_000);
+    let fixed_fee = transfer_fee(&mut fixed, &fixed_signer).await?;
+    let doubled_fee = transfer_fee(&mut doubled, &doubled_signer).await?;
+    assert_ne!(fixed_fee, Decimal::from(0));
+    assert_eq!(doubled_fee, fixed_fee.mul(Decimal::from(2))?);
+
+    // issuance grants 10 tokens: a limit escrowing 6 of them at the default
+    // price needs 12 at the doubled one
+    let gas_limit = 6_000_000_000;
+    fixed.set_gas_limit(gas_limit);
+    doubled.set_gas_limit(gas_limit);
+    api::transfer(&mut fixed, &fixed_signer, "recipient", Decimal::from(1)).await??;
+    let err = api::transfer(&mut doubled, &doubled_signer, "recipient", Decimal::from(1))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("does not have enough token to cover gas limit"),
+        "unexpected error: {}",
+        err
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gas_pricer_sees_previous_block_usage() -> Result<()> {
+    let (mut runtime, signer, _writer, _dir) = runtime_with(Arc::new(CongestionGasPricer)).await?;
+    runtime.set_gas_limit(100_000);
+    transfer_fee(&mut runtime, &signer).await?;
+
+    // block 1 consumed gas, so block 2 pays the doubled price
+    runtime
+        .set_context(2, 1, 0, 0, new_mock_transaction(2).txid, None, None)
+        .await;
+    let congested_fee = transfer_fee(&mut runtime, &signer).await?;
+
+    // nothing ran in block 3, so block 4 is back to the base price
+    runtime
+        .set_context(4, 1, 0, 0, new_mock_transaction(4).txid, None, None)
+        .await;
+    let base_fee = transfer_fee(&mut runtime, &signer).await?;
+
+    assert_ne!(base_fee, Decimal::from(0));
+    assert_eq!(congested_fee, base_fee.mul(Decimal::from(2))?);
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use indexer::{
+    database::{queries::rollback_to_height, types::ContractResultRow},
+    runtime::{Decimal, Runtime, token, wit::Signer},
+    test_utils::{new_mock_transaction, new_test_runtime},
+};
+use indexer_types::GasStats;
+
+/// Sums the receipts of the operations calling a contract in `from..=to` one
+/// by one. An operation's own result is stored after those of the calls it
+/// made, so it has the highest result index.
+async fn receipts(runtime: &Runtime, contract_id: i64, from: i64, to: i64) -> Result<GasStats> {
+    let rows = runtime.storage.results_in_range(from, to).await?;
+    let op = |r: &ContractResultRow| (r.height, r.tx_index, r.input_index, r.op_index);
+    let rows = rows.iter().filter(|r| {
+        r.contract_id == contract_id
+            && !rows
+                .iter()
+                .any(|o| op(o) == op(r) && o.result_index > r.result_index)
+    });
+    Ok(GasStats {
+        total_consumed: rows.clone().map(|r| r.gas).sum(),
+        call_count: rows.count() as i64,
+    })
+}
+
+#[tokio::test]
+async fn test_gas_stats_match_receipts_across_rollback() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(2).await?;
+    let token_id = runtime
+        .storage
+        .contract_id(&token::address())
+        .await?
+        .expect("token is published");
+    assert_eq!(
+        runtime.storage.gas_stats(token_id).await?,
+        GasStats::default()
+    );
+
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    for height in 1..=2 {
+        runtime
+            .set_context(
+                height,
+                1,
+                0,
+                0,
+                new_mock_transaction(height as u32).txid,
+                None,
+                None,
+            )
+            .await;
+        runtime.issuance(&signer).await?;
+        for tx_index in 2..=4 {
+            runtime
+                .set_context(
+                    height,
+                    tx_index,
+                    0,
+                    0,
+                    new_mock_transaction(height as u32).txid,
+                    None,
+                    None,
+                )
+                .await;
+            runtime.set_gas_limit(100_000);
+            token::api::transfer(&mut runtime, &signer, "recipient", Decimal::from(1)).await??;
+        }
+    }
+
+    let stats = runtime.storage.gas_stats(token_id).await?;
+    assert_eq!(stats, receipts(&runtime, token_id, 1, 2).await?);
+    assert!(stats.total_consumed > 0);
+    // an issuance and three transfers per block; the gas releases those
+    // operations make on the token are not counted again
+    assert_eq!(stats.call_count, 8);
+    let second_block = receipts(&runtime, token_id, 2, 2).await?;
+    assert!(second_block.call_count > 0);
+
+    rollback_to_height(&runtime.storage.conn, 1).await?;
+
+    let rolled_back = runtime.storage.gas_stats(token_id).await?;
+    assert_eq!(rolled_back, receipts(&runtime, token_id, 1, 1).await?);
+    assert_eq!(
+        rolled_back,
+        GasStats {
+            total_consumed: stats.total_consumed - second_block.total_consumed,
+            call_count: stats.call_count - second_block.call_count,
+        }
+    );
+
+    Ok(())
+}
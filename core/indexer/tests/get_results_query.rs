@@ -3,11 +3,12 @@ use indexer::{
     database::{
         queries::{
             get_results_paginated, insert_block, insert_contract, insert_contract_result,
-            insert_processed_block,
+            insert_processed_block, rollback_to_height,
         },
         types::{ContractResultRow, ContractRow, ResultQuery},
     },
-    test_utils::{new_mock_block_hash, new_test_db},
+    runtime::{ComponentCache, Runtime, Storage, wit::Signer},
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_db},
 };
 use indexer_types::BlockRow;
 use testlib::ContractAddress;
@@ -267,3 +268,53 @@ async fn test_get_results_query() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_results_in_range_after_rollback() -> Result<()> {
+    let (_, writer, _temp_dir) = new_test_db().await?;
+    let conn = writer.connection();
+    for height in 0..=2 {
+        insert_processed_block(
+            &conn,
+            BlockRow::builder()
+                .height(height)
+                .hash(new_mock_block_hash(height as u32))
+                .build(),
+        )
+        .await?;
+    }
+
+    let storage = Storage::builder().height(0).tx_index(0).conn(conn).build();
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    runtime.publish_native_contracts().await?;
+
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    for height in 1..=2 {
+        runtime
+            .set_context(
+                height,
+                1,
+                0,
+                0,
+                new_mock_transaction(height as u32).txid,
+                None,
+                None,
+            )
+            .await;
+        runtime.issuance(&signer).await?;
+    }
+
+    let results = runtime.storage.results_in_range(1, 2).await?;
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].height, 1);
+    assert_eq!(results[1].height, 2);
+
+    rollback_to_height(&runtime.storage.conn, 1).await?;
+
+    let results = runtime.storage.results_in_range(1, 2).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].height, 1);
+    assert!(runtime.storage.results_in_range(2, 2).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use indexer::height;
+
+#[test]
+fn test_heights_round_trip_within_range() {
+    for h in [0, 1, 921_300, u32::MAX as u64, i64::MAX as u64] {
+        assert_eq!(height::to_u64(height::to_i64(h)), h);
+    }
+    assert_eq!(height::to_i64(i64::MAX as u64), i64::MAX);
+    assert_eq!(height::to_u64(0), 0);
+    assert_eq!(height::to_u64(i64::MAX), i64::MAX as u64);
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "overflows the stored height")
+)]
+fn test_height_past_i64_max_does_not_wrap() {
+    // a plain cast would flip the sign
+    assert_eq!(height::to_i64(i64::MAX as u64 + 1), i64::MAX);
+    assert_eq!(height::to_i64(u64::MAX), i64::MAX);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "is negative"))]
+fn test_negative_stored_height_does_not_wrap() {
+    // a plain cast would turn -1 into u64::MAX
+    assert_eq!(height::to_u64(-1), 0);
+    assert_eq!(height::to_u64(i64::MIN), 0);
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{
+        ContractAddress, ExecutionError, Runtime,
+        fuel::{Fuel, FuelDiscriminants, FuelGauge, fuel_cost},
+        wit::Signer,
+    },
+    test_utils::{new_mock_transaction, new_test_runtime_with_signer, publish_test_contract},
+};
+
+/// Result of a single view call and the host calls it was metered for.
+async fn metered(
+    runtime: &mut Runtime,
+    tally: &ContractAddress,
+    expr: &str,
+) -> Result<(String, usize)> {
+    let gauge = FuelGauge::new();
+    runtime.gauge = Some(gauge.clone());
+    let result = runtime.execute(None, tally, expr).await?;
+    runtime.gauge = None;
+    Ok((result, gauge.history().await.len()))
+}
+
+/// Runtime with the tally contract published, and the database directory
+/// that must outlive it.
+async fn setup() -> Result<(Runtime, ContractAddress, Signer, impl Sized)> {
+    let (mut runtime, signer, _writer, temp_dir) = new_test_runtime_with_signer(2).await?;
+    let tally = publish_test_contract(&mut runtime, &signer, "tally").await?;
+    Ok((runtime, tally, signer, temp_dir))
+}
+
+#[tokio::test]
+async fn test_entries_match_keys_and_get_in_fewer_host_calls() -> Result<()> {
+    let (mut runtime, tally, signer, _temp_dir) = setup().await?;
+
+    for name in ["carol", "alice", "bob", "alice", "carol", "alice"] {
+        runtime
+            .execute(Some(&signer), &tally, &format!("vote(\"{}\")", name))
+            .await?;
+    }
+
+    let (keys_and_get, keys_and_get_calls) = metered(&mut runtime, &tally, "tallies()").await?;
+    let (entries, entries_calls) = metered(&mut runtime, &tally, "tallies-streamed()").await?;
+    assert_eq!(
+        entries,
+        "[{name: \"alice\", votes: 3}, {name: \"bob\", votes: 1}, {name: \"carol\", votes: 2}]"
+    );
+    assert_eq!(entries, keys_and_get);
+    assert!(
+        entries_calls < keys_and_get_calls,
+        "entries made {} host calls, keys and get made {}",
+        entries_calls,
+        keys_and_get_calls
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_entries_load_integer_values() -> Result<()> {
+    let (mut runtime, tally, signer, _temp_dir) = setup().await?;
+
+    for (name, weight) in [
+        ("bob", "{r0: 3, r1: 0, r2: 0, r3: 0, sign: minus}"),
+        ("alice", "{r0: 5, r1: 0, r2: 0, r3: 0, sign: plus}"),
+        ("carol", "{r0: 1, r1: 2, r2: 3, r3: 4, sign: plus}"),
+    ] {
+        runtime
+            .execute(
+                Some(&signer),
+                &tally,
+                &format!("weigh(\"{}\", {})", name, weight),
+            )
+            .await?;
+    }
+
+    let (keys_and_get, keys_and_get_calls) = metered(&mut runtime, &tally, "weights()").await?;
+    let (entries, entries_calls) = metered(&mut runtime, &tally, "weights-streamed()").await?;
+    assert_eq!(
+        entries,
+        "[(\"alice\", {r0: 5, r1: 0, r2: 0, r3: 0, sign: plus}), \
+         (\"bob\", {r0: 3, r1: 0, r2: 0, r3: 0, sign: minus}), \
+         (\"carol\", {r0: 1, r1: 2, r2: 3, r3: 4, sign: plus})]"
+    );
+    assert_eq!(entries, keys_and_get);
+    assert!(
+        entries_calls < keys_and_get_calls,
+        "entries made {} host calls, keys and get made {}",
+        entries_calls,
+        keys_and_get_calls
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keys_containing_dots_are_rejected() -> Result<()> {
+    let (mut runtime, tally, signer, _temp_dir) = setup().await?;
+
+    // "a.b" would read as key "b" nested under key "a", so it is rejected
+    for name in ["a", "a%2E", "a"] {
+        runtime
+            .execute(Some(&signer), &tally, &format!("vote(\"{}\")", name))
+            .await?;
+    }
+    let err = runtime
+        .execute(Some(&signer), &tally, "vote(\"a.b\")")
+        .await
+        .expect_err("dotted key accepted");
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::Trap(message)) if message.contains("must not contain '.'")
+    ));
+
+    let keys_and_get = runtime.execute(None, &tally, "tallies()").await?;
+    let entries = runtime.execute(None, &tally, "tallies-streamed()").await?;
+    assert_eq!(
+        keys_and_get,
+        "[{name: \"a\", votes: 2}, {name: \"a%2E\", votes: 1}]"
+    );
+    assert_eq!(entries, keys_and_get);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clear_removes_every_key_in_one_host_call() -> Result<()> {
+    let (mut runtime, tally, signer, _temp_dir) = setup().await?;
+
+    for name in ["carol", "alice", "bob", "alice"] {
+        runtime
+            .execute(Some(&signer), &tally, &format!("vote(\"{}\")", name))
+            .await?;
+    }
+
+    // Clear in a later block, so every entry needs a tombstone rather than
+    // being dropped in place
+    runtime
+        .set_context(2, 1, 0, 0, new_mock_transaction(2).txid, None, None)
+        .await;
+    let gauge = FuelGauge::new();
+    runtime.gauge = Some(gauge.clone());
+    let cleared = runtime.execute(Some(&signer), &tally, "reset()").await?;
+    runtime.gauge = None;
+    assert_eq!(cleared, "3");
+    let history = gauge.history().await;
+    assert!(
+        !history
+            .iter()
+            .any(|(kind, _)| *kind == FuelDiscriminants::DeleteMatchingPaths)
+    );
+    // the one call is still charged for every path it tombstones
+    assert_eq!(
+        history
+            .iter()
+            .filter(|(kind, _)| *kind == FuelDiscriminants::DeletePrefix)
+            .map(|(_, fuel)| *fuel)
+            .collect::<Vec<_>>(),
+        vec![fuel_cost(&Fuel::DeletePrefix(3))]
+    );
+
+    assert_eq!(runtime.execute(None, &tally, "tallies()").await?, "[]");
+    assert_eq!(
+        runtime.execute(None, &tally, "tallies-streamed()").await?,
+        "[]"
+    );
+
+    runtime
+        .execute(Some(&signer), &tally, "vote(\"bob\")")
+        .await?;
+    assert_eq!(
+        runtime.execute(None, &tally, "tallies()").await?,
+        "[{name: \"bob\", votes: 1}]"
+    );
+    assert_eq!(
+        runtime.execute(Some(&signer), &tally, "reset()").await?,
+        "1"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use bitcoin::{BlockHash, hashes::Hash};
+use indexer::{
+    bitcoin_follower::{ctrl::CtrlChannel, events::Event},
+    reactor,
+    runtime::{self, pool::PoolConfig, token::address as token_address},
+    test_utils::{await_block_at_height, new_mock_transaction, new_test_db},
+};
+use indexer_types::{Block, Op, OpMetadata, Transaction};
+use testlib::*;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::sleep,
+};
+use tokio_util::sync::CancellationToken;
+
+import!(
+    name = "token",
+    height = 0,
+    tx_index = 0,
+    path = "../../native-contracts/token/wit",
+);
+
+fn metadata(signer: &str) -> OpMetadata {
+    OpMetadata {
+        previous_output: bitcoin::OutPoint::null(),
+        input_index: 0,
+        signer: indexer_types::Signer::XOnlyPubKey(signer.to_string()),
+    }
+}
+
+async fn pending_balance(
+    pending_tx: &mpsc::Sender<reactor::PendingView>,
+    account: &str,
+) -> Result<Option<Decimal>> {
+    let (ret_tx, ret_rx) = oneshot::channel();
+    pending_tx
+        .send((
+            token_address(),
+            token::wave::balance_call_expr(account),
+            None,
+            ret_tx,
+        ))
+        .await?;
+    Ok(token::wave::balance_parse_return_expr(&ret_rx.await??))
+}
+
+#[tokio::test]
+async fn test_reactor_pending_view_reflects_mempool() -> Result<()> {
+    let cancel_token = CancellationToken::new();
+    let (ctrl, mut ctrl_rx) = CtrlChannel::create();
+    let (reader, writer, (temp_dir, db_name)) = new_test_db().await?;
+    let (pending_tx, pending_rx) = mpsc::channel(10);
+
+    let handle = reactor::run(
+        1,
+        cancel_token.clone(),
+        reader.clone(),
+        writer.clone(),
+        ctrl,
+        reactor::ReactorChannels {
+            pending_rx: Some(pending_rx),
+            ..Default::default()
+        },
+    );
+
+    let start = ctrl_rx.recv().await.unwrap();
+    let tx = start.event_tx;
+
+    let mut issuance = new_mock_transaction(1);
+    issuance.ops = vec![Op::Issuance {
+        metadata: metadata("sender"),
+    }];
+    tx.send(Event::BlockInsert((
+        1,
+        Block {
+            height: 1,
+            hash: BlockHash::from_byte_array([0x01; 32]),
+            prev_hash: BlockHash::from_byte_array([0x00; 32]),
+            time: 0,
+            transactions: vec![issuance],
+        },
+    )))
+    .await?;
+    await_block_at_height(&*reader.connection().await?, 1).await;
+
+    let mut transfer = new_mock_transaction(2);
+    transfer.ops = vec![Op::Call {
+        metadata: metadata("sender"),
+        gas_limit: 100_000,
+        contract: token_address().into(),
+        expr: token::wave::transfer_call_expr("recipient", Decimal::from(1)),
+    }];
+    tx.send(Event::MempoolInsert(vec![transfer.clone()]))
+        .await?;
+
+    // pending views are served on a separate channel, so wait for the
+    // mempool event to be tracked
+    let mut balance = None;
+    for _ in 0..100 {
+        balance = pending_balance(&pending_tx, "recipient").await?;
+        if balance.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(balance, Some(Decimal::from(1)));
+
+    // each pending view is rolled back once served, so confirmed state is
+    // untouched
+    let pool = runtime::pool::new(
+        temp_dir.path().to_path_buf(),
+        db_name,
+        PoolConfig::default(),
+    )
+    .await?;
+    let confirmed = pool
+        .get()
+        .await?
+        .execute(
+            None,
+            &token_address(),
+            &token::wave::balance_call_expr("recipient"),
+        )
+        .await?;
+    assert_eq!(token::wave::balance_parse_return_expr(&confirmed), None);
+
+    // dropping the transaction from the mempool discards the pending transfer
+    tx.send(Event::MempoolRemove(vec![transfer.txid])).await?;
+    let mut balance = Some(Decimal::from(1));
+    for _ in 0..100 {
+        balance = pending_balance(&pending_tx, "recipient").await?;
+        if balance.is_none() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(balance, None);
+
+    cancel_token.cancel();
+    let _ = handle.await;
+
+    Ok(())
+}
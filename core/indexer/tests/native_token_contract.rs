@@ -23,10 +23,8 @@ async fn run_test_native_token_contract(runtime: &mut Runtime) -> Result<()> {
     );
 
     let result = token::transfer(runtime, &holder, &minter, 123.into()).await?;
-    assert_eq!(
-        result,
-        Err(Error::Message("insufficient funds".to_string()))
-    );
+    assert_eq!(result, Err(Error::contract(2, "insufficient funds")));
+    assert_eq!(result.unwrap_err().code(), Some(2));
 
     token::transfer(runtime, &minter, &holder, 50.into()).await??;
     token::transfer(runtime, &minter, &holder, 2.into()).await??;
@@ -56,12 +54,126 @@ async fn run_test_native_token_contract(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn run_test_native_token_balances_page(runtime: &mut Runtime) -> Result<()> {
+    let minter = runtime.identity().await?;
+    for i in 0..149 {
+        token::transfer(
+            runtime,
+            &minter,
+            &format!("acc{}", i),
+            Decimal::from("0.01"),
+        )
+        .await??;
+    }
+
+    // gas escrow puts the core account in the ledger, where pages must skip it
+    let mut accs = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let page = token::balances_page(runtime, after.as_deref(), 40).await?;
+        assert!(page.entries.len() <= 40);
+        accs.extend(page.entries.into_iter().map(|b| b.acc));
+        match page.next_cursor {
+            Some(cursor) => after = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(accs.len(), 150);
+    assert!(accs.windows(2).all(|w| w[0] < w[1]));
+    let all = token::balances(runtime).await?;
+    assert_eq!(accs, all.into_iter().map(|b| b.acc).collect::<Vec<_>>());
+
+    let large = token::balances_above(runtime, 1.into()).await?;
+    assert_eq!(large.len(), 1);
+    assert_eq!(large[0].acc, &*minter);
+
+    Ok(())
+}
+
+async fn run_test_native_token_none_argument(runtime: &mut Runtime) -> Result<()> {
+    let minter = runtime.identity().await?;
+    token::transfer(runtime, &minter, "acc", 1.into()).await??;
+
+    // `None` is encoded as WAVE `none`, so the page starts at the first key
+    let page = token::balances_page(runtime, None, 10).await?;
+    assert_eq!(page.next_cursor, None);
+    let accs = page.entries.into_iter().map(|b| b.acc).collect::<Vec<_>>();
+    assert_eq!(accs.len(), 2);
+    assert!(accs.iter().any(|acc| acc == "acc"));
+    assert!(accs.iter().any(|acc| acc == &*minter));
+
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_native_token_contract() -> Result<()> {
     run_test_native_token_contract(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_native_token_none_argument() -> Result<()> {
+    run_test_native_token_none_argument(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_native_token_balances_page() -> Result<()> {
+    run_test_native_token_balances_page(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
 async fn test_native_token_contract_regtest() -> Result<()> {
     run_test_native_token_contract(runtime).await
 }
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_funded_identities() -> Result<()> {
+    let signers = runtime.identities(5).await?;
+    assert_eq!(signers.len(), 5);
+    for signer in &signers {
+        let result = token::balance(runtime, signer).await?;
+        assert_eq!(result, Some(Decimal::from(10)));
+    }
+
+    runtime.fund(&signers[0], 100.into()).await?;
+    let result = token::balance(runtime, &signers[0]).await?;
+    assert!(result.is_some_and(|d| d > Decimal::from(109)));
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_native_token_decimals() -> Result<()> {
+    assert_eq!(token::decimals(runtime).await?, 8);
+
+    // no operation touches the value set at init
+    let minter = runtime.identity().await?;
+    let holder = runtime.identity().await?;
+    token::mint(runtime, &minter, 100.into()).await??;
+    token::transfer(runtime, &minter, &holder, 5.into()).await??;
+    token::burn(runtime, &holder, 1.into()).await??;
+    assert_eq!(token::decimals(runtime).await?, 8);
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_native_token_balances_exclude_system_accounts() -> Result<()> {
+    let holder = runtime.identity().await?;
+    token::mint(runtime, &holder, 100.into()).await??;
+    token::burn(runtime, &holder, 5.into()).await??;
+    assert!(
+        token::balance(runtime, "burn")
+            .await?
+            .is_some_and(|d| d >= 5.into())
+    );
+
+    let accounts = token::balances(runtime)
+        .await?
+        .into_iter()
+        .map(|b| b.acc)
+        .collect::<Vec<_>>();
+    assert!(accounts.contains(&holder.to_string()));
+    assert!(!accounts.iter().any(|acc| acc == "burn" || acc == "core"));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anyhow::{Result, anyhow};
+use indexer::{
+    runtime::{ContractAddress, Runtime, to_wave_expr, wit::Signer},
+    test_utils::{new_test_runtime_with_signer, publish_test_contract},
+};
+
+async fn burn_pair(
+    runtime: &mut Runtime,
+    signer: &Signer,
+    fib: &ContractAddress,
+    arith: &ContractAddress,
+    first: u64,
+    second: u64,
+) -> Result<String> {
+    runtime
+        .execute(
+            Some(signer),
+            fib,
+            &format!(
+                "burn-pair({}, {}, {})",
+                to_wave_expr(arith.clone()),
+                first,
+                second
+            ),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_nested_call_budget() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let arith = publish_test_contract(&mut runtime, &signer, "arith").await?;
+    let fib = publish_test_contract(&mut runtime, &signer, "fib").await?;
+
+    // measure what the pair costs with no sub-budget
+    burn_pair(&mut runtime, &signer, &fib, &arith, 100, 1).await?;
+    let gas = runtime
+        .storage
+        .results_in_range(1, 1)
+        .await?
+        .into_iter()
+        .rfind(|row| row.func == "burn-pair")
+        .ok_or(anyhow!("burn-pair result not found"))?
+        .gas as u64;
+
+    // enough for the whole call tree when the first child may use it all
+    runtime.set_gas_limit(gas + gas / 10);
+    assert_eq!(
+        burn_pair(&mut runtime, &signer, &fib, &arith, 100, 1).await?,
+        "101"
+    );
+
+    // the first child needs nearly all of it, so a 50% cap stops it
+    runtime.set_nested_call_budget_percent(50)?;
+    let err = burn_pair(&mut runtime, &signer, &fib, &arith, 100, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("fuel"));
+
+    // a child that fits in its share still succeeds
+    assert_eq!(
+        burn_pair(&mut runtime, &signer, &fib, &arith, 1, 1).await?,
+        "2"
+    );
+
+    assert!(runtime.set_nested_call_budget_percent(0).is_err());
+    assert!(runtime.set_nested_call_budget_percent(101).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{
+        Integer,
+        fuel::{FuelDiscriminants, FuelGauge},
+        to_wave_expr,
+    },
+    test_utils::{new_test_runtime_with_signer, publish_test_contract},
+};
+
+#[tokio::test]
+async fn test_three_way_branch_takes_one_comparison() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let arith = publish_test_contract(&mut runtime, &signer, "arith").await?;
+
+    for (a, b, expected) in [(1u64, 2u64, "less"), (2, 2, "equal"), (3, 2, "greater")] {
+        let gauge = FuelGauge::new();
+        runtime.gauge = Some(gauge.clone());
+        let result = runtime
+            .execute(
+                None,
+                &arith,
+                &format!(
+                    "compare({}, {})",
+                    to_wave_expr(Integer::from(a)),
+                    to_wave_expr(Integer::from(b))
+                ),
+            )
+            .await?;
+        assert_eq!(result, format!("\"{}\"", expected));
+
+        let history = gauge.history().await;
+        let count = |kind| history.iter().filter(|(k, _)| *k == kind).count();
+        assert_eq!(count(FuelDiscriminants::NumbersCmpInteger), 1);
+        assert_eq!(count(FuelDiscriminants::NumbersEqInteger), 0);
+    }
+
+    Ok(())
+}
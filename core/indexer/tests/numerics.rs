@@ -94,6 +94,116 @@ async fn test_runtime_decimal_operations() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_decimal_div_rounded() -> Result<()> {
+    let one = Decimal::from(1);
+    let two = Decimal::from(2);
+    let three = Decimal::from(3);
+
+    // plain division keeps its existing behaviour
+    assert_eq!(one / three, "0.333333333333333333".into());
+
+    assert_eq!(
+        one.div_rounded(three, RoundingMode::Down)?,
+        "0.333333333333333333".into()
+    );
+    assert_eq!(
+        one.div_rounded(three, RoundingMode::Up)?,
+        "0.333333333333333334".into()
+    );
+    assert_eq!(
+        one.div_rounded(three, RoundingMode::HalfUp)?,
+        "0.333333333333333333".into()
+    );
+    assert_eq!(
+        one.div_rounded(three, RoundingMode::HalfEven)?,
+        "0.333333333333333333".into()
+    );
+
+    assert_eq!(
+        two.div_rounded(three, RoundingMode::Down)?,
+        "0.666666666666666666".into()
+    );
+    assert_eq!(
+        two.div_rounded(three, RoundingMode::HalfUp)?,
+        "0.666666666666666667".into()
+    );
+
+    // a tie in the 19th decimal: 2.5 and 3.5 units of the smallest step
+    let five = Decimal::from(5);
+    let seven = Decimal::from(7);
+    let divisor = Decimal::from("2_000_000_000_000_000_000");
+    assert_eq!(
+        five.div_rounded(divisor, RoundingMode::Down)?,
+        "0.000000000000000002".into()
+    );
+    assert_eq!(
+        five.div_rounded(divisor, RoundingMode::Up)?,
+        "0.000000000000000003".into()
+    );
+    assert_eq!(
+        five.div_rounded(divisor, RoundingMode::HalfUp)?,
+        "0.000000000000000003".into()
+    );
+    assert_eq!(
+        five.div_rounded(divisor, RoundingMode::HalfEven)?,
+        "0.000000000000000002".into()
+    );
+    assert_eq!(
+        seven.div_rounded(divisor, RoundingMode::HalfEven)?,
+        "0.000000000000000004".into()
+    );
+
+    // down and up round toward and away from zero for negative quotients
+    assert_eq!(
+        Decimal::from(-1).div_rounded(three, RoundingMode::Down)?,
+        "-0.333333333333333333".into()
+    );
+    assert_eq!(
+        Decimal::from(-1).div_rounded(three, RoundingMode::Up)?,
+        "-0.333333333333333334".into()
+    );
+
+    assert_eq!(
+        one.div_rounded(Decimal::from(0), RoundingMode::HalfEven),
+        Err(Error::DivByZero("decimal divide by zero".to_string()))
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_string_to_integer_grammar() -> Result<()> {
+    assert_eq!(numbers::string_to_integer("0xff")?, 255.into());
+    assert_eq!(numbers::string_to_integer("0XFF")?, 255.into());
+    assert_eq!(numbers::string_to_integer("-0x10")?, (-16).into());
+    assert_eq!(
+        numbers::string_to_integer("0xdead_beef")?,
+        0xdead_beefu64.into()
+    );
+    assert_eq!(numbers::string_to_integer("1_000")?, 1000.into());
+    assert_eq!(numbers::string_to_integer("+42")?, 42.into());
+    assert_eq!(numbers::string_to_integer("-7")?, (-7).into());
+
+    for invalid in [
+        "", "-", "0x", "0x_", "_1", "1_", "1__0", "0x_ff", "ff", "12ab", "0xfg", "0b101", "1.5",
+        "1e3", "--1", "- 1", "0x-1", "0x0x1",
+    ] {
+        assert!(
+            matches!(numbers::string_to_integer(invalid), Err(Error::Syntax(_))),
+            "{invalid:?} should be rejected"
+        );
+    }
+
+    let max_hex = format!("0x1{}", "0".repeat(64));
+    assert!(matches!(
+        numbers::string_to_integer(&max_hex),
+        Err(Error::Overflow(_))
+    ));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_numerics_limits() -> Result<()> {
     let max_int = "115_792_089_237_316_195_423_570_985_008_687_907_853_269_984_665_640_564_039_457";
@@ -161,3 +271,128 @@ async fn test_decimals_scientific() -> Result<()> {
     assert_eq!(x, Decimal::from("100000"));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_numerics_bps() -> Result<()> {
+    let amount = Integer::from(12_345);
+
+    assert_eq!(amount.mul_bps(0.into()).unwrap(), 0.into());
+    assert_eq!(amount.mul_bps(10_000.into()).unwrap(), amount);
+    assert_eq!(amount.apply_fee(0.into()).unwrap(), amount);
+    assert_eq!(amount.apply_fee(10_000.into()).unwrap(), 0.into());
+
+    // 12_345 * 30 / 10_000 = 37.035, rounded down
+    assert_eq!(amount.mul_bps(30.into()).unwrap(), 37.into());
+    // 12_345 * 9_970 / 10_000 = 12_307.965, rounded down
+    assert_eq!(amount.apply_fee(30.into()).unwrap(), 12_307.into());
+    // exact boundary: no rounding
+    assert_eq!(Integer::from(10_000).mul_bps(1.into()).unwrap(), 1.into());
+    assert_eq!(Integer::from(9_999).mul_bps(1.into()).unwrap(), 0.into());
+
+    assert!(matches!(
+        amount.mul_bps(10_001.into()),
+        Err(Error::Validation(_))
+    ));
+    assert!(matches!(
+        amount.mul_bps((-1).into()),
+        Err(Error::Validation(_))
+    ));
+    assert!(matches!(
+        amount.apply_fee(10_001.into()),
+        Err(Error::Validation(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_numerics_saturating() -> Result<()> {
+    let max = Integer::max_value();
+    assert_eq!(
+        max,
+        Integer::from(
+            "115_792_089_237_316_195_423_570_985_008_687_907_853_269_984_665_640_564_039_457"
+        )
+    );
+
+    assert_eq!(max.saturating_add(1.into()), max);
+    assert_eq!(max.saturating_add(max), max);
+    assert_eq!(max.saturating_sub((-1).into()), max);
+    assert_eq!(
+        max.saturating_add((-1).into()),
+        numbers::sub_integer(max, 1.into()).unwrap()
+    );
+    assert_eq!(Integer::from(0).saturating_sub(1.into()), 0.into());
+    assert_eq!(Integer::from(5).saturating_sub(8.into()), 0.into());
+    assert_eq!(Integer::from(5).saturating_sub(3.into()), 2.into());
+    assert_eq!(Integer::from(5).saturating_add((-8).into()), 0.into());
+    assert_eq!(Integer::from(0).saturating_sub(max), 0.into());
+
+    let max = Decimal::max_value();
+    assert_eq!(max, Decimal::from(Integer::max_value()));
+    assert_eq!(
+        max.saturating_add(Decimal::from("0.000000000000000001")),
+        max
+    );
+    assert_eq!(max.saturating_add(max), max);
+    assert_eq!(max.saturating_sub(Decimal::from(-1)), max);
+    assert_eq!(
+        Decimal::from("0.25").saturating_sub(Decimal::from("0.5")),
+        Decimal::from(0)
+    );
+    assert_eq!(
+        Decimal::from("1.5").saturating_sub(Decimal::from("0.25")),
+        Decimal::from("1.25")
+    );
+    assert_eq!(Decimal::from(0).saturating_sub(max), Decimal::from(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decimal_scaled_integer() -> Result<()> {
+    for s in [
+        "0",
+        "1.5",
+        "-1.5",
+        "0.000000000000000001",
+        "-123456789.123456789123456789",
+        "57843975908.437589027340573245",
+    ] {
+        let d = Decimal::from(s);
+        let scaled = d.to_scaled_integer();
+        assert_eq!(scaled.scale, 18);
+        assert_eq!(Decimal::from_scaled_integer(scaled).unwrap(), d);
+    }
+
+    let scaled = Decimal::from("-1.25").to_scaled_integer();
+    assert_eq!(scaled.mantissa, Integer::from("-1250000000000000000"));
+
+    // other scales are accepted as long as nothing is rounded away
+    let from = |mantissa: &str, scale: u32| {
+        Decimal::from_scaled_integer(ScaledInteger {
+            mantissa: Integer::from(mantissa),
+            scale,
+        })
+    };
+    assert_eq!(from("125", 2).unwrap(), Decimal::from("1.25"));
+    assert_eq!(from("-7", 0).unwrap(), Decimal::from("-7"));
+    assert_eq!(
+        from("1000000000000000000000", 21).unwrap(),
+        Decimal::from("1")
+    );
+    assert!(matches!(from("1", 19), Err(Error::Validation(_))));
+    assert!(matches!(from("1", 96), Err(Error::Validation(_))));
+    // huge scales are rejected up front rather than building 10^scale
+    assert!(matches!(from("1", 97), Err(Error::Overflow(_))));
+    assert!(matches!(from("0", u32::MAX), Err(Error::Overflow(_))));
+    assert!(matches!(
+        from(
+            "100000000000000000000000000000000000000000000000000000000000000",
+            0
+        ),
+        Err(Error::Overflow(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::cmp::Ordering;
+
+use indexer::runtime::{
+    Decimal, Error, Integer, NumericOrdering, NumericSign, RoundingMode, numerics,
+};
+use num::{BigInt, Signed};
+use proptest::prelude::*;
+
+fn integer(i: i128) -> Integer {
+    numerics::string_to_integer(&i.to_string()).unwrap()
+}
+
+/// `mantissa * 10^-scale`, built through the string parser so the
+/// arithmetic under test isn't used to construct its own inputs.
+fn decimal(mantissa: i64, scale: u32) -> Decimal {
+    let digits = format!(
+        "{:0>width$}",
+        mantissa.unsigned_abs(),
+        width = scale as usize + 1
+    );
+    let (int, frac) = digits.split_at(digits.len() - scale as usize);
+    let sign = if mantissa < 0 { "-" } else { "" };
+    numerics::string_to_decimal(&format!("{}{}.{}0", sign, int, frac)).unwrap()
+}
+
+/// A decimal's raw value in units of 10^-18.
+fn units(d: Decimal) -> BigInt {
+    let mut big = BigInt::from(d.r3);
+    for limb in [d.r2, d.r1, d.r0] {
+        big = (big << 64) + limb;
+    }
+    if d.sign == NumericSign::Minus {
+        -big
+    } else {
+        big
+    }
+}
+
+fn ordering(o: NumericOrdering) -> Ordering {
+    match o {
+        NumericOrdering::Less => Ordering::Less,
+        NumericOrdering::Equal => Ordering::Equal,
+        NumericOrdering::Greater => Ordering::Greater,
+    }
+}
+
+fn arb_decimal() -> impl Strategy<Value = Decimal> {
+    (any::<i64>(), 0u32..=18).prop_map(|(m, s)| decimal(m, s))
+}
+
+fn arb_nonzero_decimal() -> impl Strategy<Value = Decimal> {
+    (any::<i64>().prop_filter("non-zero", |m| *m != 0), 0u32..=18).prop_map(|(m, s)| decimal(m, s))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        failure_persistence: None,
+        .. ProptestConfig::default()
+    })]
+
+    #[test]
+    fn integer_add_sub_inverse(a in any::<i128>(), b in any::<i128>()) {
+        let (a, b) = (integer(a), integer(b));
+        let sum = numerics::add_integer(a, b).unwrap();
+        prop_assert_eq!(numerics::sub_integer(sum, b).unwrap(), a);
+        let diff = numerics::sub_integer(a, b).unwrap();
+        prop_assert_eq!(numerics::add_integer(diff, b).unwrap(), a);
+    }
+
+    #[test]
+    // division truncates toward zero, so the remainder is smaller than the
+    // divisor and never pushes the quotient past the dividend
+    fn integer_div_bounds(
+        a in any::<i128>(),
+        b in any::<i128>().prop_filter("non-zero", |b| *b != 0),
+    ) {
+        let q = BigInt::from(numerics::div_integer(integer(a), integer(b)).unwrap());
+        let (a, b) = (BigInt::from(a), BigInt::from(b));
+        let r = &a - &q * &b;
+        prop_assert!(r.abs() < b.abs());
+        prop_assert!(r == BigInt::ZERO || r.signum() == a.signum());
+    }
+
+    #[test]
+    fn integer_div_by_zero(a in any::<i128>()) {
+        prop_assert!(matches!(
+            numerics::div_integer(integer(a), integer(0)),
+            Err(Error::DivByZero(_))
+        ));
+    }
+
+    #[test]
+    fn integer_sqrt_bounds(a in any::<i128>()) {
+        let res = numerics::sqrt_integer(integer(a));
+        if a < 0 {
+            prop_assert!(matches!(res, Err(Error::Validation(_))));
+        } else {
+            let r = BigInt::from(res.unwrap());
+            let a = BigInt::from(a);
+            prop_assert!(&r * &r <= a);
+            prop_assert!((&r + 1) * (&r + 1) > a);
+        }
+    }
+
+    #[test]
+    fn integer_string_round_trip(a in any::<i128>()) {
+        let s = numerics::integer_to_string(integer(a));
+        prop_assert_eq!(&s, &a.to_string());
+        prop_assert_eq!(numerics::string_to_integer(&s).unwrap(), integer(a));
+    }
+
+    #[test]
+    fn integer_cmp_total_order(a in any::<i128>(), b in any::<i128>(), c in any::<i128>()) {
+        let (x, y, z) = (integer(a), integer(b), integer(c));
+        let xy = ordering(numerics::cmp_integer(x, y));
+        prop_assert_eq!(xy, a.cmp(&b));
+        prop_assert_eq!(ordering(numerics::cmp_integer(y, x)), xy.reverse());
+        prop_assert_eq!(numerics::eq_integer(x, y), xy == Ordering::Equal);
+        if xy != Ordering::Greater && ordering(numerics::cmp_integer(y, z)) != Ordering::Greater {
+            prop_assert_ne!(ordering(numerics::cmp_integer(x, z)), Ordering::Greater);
+        }
+    }
+
+    #[test]
+    fn decimal_add_sub_inverse(a in arb_decimal(), b in arb_decimal()) {
+        let sum = numerics::add_decimal(a, b).unwrap();
+        prop_assert_eq!(units(sum), units(a) + units(b));
+        prop_assert_eq!(numerics::sub_decimal(sum, b).unwrap(), a);
+    }
+
+    #[test]
+    // the product keeps 18 decimals, so it is within half a unit of the
+    // exact value
+    fn decimal_mul_bounds(a in arb_decimal(), b in arb_decimal()) {
+        let p = units(numerics::mul_decimal(a, b).unwrap());
+        let unit = BigInt::from(10u64.pow(18));
+        let err = (p * &unit - units(a) * units(b)).abs();
+        prop_assert!(err * 2 <= unit);
+    }
+
+    #[test]
+    // rounding down never overshoots the exact quotient and rounding up never
+    // undershoots it, each by less than one unit
+    fn decimal_div_bounds(a in arb_decimal(), b in arb_nonzero_decimal()) {
+        let unit = BigInt::from(10u64.pow(18));
+        let exact = (units(a) * &unit).abs();
+        let divisor = units(b).abs();
+
+        let down = units(numerics::div_decimal_rounded(a, b, RoundingMode::Down).unwrap()).abs();
+        prop_assert!(&down * &divisor <= exact);
+        prop_assert!((&down + 1) * &divisor > exact);
+
+        let up = units(numerics::div_decimal_rounded(a, b, RoundingMode::Up).unwrap()).abs();
+        prop_assert!(&up * &divisor >= exact);
+        prop_assert!((&up - 1) * &divisor < exact);
+
+        let q = units(numerics::div_decimal(a, b).unwrap()).abs();
+        prop_assert!(q == down || q == up);
+    }
+
+    #[test]
+    // the half modes land within half a unit of the exact quotient
+    fn decimal_div_half_modes(a in arb_decimal(), b in arb_nonzero_decimal()) {
+        let unit = BigInt::from(10u64.pow(18));
+        let exact = (units(a) * &unit).abs();
+        let divisor = units(b).abs();
+
+        for mode in [RoundingMode::HalfUp, RoundingMode::HalfEven] {
+            let q = units(numerics::div_decimal_rounded(a, b, mode).unwrap()).abs();
+            prop_assert!((&q * &divisor - &exact).abs() * 2 <= divisor);
+        }
+    }
+
+    #[test]
+    fn decimal_div_by_zero(a in arb_decimal()) {
+        prop_assert!(matches!(
+            numerics::div_decimal(a, numerics::u64_to_decimal(0)),
+            Err(Error::DivByZero(_))
+        ));
+        prop_assert!(matches!(
+            numerics::div_decimal_rounded(a, numerics::u64_to_decimal(0), RoundingMode::HalfEven),
+            Err(Error::DivByZero(_))
+        ));
+    }
+
+    #[test]
+    fn decimal_log10_domain(a in arb_decimal()) {
+        let res = numerics::log10_decimal(a);
+        if units(a).is_positive() {
+            prop_assert!(res.is_ok());
+        } else {
+            prop_assert!(matches!(res, Err(Error::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn decimal_scaled_integer_round_trip(a in arb_decimal()) {
+        let scaled = numerics::decimal_to_scaled_integer(a);
+        prop_assert_eq!(BigInt::from(scaled.mantissa), units(a));
+        prop_assert_eq!(numerics::scaled_integer_to_decimal(scaled).unwrap(), a);
+    }
+
+    #[test]
+    fn decimal_string_round_trip(a in arb_decimal()) {
+        let s = numerics::decimal_to_string(a);
+        prop_assert_eq!(numerics::string_to_decimal(&s).unwrap(), a);
+    }
+
+    #[test]
+    fn decimal_cmp_total_order(a in arb_decimal(), b in arb_decimal(), c in arb_decimal()) {
+        let ab = ordering(numerics::cmp_decimal(a, b));
+        prop_assert_eq!(ab, units(a).cmp(&units(b)));
+        prop_assert_eq!(ordering(numerics::cmp_decimal(b, a)), ab.reverse());
+        prop_assert_eq!(numerics::eq_decimal(a, b), ab == Ordering::Equal);
+        if ab != Ordering::Greater && ordering(numerics::cmp_decimal(b, c)) != Ordering::Greater {
+            prop_assert_ne!(ordering(numerics::cmp_decimal(a, c)), Ordering::Greater);
+        }
+    }
+}
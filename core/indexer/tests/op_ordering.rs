@@ -0,0 +1,58 @@
+use anyhow::Result;
+use bitcoin::OutPoint;
+use indexer::{
+    block::sort_ops,
+    database::queries::get_contract_results_in_range,
+    reactor::block_handler,
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_runtime},
+};
+use indexer_types::{Block, Op, OpMetadata, Signer};
+
+fn issuance(input_index: i64) -> Op {
+    Op::Issuance {
+        metadata: OpMetadata {
+            previous_output: OutPoint::null(),
+            input_index,
+            signer: Signer::XOnlyPubKey(format!("signer-{}", input_index)),
+        },
+    }
+}
+
+fn input_indexes(ops: &[Op]) -> Vec<i64> {
+    ops.iter().map(|op| op.metadata().input_index).collect()
+}
+
+#[test]
+fn test_sort_ops_orders_by_input_index() {
+    let mut ops = [3, 0, 2, 1].map(issuance).to_vec();
+    sort_ops(&mut ops);
+    assert_eq!(input_indexes(&ops), vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_block_handler_executes_shuffled_ops_in_canonical_order() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(0).await?;
+
+    let mut tx = new_mock_transaction(1);
+    tx.ops = [2, 0, 3, 1].map(issuance).to_vec();
+    block_handler(
+        &mut runtime,
+        &[],
+        &Block {
+            height: 1,
+            hash: new_mock_block_hash(1),
+            prev_hash: new_mock_block_hash(0),
+            time: 0,
+            transactions: vec![tx],
+        },
+    )
+    .await?;
+
+    // result ids are assigned as the ops execute
+    let mut results = get_contract_results_in_range(&runtime.storage.conn, 1, 1).await?;
+    results.sort_by_key(|r| r.id);
+    let executed = results.iter().map(|r| r.input_index).collect::<Vec<_>>();
+    assert_eq!(executed, vec![0, 1, 2, 3]);
+
+    Ok(())
+}
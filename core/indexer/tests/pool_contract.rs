@@ -1,5 +1,8 @@
+use indexer::test_utils::{new_test_runtime_with_signer, read_test_contract};
 use testlib::*;
 use tracing::info;
+use wit_parser::{Resolve, WorldItem, WorldKey};
+use wit_validator::{ErrorCode, TEST_ONLY_FEATURE, Validator};
 
 interface!(name = "token", path = "../../test-contracts/token/wit",);
 
@@ -539,6 +542,96 @@ async fn run_test_amm_limits(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn run_test_amm_empty_pool(runtime: &mut Runtime) -> Result<()> {
+    info!("test_amm_empty_pool");
+    let admin = runtime.identity().await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+    let pool = runtime.publish(&admin, "pool").await?;
+
+    token::mint(runtime, &token_a, &admin, 100.into()).await??;
+    token::mint(runtime, &token_b, &admin, 500.into()).await??;
+
+    let shares = pool::re_init(
+        runtime,
+        &pool,
+        &admin,
+        token_a.clone(),
+        100.into(),
+        token_b.clone(),
+        500.into(),
+        0.into(),
+    )
+    .await??;
+
+    // drain the pool to zero supply and zero balances
+    let res = pool::withdraw(runtime, &pool, &admin, shares).await?;
+    assert_eq!(
+        res,
+        Ok(pool::WithdrawResult {
+            amount_a: 100.into(),
+            amount_b: 500.into(),
+        })
+    );
+
+    let res = pool::quote_deposit(runtime, &pool, 10.into(), 10.into()).await?;
+    assert_eq!(res, Err(Error::Message("empty pool".to_string())));
+    let res = pool::quote_withdraw(runtime, &pool, 1.into()).await?;
+    assert_eq!(res, Err(Error::Message("empty pool".to_string())));
+    let res = pool::quote_swap(runtime, &pool, token_a.clone(), 10.into()).await?;
+    assert_eq!(res, Err(Error::Message("empty pool".to_string())));
+
+    Ok(())
+}
+
+async fn run_test_pool_direct_token_balance(runtime: &mut Runtime) -> Result<()> {
+    info!("test_pool_direct_token_balance");
+    let admin = runtime.identity().await?;
+    let minter = runtime.identity().await?;
+
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+    let pool = runtime.publish(&admin, "pool").await?;
+
+    token::mint(runtime, &token_a, &admin, 1000.into()).await??;
+    token::mint(runtime, &token_b, &admin, 1000.into()).await??;
+    token::mint(runtime, &token_a, &minter, 1000.into()).await??;
+    pool::re_init(
+        runtime,
+        &pool,
+        &admin,
+        token_a.clone(),
+        100.into(),
+        token_b.clone(),
+        500.into(),
+        0.into(),
+    )
+    .await??;
+    pool::swap(
+        runtime,
+        &pool,
+        &minter,
+        token_a.clone(),
+        10.into(),
+        1.into(),
+    )
+    .await??;
+
+    // reading the token's public ledger directly matches calling its view
+    for token in [&token_a, &token_b] {
+        let direct = pool::token_balance_direct(runtime, &pool, token.clone()).await?;
+        let called = pool::token_balance(runtime, &pool, token.clone()).await?;
+        assert!(direct.is_ok());
+        assert_eq!(direct, called);
+    }
+    assert_eq!(
+        pool::token_balance_direct(runtime, &pool, token_a.clone()).await?,
+        Ok(110.into())
+    );
+
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_amm_swaps() -> Result<()> {
     run_test_amm_swaps(runtime).await
@@ -569,14 +662,108 @@ async fn test_amm_limits() -> Result<()> {
     run_test_amm_limits(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_amm_empty_pool() -> Result<()> {
+    run_test_amm_empty_pool(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_pool_direct_token_balance() -> Result<()> {
+    run_test_pool_direct_token_balance(runtime).await
+}
+
+fn pool_wit(local: bool) -> Resolve {
+    let mut resolve = Resolve::new();
+    if local {
+        resolve.features.insert(TEST_ONLY_FEATURE.to_string());
+    }
+    resolve
+        .push_dir("../../test-contracts/pool/wit")
+        .expect("Failed to parse pool WIT");
+    resolve
+}
+
+fn exports(resolve: &Resolve) -> Vec<String> {
+    resolve
+        .worlds
+        .iter()
+        .filter(|(_, world)| world.name == "root")
+        .flat_map(|(_, world)| world.exports.iter())
+        .filter_map(|(key, item)| match (key, item) {
+            (WorldKey::Name(name), WorldItem::Function(_)) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_test_only_export_callable_locally() -> Result<()> {
+    let admin = runtime.identity().await?;
+    let token_a = runtime.publish_as(&admin, "token", "token-a").await?;
+    let token_b = runtime.publish_as(&admin, "token", "token-b").await?;
+    let pool = runtime.publish(&admin, "pool").await?;
+    token::mint(runtime, &token_a, &admin, 100.into()).await??;
+    token::mint(runtime, &token_b, &admin, 500.into()).await??;
+
+    let res = pool::re_init(
+        runtime,
+        &pool,
+        &admin,
+        token_a.clone(),
+        100.into(),
+        token_b.clone(),
+        500.into(),
+        0.into(),
+    )
+    .await?;
+    assert_eq!(res, Ok(223.into()));
+
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
-async fn test_pool_contract_regtest() -> Result<()> {
-    logging::setup();
-    run_test_amm_swaps(runtime).await?;
-    run_test_amm_swap_fee(runtime).await?;
-    run_test_amm_shares_token_interface(runtime).await?;
-    run_test_amm_swap_low_slippage(runtime).await?;
-    run_test_amm_deposit_withdraw(runtime).await?;
-    run_test_amm_limits(runtime).await?;
+async fn test_test_only_export_rejected_by_reactor() -> Result<()> {
+    // the pool is built with `re-init`, so the indexer refuses to publish it
+    let admin = runtime.identity().await?;
+    assert!(runtime.publish(&admin, "pool").await.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_test_only_export_absent_from_release_wit() {
+    let release = pool_wit(false);
+    let release_exports = exports(&release);
+    assert!(release_exports.contains(&"fee".to_string()));
+    assert!(!release_exports.contains(&"re-init".to_string()));
+    assert!(Validator::validate_resolve(&release).is_valid());
+
+    let local = pool_wit(true);
+    assert!(exports(&local).contains(&"re-init".to_string()));
+    let result = Validator::validate_resolve(&local);
+    assert!(
+        result
+            .errors
+            .iter()
+            .any(|e| e.code == Some(ErrorCode::TestOnlyExport)),
+        "{}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_publish_rejects_test_only_exports() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+
+    // the built pool carries `re-init`, which only local tests may call
+    let bytes = read_test_contract("pool").await?;
+    let err = runtime.publish(&signer, "pool", &bytes).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::TestOnlyExport(message)) if message.contains("re-init")
+    ));
+
+    runtime.allow_test_only_exports = true;
+    runtime.publish(&signer, "pool", &bytes).await?;
+
     Ok(())
 }
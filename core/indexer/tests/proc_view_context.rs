@@ -0,0 +1,19 @@
+use testlib::*;
+
+interface!(name = "arith", path = "../../test-contracts/arith/wit",);
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_proc_view_context_sees_uncommitted_writes() -> Result<()> {
+    let signer = runtime.identity().await?;
+    let arith = runtime.publish(&signer, "arith").await?;
+    assert_eq!(arith::last_op(runtime, &arith).await?, Some(arith::Op::Id));
+
+    // the view context reads through the proc's savepoint, so the write made
+    // earlier in the same call is visible before it is committed
+    let op = arith::Op::Mul(arith::Operand { y: 3 });
+    let result = arith::record_op(runtime, &arith, &signer, op.clone()).await?;
+    assert_eq!(result, Some(op.clone()));
+    assert_eq!(arith::last_op(runtime, &arith).await?, Some(op));
+
+    Ok(())
+}
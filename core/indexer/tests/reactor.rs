@@ -1,5 +1,6 @@
 use anyhow::Result;
-use indexer_types::Block;
+use indexer_types::{Block, Event as ReactorEvent};
+use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 use bitcoin::{BlockHash, hashes::Hash};
@@ -26,9 +27,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     );
 
     let start = ctrl_rx.recv().await.unwrap();
@@ -42,6 +41,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
                 height: 91,
                 hash: BlockHash::from_byte_array([0x10; 32]),
                 prev_hash: BlockHash::from_byte_array([0x00; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -56,6 +56,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x20; 32]),
                 prev_hash: BlockHash::from_byte_array([0x10; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -70,6 +71,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
                 height: 93,
                 hash: BlockHash::from_byte_array([0x30; 32]),
                 prev_hash: BlockHash::from_byte_array([0x20; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -103,6 +105,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x21; 32]),
                 prev_hash: BlockHash::from_byte_array([0x10; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -117,6 +120,7 @@ async fn test_reactor_rollback_event() -> Result<()> {
                 height: 93,
                 hash: BlockHash::from_byte_array([0x31; 32]),
                 prev_hash: BlockHash::from_byte_array([0x21; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -152,9 +156,7 @@ async fn test_reactor_unexpected_block() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     );
 
     let start = ctrl_rx.recv().await.unwrap();
@@ -168,6 +170,7 @@ async fn test_reactor_unexpected_block() -> Result<()> {
                 height: 82, // skipping 81
                 hash: BlockHash::from_byte_array([0x01; 32]),
                 prev_hash: BlockHash::from_byte_array([0x00; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -195,9 +198,7 @@ async fn test_reactor_rollback_due_to_hash_mismatch() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     );
 
     let start = ctrl_rx.recv().await.unwrap();
@@ -211,6 +212,7 @@ async fn test_reactor_rollback_due_to_hash_mismatch() -> Result<()> {
                 height: 91,
                 hash: BlockHash::from_byte_array([0x01; 32]),
                 prev_hash: BlockHash::from_byte_array([0x00; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -225,6 +227,7 @@ async fn test_reactor_rollback_due_to_hash_mismatch() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x02; 32]),
                 prev_hash: BlockHash::from_byte_array([0x01; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -267,6 +270,7 @@ async fn test_reactor_rollback_due_to_hash_mismatch() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x12; 32]),
                 prev_hash: BlockHash::from_byte_array([0x01; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -298,9 +302,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     );
 
     let start = ctrl_rx.recv().await.unwrap();
@@ -314,6 +316,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
                 height: 91,
                 hash: BlockHash::from_byte_array([0x01; 32]),
                 prev_hash: BlockHash::from_byte_array([0x00; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -328,6 +331,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x02; 32]),
                 prev_hash: BlockHash::from_byte_array([0x01; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -342,6 +346,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
                 height: 93,
                 hash: BlockHash::from_byte_array([0x03; 32]),
                 prev_hash: BlockHash::from_byte_array([0x02; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -356,6 +361,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
                 height: 92,                                   // lower height
                 hash: BlockHash::from_byte_array([0x12; 32]), // new hash
                 prev_hash: BlockHash::from_byte_array([0x01; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -382,6 +388,7 @@ async fn test_reactor_rollback_due_to_reverting_height() -> Result<()> {
                 height: 92,
                 hash: BlockHash::from_byte_array([0x12; 32]),
                 prev_hash: BlockHash::from_byte_array([0x01; 32]),
+                time: 0,
                 transactions: vec![],
             },
         )))
@@ -420,9 +427,7 @@ async fn test_reactor_rollback_hash_event() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     );
 
     let start = ctrl_rx.recv().await.unwrap();
@@ -444,3 +449,192 @@ async fn test_reactor_rollback_hash_event() -> Result<()> {
     let _ = handle.await;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_reactor_emits_rollback_event() -> Result<()> {
+    let cancel_token = CancellationToken::new();
+    let (ctrl, mut ctrl_rx) = CtrlChannel::create();
+    let (reader, writer, _temp_dir) = new_test_db().await?;
+    let (event_tx, mut event_rx) = mpsc::channel(10);
+
+    let handle = reactor::run(
+        91,
+        cancel_token.clone(),
+        reader.clone(),
+        writer.clone(),
+        ctrl,
+        reactor::ReactorChannels {
+            event_tx: Some(event_tx),
+            ..Default::default()
+        },
+    );
+
+    let start = ctrl_rx.recv().await.unwrap();
+    let tx = start.event_tx;
+
+    let block = |height: u64, hash: u8, prev_hash: u8| Block {
+        height,
+        hash: BlockHash::from_byte_array([hash; 32]),
+        prev_hash: BlockHash::from_byte_array([prev_hash; 32]),
+        time: 0,
+        transactions: vec![],
+    };
+
+    tx.send(Event::BlockInsert((100, block(91, 0x01, 0x00))))
+        .await?;
+    tx.send(Event::BlockInsert((100, block(92, 0x02, 0x01))))
+        .await?;
+    // a reorg: 93 does not build on the stored 92
+    tx.send(Event::BlockInsert((100, block(93, 0x03, 0x12))))
+        .await?;
+
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 92);
+    start
+        .event_tx
+        .send(Event::BlockInsert((100, block(92, 0x12, 0x01))))
+        .await?;
+
+    let mut events = vec![];
+    for _ in 0..4 {
+        events.push(event_rx.recv().await.unwrap());
+    }
+    let heights = events
+        .iter()
+        .map(|event| match event {
+            ReactorEvent::Processed { block } => format!("processed {}", block.height),
+            ReactorEvent::Rolledback { height } => format!("rolledback {}", height),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        heights,
+        vec![
+            "processed 91",
+            "processed 92",
+            "rolledback 91",
+            "processed 92"
+        ]
+    );
+    assert!(matches!(
+        &events[3],
+        ReactorEvent::Processed { block } if block.hash == BlockHash::from_byte_array([0x12; 32])
+    ));
+
+    cancel_token.cancel();
+    let _ = handle.await;
+
+    Ok(())
+}
+
+fn empty_block(height: u64, hash: u8, prev_hash: u8) -> Block {
+    Block {
+        height,
+        hash: BlockHash::from_byte_array([hash; 32]),
+        prev_hash: BlockHash::from_byte_array([prev_hash; 32]),
+        time: 0,
+        transactions: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_reactor_rollback_at_height_one() -> Result<()> {
+    let cancel_token = CancellationToken::new();
+    let (ctrl, mut ctrl_rx) = CtrlChannel::create();
+    let (reader, writer, _temp_dir) = new_test_db().await?;
+
+    let handle = reactor::run(
+        1,
+        cancel_token.clone(),
+        reader.clone(),
+        writer.clone(),
+        ctrl,
+        reactor::ReactorChannels::default(),
+    );
+
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 1);
+    let tx = start.event_tx;
+    tx.send(Event::BlockInsert((100, empty_block(1, 0x01, 0x00))))
+        .await?;
+    tx.send(Event::BlockInsert((100, empty_block(2, 0x02, 0x01))))
+        .await?;
+    let conn = &*reader.connection().await?;
+    await_block_at_height(conn, 2).await;
+
+    // a replacement for block 1 rolls back to the native block, which must
+    // not be mistaken for the chain's genesis
+    tx.send(Event::BlockInsert((100, empty_block(1, 0x11, 0x00))))
+        .await?;
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 1);
+    assert_eq!(start.last_hash, None);
+    let tx = start.event_tx;
+
+    // a block 2 not building on block 1 rolls back past it as well
+    tx.send(Event::BlockInsert((100, empty_block(1, 0x11, 0x00))))
+        .await?;
+    tx.send(Event::BlockInsert((100, empty_block(2, 0x12, 0x01))))
+        .await?;
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 1);
+    assert_eq!(start.last_hash, None);
+    let tx = start.event_tx;
+
+    tx.send(Event::BlockInsert((100, empty_block(1, 0x21, 0x00))))
+        .await?;
+    let block = await_block_at_height(conn, 1).await;
+    assert_eq!(block.hash, BlockHash::from_byte_array([0x21; 32]));
+    assert!(!handle.is_finished());
+
+    cancel_token.cancel();
+    let _ = handle.await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reactor_block_below_starting_height_is_clamped() -> Result<()> {
+    let cancel_token = CancellationToken::new();
+    let (ctrl, mut ctrl_rx) = CtrlChannel::create();
+    let (reader, writer, _temp_dir) = new_test_db().await?;
+
+    let handle = reactor::run(
+        91,
+        cancel_token.clone(),
+        reader.clone(),
+        writer.clone(),
+        ctrl,
+        reactor::ReactorChannels::default(),
+    );
+
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 91);
+    let tx = start.event_tx;
+    tx.send(Event::BlockInsert((100, empty_block(91, 0x01, 0x00))))
+        .await?;
+    tx.send(Event::BlockInsert((100, empty_block(92, 0x02, 0x01))))
+        .await?;
+    let conn = &*reader.connection().await?;
+    await_block_at_height(conn, 92).await;
+
+    // the rollback stops just before the starting height rather than at 49
+    tx.send(Event::BlockInsert((100, empty_block(50, 0x50, 0x49))))
+        .await?;
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 91);
+    assert_eq!(start.last_hash, None);
+    assert!(queries::select_block_at_height(conn, 91).await?.is_none());
+    let tx = start.event_tx;
+
+    // a block at height 0 can't underflow the rollback height either
+    tx.send(Event::BlockInsert((100, empty_block(0, 0x00, 0x00))))
+        .await?;
+    let start = ctrl_rx.recv().await.unwrap();
+    assert_eq!(start.start_height, 91);
+    assert!(!handle.is_finished());
+
+    cancel_token.cancel();
+    let _ = handle.await;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use bitcoin::{OutPoint, XOnlyPublicKey};
+use indexer::{
+    reactor::block_handler,
+    runtime::{Runtime, token::address as token_address},
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_runtime},
+};
+use indexer_types::{Block, Op, OpMetadata, OpReturnData};
+use indexmap::IndexMap;
+use testlib::*;
+
+import!(
+    name = "token",
+    height = 0,
+    tx_index = 0,
+    path = "../../native-contracts/token/wit",
+);
+
+fn call(signer: &str, previous_output: OutPoint, expr: String) -> Op {
+    Op::Call {
+        metadata: OpMetadata {
+            previous_output,
+            input_index: 0,
+            signer: indexer_types::Signer::XOnlyPubKey(signer.to_string()),
+        },
+        gas_limit: 100_000,
+        contract: token_address().into(),
+        expr,
+    }
+}
+
+async fn balance(runtime: &mut Runtime, account: &str) -> Result<Option<Decimal>> {
+    let value = runtime
+        .execute(
+            None,
+            &token_address(),
+            &token::wave::balance_call_expr(account),
+        )
+        .await?;
+    Ok(token::wave::balance_parse_return_expr(&value))
+}
+
+#[tokio::test]
+async fn test_block_handler_detach_uses_out_point_and_op_return() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(0).await?;
+
+    let mut issuance = new_mock_transaction(1);
+    issuance.ops = ["owner", "spender"]
+        .into_iter()
+        .enumerate()
+        .map(|(input_index, signer)| Op::Issuance {
+            metadata: OpMetadata {
+                previous_output: OutPoint::null(),
+                input_index: input_index as i64,
+                signer: indexer_types::Signer::XOnlyPubKey(signer.to_string()),
+            },
+        })
+        .collect();
+
+    let mut attach = new_mock_transaction(2);
+    attach.index = 1;
+    attach.ops = vec![call(
+        "owner",
+        OutPoint::null(),
+        token::wave::attach_call_expr(0, Decimal::from(2)),
+    )];
+    let utxo = OutPoint::new(attach.txid, 0);
+
+    // the detach spends the attached output and names the recipient in its
+    // op-return rather than relying on the signer
+    let recipient = XOnlyPublicKey::from_str(
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    )?;
+    let mut detach = new_mock_transaction(3);
+    detach.index = 2;
+    detach.ops = vec![call("spender", utxo, token::wave::detach_call_expr())];
+    detach.op_return_data = IndexMap::from([(0, OpReturnData::PubKey(recipient))]);
+
+    block_handler(
+        &mut runtime,
+        &[],
+        &Block {
+            height: 1,
+            hash: new_mock_block_hash(1),
+            prev_hash: new_mock_block_hash(0),
+            time: 0,
+            transactions: vec![issuance, attach, detach],
+        },
+    )
+    .await?;
+
+    assert_eq!(
+        balance(&mut runtime, &recipient.to_string()).await?,
+        Some(Decimal::from(2))
+    );
+    assert_eq!(
+        balance(&mut runtime, &utxo.to_string())
+            .await?
+            .unwrap_or_default(),
+        Decimal::default()
+    );
+
+    Ok(())
+}
@@ -241,16 +241,7 @@ proptest! {
             let cancel_token = CancellationToken::new();
             let (ctrl, mut ctrl_rx) = CtrlChannel::create();
 
-            let handle = reactor::run(
-                1,
-                cancel_token.clone(),
-                db.reader.clone(),
-                db.writer.clone(),
-                ctrl,
-                None,
-                None,
-                None,
-            );
+            let handle = reactor::run(1, cancel_token.clone(), db.reader.clone(), db.writer.clone(), ctrl, reactor::ReactorChannels::default());
 
             let start = ctrl_rx.recv().await.unwrap();
             assert_eq!(start.start_height, 1);
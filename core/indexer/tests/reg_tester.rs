@@ -0,0 +1,40 @@
+use testlib::*;
+
+#[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
+async fn test_mine_blocks_regtest() -> Result<()> {
+    let start_height = reg_tester.info().await?.height;
+    let block_hashes = runtime.mine(3).await?;
+    assert_eq!(block_hashes.len(), 3);
+    assert_eq!(reg_tester.info().await?.height, start_height + 3);
+
+    // identities are still funded after mining
+    runtime.identity().await?;
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
+async fn test_indexed_height_regtest() -> Result<()> {
+    let alice = runtime.identity().await?;
+    runtime.mine(2).await?;
+    let fib = runtime.publish(&alice, "fib").await?;
+
+    let height = tokio::time::timeout(std::time::Duration::from_secs(30), async {
+        loop {
+            let height = runtime.height().await?;
+            if height >= fib.height as i64 {
+                return Ok::<_, anyhow::Error>(height);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("Indexer did not reach the publish height in time")?;
+    assert_eq!(height, reg_tester.height().await);
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_indexed_height() -> Result<()> {
+    assert_eq!(runtime.height().await?, 1);
+    Ok(())
+}
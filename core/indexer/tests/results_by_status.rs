@@ -0,0 +1,90 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{Decimal, token, wit::Signer},
+    test_utils::{new_mock_transaction, new_test_runtime},
+};
+
+#[tokio::test]
+async fn test_results_by_status_separates_failed_calls() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(2).await?;
+    let token_id = runtime
+        .storage
+        .contract_id(&token::address())
+        .await?
+        .expect("token is published");
+
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.issuance(&signer).await?;
+
+    // even tx indexes move more than was issued and fail
+    let mut expected_failed = vec![];
+    for height in 1..=2 {
+        for tx_index in 2..=5 {
+            runtime
+                .set_context(
+                    height,
+                    tx_index,
+                    0,
+                    0,
+                    new_mock_transaction(height as u32).txid,
+                    None,
+                    None,
+                )
+                .await;
+            runtime.set_gas_limit(100_000);
+            let amount = if tx_index % 2 == 0 {
+                expected_failed.push((height, tx_index));
+                Decimal::from(1_000)
+            } else {
+                Decimal::from(1)
+            };
+            let result = token::api::transfer(&mut runtime, &signer, "recipient", amount).await?;
+            assert_eq!(result.is_err(), tx_index % 2 == 0);
+        }
+    }
+
+    let all = runtime.storage.results_in_range(1, 2).await?;
+    let all = all
+        .into_iter()
+        .filter(|r| r.contract_id == token_id)
+        .collect::<Vec<_>>();
+
+    let failed = runtime.storage.results_by_status(token_id, true).await?;
+    assert!(
+        failed
+            .iter()
+            .all(|r| r.value.as_ref().is_none_or(|v| v.starts_with("err(")))
+    );
+    assert_eq!(
+        failed
+            .iter()
+            .map(|r| (r.height, r.tx_index))
+            .collect::<Vec<_>>(),
+        expected_failed
+    );
+
+    let succeeded = runtime.storage.results_by_status(token_id, false).await?;
+    assert!(
+        succeeded
+            .iter()
+            .all(|r| r.value.as_ref().is_some_and(|v| v.starts_with("ok")))
+    );
+    assert_eq!(succeeded.len() + failed.len(), all.len());
+
+    let failed_at_2 = runtime
+        .storage
+        .results_by_status_in_range(token_id, true, 2, 2)
+        .await?;
+    assert_eq!(
+        failed_at_2
+            .iter()
+            .map(|r| (r.height, r.tx_index))
+            .collect::<Vec<_>>(),
+        vec![(2, 2), (2, 4)]
+    );
+
+    Ok(())
+}
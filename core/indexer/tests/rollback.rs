@@ -72,9 +72,7 @@ async fn test_follower_reactor_fetching() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     ));
 
     mock.clone().await_running().await;
@@ -177,9 +175,7 @@ async fn test_follower_reactor_rollback_during_start() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     ));
 
     mock.clone().await_running().await;
@@ -285,9 +281,7 @@ async fn test_follower_reactor_rollback_during_catchup() -> Result<()> {
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     ));
 
     mock.await_running().await;
@@ -520,9 +514,7 @@ async fn test_follower_reactor_rollback_zmq_message_multiple_blocks() -> Result<
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     ));
 
     mock.await_running().await;
@@ -646,9 +638,7 @@ async fn test_follower_reactor_rollback_zmq_message_redundant_messages() -> Resu
         reader.clone(),
         writer.clone(),
         ctrl,
-        None,
-        None,
-        None,
+        reactor::ReactorChannels::default(),
     ));
 
     mock.await_running().await;
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use deadpool::managed::Timeouts;
+use indexer::{
+    config::Config,
+    runtime::{
+        pool::{self, PoolConfig},
+        token::address as token_address,
+    },
+    test_utils::new_test_runtime,
+};
+
+#[test]
+fn test_config_overrides_pool_defaults() {
+    let mut config = Config::new_na();
+    assert_eq!(config.runtime_pool_config(), PoolConfig::default());
+
+    config.runtime_pool_size = Some(3);
+    config.runtime_pool_max_idle_secs = Some(60);
+    assert_eq!(
+        config.runtime_pool_config(),
+        PoolConfig {
+            max_size: 3,
+            max_idle: Some(Duration::from_secs(60)),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_pool_respects_max_size_and_reuses_runtimes() -> Result<()> {
+    let (_runtime, _writer, (temp_dir, db_name)) = new_test_runtime(0).await?;
+
+    let pool = pool::new(
+        temp_dir.path().to_path_buf(),
+        db_name,
+        PoolConfig {
+            max_size: 2,
+            max_idle: None,
+        },
+    )
+    .await?;
+    assert_eq!(pool.status().max_size, 2);
+
+    for _ in 0..3 {
+        pool.get()
+            .await?
+            .execute(None, &token_address(), "balance(\"nobody\")")
+            .await?;
+    }
+    assert_eq!(pool.status().size, 1);
+
+    let first = pool.get().await?;
+    let second = pool.get().await?;
+    assert_eq!(pool.status().size, 2);
+    let no_wait = Timeouts {
+        wait: Some(Duration::ZERO),
+        ..Timeouts::default()
+    };
+    assert!(pool.timeout_get(&no_wait).await.is_err());
+    drop((first, second));
+    assert_eq!(pool.status().available, 2);
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use bitcoin::XOnlyPublicKey;
+use indexer::runtime::token;
+use indexer_types::{
+    ENVELOPE_TAG, ENVELOPE_VERSION, Inst, OpReturnData, deserialize, deserialize_envelope,
+    inst_bytes_to_json, inst_json_to_bytes, serialize, serialize_envelope,
+};
+
+fn call() -> Inst {
+    Inst::Call {
+        gas_limit: 50_000,
+        contract: token::address().into(),
+        expr: "transfer(\"recipient\", {r0: 1, r1: 0, r2: 0, r3: 0, sign: plus})".to_string(),
+    }
+}
+
+#[test]
+fn test_envelope_round_trip() -> Result<()> {
+    for inst in [
+        call(),
+        Inst::Publish {
+            gas_limit: 10_000,
+            name: "token".to_string(),
+            bytes: vec![0, 1, 2, 3],
+        },
+        Inst::Issuance,
+    ] {
+        let bytes = serialize_envelope(&inst)?;
+        assert_eq!(bytes[..2], [ENVELOPE_TAG, ENVELOPE_VERSION]);
+        assert_eq!(bytes[2..], serialize(&inst)?);
+        assert_eq!(deserialize_envelope::<Inst>(&bytes)?, inst);
+    }
+
+    let key = XOnlyPublicKey::from_str(
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    )?;
+    let entries = vec![(0u64, OpReturnData::PubKey(key))];
+    let bytes = serialize_envelope(&entries)?;
+    assert_eq!(
+        deserialize_envelope::<Vec<(u64, OpReturnData)>>(&bytes)?,
+        entries
+    );
+    Ok(())
+}
+
+#[test]
+fn test_envelope_accepts_untagged_payload() -> Result<()> {
+    for inst in [call(), Inst::Issuance] {
+        // v0 payloads are the bare postcard encoding
+        let bytes = serialize(&inst)?;
+        assert_ne!(bytes[0], ENVELOPE_TAG);
+        assert_eq!(deserialize_envelope::<Inst>(&bytes)?, inst);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_envelope_rejects_unknown_version() -> Result<()> {
+    let mut bytes = serialize_envelope(&call())?;
+    bytes[1] = ENVELOPE_VERSION + 1;
+    let err = deserialize_envelope::<Inst>(&bytes).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Unsupported envelope version {} (expected {})",
+            ENVELOPE_VERSION + 1,
+            ENVELOPE_VERSION
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_serialize_has_no_prefix() -> Result<()> {
+    // storage values and their fuel costs must not change with the envelope
+    assert_eq!(serialize(&42u64)?, vec![42]);
+    assert_eq!(deserialize::<u64>(&serialize(&42u64)?)?, 42);
+    Ok(())
+}
+
+#[test]
+fn test_inst_json_to_bytes_is_canonical() -> Result<()> {
+    let expected = serialize_envelope(&call())?;
+    let expr =
+        serde_json::to_string("transfer(\"recipient\", {r0: 1, r1: 0, r2: 0, r3: 0, sign: plus})")?;
+    // the bytes end up in transactions, so neither field order, whitespace
+    // nor how the contract address is spelled may change them
+    for json in [
+        format!(
+            r#"{{"Call":{{"gas_limit":50000,"contract":"token_0_0","expr":{}}}}}"#,
+            expr
+        ),
+        format!(
+            r#"{{"Call":{{"expr":{},"contract":"token_0_0","gas_limit":50000}}}}"#,
+            expr
+        ),
+        format!(
+            r#"{{ "Call": {{ "contract": "token_00_000", "gas_limit": 50000, "expr": {} }} }}"#,
+            expr
+        ),
+    ] {
+        assert_eq!(inst_json_to_bytes(json.clone()), expected);
+        assert_eq!(inst_json_to_bytes(json), expected);
+    }
+
+    let json = inst_bytes_to_json(expected.clone());
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"Call":{{"gas_limit":50000,"contract":"token_0_0","expr":{}}}}}"#,
+            expr
+        )
+    );
+    assert_eq!(inst_json_to_bytes(json), expected);
+    Ok(())
+}
@@ -79,10 +79,7 @@ async fn run_test_shared_account_contract(runtime: &mut Runtime) -> Result<()> {
         1.into(),
     )
     .await?;
-    assert_eq!(
-        result,
-        Err(Error::Message("insufficient balance".to_string()))
-    );
+    assert_eq!(result, Err(Error::contract(100, "insufficient balance")));
 
     let result = shared_account::withdraw(
         runtime,
@@ -93,7 +90,7 @@ async fn run_test_shared_account_contract(runtime: &mut Runtime) -> Result<()> {
         1.into(),
     )
     .await?;
-    assert_eq!(result, Err(Error::Message("unauthorized".to_string())));
+    assert_eq!(result, Err(Error::contract(101, "unauthorized")));
 
     let result =
         shared_account::token_balance(runtime, &shared_account, token.clone(), &alice).await?;
@@ -113,6 +110,42 @@ async fn run_test_shared_account_contract(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+async fn run_test_shared_account_update_around_nested_call(runtime: &mut Runtime) -> Result<()> {
+    let alice = runtime.identity().await?;
+
+    let token = runtime.publish(&alice, "token").await?;
+    let shared_account = runtime.publish(&alice, "shared-account").await?;
+
+    token::mint(runtime, &token, &alice, 100.into()).await??;
+    let account_id = shared_account::open(
+        runtime,
+        &shared_account,
+        &alice,
+        token.clone(),
+        30.into(),
+        vec![],
+    )
+    .await??;
+
+    // the token transfer runs inside the update closure, after the balance
+    // was read and before it is written back
+    let result =
+        shared_account::sweep(runtime, &shared_account, &alice, token.clone(), &account_id).await?;
+    assert_eq!(result, Ok(100.into()));
+
+    let result = shared_account::balance(runtime, &shared_account, &account_id).await?;
+    assert_eq!(result, Some(100.into()));
+    let result = token::balance(runtime, &token, &alice).await?;
+    assert_eq!(result.unwrap_or_default(), 0.into());
+
+    Ok(())
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_shared_account_update_around_nested_call() -> Result<()> {
+    run_test_shared_account_update_around_nested_call(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_shared_account_contract() -> Result<()> {
     run_test_shared_account_contract(runtime).await
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{filestorage, token, verify_contract_id_signer, wit::Signer},
+    test_utils::new_test_runtime,
+};
+use testlib::{Decimal, to_wave_expr};
+
+#[test]
+fn test_verify_contract_id_signer() {
+    let own = Signer::new_contract_id(1);
+    let forged = Signer::new_contract_id(2);
+    let user = Signer::XOnlyPubKey("user".to_string());
+
+    assert!(verify_contract_id_signer(Some(&own), Some(1)).is_ok());
+    assert!(verify_contract_id_signer(Some(&user), Some(1)).is_ok());
+    assert!(verify_contract_id_signer(Some(&user), None).is_ok());
+    assert!(verify_contract_id_signer(None, Some(1)).is_ok());
+
+    for (signer, caller) in [(&forged, Some(1)), (&own, None)] {
+        assert_eq!(
+            verify_contract_id_signer(Some(signer), caller)
+                .unwrap_err()
+                .to_string(),
+            "Invalid contract id signer"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_nested_call_rejects_forged_contract_signer() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(1).await?;
+    runtime.set_gas_limit(100_000);
+
+    // contract A (filestorage) is mid-call into contract B (token) and hands
+    // over a signer claiming to be contract C (the token itself)
+    let a = runtime
+        .storage
+        .contract_id(&filestorage::address())
+        .await?
+        .expect("filestorage is published");
+    let c = runtime
+        .storage
+        .contract_id(&token::address())
+        .await?
+        .expect("token is published");
+    runtime.stack.push(a).await?;
+
+    let err = runtime
+        .execute(
+            Some(&Signer::new_contract_id(c)),
+            &token::address(),
+            &format!("transfer(\"thief\", {})", to_wave_expr(Decimal::from(1))),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Invalid contract id signer");
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use axum::{Router, http::StatusCode, routing::post};
+use axum_test::TestServer;
+use bitcoin::{BlockHash, hashes::Hash};
+use indexer::{
+    api::{Env, handlers::post_simulate_instruction},
+    bitcoin_follower::{ctrl::CtrlChannel, events::Event},
+    database::{queries::get_op_result, types::OpResultId},
+    reactor,
+    runtime::{self, pool::PoolConfig, token::address as token_address},
+    test_utils::{await_block_at_height, new_mock_transaction, new_test_db},
+};
+use indexer_types::{Block, Inst, InstructionSimulation, Op, OpMetadata, OpWithResult};
+use serde::Deserialize;
+use testlib::*;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+import!(
+    name = "token",
+    height = 0,
+    tx_index = 0,
+    path = "../../native-contracts/token/wit",
+);
+
+// x coordinate of the secp256k1 generator, a valid x-only public key
+const SIGNER: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+#[derive(Deserialize)]
+struct SimulationResponse {
+    result: OpWithResult,
+}
+
+fn metadata() -> OpMetadata {
+    OpMetadata {
+        previous_output: bitcoin::OutPoint::null(),
+        input_index: 0,
+        signer: indexer_types::Signer::XOnlyPubKey(SIGNER.to_string()),
+    }
+}
+
+fn block(height: u64, transactions: Vec<indexer_types::Transaction>) -> Block {
+    Block {
+        height,
+        hash: BlockHash::from_byte_array([height as u8; 32]),
+        prev_hash: BlockHash::from_byte_array([height as u8 - 1; 32]),
+        time: 0,
+        transactions,
+    }
+}
+
+#[tokio::test]
+async fn test_simulate_instruction_projects_mined_result() -> Result<()> {
+    let cancel_token = CancellationToken::new();
+    let (ctrl, mut ctrl_rx) = CtrlChannel::create();
+    let (reader, writer, (temp_dir, db_name)) = new_test_db().await?;
+    let (simulate_tx, simulate_rx) = mpsc::channel(10);
+
+    let handle = reactor::run(
+        1,
+        cancel_token.clone(),
+        reader.clone(),
+        writer.clone(),
+        ctrl,
+        reactor::ReactorChannels {
+            simulate_rx: Some(simulate_rx),
+            ..Default::default()
+        },
+    );
+
+    let start = ctrl_rx.recv().await.unwrap();
+    let tx = start.event_tx;
+
+    let mut issuance = new_mock_transaction(1);
+    issuance.ops = vec![Op::Issuance {
+        metadata: metadata(),
+    }];
+    tx.send(Event::BlockInsert((1, block(1, vec![issuance]))))
+        .await?;
+    await_block_at_height(&*reader.connection().await?, 1).await;
+
+    let mut env = Env::new_test(reader.clone(), temp_dir.path(), db_name.clone()).await?;
+    env.simulate_tx = simulate_tx;
+    let server = TestServer::new(
+        Router::new()
+            .route(
+                "/api/transactions/simulate/instruction",
+                post(post_simulate_instruction),
+            )
+            .with_state(env),
+    )?;
+
+    let expr = token::wave::transfer_call_expr("recipient", Decimal::from(1));
+    let response = server
+        .post("/api/transactions/simulate/instruction")
+        .json(&InstructionSimulation {
+            x_only_public_key: SIGNER.to_string(),
+            instruction: Inst::Call {
+                gas_limit: 100_000,
+                contract: token_address().into(),
+                expr: expr.clone(),
+            },
+        })
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let simulated = serde_json::from_slice::<SimulationResponse>(response.as_bytes())?
+        .result
+        .result
+        .expect("simulated call has a result");
+    assert!(simulated.gas > 0);
+    let value = simulated.value.clone().expect("transfer succeeded");
+    assert!(value.starts_with("ok("), "unexpected result: {}", value);
+    assert!(value.contains("recipient"), "unexpected result: {}", value);
+
+    // nothing was applied
+    let pool = runtime::pool::new(
+        temp_dir.path().to_path_buf(),
+        db_name,
+        PoolConfig::default(),
+    )
+    .await?;
+    let balance = pool
+        .get()
+        .await?
+        .execute(
+            None,
+            &token_address(),
+            &token::wave::balance_call_expr("recipient"),
+        )
+        .await?;
+    assert_eq!(token::wave::balance_parse_return_expr(&balance), None);
+
+    // mining the same call produces the projected result and gas
+    let mut transfer = new_mock_transaction(2);
+    transfer.ops = vec![Op::Call {
+        metadata: metadata(),
+        gas_limit: 100_000,
+        contract: token_address().into(),
+        expr,
+    }];
+    tx.send(Event::BlockInsert((2, block(2, vec![transfer.clone()]))))
+        .await?;
+    let conn = reader.connection().await?;
+    await_block_at_height(&*conn, 2).await;
+    let mined = get_op_result(
+        &conn,
+        &OpResultId::builder()
+            .txid(transfer.txid.to_string())
+            .build(),
+    )
+    .await?
+    .expect("mined call has a result");
+    assert_eq!(mined.gas, simulated.gas);
+    assert_eq!(mined.value, simulated.value);
+
+    cancel_token.cancel();
+    let _ = handle.await;
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use indexer::{
+    runtime::{ComponentCache, DEFAULT_MAX_VALUE_SIZE, MemoryStorage, Runtime, StorageBackend},
+    test_utils::{new_mock_transaction, new_test_signer, new_test_storage, publish_test_contract},
+};
+use testlib::{Integer, to_wave_expr};
+
+/// Exercises every backend operation the same way, so both backends are held
+/// to one set of expectations.
+async fn run_suite(backend: &impl StorageBackend, contract_id: i64) -> Result<()> {
+    assert_eq!(backend.get(100, contract_id, "count").await?, None);
+    assert!(!backend.exists(contract_id, "count").await?);
+
+    backend.set(contract_id, "count", b"1").await?;
+    backend.set(contract_id, "count", b"2").await?;
+    assert_eq!(
+        backend.get(100, contract_id, "count").await?,
+        Some(b"2".to_vec())
+    );
+    assert!(backend.exists(contract_id, "count").await?);
+    assert!(backend.get(0, contract_id, "count").await.is_err());
+    assert!(
+        backend
+            .set(contract_id, "big", &vec![0; DEFAULT_MAX_VALUE_SIZE + 1])
+            .await
+            .is_err()
+    );
+
+    for path in [
+        "votes.carol",
+        "votes.alice.count",
+        "votes.bob",
+        "votes.alice.at",
+    ] {
+        backend.set(contract_id, path, b"x").await?;
+    }
+    assert_eq!(
+        backend.keys(contract_id, "votes").await?,
+        ["alice", "bob", "carol"]
+    );
+    assert!(backend.exists(contract_id, "votes").await?);
+    assert!(backend.keys(contract_id, "count").await?.is_empty());
+
+    assert!(backend.delete(contract_id, "votes.bob").await?);
+    assert!(!backend.delete(contract_id, "votes.bob").await?);
+    assert_eq!(backend.get(100, contract_id, "votes.bob").await?, None);
+    assert_eq!(
+        backend.keys(contract_id, "votes").await?,
+        ["alice", "carol"]
+    );
+
+    backend.savepoint().await?;
+    backend.set(contract_id, "count", b"3").await?;
+    backend.savepoint().await?;
+    backend.set(contract_id, "votes.dave", b"x").await?;
+    backend.commit().await?;
+    assert_eq!(
+        backend.keys(contract_id, "votes").await?,
+        ["alice", "carol", "dave"]
+    );
+    backend.rollback().await?;
+    assert_eq!(
+        backend.get(100, contract_id, "count").await?,
+        Some(b"2".to_vec())
+    );
+    assert_eq!(
+        backend.keys(contract_id, "votes").await?,
+        ["alice", "carol"]
+    );
+
+    backend.savepoint().await?;
+    backend.set(contract_id, "count", b"4").await?;
+    backend.commit().await?;
+    assert_eq!(
+        backend.get(100, contract_id, "count").await?,
+        Some(b"4".to_vec())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_backend() -> Result<()> {
+    run_suite(&MemoryStorage::new(), 1).await
+}
+
+#[tokio::test]
+async fn test_sqlite_backend() -> Result<()> {
+    let (mut storage, _writer, _temp_dir) = new_test_storage(1).await?;
+    storage.height = 1;
+    storage.tx_index = 1;
+    let contract_id = storage.insert_contract("test", &[]).await?;
+    run_suite(&storage, contract_id).await
+}
+
+#[tokio::test]
+async fn test_runtime_on_memory_backend() -> Result<()> {
+    let (storage, _writer, _temp_dir) = new_test_storage(1).await?;
+    let mut runtime = Runtime::new(ComponentCache::new(), storage).await?;
+    let memory = Arc::new(MemoryStorage::new());
+    runtime.state_backend = Some(memory.clone());
+    runtime.publish_native_contracts().await?;
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.set_gas_limit(100_000);
+
+    let minter = new_test_signer(&mut runtime, "minter").await?;
+    let other = new_test_signer(&mut runtime, "other").await?;
+    let token = publish_test_contract(&mut runtime, &minter, "token").await?;
+
+    runtime
+        .execute(
+            Some(&minter),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(10))),
+        )
+        .await?;
+    runtime
+        .execute(
+            Some(&minter),
+            &token,
+            &format!("transfer(\"other\", {})", to_wave_expr(Integer::from(4))),
+        )
+        .await?;
+    let balance = runtime.execute(None, &token, "balance(\"other\")").await?;
+    assert_eq!(balance, format!("some({})", to_wave_expr(Integer::from(4))));
+
+    // a failed call rolls the in-memory state back with the database
+    let result = runtime
+        .execute(
+            Some(&other),
+            &token,
+            &format!("transfer(\"minter\", {})", to_wave_expr(Integer::from(5))),
+        )
+        .await?;
+    assert!(result.starts_with("err("));
+    assert_eq!(
+        runtime.execute(None, &token, "balance(\"other\")").await?,
+        balance
+    );
+    let balances = runtime.execute(None, &token, "balances()").await?;
+    assert!(balances.contains("\"minter\"") && balances.contains("\"other\""));
+
+    // the state never reached the database
+    let contract_id = runtime
+        .storage
+        .contract_id(&token)
+        .await?
+        .expect("token is published");
+    assert!(memory.has_state(contract_id).await?);
+    assert!(!runtime.storage.has_state(contract_id).await?);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use indexer::{
+    runtime::Runtime,
+    test_utils::{
+        new_mock_transaction, new_test_runtime_with_signer, new_test_storage, publish_test_contract,
+    },
+};
+
+const MAX_WRITES: u64 = 100;
+
+/// Starts a fresh operation so the per-transaction write count is reset.
+async fn next_op(runtime: &mut Runtime, op_index: i64) {
+    runtime
+        .set_context(1, 1, 0, op_index, new_mock_transaction(1).txid, None, None)
+        .await;
+}
+
+#[tokio::test]
+async fn test_storage_write_limit_stops_write_loop() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    runtime.storage.max_writes_per_tx = MAX_WRITES;
+    runtime.storage.write_limit_activation_height = 1;
+    next_op(&mut runtime, 1).await;
+    let arith = publish_test_contract(&mut runtime, &signer, "arith").await?;
+
+    next_op(&mut runtime, 2).await;
+    assert_eq!(
+        runtime.execute(Some(&signer), &arith, "burn(1)").await?,
+        "1"
+    );
+    let last_op = runtime.execute(None, &arith, "last-op()").await?;
+
+    // each iteration writes at least once, so the loop hits the cap well
+    // before running out of fuel
+    next_op(&mut runtime, 3).await;
+    let err = runtime
+        .execute(Some(&signer), &arith, &format!("burn({})", MAX_WRITES * 10))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(&format!(
+            "Storage write limit of {} per transaction exceeded",
+            MAX_WRITES
+        )),
+        "unexpected error: {}",
+        err
+    );
+    assert_eq!(runtime.execute(None, &arith, "last-op()").await?, last_op);
+
+    // the count starts over with the next operation
+    next_op(&mut runtime, 4).await;
+    assert_eq!(
+        runtime.execute(Some(&signer), &arith, "burn(1)").await?,
+        "1"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_storage_write_limit_counts_each_path_of_a_prefix_delete() -> Result<()> {
+    let (mut storage, _writer, _temp_dir) = new_test_storage(1).await?;
+    storage.height = 1;
+    storage.max_writes_per_tx = 5;
+    storage.write_limit_activation_height = 1;
+    let contract_id = 1;
+    for i in 0..6 {
+        storage
+            .set(contract_id, &format!("map.{}", i), &[1])
+            .await?;
+        storage.write_counter.reset().await;
+    }
+
+    let err = storage.delete_prefix(contract_id, "map").await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Storage write limit of 5 per transaction exceeded"),
+        "unexpected error: {}",
+        err
+    );
+    // the cap is checked before tombstoning anything
+    assert_eq!(storage.count_prefix(contract_id, "map").await?, 6);
+
+    storage.write_counter.reset().await;
+    storage.max_writes_per_tx = 6;
+    assert_eq!(storage.delete_prefix(contract_id, "map").await?, (6, 6));
+    assert_eq!(storage.count_prefix(contract_id, "map").await?, 0);
+
+    Ok(())
+}
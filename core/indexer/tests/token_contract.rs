@@ -1,3 +1,11 @@
+use indexer::{
+    database::queries::rollback_to_height,
+    runtime::{Runtime as IndexerRuntime, fuel::FuelGauge},
+    test_utils::{
+        new_mock_transaction, new_test_runtime_with_signer, new_test_signer, publish_test_contract,
+        read_test_contract,
+    },
+};
 use testlib::*;
 use tracing::info;
 
@@ -16,10 +24,15 @@ async fn run_test_token_contract(runtime: &mut Runtime) -> Result<()> {
     assert_eq!(result, Some(1000.into()));
 
     let result = token::transfer(runtime, &token, &holder, &minter, 123.into()).await?;
-    assert_eq!(
-        result,
-        Err(Error::Message("insufficient funds".to_string()))
-    );
+    assert!(matches!(
+        &result,
+        Err(Error::Contract(ContractError { code: 2, .. }))
+    ));
+    assert_eq!(result, Err(Error::contract(2, "insufficient funds")));
+    assert_eq!(result.unwrap_err().code(), Some(2));
+
+    let result = token::transfer(runtime, &token, &minter, &holder, 0.into()).await?;
+    assert_eq!(result.unwrap_err().code(), Some(1));
 
     token::transfer(runtime, &token, &minter, &holder, 40.into()).await??;
     token::transfer(runtime, &token, &minter, &holder, 2.into()).await??;
@@ -101,6 +114,27 @@ async fn run_test_token_contract_large_numbers(runtime: &mut Runtime) -> Result<
     Ok(())
 }
 
+async fn run_test_token_contract_balances_limited(runtime: &mut Runtime) -> Result<()> {
+    info!("test_token_contract_balances_limited");
+    let minter = runtime.identity().await?;
+    let token = runtime.publish(&minter, "token").await?;
+
+    token::mint(runtime, &token, &minter, 1000.into()).await??;
+    for i in 0..199 {
+        token::transfer(runtime, &token, &minter, &format!("acc{}", i), 1.into()).await??;
+    }
+
+    let page = token::balances_limited(runtime, &token, 50).await?;
+    assert_eq!(page.entries.len(), 50);
+    assert!(page.truncated);
+
+    let page = token::balances_limited(runtime, &token, 200).await?;
+    assert_eq!(page.entries.len(), 200);
+    assert!(!page.truncated);
+
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_token_contract() -> Result<()> {
     run_test_token_contract(runtime).await
@@ -111,6 +145,11 @@ async fn test_token_contract_large_numbers() -> Result<()> {
     run_test_token_contract_large_numbers(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_token_contract_balances_limited() -> Result<()> {
+    run_test_token_contract_balances_limited(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
 async fn test_token_contract_regtest() -> Result<()> {
     logging::setup();
@@ -118,3 +157,242 @@ async fn test_token_contract_regtest() -> Result<()> {
     run_test_token_contract_large_numbers(runtime).await?;
     Ok(())
 }
+
+fn transfer_expr(to: &str) -> String {
+    format!("transfer(\"{}\", {})", to, to_wave_expr(Integer::from(1)))
+}
+
+#[tokio::test]
+async fn test_deprecated_contract_rejects_procedures() -> Result<()> {
+    let (mut runtime, deployer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let other = new_test_signer(&mut runtime, "other").await?;
+    let token = publish_test_contract(&mut runtime, &deployer, "token").await?;
+
+    runtime
+        .execute(
+            Some(&deployer),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(10))),
+        )
+        .await?;
+    assert!(
+        !runtime
+            .execute(Some(&deployer), &token, &transfer_expr("other"))
+            .await?
+            .starts_with("err(")
+    );
+
+    let err = runtime.deprecate(&other, &token).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Only the deployer of {} can deprecate it", token)
+    );
+    runtime.deprecate(&deployer, &token).await?;
+
+    let err = runtime
+        .execute(Some(&deployer), &token, &transfer_expr("other"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::Deprecated(address)) if address == &token
+    ));
+
+    // views stay available by default so holders can still read balances
+    let balance = runtime.execute(None, &token, "balance(\"other\")").await?;
+    assert_eq!(balance, format!("some({})", to_wave_expr(Integer::from(1))));
+
+    runtime.allow_deprecated_views = false;
+    let err = runtime
+        .execute(None, &token, "balance(\"other\")")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::Deprecated(_))
+    ));
+
+    Ok(())
+}
+
+/// Host fuel spent by a single view call.
+async fn host_fuel(
+    runtime: &mut IndexerRuntime,
+    token: &ContractAddress,
+    expr: &str,
+) -> Result<u64> {
+    let gauge = FuelGauge::new();
+    runtime.gauge = Some(gauge.clone());
+    runtime.execute(None, token, expr).await?;
+    runtime.gauge = None;
+    Ok(gauge.total_host_fuel().await)
+}
+
+#[tokio::test]
+async fn test_contains_key_skips_value_load() -> Result<()> {
+    let (mut runtime, minter, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let token = publish_test_contract(&mut runtime, &minter, "token").await?;
+    runtime
+        .execute(
+            Some(&minter),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(10))),
+        )
+        .await?;
+
+    assert_eq!(
+        runtime
+            .execute(None, &token, "has-balance(\"test_signer\")")
+            .await?,
+        "true"
+    );
+    assert_eq!(
+        runtime
+            .execute(None, &token, "has-balance(\"nobody\")")
+            .await?,
+        "false"
+    );
+
+    let contains = host_fuel(&mut runtime, &token, "has-balance(\"test_signer\")").await?;
+    let get = host_fuel(&mut runtime, &token, "balance(\"test_signer\")").await?;
+    assert!(
+        contains < get,
+        "contains_key cost {} host fuel, get cost {}",
+        contains,
+        get
+    );
+
+    Ok(())
+}
+
+fn int(n: u64) -> String {
+    to_wave_expr(Integer::from(n))
+}
+
+#[tokio::test]
+async fn test_upgrade_keeps_storage() -> Result<()> {
+    let (mut runtime, deployer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let other = new_test_signer(&mut runtime, "other").await?;
+    let token = publish_test_contract(&mut runtime, &deployer, "token").await?;
+    let v2 = read_test_contract("token-v2").await?;
+
+    let result = runtime
+        .execute(Some(&deployer), &token, &format!("mint({})", int(100)))
+        .await?;
+    assert!(!result.starts_with("err("), "unexpected result: {}", result);
+    // v1 is compiled and cached without the v2 function
+    assert!(runtime.execute(None, &token, "holders()").await.is_err());
+
+    let err = runtime.upgrade(&other, &token, &v2).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Only the deployer of {} can upgrade it", token)
+    );
+    assert!(
+        runtime
+            .upgrade(&deployer, &token, b"not a contract")
+            .await
+            .is_err()
+    );
+    assert!(runtime.execute(None, &token, "holders()").await.is_err());
+
+    runtime.upgrade(&deployer, &token, &v2).await?;
+    assert_eq!(
+        runtime
+            .execute(None, &token, "balance(\"test_signer\")")
+            .await?,
+        format!("some({})", int(100))
+    );
+    assert_eq!(runtime.execute(None, &token, "holders()").await?, "1");
+
+    // the upgraded code keeps writing to the same storage
+    let result = runtime
+        .execute(
+            Some(&deployer),
+            &token,
+            &format!("transfer(\"other\", {})", int(40)),
+        )
+        .await?;
+    assert!(!result.starts_with("err("), "unexpected result: {}", result);
+    assert_eq!(runtime.execute(None, &token, "holders()").await?, "2");
+    assert_eq!(
+        runtime.execute(None, &token, "total-supply()").await?,
+        int(100)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upgrade_is_undone_by_rollback() -> Result<()> {
+    let (mut runtime, deployer, _writer, _temp_dir) = new_test_runtime_with_signer(2).await?;
+    let token = publish_test_contract(&mut runtime, &deployer, "token").await?;
+    let v1 = read_test_contract("token").await?;
+    let v2 = read_test_contract("token-v2").await?;
+    let contract_id = runtime.storage.contract_id(&token).await?.unwrap();
+    let v1_hash = runtime.storage.contract_hash(contract_id).await?;
+
+    runtime
+        .set_context(2, 1, 0, 0, new_mock_transaction(2).txid, None, None)
+        .await;
+    runtime.upgrade(&deployer, &token, &v2).await?;
+    assert_eq!(runtime.execute(None, &token, "holders()").await?, "0");
+    assert_ne!(runtime.storage.contract_hash(contract_id).await?, v1_hash);
+
+    // a reorg of the upgrade block brings back the code published at height 1
+    rollback_to_height(&runtime.storage.conn, 1).await?;
+    runtime.component_cache.clear();
+    assert!(runtime.execute(None, &token, "holders()").await.is_err());
+    assert_eq!(runtime.storage.contract_bytes(contract_id).await?, Some(v1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_init_is_rejected_once_contract_is_initialized() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    runtime.init_guard_activation_height = 1;
+    let token = publish_test_contract(&mut runtime, &signer, "token").await?;
+    let contract_id = runtime.storage.contract_id(&token).await?.unwrap();
+    assert!(runtime.storage.is_initialized(contract_id).await?);
+
+    runtime
+        .execute(Some(&signer), &token, &format!("mint({})", int(10)))
+        .await?;
+
+    let err = runtime
+        .execute(Some(&signer), &token, "init()")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError::AlreadyInitialized(address)) if address == &token
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!("Contract {} is already initialized", token)
+    );
+
+    let balance = runtime
+        .execute(None, &token, "balance(\"test_signer\")")
+        .await?;
+    assert_eq!(balance, format!("some({})", int(10)));
+    assert_eq!(
+        runtime.execute(None, &token, "total-supply()").await?,
+        int(10)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_init_is_allowed_before_activation_height() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    runtime.init_guard_activation_height = 2;
+    let token = publish_test_contract(&mut runtime, &signer, "token").await?;
+
+    // blocks before the guard replay as they always have
+    runtime.execute(Some(&signer), &token, "init()").await?;
+
+    Ok(())
+}
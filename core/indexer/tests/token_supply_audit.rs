@@ -0,0 +1,47 @@
+use anyhow::Result;
+use indexer::test_utils::{new_test_runtime_with_signer, new_test_signer, publish_test_contract};
+use testlib::{Integer, to_wave_expr};
+
+fn int(n: u64) -> String {
+    to_wave_expr(Integer::from(n))
+}
+
+#[tokio::test]
+async fn test_token_audit_supply() -> Result<()> {
+    let (mut runtime, minter, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let holder = new_test_signer(&mut runtime, "holder").await?;
+    let token = publish_test_contract(&mut runtime, &minter, "token").await?;
+
+    for (signer, expr) in [
+        (&minter, format!("mint({})", int(1000))),
+        (&minter, format!("transfer(\"holder\", {})", int(250))),
+        (&holder, format!("burn({})", int(50))),
+        (&minter, format!("burn({})", int(100))),
+    ] {
+        let result = runtime.execute(Some(signer), &token, &expr).await?;
+        assert!(!result.starts_with("err("), "{} failed: {}", expr, result);
+    }
+    assert_eq!(
+        runtime.execute(None, &token, "total-supply()").await?,
+        int(850)
+    );
+    let result = runtime.execute(None, &token, "audit-supply()").await?;
+    assert!(!result.starts_with("err("), "unexpected result: {}", result);
+
+    // dropping a balance behind the contract's back leaves the supply
+    // unaccounted for
+    let contract_id = runtime.storage.contract_id(&token).await?.unwrap();
+    runtime
+        .storage
+        .delete_matching_paths(contract_id, r"^ledger\.holder(\..*)?$")
+        .await?;
+    let result = runtime.execute(None, &token, "audit-supply()").await?;
+    assert!(result.starts_with("err("), "unexpected result: {}", result);
+    assert!(
+        result.contains("ledger balances must sum to the total supply: expected 650 == 850"),
+        "unexpected result: {}",
+        result
+    );
+
+    Ok(())
+}
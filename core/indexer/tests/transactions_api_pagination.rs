@@ -18,7 +18,7 @@ use indexer::{
         types::{ContractRow, ContractStateRow},
     },
     event::EventSubscriber,
-    runtime,
+    runtime::{self, pool::PoolConfig},
     test_utils::new_test_db,
 };
 use indexer_types::{BlockRow, PaginatedResponse, TransactionRow};
@@ -158,15 +158,17 @@ async fn create_test_app(
     }
 
     let (simulate_tx, _) = mpsc::channel(10);
+    let (pending_view_tx, _) = mpsc::channel(10);
     let env = Env {
         bitcoin: Client::new("".to_string(), "".to_string(), "".to_string())?,
         config: Config::new_na(),
         cancel_token: CancellationToken::new(),
         available: Arc::new(RwLock::new(true)),
         event_subscriber: EventSubscriber::new(),
-        runtime_pool: runtime::pool::new(db_dir, db_name).await?,
+        runtime_pool: runtime::pool::new(db_dir, db_name, PoolConfig::default()).await?,
         reader,
         simulate_tx,
+        pending_view_tx,
     };
 
     Ok(Router::new()
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{ArgError, ExecutionError, MAX_EXPR_DEPTH, parse_call_expr, split_call, token},
+    test_utils::new_test_runtime,
+};
+
+#[test]
+fn test_parse_call_expr_rejects_deep_nesting() {
+    let nested = |depth: usize| format!("f({}{})", "[".repeat(depth), "]".repeat(depth));
+    // the call's own parentheses count as a level
+    assert!(parse_call_expr(&nested(MAX_EXPR_DEPTH - 1)).is_ok());
+    for depth in [MAX_EXPR_DEPTH, 100_000] {
+        assert!(matches!(
+            parse_call_expr(&nested(depth)),
+            Err(ExecutionError::ParseExpr(message)) if message.contains("nested deeper")
+        ));
+    }
+    // brackets inside literals don't nest
+    let literal = format!("f(\"{}\")", "[".repeat(100_000));
+    assert!(parse_call_expr(&literal).is_ok());
+    assert!(matches!(
+        split_call(&nested(100_000)),
+        Err(ArgError::Syntax(message)) if message.contains("nested deeper")
+    ));
+}
+
+#[test]
+fn test_split_call_ignores_commas_in_literals() -> Result<()> {
+    let (name, args) = split_call(r#"f(',', "a, (b", '\'', [1, 2], 'x')"#)?;
+    assert_eq!(name, "f");
+    assert_eq!(args, [r"','", r#""a, (b""#, r"'\''", "[1, 2]", "'x'"]);
+
+    let (_, args) = split_call(r#"g("say \"hi, there\"", ')')"#)?;
+    assert_eq!(args, [r#""say \"hi, there\"""#, "')'"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_validate_args() -> Result<()> {
+    let (runtime, _writer, _temp_dir) = new_test_runtime(0).await?;
+    let token = token::address();
+
+    runtime.validate_args("balance(\"alice\")", &token).await?;
+    runtime
+        .validate_args(
+            "transfer(\"bob\", {r0: 1, r1: 0, r2: 0, r3: 0, sign: plus})",
+            &token,
+        )
+        .await?;
+
+    let err = runtime
+        .validate_args("transfer(\"bob\", \"ten\")", &token)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        ArgError::Type { index: 2, provided, .. } if provided == "string"
+    ));
+    assert!(err.to_string().starts_with("arg 2: expected record"));
+
+    let err = runtime
+        .validate_args("balance(1)", &token)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "arg 1: expected string, got number");
+
+    let err = runtime
+        .validate_args("balance()", &token)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ArgError::Arity {
+            expected: 1,
+            provided: 0
+        }
+    ));
+
+    let err = runtime
+        .validate_args("balance(\"alice\", \"bob\")", &token)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ArgError::Arity {
+            expected: 1,
+            provided: 2
+        }
+    ));
+
+    assert!(matches!(
+        runtime.validate_args("nope()", &token).await,
+        Err(ArgError::UnknownFunction(name)) if name == "nope"
+    ));
+
+    for expr in ["", "balance(", "balance(\"alice\"", ")("] {
+        let err = runtime.execute(None, &token, expr).await.unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<ExecutionError>(),
+                Some(ExecutionError::ParseExpr(_))
+            ),
+            "{:?} should be a parse error, got {}",
+            expr,
+            err
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use deadpool::managed::Pool;
+use indexer::{
+    database::queries::{insert_processed_block, rollback_to_height},
+    runtime::{
+        pool::{self, PoolConfig},
+        token,
+        wit::Signer,
+    },
+    test_utils::{new_mock_block_hash, new_mock_transaction, new_test_runtime},
+};
+use indexer_types::BlockRow;
+
+async fn view(pool: &Pool<pool::Manager>, expr: &str) -> Result<String> {
+    pool.get()
+        .await?
+        .execute_view(&token::address(), expr, None)
+        .await
+}
+
+#[tokio::test]
+async fn test_view_cache_reuses_results() -> Result<()> {
+    let (mut runtime, _writer, _temp_dir) = new_test_runtime(1).await?;
+    let component_cache = runtime.component_cache.clone();
+
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    runtime
+        .set_context(1, 1, 0, 0, new_mock_transaction(1).txid, None, None)
+        .await;
+    runtime.issuance(&signer).await?;
+
+    runtime.enable_view_cache();
+    let expr = format!("balance(\"{}\")", &*signer);
+    let before = component_cache.stats();
+    let first = runtime.execute(None, &token::address(), &expr).await?;
+    let after_first = component_cache.stats();
+    let second = runtime.execute(None, &token::address(), &expr).await?;
+    let after_second = component_cache.stats();
+
+    assert_eq!(first, second);
+    assert_eq!(
+        (after_first.hits + after_first.misses) - (before.hits + before.misses),
+        1
+    );
+    assert_eq!(after_second, after_first);
+
+    // moving to a new height invalidates the cache
+    runtime
+        .set_context(2, 1, 0, 0, new_mock_transaction(2).txid, None, None)
+        .await;
+    let third = runtime.execute(None, &token::address(), &expr).await?;
+    assert_eq!(third, first);
+    let after_third = component_cache.stats();
+    assert_eq!(after_third.hits, after_second.hits + 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pool_caches_views_until_state_or_the_latest_block_changes() -> Result<()> {
+    let (mut runtime, _writer, (temp_dir, db_name)) = new_test_runtime(1).await?;
+    let signer = Signer::XOnlyPubKey("test_signer".to_string());
+    runtime.issuance(&signer).await?;
+
+    let pool = pool::new(
+        temp_dir.path().to_path_buf(),
+        db_name,
+        PoolConfig {
+            max_size: 1,
+            max_idle: None,
+        },
+    )
+    .await?;
+    let expr = format!("balance(\"{}\")", &*signer);
+    let first = view(&pool, &expr).await?;
+
+    // a write in the middle of a block bumps the contract's state version,
+    // so the cached result isn't served past it
+    assert_eq!(view(&pool, &expr).await?, first);
+    runtime
+        .set_context(1, 2, 0, 0, new_mock_transaction(2).txid, None, None)
+        .await;
+    runtime.issuance(&signer).await?;
+    let mid_block = view(&pool, &expr).await?;
+    assert_ne!(mid_block, first);
+
+    runtime
+        .set_context(1, 4, 0, 0, new_mock_transaction(4).txid, None, None)
+        .await;
+    runtime.issuance(&signer).await?;
+    insert_processed_block(
+        &runtime.storage.conn,
+        BlockRow::builder()
+            .height(2)
+            .hash(new_mock_block_hash(2))
+            .build(),
+    )
+    .await?;
+    let second = view(&pool, &expr).await?;
+    assert_ne!(second, mid_block);
+
+    // a reorg replacing the block at the same height invalidates it too
+    runtime
+        .set_context(1, 3, 0, 0, new_mock_transaction(3).txid, None, None)
+        .await;
+    runtime.issuance(&signer).await?;
+    rollback_to_height(&runtime.storage.conn, 1).await?;
+    insert_processed_block(
+        &runtime.storage.conn,
+        BlockRow::builder()
+            .height(2)
+            .hash(new_mock_block_hash(102))
+            .build(),
+    )
+    .await?;
+    assert_ne!(view(&pool, &expr).await?, second);
+
+    Ok(())
+}
@@ -1,5 +1,10 @@
 use futures_util::future::join_all;
-use indexer::{api, runtime};
+use indexer::{
+    api,
+    config::Config,
+    runtime,
+    test_utils::{new_test_runtime_with_signer, publish_test_contract},
+};
 use indexer_types::ViewResult;
 use testlib::*;
 use tokio::time::Instant;
@@ -41,3 +46,75 @@ async fn test_view_contract() -> Result<()> {
     tracing::info!("Duration: {:?}", duration);
     Ok(())
 }
+
+const ACCOUNTS: usize = 100;
+
+#[tokio::test]
+async fn test_view_runs_within_its_own_gas_limit() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let token = publish_test_contract(&mut runtime, &signer, "token").await?;
+
+    runtime
+        .execute(
+            Some(&signer),
+            &token,
+            &format!("mint({})", to_wave_expr(Integer::from(ACCOUNTS as u64))),
+        )
+        .await?;
+    for i in 0..ACCOUNTS {
+        runtime
+            .execute(
+                Some(&signer),
+                &token,
+                &format!(
+                    "transfer(\"account_{}\", {})",
+                    i,
+                    to_wave_expr(Integer::from(1))
+                ),
+            )
+            .await?;
+    }
+
+    let err = runtime
+        .execute_view(&token, "balances()", Some(10))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("fuel"),
+        "unexpected error: {}",
+        err
+    );
+
+    let balances = runtime
+        .execute_view(&token, "balances()", Some(1_000_000))
+        .await?;
+    assert_eq!(balances.matches("account_").count(), ACCOUNTS);
+
+    // without a limit of its own the view gets the default, and the
+    // runtime's limit for procedures is left as it was
+    runtime.execute_view(&token, "balances()", None).await?;
+    assert_eq!(runtime.gas_limit, Some(100_000));
+
+    // a limit too large to convert to fuel is an error, not a wrapped budget
+    let err = runtime
+        .execute_view(&token, "balances()", Some(u64::MAX))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("overflows"),
+        "unexpected error: {}",
+        err
+    );
+    assert_eq!(runtime.gas_limit, Some(100_000));
+
+    Ok(())
+}
+
+#[test]
+fn test_view_gas_limit_is_capped_by_config() {
+    let mut config = Config::new_na();
+    config.max_view_gas_limit = 50_000;
+    assert_eq!(config.view_gas_limit(None), None);
+    assert_eq!(config.view_gas_limit(Some(10)), Some(10));
+    assert_eq!(config.view_gas_limit(Some(u64::MAX)), Some(50_000));
+}
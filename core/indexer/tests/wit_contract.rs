@@ -1,4 +1,8 @@
-use indexer::runtime::token;
+use indexer::{
+    runtime::{Compression, Runtime as IndexerRuntime, token},
+    test_utils::{new_test_runtime_with_signer, publish_test_contract, read_test_contract},
+};
+use indexer_types::{WitNamedType, WitResultRef, WitTypeDef, WitTypeRef};
 use testlib::*;
 
 const WIT: &str = r#"package root:component;
@@ -38,6 +42,7 @@ world root {
   export balance: async func(ctx: borrow<view-context>, acc: string) -> option<decimal>;
   export balances: async func(ctx: borrow<view-context>) -> list<balance>;
   export total-supply: async func(ctx: borrow<view-context>) -> decimal;
+  export decimals: async func(ctx: borrow<view-context>) -> u32;
   export attach: async func(ctx: borrow<proc-context>, vout: u64, amt: decimal) -> result<transfer, error>;
   export detach: async func(ctx: borrow<proc-context>) -> result<transfer, error>;
 }
@@ -49,12 +54,233 @@ async fn run_test(runtime: &mut Runtime) -> Result<()> {
     Ok(())
 }
 
+fn named(name: &str) -> WitTypeRef {
+    WitTypeRef::Named(name.to_string())
+}
+
+async fn run_test_schema(runtime: &mut Runtime) -> Result<()> {
+    let schema = runtime.schema(&token::address()).await?;
+
+    let functions = schema
+        .functions
+        .iter()
+        .map(|f| (f.name.as_str(), f.context.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        functions,
+        [
+            ("attach", Some("proc-context")),
+            ("balance", Some("view-context")),
+            ("balances", Some("view-context")),
+            ("burn", Some("proc-context")),
+            ("decimals", Some("view-context")),
+            ("detach", Some("proc-context")),
+            ("hold", Some("core-context")),
+            ("init", Some("proc-context")),
+            ("issuance", Some("core-context")),
+            ("mint", Some("proc-context")),
+            ("release", Some("core-context")),
+            ("total-supply", Some("view-context")),
+            ("transfer", Some("proc-context")),
+        ]
+    );
+
+    let transfer = schema
+        .functions
+        .iter()
+        .find(|f| f.name == "transfer")
+        .unwrap();
+    assert_eq!(
+        transfer.params,
+        [
+            WitNamedType {
+                name: "dst".to_string(),
+                ty: Some(named("string")),
+            },
+            WitNamedType {
+                name: "amt".to_string(),
+                ty: Some(named("decimal")),
+            },
+        ]
+    );
+    assert_eq!(
+        transfer.result,
+        Some(WitTypeRef::Result {
+            result: WitResultRef {
+                ok: Some(Box::new(named("transfer"))),
+                err: Some(Box::new(named("error"))),
+            },
+        })
+    );
+
+    assert_eq!(
+        schema.types.get("balance"),
+        Some(&WitTypeDef::Record {
+            fields: vec![
+                WitNamedType {
+                    name: "acc".to_string(),
+                    ty: Some(named("string")),
+                },
+                WitNamedType {
+                    name: "amt".to_string(),
+                    ty: Some(named("decimal")),
+                },
+            ],
+        })
+    );
+    assert!(
+        ["burn", "mint", "transfer"]
+            .iter()
+            .all(|name| schema.types.contains_key(*name))
+    );
+    Ok(())
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts")]
 async fn test_get_wit_from_api() -> Result<()> {
     run_test(runtime).await
 }
 
+#[testlib::test(contracts_dir = "../../test-contracts")]
+async fn test_get_schema() -> Result<()> {
+    run_test_schema(runtime).await
+}
+
+#[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
+async fn test_get_schema_regtest() -> Result<()> {
+    run_test_schema(runtime).await
+}
+
 #[testlib::test(contracts_dir = "../../test-contracts", mode = "regtest")]
 async fn test_get_wit_from_api_regtest() -> Result<()> {
     run_test(runtime).await
 }
+
+async fn describe(runtime: &mut IndexerRuntime, shapes: &ContractAddress) -> Result<String> {
+    runtime.execute(None, shapes, "describe()").await
+}
+
+#[tokio::test]
+async fn test_stored_enum_survives_variant_reordering() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let shapes = publish_test_contract(&mut runtime, &signer, "shapes").await?;
+    let v1 = read_test_contract("shapes").await?;
+    let v2 = read_test_contract("shapes-v2").await?;
+
+    runtime
+        .execute(Some(&signer), &shapes, "set-square(4)")
+        .await?;
+    assert_eq!(describe(&mut runtime, &shapes).await?, "\"square(4)\"");
+
+    // v2 lists the variants in a different order and renames `Square` to
+    // `Quad`, keeping its discriminant
+    runtime.upgrade(&signer, &shapes, &v2).await?;
+    assert_eq!(describe(&mut runtime, &shapes).await?, "\"quad(4)\"");
+    runtime
+        .execute(Some(&signer), &shapes, "set-circle(3)")
+        .await?;
+    assert_eq!(describe(&mut runtime, &shapes).await?, "\"circle(3)\"");
+    runtime
+        .execute(Some(&signer), &shapes, "set-quad(5)")
+        .await?;
+
+    runtime.upgrade(&signer, &shapes, &v1).await?;
+    assert_eq!(describe(&mut runtime, &shapes).await?, "\"square(5)\"");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_contract_round_trips_at_any_level() -> Result<()> {
+    let reader = ContractReader::new("../../test-contracts").await?;
+    let compressed = reader
+        .read("token")
+        .await?
+        .expect("token contract has not been built");
+    let module = reader.read_module("token").await?.unwrap();
+    assert_eq!(module, Compression::default().decompress(&compressed)?);
+    assert_eq!(&module[..4], b"\0asm");
+
+    let fast = Compression::new(1).compress(&module);
+    let best = Compression::new(Compression::MAX_LEVEL).compress(&module);
+    assert!(best.len() <= fast.len());
+    // decompression doesn't depend on the level the bytes were written at
+    assert_eq!(Compression::new(1).decompress(&best)?, module);
+    assert_eq!(Compression::default().decompress(&fast)?, module);
+    assert_eq!(Compression::new(99).level(), Compression::MAX_LEVEL);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_publish_loads_contract_compressed_at_low_level() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let module = Compression::default().decompress(&read_test_contract("tally").await?)?;
+    runtime
+        .publish(&signer, "tally", &Compression::new(1).compress(&module))
+        .await?;
+    let tally = ContractAddress {
+        name: "tally".to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+    runtime
+        .execute(Some(&signer), &tally, "vote(\"alice\")")
+        .await?;
+    assert_eq!(
+        runtime.execute(None, &tally, "tallies()").await?,
+        "[{name: \"alice\", votes: 1}]"
+    );
+
+    let contract_id = runtime.storage.contract_id(&tally).await?.unwrap();
+    assert_eq!(runtime.storage.module_bytes(contract_id).await?, module);
+    assert_eq!(
+        runtime
+            .component_cache
+            .get_module(&contract_id)
+            .await
+            .as_deref(),
+        Some(&module)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_check_publish_reports_without_persisting() -> Result<()> {
+    let (mut runtime, signer, _writer, _temp_dir) = new_test_runtime_with_signer(1).await?;
+    let address = |name: &str| ContractAddress {
+        name: name.to_string(),
+        height: 1,
+        tx_index: 1,
+    };
+
+    let tally = read_test_contract("tally").await?;
+    let report = runtime.check_publish(&signer, "tally", &tally).await?;
+    assert!(report.is_ok(), "{:?}", report);
+    assert_eq!(runtime.storage.contract_id(&address("tally")).await?, None);
+
+    let no_init = read_test_contract("no-init").await?;
+    let report = runtime.check_publish(&signer, "no-init", &no_init).await?;
+    assert!(!report.is_ok());
+    assert_eq!(report.validation_errors.len(), 1, "{:?}", report);
+    assert!(report.validation_errors[0].contains("contract must export an init function"));
+    assert!(report.init_error.is_some());
+    assert_eq!(
+        runtime.storage.contract_id(&address("no-init")).await?,
+        None
+    );
+
+    // nothing of the dry runs is left behind for a real publish to trip on
+    let tally = publish_test_contract(&mut runtime, &signer, "tally").await?;
+    assert_eq!(runtime.execute(None, &tally, "tallies()").await?, "[]");
+
+    assert!(
+        runtime
+            .check_publish(&signer, "garbage", b"not a contract")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use indexer::{
+    runtime::{Compression, WitError},
+    test_utils::new_test_storage,
+};
+
+#[tokio::test]
+async fn test_component_wit_errors() -> Result<()> {
+    let (storage, _writer, _temp_dir) = new_test_storage(1).await?;
+
+    // an empty module: valid wasm without a component-type section
+    let empty_module = b"\0asm\x01\0\0\0";
+    let contract_id = storage
+        .insert_contract("empty", &Compression::default().compress(empty_module))
+        .await?;
+    assert!(matches!(
+        storage.component_wit(contract_id).await,
+        Err(WitError::Missing)
+    ));
+
+    let contract_id = storage
+        .insert_contract("garbage", &Compression::default().compress(b"not wasm"))
+        .await?;
+    assert!(matches!(
+        storage.component_wit(contract_id).await,
+        Err(WitError::Malformed(_))
+    ));
+
+    Ok(())
+}
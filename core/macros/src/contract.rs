@@ -1,6 +1,6 @@
 use darling::FromMeta;
 use heck::ToPascalCase;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use std::path::Path;
 use syn::Ident;
@@ -11,36 +11,73 @@ use wit_validator::Validator;
 pub struct Config {
     name: String,
     path: Option<String>,
+    /// Generates `read_foreign`, for contracts that read other contracts'
+    /// `#[public]` storage directly.
+    #[darling(default)]
+    read_foreign: bool,
 }
 
-pub fn generate(config: Config) -> TokenStream {
-    let name = Ident::from_string(&config.name.to_pascal_case()).unwrap();
+/// Fails with one error per problem found, so an invalid WIT reports every
+/// validation error at once rather than the first.
+pub fn generate(config: Config) -> syn::Result<TokenStream> {
+    let name = Ident::from_string(&config.name.to_pascal_case())
+        .map_err(|e| syn::Error::new(Span::call_site(), e))?;
     let abs_path = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap())
         .canonicalize()
         .expect("Failed to canonicalize manifest directory")
         .join(config.path.unwrap_or("wit".to_string()));
     if !abs_path.exists() {
-        panic!("Path does not exist: {}", abs_path.display());
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("Path does not exist: {}", abs_path.display()),
+        ));
     }
 
     let mut resolve = Resolve::new();
-    resolve
-        .push_dir(&abs_path)
-        .unwrap_or_else(|e| panic!("Failed to parse WIT at {}: {}", abs_path.display(), e));
+    resolve.push_dir(&abs_path).map_err(|e| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("Failed to parse WIT at {}: {:#}", abs_path.display(), e),
+        )
+    })?;
 
     let result = Validator::validate_resolve(&resolve);
-    if result.has_errors() {
-        let error_messages: Vec<String> =
-            result.errors.iter().map(|e| format!("  - {}", e)).collect();
-        panic!(
-            "WIT validation failed for {}:\n{}",
-            abs_path.display(),
-            error_messages.join("\n")
-        );
+    if let Some(error) = result
+        .errors
+        .iter()
+        .map(|e| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("WIT validation failed for {}: {}", abs_path.display(), e),
+            )
+        })
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+    {
+        return Err(error);
     }
 
     let path = abs_path.to_string_lossy().to_string();
-    quote! {
+    let read_foreign = if config.read_foreign {
+        quote! {
+            /// Loads `path` from another contract's storage, which must lie under
+            /// one of that contract's `#[public]` fields. Cheaper than a
+            /// `foreign::call` to a view when only a stored value is needed.
+            fn read_foreign<T: Retrieve<context::ViewStorage>>(
+                contract_address: &foreign::ContractAddress,
+                path: &str,
+            ) -> Option<T> {
+                let storage = alloc::rc::Rc::new(foreign::view_storage(contract_address)?);
+                stdlib::ReadStorage::__get(&storage, path.parse().ok()?)
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let test_only_feature = wit_validator::TEST_ONLY_FEATURE;
+    Ok(quote! {
         extern crate alloc;
 
         use alloc::{
@@ -50,6 +87,21 @@ pub fn generate(config: Config) -> TokenStream {
         };
         use core::{fmt::Debug, str::FromStr};
 
+        // `test-only` exports are bound only in `kontor_test` builds
+        #[cfg(kontor_test)]
+        wit_bindgen::generate!({
+            world: "root",
+            path: #path,
+            generate_all,
+            generate_unused_types: true,
+            additional_derives: [stdlib::Storage, stdlib::Wavey],
+            export_macro_name: "__export__",
+            runtime_path: "crate::__rt",
+            async: false,
+            features: [#test_only_feature],
+        });
+
+        #[cfg(not(kontor_test))]
         wit_bindgen::generate!({
             world: "root",
             path: #path,
@@ -57,12 +109,13 @@ pub fn generate(config: Config) -> TokenStream {
             generate_unused_types: true,
             additional_derives: [stdlib::Storage, stdlib::Wavey],
             export_macro_name: "__export__",
-            runtime_path: "stdlib::wit_bindgen::rt",
+            runtime_path: "crate::__rt",
             async: false,
         });
 
         use kontor::built_in::*;
-        use kontor::built_in::foreign::{ContractAddressModel, ContractAddressWriteModel, get_contract_address};
+        use kontor::built_in::context::{ContractAddressModel, ContractAddressWriteModel};
+        use kontor::built_in::foreign::get_contract_address;
         use kontor::built_in::numbers::{IntegerModel, IntegerWriteModel, DecimalModel, DecimalWriteModel};
 
         type Map<K, V> = stdlib::StorageMap<K, V, context::ProcStorage>;
@@ -71,6 +124,87 @@ pub fn generate(config: Config) -> TokenStream {
             fn next(&self) -> Option<String> {
                 self.next()
             }
+
+            fn truncated(&self) -> bool {
+                self.truncated()
+            }
+        }
+
+        impl stdlib::HasNextEntry for context::Entries {
+            fn next(&self) -> Option<(String, stdlib::EntryValue)> {
+                self.next().map(|(k, v)| {
+                    let v = match v {
+                        context::EntryValue::Str(v) => stdlib::EntryValue::Str(v),
+                        context::EntryValue::U64(v) => stdlib::EntryValue::U64(v),
+                        context::EntryValue::S64(v) => stdlib::EntryValue::S64(v),
+                        context::EntryValue::Bool(v) => stdlib::EntryValue::Bool(v),
+                        context::EntryValue::ListU8(v) => stdlib::EntryValue::ListU8(v),
+                        context::EntryValue::Integer(v) => stdlib::EntryValue::Integer(stdlib::NumberLimbs {
+                            r0: v.r0,
+                            r1: v.r1,
+                            r2: v.r2,
+                            r3: v.r3,
+                            negative: matches!(v.sign, numbers::Sign::Minus),
+                        }),
+                        context::EntryValue::Decimal(v) => stdlib::EntryValue::Decimal(stdlib::NumberLimbs {
+                            r0: v.r0,
+                            r1: v.r1,
+                            r2: v.r2,
+                            r3: v.r3,
+                            negative: matches!(v.sign, numbers::Sign::Minus),
+                        }),
+                    };
+                    (k, v)
+                })
+            }
+        }
+
+        impl From<stdlib::EntryKind> for context::EntryKind {
+            fn from(kind: stdlib::EntryKind) -> Self {
+                match kind {
+                    stdlib::EntryKind::Str => context::EntryKind::Str,
+                    stdlib::EntryKind::U64 => context::EntryKind::U64,
+                    stdlib::EntryKind::S64 => context::EntryKind::S64,
+                    stdlib::EntryKind::Bool => context::EntryKind::Bool,
+                    stdlib::EntryKind::ListU8 => context::EntryKind::ListU8,
+                    stdlib::EntryKind::Integer => context::EntryKind::Integer,
+                    stdlib::EntryKind::Decimal => context::EntryKind::Decimal,
+                }
+            }
+        }
+
+        impl stdlib::FromEntry for numbers::Integer {
+            const KIND: stdlib::EntryKind = stdlib::EntryKind::Integer;
+
+            fn from_entry(value: stdlib::EntryValue) -> Option<Self> {
+                match value {
+                    stdlib::EntryValue::Integer(v) => Some(numbers::Integer {
+                        sign: if v.negative { numbers::Sign::Minus } else { numbers::Sign::Plus },
+                        r0: v.r0,
+                        r1: v.r1,
+                        r2: v.r2,
+                        r3: v.r3,
+                    }),
+                    _ => None,
+                }
+            }
+        }
+
+        impl stdlib::FromEntry for numbers::Decimal {
+            const KIND: stdlib::EntryKind = stdlib::EntryKind::Decimal;
+
+            fn from_entry(value: stdlib::EntryValue) -> Option<Self> {
+                match value {
+                    stdlib::EntryValue::Decimal(v) => Some(numbers::Decimal {
+                        sign: if v.negative { numbers::Sign::Minus } else { numbers::Sign::Plus },
+                        r0: v.r0,
+                        r1: v.r1,
+                        r2: v.r2,
+                        r3: v.r3,
+                    }),
+                    _ => None,
+                }
+            }
         }
 
         #[automatically_derived]
@@ -99,7 +233,31 @@ pub fn generate(config: Config) -> TokenStream {
             where
                 <T as FromStr>::Err: Debug,
             {
-                stdlib::make_keys_iterator(self.get_keys(path))
+                stdlib::make_keys_iterator(self.get_keys(path, None))
+            }
+
+            fn __get_keys_limited<T: FromStr>(self: &alloc::rc::Rc<Self>, path: &str, max_keys: u64) -> stdlib::LimitedKeys<T>
+            where
+                <T as FromStr>::Err: Debug,
+            {
+                stdlib::LimitedKeys::new(self.get_keys(path, Some(max_keys)))
+            }
+
+            fn __get_keys_after<T: FromStr>(self: &alloc::rc::Rc<Self>, path: &str, after: Option<&str>, max_keys: u64) -> stdlib::LimitedKeys<T>
+            where
+                <T as FromStr>::Err: Debug,
+            {
+                match after {
+                    Some(after) => stdlib::LimitedKeys::new(self.get_keys_after(path, &DotPathBuf::new().push(after), Some(max_keys))),
+                    None => stdlib::LimitedKeys::new(self.get_keys(path, Some(max_keys))),
+                }
+            }
+
+            fn __get_entries<'a, K: FromStr + 'a, V: stdlib::FromEntry + 'a>(self: &alloc::rc::Rc<Self>, path: &'a str) -> impl Iterator<Item = (K, V)> + 'a
+            where
+                <K as FromStr>::Err: Debug,
+            {
+                stdlib::make_entries_iterator(self.get_entries(path, V::KIND.into()))
             }
 
             fn __exists(self: &alloc::rc::Rc<Self>, path: &str) -> bool {
@@ -141,7 +299,31 @@ pub fn generate(config: Config) -> TokenStream {
             where
                 <T as FromStr>::Err: Debug,
             {
-                stdlib::make_keys_iterator(self.get_keys(path))
+                stdlib::make_keys_iterator(self.get_keys(path, None))
+            }
+
+            fn __get_keys_limited<T: FromStr>(self: &alloc::rc::Rc<Self>, path: &str, max_keys: u64) -> stdlib::LimitedKeys<T>
+            where
+                <T as FromStr>::Err: Debug,
+            {
+                stdlib::LimitedKeys::new(self.get_keys(path, Some(max_keys)))
+            }
+
+            fn __get_keys_after<T: FromStr>(self: &alloc::rc::Rc<Self>, path: &str, after: Option<&str>, max_keys: u64) -> stdlib::LimitedKeys<T>
+            where
+                <T as FromStr>::Err: Debug,
+            {
+                match after {
+                    Some(after) => stdlib::LimitedKeys::new(self.get_keys_after(path, &DotPathBuf::new().push(after), Some(max_keys))),
+                    None => stdlib::LimitedKeys::new(self.get_keys(path, Some(max_keys))),
+                }
+            }
+
+            fn __get_entries<'a, K: FromStr + 'a, V: stdlib::FromEntry + 'a>(self: &alloc::rc::Rc<Self>, path: &'a str) -> impl Iterator<Item = (K, V)> + 'a
+            where
+                <K as FromStr>::Err: Debug,
+            {
+                stdlib::make_entries_iterator(self.get_entries(path, V::KIND.into()))
             }
 
             fn __exists(self: &alloc::rc::Rc<Self>, path: &str) -> bool {
@@ -190,17 +372,23 @@ pub fn generate(config: Config) -> TokenStream {
             fn __delete_matching_paths(self: &alloc::rc::Rc<Self>, base_path: &str, variants: &[&str]) -> u64 {
                 self.delete_matching_paths(base_path, &variants.iter().map(|s| s.to_string()).collect::<Vec<_>>())
             }
+
+            fn __delete_prefix(self: &alloc::rc::Rc<Self>, base_path: &str) -> u64 {
+                self.delete_prefix(base_path)
+            }
         }
 
+        #read_foreign
+
         impl Retrieve<crate::context::ViewStorage> for foreign::ContractAddress {
             fn __get(ctx: &alloc::rc::Rc<crate::context::ViewStorage>, path: stdlib::DotPathBuf) -> Option<Self> {
-                stdlib::ReadStorage::__exists(ctx, &path).then(|| foreign::ContractAddressModel::new(ctx.clone(), path).load())
+                stdlib::ReadStorage::__exists(ctx, &path).then(|| context::ContractAddressModel::new(ctx.clone(), path).load())
             }
         }
 
         impl Retrieve<crate::context::ProcStorage> for foreign::ContractAddress {
             fn __get(ctx: &alloc::rc::Rc<crate::context::ProcStorage>, path: stdlib::DotPathBuf) -> Option<Self> {
-                stdlib::ReadStorage::__exists(ctx, &path).then(|| foreign::ContractAddressWriteModel::new(ctx.clone(), path).load())
+                stdlib::ReadStorage::__exists(ctx, &path).then(|| context::ContractAddressWriteModel::new(ctx.clone(), path).load())
             }
         }
 
@@ -230,8 +418,22 @@ pub fn generate(config: Config) -> TokenStream {
 
         impls!();
 
+        // the runtime the bindings call into; every export calls
+        // `run_ctors_once` before any contract code runs, which is where the
+        // panic hook gets installed
+        #[doc(hidden)]
+        mod __rt {
+            pub use stdlib::wit_bindgen::rt::*;
+
+            #[cfg(target_arch = "wasm32")]
+            pub fn run_ctors_once() {
+                stdlib::wit_bindgen::rt::run_ctors_once();
+                stdlib::set_panic_hook(|message| super::kontor::built_in::error::trap(message));
+            }
+        }
+
         struct #name;
 
         __export__!(#name);
-    }
+    })
 }
@@ -27,6 +27,7 @@ pub fn generate(config: Config) -> TokenStream {
                     (kontor::built_in::error::Error::DivByZero(msg1), kontor::built_in::error::Error::DivByZero(msg2)) => msg1 == msg2,
                     (kontor::built_in::error::Error::Syntax(msg1), kontor::built_in::error::Error::Syntax(msg2)) => msg1 == msg2,
                     (kontor::built_in::error::Error::Validation(msg1), kontor::built_in::error::Error::Validation(msg2)) => msg1 == msg2,
+                    (kontor::built_in::error::Error::Contract(err1), kontor::built_in::error::Error::Contract(err2)) => err1.code == err2.code && err1.message == err2.message,
                     _ => false,
                 }
             }
@@ -40,33 +41,77 @@ pub fn generate(config: Config) -> TokenStream {
             pub fn new(message: impl Into<String>) -> Self {
                 kontor::built_in::error::Error::Message(message.into())
             }
+
+            /// A contract-defined error carrying a stable `code` clients can match on.
+            pub fn contract(code: u32, message: impl Into<String>) -> Self {
+                kontor::built_in::error::Error::Contract(kontor::built_in::error::ContractError {
+                    code,
+                    message: message.into(),
+                })
+            }
+
+            /// The contract-defined code, if this is a `Contract` error.
+            pub fn code(&self) -> Option<u32> {
+                match self {
+                    kontor::built_in::error::Error::Contract(err) => Some(err.code),
+                    _ => None,
+                }
+            }
         }
 
         #[automatically_derived]
         impl From<core::num::ParseIntError> for kontor::built_in::error::Error {
             fn from(err: core::num::ParseIntError) -> Self {
-                kontor::built_in::error::Error::Message(alloc::format!("Parse integer error: {:?}", err))
+                kontor::built_in::error::Error::Message(alloc::format!("Parse integer error: {}", err))
             }
         }
 
         #[automatically_derived]
         impl From<core::num::TryFromIntError> for kontor::built_in::error::Error {
             fn from(err: core::num::TryFromIntError) -> Self {
-                kontor::built_in::error::Error::Message(alloc::format!("Try from integer error: {:?}", err))
+                kontor::built_in::error::Error::Message(alloc::format!("Try from integer error: {}", err))
             }
         }
 
         #[automatically_derived]
         impl From<core::str::Utf8Error> for kontor::built_in::error::Error {
             fn from(err: core::str::Utf8Error) -> Self {
-                kontor::built_in::error::Error::Message(alloc::format!("UTF-8 parse error: {:?}", err))
+                kontor::built_in::error::Error::Message(alloc::format!("UTF-8 parse error: {}", err))
+            }
+        }
+
+        #[automatically_derived]
+        impl From<stdlib::LedgerError> for kontor::built_in::error::Error {
+            fn from(err: stdlib::LedgerError) -> Self {
+                kontor::built_in::error::Error::contract(err.code(), alloc::format!("{}", err))
+            }
+        }
+
+        #[automatically_derived]
+        impl From<stdlib::NonceError> for kontor::built_in::error::Error {
+            fn from(err: stdlib::NonceError) -> Self {
+                kontor::built_in::error::Error::contract(err.code(), alloc::format!("{}", err))
+            }
+        }
+
+        #[automatically_derived]
+        impl From<stdlib::RequireError> for kontor::built_in::error::Error {
+            fn from(err: stdlib::RequireError) -> Self {
+                kontor::built_in::error::Error::Message(err.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl From<core::str::ParseBoolError> for kontor::built_in::error::Error {
+            fn from(err: core::str::ParseBoolError) -> Self {
+                kontor::built_in::error::Error::Message(alloc::format!("Parse bool error: {}", err))
             }
         }
 
         #[automatically_derived]
         impl From<core::char::ParseCharError> for kontor::built_in::error::Error {
             fn from(err: core::char::ParseCharError) -> Self {
-                kontor::built_in::error::Error::Message(alloc::format!("Parse char error: {:?}", err))
+                kontor::built_in::error::Error::Message(alloc::format!("Parse char error: {}", err))
             }
         }
 
@@ -75,6 +120,62 @@ pub fn generate(config: Config) -> TokenStream {
             pub fn sqrt(&self) -> Result<kontor::built_in::numbers::Integer, kontor::built_in::error::Error> {
                 #numerics_mod_name::sqrt_integer(*self)
             }
+
+            /// `self * bps / 10_000`, rounded down. Fails unless `bps` is within `0..=10_000`.
+            pub fn mul_bps(&self, bps: kontor::built_in::numbers::Integer) -> Result<kontor::built_in::numbers::Integer, kontor::built_in::error::Error> {
+                let max_bps = #numerics_mod_name::u64_to_integer(10_000);
+                if bps < Self::default() || bps > max_bps {
+                    return Err(kontor::built_in::error::Error::Validation(alloc::format!(
+                        "basis points must be within 0..=10000, got {}",
+                        bps
+                    )));
+                }
+                #numerics_mod_name::div_integer(#numerics_mod_name::mul_integer(*self, bps)?, max_bps)
+            }
+
+            /// `self` less a fee of `fee_bps`, rounded down so any remainder stays with the fee.
+            pub fn apply_fee(&self, fee_bps: kontor::built_in::numbers::Integer) -> Result<kontor::built_in::numbers::Integer, kontor::built_in::error::Error> {
+                let max_bps = #numerics_mod_name::u64_to_integer(10_000);
+                if fee_bps < Self::default() || fee_bps > max_bps {
+                    return Err(kontor::built_in::error::Error::Validation(alloc::format!(
+                        "fee basis points must be within 0..=10000, got {}",
+                        fee_bps
+                    )));
+                }
+                self.mul_bps(#numerics_mod_name::sub_integer(max_bps, fee_bps)?)
+            }
+
+            /// The largest value an `Integer` holds; the smallest is its negation.
+            pub fn max_value() -> kontor::built_in::numbers::Integer {
+                #numerics_mod_name::string_to_integer(
+                    "115_792_089_237_316_195_423_570_985_008_687_907_853_269_984_665_640_564_039_457",
+                )
+                .unwrap()
+            }
+
+            /// `self + other` clamped to `0..=Integer::max_value()` rather than failing. Meant
+            /// for amounts that never go negative and where capping is the intended outcome,
+            /// such as a counter; use the checked `add` wherever overflow would be a bug.
+            pub fn saturating_add(&self, other: kontor::built_in::numbers::Integer) -> kontor::built_in::numbers::Integer {
+                let up = other >= Self::default();
+                Self::saturate(#numerics_mod_name::add_integer(*self, other), up)
+            }
+
+            /// `self - other` clamped to `0..=Integer::max_value()`, so taking more than is
+            /// there leaves zero, like `u64::saturating_sub`.
+            pub fn saturating_sub(&self, other: kontor::built_in::numbers::Integer) -> kontor::built_in::numbers::Integer {
+                let up = other <= Self::default();
+                Self::saturate(#numerics_mod_name::sub_integer(*self, other), up)
+            }
+
+            fn saturate(result: Result<kontor::built_in::numbers::Integer, kontor::built_in::error::Error>, up: bool) -> kontor::built_in::numbers::Integer {
+                match result {
+                    Ok(value) if value < Self::default() => Self::default(),
+                    Ok(value) => value,
+                    Err(_) if up => Self::max_value(),
+                    Err(_) => Self::default(),
+                }
+            }
         }
 
         #[automatically_derived]
@@ -168,6 +269,17 @@ pub fn generate(config: Config) -> TokenStream {
             }
         }
 
+        #[automatically_derived]
+        impl From<kontor::built_in::numbers::Ordering> for core::cmp::Ordering {
+            fn from(ordering: kontor::built_in::numbers::Ordering) -> Self {
+                match ordering {
+                    kontor::built_in::numbers::Ordering::Less => core::cmp::Ordering::Less,
+                    kontor::built_in::numbers::Ordering::Equal => core::cmp::Ordering::Equal,
+                    kontor::built_in::numbers::Ordering::Greater => core::cmp::Ordering::Greater,
+                }
+            }
+        }
+
         #[automatically_derived]
         impl PartialOrd for kontor::built_in::numbers::Integer {
             fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
@@ -175,14 +287,12 @@ pub fn generate(config: Config) -> TokenStream {
             }
         }
 
+        /// Each comparison is a single host call, so branch three ways with
+        /// `match a.cmp(&b)` rather than `<` followed by `==`.
         #[automatically_derived]
         impl Ord for kontor::built_in::numbers::Integer {
             fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-                match #numerics_mod_name::cmp_integer(*self, *other) {
-                    kontor::built_in::numbers::Ordering::Less => core::cmp::Ordering::Less,
-                    kontor::built_in::numbers::Ordering::Equal => core::cmp::Ordering::Equal,
-                    kontor::built_in::numbers::Ordering::Greater => core::cmp::Ordering::Greater,
-                }
+                #numerics_mod_name::cmp_integer(*self, *other).into()
             }
         }
 
@@ -243,6 +353,52 @@ pub fn generate(config: Config) -> TokenStream {
             pub fn log10(&self) -> Result<kontor::built_in::numbers::Decimal, kontor::built_in::error::Error> {
                 #numerics_mod_name::log10_decimal(*self)
             }
+
+            /// `self / other` rounded to 18 decimal places using `mode`.
+            pub fn div_rounded(&self, other: kontor::built_in::numbers::Decimal, mode: kontor::built_in::numbers::RoundingMode) -> Result<kontor::built_in::numbers::Decimal, kontor::built_in::error::Error> {
+                #numerics_mod_name::div_decimal_rounded(*self, other, mode)
+            }
+
+            /// The exact value as `mantissa * 10^-scale`, for storing without going through a string.
+            pub fn to_scaled_integer(&self) -> kontor::built_in::numbers::ScaledInteger {
+                #numerics_mod_name::decimal_to_scaled_integer(*self)
+            }
+
+            /// Rebuilds a decimal from `mantissa * 10^-scale`. Fails rather than rounding if it needs more than 18 decimal places.
+            pub fn from_scaled_integer(s: kontor::built_in::numbers::ScaledInteger) -> Result<kontor::built_in::numbers::Decimal, kontor::built_in::error::Error> {
+                #numerics_mod_name::scaled_integer_to_decimal(s)
+            }
+
+            /// The largest value saturating arithmetic clamps to, `Integer::max_value()` as a decimal.
+            pub fn max_value() -> kontor::built_in::numbers::Decimal {
+                #numerics_mod_name::integer_to_decimal(kontor::built_in::numbers::Integer::max_value())
+            }
+
+            /// `self + other` clamped to `0..=Decimal::max_value()` rather than failing. Meant
+            /// for amounts that never go negative and where capping is the intended outcome;
+            /// use the checked `add` wherever overflow would be a bug.
+            pub fn saturating_add(&self, other: kontor::built_in::numbers::Decimal) -> kontor::built_in::numbers::Decimal {
+                let up = other >= Self::default();
+                Self::saturate(#numerics_mod_name::add_decimal(*self, other), up)
+            }
+
+            /// `self - other` clamped to `0..=Decimal::max_value()`, so taking more than is
+            /// there leaves zero.
+            pub fn saturating_sub(&self, other: kontor::built_in::numbers::Decimal) -> kontor::built_in::numbers::Decimal {
+                let up = other <= Self::default();
+                Self::saturate(#numerics_mod_name::sub_decimal(*self, other), up)
+            }
+
+            fn saturate(result: Result<kontor::built_in::numbers::Decimal, kontor::built_in::error::Error>, up: bool) -> kontor::built_in::numbers::Decimal {
+                let max = Self::max_value();
+                match result {
+                    Ok(value) if value < Self::default() => Self::default(),
+                    Ok(value) if value > max => max,
+                    Ok(value) => value,
+                    Err(_) if up => max,
+                    Err(_) => Self::default(),
+                }
+            }
         }
 
 
@@ -311,14 +467,12 @@ pub fn generate(config: Config) -> TokenStream {
             }
         }
 
+        /// Each comparison is a single host call, so branch three ways with
+        /// `match a.cmp(&b)` rather than `<` followed by `==`.
         #[automatically_derived]
         impl Ord for kontor::built_in::numbers::Decimal {
             fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-                match #numerics_mod_name::cmp_decimal(*self, *other) {
-                    kontor::built_in::numbers::Ordering::Less => core::cmp::Ordering::Less,
-                    kontor::built_in::numbers::Ordering::Equal => core::cmp::Ordering::Equal,
-                    kontor::built_in::numbers::Ordering::Greater => core::cmp::Ordering::Greater,
-                }
+                #numerics_mod_name::cmp_decimal(*self, *other).into()
             }
         }
 
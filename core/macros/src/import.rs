@@ -22,10 +22,10 @@ pub struct Config {
     public: Option<bool>,
 }
 
-pub fn generate(config: Config, test: bool) -> TokenStream {
+pub fn generate(config: Config, test: bool) -> syn::Result<TokenStream> {
     let name = config.name;
-    let module_name =
-        Ident::from_string(&config.mod_name.unwrap_or(name.clone().to_snake_case())).unwrap();
+    let module_name = Ident::from_string(&config.mod_name.unwrap_or(name.clone().to_snake_case()))
+        .map_err(|e| syn::Error::new(Span::call_site(), e))?;
     let height = config.height;
     let tx_index = config.tx_index;
     let path = config.path;
@@ -48,24 +48,43 @@ pub fn import(
     contract_id: Option<(&str, u64, u64)>,
     test: bool,
     public: bool,
-) -> TokenStream {
+) -> syn::Result<TokenStream> {
     let abs_path = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap())
         .canonicalize()
         .expect("Failed to canonicalize path")
         .join(&path);
     if !abs_path.exists() {
-        panic!("Path does not exist: {}", abs_path.display());
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("Path does not exist: {}", abs_path.display()),
+        ));
     }
     let mut resolve = Resolve::new();
+    if test {
+        // tests run against local builds, which keep `test-only` exports
+        resolve
+            .features
+            .insert(wit_validator::TEST_ONLY_FEATURE.to_string());
+    }
     resolve
         .push_dir(abs_path.to_string_lossy().to_string())
-        .unwrap();
+        .map_err(|e| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("Failed to parse WIT at {}: {:#}", abs_path.display(), e),
+            )
+        })?;
 
     let (_world_id, world) = resolve
         .worlds
         .iter()
         .find(|(_, w)| w.name == world_name)
-        .unwrap();
+        .ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("World `{}` not found in {}", world_name, abs_path.display()),
+            )
+        })?;
 
     let exports = world
         .exports
@@ -99,6 +118,7 @@ pub fn import(
                 "challenge-input",
                 "verify-result",
                 "error",
+                "contract-error",
                 "keys",
                 "integer",
                 "decimal",
@@ -133,6 +153,12 @@ pub fn import(
             .push(generate_wave_functions(&resolve, export).expect("Wave function didn't generate"))
     }
 
+    let mock_stream = if test {
+        generate_mock(&resolve, &module_name, &exports, contract_id).expect("Mock didn't generate")
+    } else {
+        quote! {}
+    };
+
     let supers = if test {
         quote! {
             use super::ContractAddress;
@@ -166,7 +192,7 @@ pub fn import(
         quote! { mod }
     };
 
-    quote! {
+    Ok(quote! {
         #mod_keywords #module_name {
             extern crate alloc;
 
@@ -186,8 +212,117 @@ pub fn import(
 
             #(#type_streams)*
             #(#func_streams)*
+            #mock_stream
         }
-    }
+    })
+}
+
+/// A stand-in for the imported contract that tests install in place of a
+/// deployed one: it answers calls with preset return values and records the
+/// expressions it was called with.
+fn generate_mock(
+    resolve: &Resolve,
+    module_name: &Ident,
+    exports: &[&Function],
+    contract_id: Option<(&str, u64, u64)>,
+) -> Result<TokenStream> {
+    let mock_name = format_ident!("Mock{}", module_name.to_string().to_upper_camel_case());
+
+    let unit_fn_names = exports
+        .iter()
+        .filter(|export| export.result.is_none())
+        .map(|export| export.name.clone())
+        .collect::<Vec<_>>();
+
+    let setters = exports
+        .iter()
+        .filter(|export| export.result.is_some())
+        .map(|export| {
+            let setter_name = format_ident!("{}_returns", make_fn_ident(export));
+            let fn_name = &export.name;
+            let ret_ty = make_return_type(resolve, export)?;
+            Ok(quote! {
+                pub fn #setter_name(&self, value: #ret_ty) {
+                    self.returns
+                        .lock()
+                        .expect("Mock returns lock poisoned")
+                        .insert(#fn_name.to_string(), stdlib::to_wave_expr(value));
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (install_params, contract_arg) = if let Some((name, height, tx_index)) = contract_id {
+        (
+            quote! { runtime: &mut Runtime },
+            quote! {
+                &ContractAddress {
+                    name: #name.to_string(),
+                    height: #height,
+                    tx_index: #tx_index,
+                }
+            },
+        )
+    } else {
+        (
+            quote! { runtime: &mut Runtime, contract_address_: &ContractAddress },
+            quote! { contract_address_ },
+        )
+    };
+
+    Ok(quote! {
+        #[derive(Clone)]
+        pub struct #mock_name {
+            returns: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>,
+            calls: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+        }
+
+        impl #mock_name {
+            pub fn new() -> Self {
+                let returns = [#(#unit_fn_names),*]
+                    .into_iter()
+                    .map(|name: &str| (name.to_string(), String::new()))
+                    .collect();
+                Self {
+                    returns: std::sync::Arc::new(std::sync::Mutex::new(returns)),
+                    calls: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+                }
+            }
+
+            #(#setters)*
+
+            /// The expressions the mock was called with, oldest first.
+            pub fn calls(&self) -> Vec<String> {
+                self.calls.lock().expect("Mock calls lock poisoned").clone()
+            }
+
+            /// Routes calls to the contract through this mock.
+            pub async fn install(&self, #install_params) -> Result<(), AnyhowError> {
+                let returns = self.returns.clone();
+                let calls = self.calls.clone();
+                runtime
+                    .mock(
+                        #contract_arg,
+                        std::sync::Arc::new(move |expr: &str| {
+                            calls
+                                .lock()
+                                .expect("Mock calls lock poisoned")
+                                .push(expr.to_string());
+                            let name = expr.split('(').next().unwrap_or_default().trim();
+                            returns
+                                .lock()
+                                .expect("Mock returns lock poisoned")
+                                .get(name)
+                                .cloned()
+                                .ok_or_else(|| {
+                                    AnyhowError::msg(format!("No return value mocked for {}", expr))
+                                })
+                        }),
+                    )
+                    .await
+            }
+        }
+    })
 }
 
 fn make_params(resolve: &Resolve, export: &Function) -> Result<Vec<TokenStream>> {
@@ -222,7 +357,7 @@ fn make_call_expr(resolve: &Resolve, export: &Function) -> Result<TokenStream> {
                     quote! {
                         match #param_name {
                             Some(val) => stdlib::to_wave_expr(val),
-                            None => "null".to_string(),
+                            None => "none".to_string(),
                         }
                     }
                 }
@@ -1,6 +1,6 @@
 use darling::FromMeta;
 use heck::ToSnakeCase;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use syn::Ident;
 
 use crate::import;
@@ -11,9 +11,10 @@ pub struct Config {
     path: Option<String>,
 }
 
-pub fn generate(config: Config, test: bool) -> TokenStream {
+pub fn generate(config: Config, test: bool) -> syn::Result<TokenStream> {
     let name = config.name;
-    let module_name = Ident::from_string(&name.clone().to_snake_case()).unwrap();
+    let module_name = Ident::from_string(&name.clone().to_snake_case())
+        .map_err(|e| syn::Error::new(Span::call_site(), e))?;
     let path = config.path.unwrap_or("../contract/wit".to_string());
 
     import::import(path, module_name, "root".to_string(), None, test, false)
@@ -17,49 +17,80 @@ mod test;
 mod utils;
 mod wavey;
 
+/// Parses a function-like macro's `key = value` arguments into its config,
+/// turning malformed arguments into compile errors at the offending span.
+fn parse_config<T: FromMeta>(input: TokenStream) -> Result<T, TokenStream> {
+    NestedMeta::parse_meta_list(input.into())
+        .map_err(darling::Error::from)
+        .and_then(|attr_args| T::from_list(&attr_args))
+        .map_err(|err| err.write_errors().into())
+}
+
 #[proc_macro]
 pub fn contract(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.into()).unwrap();
-    let config = contract::Config::from_list(&attr_args).unwrap();
-    contract::generate(config).into()
+    let config: contract::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
+    contract::generate(config)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro]
 pub fn impls(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.into()).unwrap();
-    let config = impls::Config::from_list(&attr_args).unwrap();
+    let config: impls::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
     impls::generate(config).into()
 }
 
 #[proc_macro]
 pub fn import(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.clone().into()).unwrap();
-    let config = import::Config::from_list(&attr_args).unwrap();
-    import::generate(config, false).into()
+    let config: import::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
+    import::generate(config, false)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro]
 pub fn import_test(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.clone().into()).unwrap();
-    let config = import::Config::from_list(&attr_args).unwrap();
-    import::generate(config, true).into()
+    let config: import::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
+    import::generate(config, true)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro]
 pub fn interface(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.clone().into()).unwrap();
-    let config = interface::Config::from_list(&attr_args).unwrap();
-    interface::generate(config, false).into()
+    let config: interface::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
+    interface::generate(config, false)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro]
 pub fn interface_test(input: TokenStream) -> TokenStream {
-    let attr_args = NestedMeta::parse_meta_list(input.clone().into()).unwrap();
-    let config = interface::Config::from_list(&attr_args).unwrap();
-    interface::generate(config, true).into()
+    let config: interface::Config = match parse_config(input) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
+    interface::generate(config, true)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
 }
 
-#[proc_macro_derive(Store)]
+#[proc_macro_derive(Store, attributes(store))]
 pub fn derive_store(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -88,6 +119,16 @@ pub fn derive_store(input: TokenStream) -> TokenStream {
         Err(err) => return err.to_compile_error().into(),
     };
 
+    let field_checks = match &input.data {
+        Data::Struct(data_struct) => store::generate_struct_field_checks(data_struct, name),
+        _ => Ok(quote! {}),
+    };
+
+    let field_checks = match field_checks {
+        Ok(field_checks) => field_checks,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let expanded = quote! {
         #[automatically_derived]
@@ -96,12 +137,32 @@ pub fn derive_store(input: TokenStream) -> TokenStream {
                 #body
             }
         }
+
+        #[automatically_derived]
+        impl #impl_generics stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<#name #ty_generics> #where_clause {
+            fn __set(ctx: &alloc::rc::Rc<crate::context::ProcStorage>, base_path: stdlib::DotPathBuf, value: alloc::vec::Vec<#name #ty_generics>) {
+                stdlib::__set_list(ctx, base_path, value)
+            }
+        }
+
+        #field_checks
     };
 
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Model)]
+/// Generates read and write models over a struct's storage. Write models get
+/// `set_<field>` for every field and, for primitive fields, `update_<field>`
+/// and `try_update_<field>`, which read the stored value, pass it to the
+/// closure and write back what it returns.
+///
+/// The read, the closure and the write run in turn within the calling
+/// contract, so nothing else can change the field between them: a contract
+/// only writes its own storage, and the runtime rejects nested calls that
+/// would re-enter a contract already on the call stack. Only the closure
+/// itself can interfere, by writing the same field through another model,
+/// and that write is overwritten.
+#[proc_macro_derive(Model, attributes(store))]
 pub fn derive_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -142,14 +203,14 @@ pub fn derive_model(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(Storage)]
+#[proc_macro_derive(Storage, attributes(store))]
 pub fn derive_storage(input: TokenStream) -> TokenStream {
     let mut tokens = derive_store(input.clone());
     tokens.extend(derive_model(input));
     tokens
 }
 
-#[proc_macro_derive(Root)]
+#[proc_macro_derive(Root, attributes(config, public))]
 pub fn derive_root(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -175,7 +236,7 @@ pub fn derive_root(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(StorageRoot)]
+#[proc_macro_derive(StorageRoot, attributes(config, public))]
 pub fn derive_storage_root(input: TokenStream) -> TokenStream {
     let mut tokens = derive_storage(input.clone());
     tokens.extend(derive_root(input));
@@ -275,6 +336,28 @@ pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
     test::generate(config, func).into()
 }
 
+/// Keeps a contract function out of release builds: it is only compiled with
+/// the `kontor_test` cfg, which the test contracts' `kontor-test` profile sets.
+/// The matching export must be marked `@unstable(feature = test-only)` in the
+/// contract's WIT so `contract!` only binds it in those builds too.
+#[proc_macro_attribute]
+pub fn test_only(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::TokenStream::from(attr).span(),
+            "test_only takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let item = proc_macro2::TokenStream::from(item);
+    quote! {
+        #[cfg(kontor_test)]
+        #item
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn contract_address(input: TokenStream) -> TokenStream {
     contract_address::generate(input)
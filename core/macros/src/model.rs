@@ -13,285 +13,390 @@ pub fn generate_struct(
     type_name: &Ident,
     write: bool,
 ) -> Result<TokenStream> {
-    match &data_struct.fields {
-        Fields::Named(fields) => {
-            let write_prefix = if write { "Write" } else { "" };
-            let read_only_model_name = Ident::new(&format!("{}Model", type_name), type_name.span());
-            let model_name = Ident::new(
-                &format!("{}{}Model", type_name, write_prefix),
-                type_name.span(),
-            );
-            let context_param = if write {
-                quote! { crate::context::ProcStorage }
-            } else {
-                quote! { crate::context::ViewStorage }
-            };
-
-            let mut special_models = vec![];
+    let fields = utils::struct_fields(&data_struct.fields, type_name, "Model")?;
+    let write_prefix = if write { "Write" } else { "" };
+    let read_only_model_name = Ident::new(&format!("{}Model", type_name), type_name.span());
+    let model_name = Ident::new(
+        &format!("{}{}Model", type_name, write_prefix),
+        type_name.span(),
+    );
+    let context_param = if write {
+        quote! { crate::context::ProcStorage }
+    } else {
+        quote! { crate::context::ViewStorage }
+    };
 
-            let getters = fields.named.iter().map(|field| {
-                let field_name = field.ident.as_ref().unwrap();
-                let field_name_str = field_name.to_string();
-                let field_ty = &field.ty;
+    let mut special_models = vec![];
 
-                if utils::is_map_type(field_ty) {
-                    let (k_ty, v_ty) = get_map_types(field_ty)?;
-                    let field_model_name = Ident::new(&format!("{}{}{}Model", type_name, &field_name.to_string().to_pascal_case(), write_prefix), field.span());
+    let getters = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let field_name_str = &field.segment;
+        let field_ty = field.ty;
 
-                    let (get_return, get_body) = if utils::is_primitive_type(&v_ty) {
-                        (quote! { Option<#v_ty> }, quote! { stdlib::ReadStorage::__get(&self.ctx, base_path) })
-                    } else {
-                        let v_model_ty = get_model_ident(write, &v_ty, field.span())?;
-                        (quote! { Option<#v_model_ty> }, quote! { stdlib::ReadStorage::__exists(&self.ctx, &base_path).then(|| #v_model_ty::new(self.ctx.clone(), base_path)) })
-                    };
-
-                    let setter = if write {
-                        quote! {
-                            pub fn set(&self, key: #k_ty, value: #v_ty) {
-                                stdlib::WriteStorage::__set(&self.ctx, self.base_path.push(key.to_string()), value)
-                            }
-                        }
-                    } else {
-                        quote!{}
-                    };
-
-                    special_models.push(quote! {
-                        #[derive(Clone)]
-                        pub struct #field_model_name {
-                            pub base_path: stdlib::DotPathBuf,
-                            ctx: alloc::rc::Rc<#context_param>,
-                        }
+        if utils::is_map_type(field_ty) {
+            let (k_ty, v_ty) = get_map_types(field_ty)?;
+            let field_model_name = Ident::new(&format!("{}{}{}Model", type_name, &field_name.to_string().to_pascal_case(), write_prefix), field_name.span());
 
-                        impl #field_model_name {
-                            pub fn get(&self, key: impl ToString) -> #get_return {
-                                let base_path = self.base_path.push(key.to_string());
-                                #get_body
-                            }
-
-                            #setter
-
-                            pub fn load(&self) -> Map<#k_ty, #v_ty> {
-                                Map::new(&[])
-                            }
-
-                            pub fn keys<'a, T: ToString + FromStr + Clone + 'a>(
-                                &'a self,
-                            ) -> impl Iterator<Item = T> + 'a
-                            where                                       // <--- Add this section
-                                <T as FromStr>::Err: Debug,
-                            {
-                                stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
-                            }
-                        }
-                    });
+            let (get_return, get_body) = if utils::is_primitive_type(&v_ty) {
+                (quote! { Option<#v_ty> }, quote! { stdlib::ReadStorage::__get(&self.ctx, base_path) })
+            } else {
+                let v_model_ty = get_model_ident(write, &v_ty, field_name.span())?;
+                (quote! { Option<#v_model_ty> }, quote! { stdlib::ReadStorage::__exists(&self.ctx, &base_path).then(|| #v_model_ty::new(self.ctx.clone(), base_path)) })
+            };
 
-                    Ok(quote! {
-                        pub fn #field_name(&self) -> #field_model_name {
-                            #field_model_name { base_path: self.base_path.push(#field_name_str), ctx: self.ctx.clone() }
-                        }
-                    })
-                } else if utils::is_option_type(field_ty) {
-                    let inner_ty = get_option_inner_type(field_ty)?;
-                    let base_path = quote! { self.base_path.push(#field_name_str) };
-                    if utils::is_primitive_type(&inner_ty) {
-                        Ok(quote! {
-                            pub fn #field_name(&self) -> Option<#inner_ty> {
-                                let base_path = #base_path;
-                                if stdlib::ReadStorage::__extend_path_with_match(&self.ctx, &base_path, &["none"]).is_some() {
-                                    None
-                                } else {
-                                    stdlib::ReadStorage::__get(&self.ctx, base_path.push("some"))
-                                }
-                            }
-                        })
-                    } else {
-                        let inner_model_ty = get_model_ident(write, &inner_ty, field.span())?;
-                        let (load, ret_ty) = (quote! {}, quote! { #inner_model_ty });
-                        Ok(quote! {
-                            pub fn #field_name(&self) -> Option<#ret_ty> {
-                                let base_path = #base_path;
-                                if stdlib::ReadStorage::__extend_path_with_match(&self.ctx, &base_path, &["none"]).is_some() {
-                                    None
-                                } else {
-                                    Some(#inner_model_ty::new(self.ctx.clone(), base_path.push("some"))#load)
-                                }
-                            }
-                        })
+            let entries = if utils::is_entry_type(&v_ty) {
+                quote! {
+                    pub fn entries<'a, T: ToString + FromStr + Clone + 'a>(
+                        &'a self,
+                    ) -> impl Iterator<Item = (T, #v_ty)> + 'a
+                    where
+                        <T as FromStr>::Err: Debug,
+                    {
+                        stdlib::ReadStorage::__get_entries(&self.ctx, &self.base_path)
                     }
-                } else if utils::is_primitive_type(field_ty) {
-                    Ok(quote! {
-                        pub fn #field_name(&self) -> #field_ty {
-                            stdlib::ReadStorage::__get(&self.ctx, self.base_path.push(#field_name_str)).unwrap()
-                        }
-                    })
-                } else {
-                    let field_model_ty = get_model_ident(write, field_ty, field.span())?;
-                    Ok(quote! {
-                        pub fn #field_name(&self) -> #field_model_ty {
-                            #field_model_ty::new(self.ctx.clone(), self.base_path.push(#field_name_str))
-                        }
-                    })
                 }
-            }).collect::<Result<Vec<_>>>()?;
-
-            let setters = if write {
-                fields
-                    .named
-                    .iter()
-                    .map(|field| {
-                        let field_name = field.ident.as_ref().unwrap();
-                        let field_name_str = field_name.to_string();
-                        let field_ty = &field.ty;
-                        let set_field_name =
-                            Ident::new(&format!("set_{}", field_name), field_name.span());
-                        let setter = quote! {
-                            pub fn #set_field_name(&self, value: #field_ty) {
-                                stdlib::WriteStorage::__set(&self.ctx, self.base_path.push(#field_name_str), value);
-                            }
-                        };
-                        if utils::is_map_type(field_ty) {
-                            Ok(quote! {})
-                        } else if utils::is_primitive_type(field_ty) {
-                            let update_field_name = Ident::new(&format!("update_{}", field_name), field_name.span());
-                            let try_update_field_name = Ident::new(&format!("try_update_{}", field_name), field_name.span());
-                            Ok(quote! {
-                                #setter
-
-                                pub fn #update_field_name(&self, f: impl Fn(#field_ty) -> #field_ty) {
-                                    let path = self.base_path.push(#field_name_str);
-                                    stdlib::WriteStorage::__set(&self.ctx, path.clone(), f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap()));
-                                }
-
-                                pub fn #try_update_field_name(&self, f: impl Fn(#field_ty) -> Result<#field_ty, crate::error::Error>) -> Result<(), crate::error::Error> {
-                                    let path = self.base_path.push(#field_name_str);
-                                    stdlib::WriteStorage::__set(&self.ctx, path.clone(), f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap())?);
-                                    Ok(())
-                                }
-                            })
-                        } else {
-                            Ok(setter)
-                        }
-                    })
-                    .collect::<Result<Vec<_>>>()?
             } else {
-                Vec::new()
+                quote!{}
             };
 
-            let load_fields = fields
-                .named
-                .iter()
-                .map(|field| {
-                    let field_name = field.ident.as_ref().unwrap();
-                    let _field_name_str = field_name.to_string();
-                    let field_ty = &field.ty;
-
-                    if utils::is_map_type(field_ty) {
-                        let (_k_ty, _v_ty) = get_map_types(field_ty)?;
-                        Ok(quote! {
-                            #field_name: self.#field_name().load()
-                        })
-                    } else if utils::is_option_type(field_ty) {
-                        let inner_ty = get_option_inner_type(field_ty)?;
-                        if utils::is_primitive_type(&inner_ty) {
-                            Ok(quote! {
-                                #field_name: self.#field_name()
-                            })
-                        } else {
-                            let load = quote! { .map(|p| p.load()) };
-                            Ok(quote! {
-                                #field_name: self.#field_name()#load
-                            })
-                        }
-                    } else if utils::is_primitive_type(field_ty) {
-                        Ok(quote! {
-                            #field_name: self.#field_name()
-                        })
-                    } else {
-                        Ok(quote! {
-                            #field_name: self.#field_name().load()
-                        })
+            let setter = if write {
+                quote! {
+                    pub fn set(&self, key: #k_ty, value: #v_ty) {
+                        stdlib::WriteStorage::__set(&self.ctx, self.base_path.push(key.to_string()), value)
                     }
-                })
-                .collect::<Result<Vec<_>>>()?;
 
-            let proc_props = if write {
-                quote! {
-                    model: #read_only_model_name,
+                    pub fn clear(&self) -> u64 {
+                        stdlib::WriteStorage::__delete_prefix(&self.ctx, &self.base_path)
+                    }
                 }
             } else {
-                quote! {}
+                quote!{}
             };
 
-            let proc_prelude = if write {
-                quote! {
-                    let view_storage = ctx.view_storage();
+            special_models.push(quote! {
+                #[derive(Clone)]
+                pub struct #field_model_name {
+                    pub base_path: stdlib::DotPathBuf,
+                    ctx: alloc::rc::Rc<#context_param>,
                 }
-            } else {
-                quote! {}
-            };
 
-            let proc_assigns = if write {
-                quote! {
-                    model: #read_only_model_name::new(alloc::rc::Rc::new(view_storage), base_path.clone()),
+                impl #field_model_name {
+                    pub fn get(&self, key: impl ToString) -> #get_return {
+                        let base_path = self.base_path.push(key.to_string());
+                        #get_body
+                    }
+
+                    pub fn contains_key(&self, key: impl ToString) -> bool {
+                        let base_path = self.base_path.push(key.to_string());
+                        stdlib::ReadStorage::__exists(&self.ctx, &base_path)
+                    }
+
+                    #setter
+
+                    pub fn load(&self) -> Map<#k_ty, #v_ty> {
+                        Map::new(&[])
+                    }
+
+                    pub fn keys<'a, T: ToString + FromStr + Clone + 'a>(
+                        &'a self,
+                    ) -> impl Iterator<Item = T> + 'a
+                    where                                       // <--- Add this section
+                        <T as FromStr>::Err: Debug,
+                    {
+                        stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
+                    }
+
+                    pub fn keys_limited<T: ToString + FromStr + Clone>(
+                        &self,
+                        max_keys: u64,
+                    ) -> stdlib::LimitedKeys<T>
+                    where
+                        <T as FromStr>::Err: Debug,
+                    {
+                        stdlib::ReadStorage::__get_keys_limited(&self.ctx, &self.base_path, max_keys)
+                    }
+
+                    pub fn keys_range<T: ToString + FromStr + Clone>(
+                        &self,
+                        after: Option<T>,
+                        max_keys: u64,
+                    ) -> stdlib::LimitedKeys<T>
+                    where
+                        <T as FromStr>::Err: Debug,
+                    {
+                        stdlib::ReadStorage::__get_keys_after(&self.ctx, &self.base_path, after.map(|key| key.to_string()).as_deref(), max_keys)
+                    }
+
+                    #entries
+                }
+            });
+
+            Ok(quote! {
+                pub fn #field_name(&self) -> #field_model_name {
+                    #field_model_name { base_path: self.base_path.push(#field_name_str), ctx: self.ctx.clone() }
                 }
+            })
+        } else if utils::is_list_type(field_ty) {
+            let elem_ty = get_list_elem_type(field_ty)?;
+            let field_model_name = Ident::new(&format!("{}{}{}Model", type_name, &field_name.to_string().to_pascal_case(), write_prefix), field_name.span());
+
+            let (get_return, get_body, load_body) = if utils::is_primitive_type(&elem_ty) {
+                (
+                    quote! { #elem_ty },
+                    quote! { stdlib::ReadStorage::__get(&self.ctx, base_path).unwrap() },
+                    quote! { stdlib::ReadStorage::__get(&self.ctx, self.base_path.push(i.to_string())).unwrap() },
+                )
             } else {
-                quote! {}
+                let elem_model_ty = get_model_ident(write, &elem_ty, field_name.span())?;
+                (
+                    quote! { #elem_model_ty },
+                    quote! { #elem_model_ty::new(self.ctx.clone(), base_path) },
+                    quote! { #elem_model_ty::new(self.ctx.clone(), self.base_path.push(i.to_string())).load() },
+                )
             };
 
-            let proc_impls = if write {
+            let push = if write {
                 quote! {
-                    impl core::ops::Deref for #model_name {
-                        type Target = #read_only_model_name;
-
-                        fn deref(&self) -> &Self::Target {
-                            &self.model
-                        }
+                    pub fn push(&self, value: #elem_ty) {
+                        let len = self.len();
+                        stdlib::WriteStorage::__set(&self.ctx, self.base_path.push(len.to_string()), value);
+                        stdlib::WriteStorage::__set(&self.ctx, self.base_path.push("len"), len + 1);
                     }
                 }
             } else {
-                quote! {}
+                quote!{}
             };
 
-            let result = quote! {
-                pub struct #model_name {
+            special_models.push(quote! {
+                #[derive(Clone)]
+                pub struct #field_model_name {
                     pub base_path: stdlib::DotPathBuf,
                     ctx: alloc::rc::Rc<#context_param>,
-                    #proc_props
                 }
 
-                impl #model_name {
-                    pub fn new(ctx: alloc::rc::Rc<#context_param>, base_path: stdlib::DotPathBuf) -> Self {
-                        #proc_prelude
-                        Self {
-                            base_path: base_path.clone(),
-                            ctx,
-                            #proc_assigns
-                        }
+                impl #field_model_name {
+                    pub fn len(&self) -> u64 {
+                        stdlib::ReadStorage::__get(&self.ctx, self.base_path.push("len")).unwrap_or_default()
                     }
 
-                    #(#getters)*
+                    pub fn is_empty(&self) -> bool {
+                        self.len() == 0
+                    }
 
-                    #(#setters)*
+                    pub fn get(&self, index: u64) -> Option<#get_return> {
+                        (index < self.len()).then(|| {
+                            let base_path = self.base_path.push(index.to_string());
+                            #get_body
+                        })
+                    }
+
+                    #push
+
+                    pub fn load(&self) -> alloc::vec::Vec<#elem_ty> {
+                        (0..self.len()).map(|i| #load_body).collect()
+                    }
+                }
+            });
 
-                    pub fn load(&self) -> #type_name {
-                        #type_name {
-                            #(#load_fields,)*
+            Ok(quote! {
+                pub fn #field_name(&self) -> #field_model_name {
+                    #field_model_name { base_path: self.base_path.push(#field_name_str), ctx: self.ctx.clone() }
+                }
+            })
+        } else if utils::is_option_type(field_ty) {
+            let inner_ty = get_option_inner_type(field_ty)?;
+            let base_path = quote! { self.base_path.push(#field_name_str) };
+            if utils::is_primitive_type(&inner_ty) {
+                Ok(quote! {
+                    pub fn #field_name(&self) -> Option<#inner_ty> {
+                        let base_path = #base_path;
+                        if stdlib::ReadStorage::__extend_path_with_match(&self.ctx, &base_path, &["none"]).is_some() {
+                            None
+                        } else {
+                            stdlib::ReadStorage::__get(&self.ctx, base_path.push("some"))
+                        }
+                    }
+                })
+            } else {
+                let inner_model_ty = get_model_ident(write, &inner_ty, field_name.span())?;
+                let (load, ret_ty) = (quote! {}, quote! { #inner_model_ty });
+                Ok(quote! {
+                    pub fn #field_name(&self) -> Option<#ret_ty> {
+                        let base_path = #base_path;
+                        if stdlib::ReadStorage::__extend_path_with_match(&self.ctx, &base_path, &["none"]).is_some() {
+                            None
+                        } else {
+                            Some(#inner_model_ty::new(self.ctx.clone(), base_path.push("some"))#load)
                         }
                     }
+                })
+            }
+        } else if utils::is_primitive_type(field_ty) {
+            Ok(quote! {
+                pub fn #field_name(&self) -> #field_ty {
+                    stdlib::ReadStorage::__get(&self.ctx, self.base_path.push(#field_name_str)).unwrap()
                 }
+            })
+        } else {
+            let field_model_ty = get_model_ident(write, field_ty, field_name.span())?;
+            Ok(quote! {
+                pub fn #field_name(&self) -> #field_model_ty {
+                    #field_model_ty::new(self.ctx.clone(), self.base_path.push(#field_name_str))
+                }
+            })
+        }
+    }).collect::<Result<Vec<_>>>()?;
 
-                #proc_impls
+    let setters = if write {
+        fields
+            .iter()
+            .map(|field| {
+                let field_name = &field.ident;
+                let field_name_str = &field.segment;
+                let field_ty = field.ty;
+                let set_field_name =
+                    Ident::new(&format!("set_{}", field_name_str), field_name.span());
+                let setter = quote! {
+                    pub fn #set_field_name(&self, value: #field_ty) {
+                        stdlib::WriteStorage::__set(&self.ctx, self.base_path.push(#field_name_str), value);
+                    }
+                };
+                if utils::is_map_type(field_ty) {
+                    Ok(quote! {})
+                } else if utils::is_primitive_type(field_ty) {
+                    let update_field_name = Ident::new(&format!("update_{}", field_name_str), field_name.span());
+                    let try_update_field_name = Ident::new(&format!("try_update_{}", field_name_str), field_name.span());
+                    Ok(quote! {
+                        #setter
 
-                #(#special_models)*
-            };
+                        pub fn #update_field_name(&self, f: impl Fn(#field_ty) -> #field_ty) {
+                            let path = self.base_path.push(#field_name_str);
+                            stdlib::WriteStorage::__set(&self.ctx, path.clone(), f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap()));
+                        }
+
+                        pub fn #try_update_field_name(&self, f: impl Fn(#field_ty) -> Result<#field_ty, crate::error::Error>) -> Result<(), crate::error::Error> {
+                            let path = self.base_path.push(#field_name_str);
+                            stdlib::WriteStorage::__set(&self.ctx, path.clone(), f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap())?);
+                            Ok(())
+                        }
+                    })
+                } else {
+                    Ok(setter)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        Vec::new()
+    };
+
+    let load_fields = fields
+        .iter()
+        .map(|field| {
+            let field_name = &field.ident;
+            let member = &field.member;
+            let field_ty = field.ty;
+
+            if utils::is_map_type(field_ty) {
+                let (_k_ty, _v_ty) = get_map_types(field_ty)?;
+                Ok(quote! {
+                    #member: self.#field_name().load()
+                })
+            } else if utils::is_option_type(field_ty) {
+                let inner_ty = get_option_inner_type(field_ty)?;
+                if utils::is_primitive_type(&inner_ty) {
+                    Ok(quote! {
+                        #member: self.#field_name()
+                    })
+                } else {
+                    let load = quote! { .map(|p| p.load()) };
+                    Ok(quote! {
+                        #member: self.#field_name()#load
+                    })
+                }
+            } else if utils::is_primitive_type(field_ty) {
+                Ok(quote! {
+                    #member: self.#field_name()
+                })
+            } else {
+                Ok(quote! {
+                    #member: self.#field_name().load()
+                })
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-            Ok(result)
+    let proc_props = if write {
+        quote! {
+            model: #read_only_model_name,
         }
-        _ => Err(Error::new(
-            type_name.span(),
-            "Model derive only supports structs with named fields",
-        )),
-    }
+    } else {
+        quote! {}
+    };
+
+    let proc_prelude = if write {
+        quote! {
+            let view_storage = ctx.view_storage();
+        }
+    } else {
+        quote! {}
+    };
+
+    let proc_assigns = if write {
+        quote! {
+            model: #read_only_model_name::new(alloc::rc::Rc::new(view_storage), base_path.clone()),
+        }
+    } else {
+        quote! {}
+    };
+
+    let proc_impls = if write {
+        quote! {
+            impl core::ops::Deref for #model_name {
+                type Target = #read_only_model_name;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.model
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let result = quote! {
+        pub struct #model_name {
+            pub base_path: stdlib::DotPathBuf,
+            ctx: alloc::rc::Rc<#context_param>,
+            #proc_props
+        }
+
+        impl #model_name {
+            pub fn new(ctx: alloc::rc::Rc<#context_param>, base_path: stdlib::DotPathBuf) -> Self {
+                #proc_prelude
+                Self {
+                    base_path: base_path.clone(),
+                    ctx,
+                    #proc_assigns
+                }
+            }
+
+            #(#getters)*
+
+            #(#setters)*
+
+            pub fn load(&self) -> #type_name {
+                #type_name {
+                    #(#load_fields,)*
+                }
+            }
+        }
+
+        #proc_impls
+
+        #(#special_models)*
+    };
+
+    Ok(result)
 }
 
 pub fn generate_enum(data_enum: &DataEnum, type_name: &Ident, write: bool) -> Result<TokenStream> {
@@ -333,18 +438,10 @@ pub fn generate_enum(data_enum: &DataEnum, type_name: &Ident, write: bool) -> Re
 
     let model_variants = model_variants?;
 
-    let variant_names = data_enum
-        .variants
-        .iter()
-        .map(|variant| {
-            let variant_name = variant.ident.to_string().to_lowercase();
-            quote! { #variant_name }
-        })
-        .collect::<Vec<_>>();
+    let variant_names = utils::variant_segments(data_enum)?;
 
-    let new_arms = data_enum.variants.iter().map(|variant| {
+    let new_arms = data_enum.variants.iter().zip(&variant_names).map(|(variant, variant_name)| {
         let variant_ident = &variant.ident;
-        let variant_name = variant_ident.to_string().to_lowercase();
 
         match &variant.fields {
             Fields::Unit => Ok(quote! {
@@ -446,6 +543,19 @@ fn get_option_inner_type(ty: &Type) -> Result<Type> {
     Err(Error::new(ty.span(), "Expected Option<T> type"))
 }
 
+fn get_list_elem_type(ty: &Type) -> Result<Type> {
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Vec"
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && args.args.len() == 1
+        && let GenericArgument::Type(elem_ty) = &args.args[0]
+    {
+        return Ok(elem_ty.clone());
+    }
+    Err(Error::new(ty.span(), "Expected Vec<T> type"))
+}
+
 fn get_map_types(ty: &Type) -> Result<(Type, Type)> {
     if let Type::Path(type_path) = ty
         && let Some(segment) = type_path.path.segments.last()
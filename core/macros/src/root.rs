@@ -2,16 +2,90 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{DataStruct, Error, Fields, Ident, Result};
 
+use crate::utils;
+
 pub fn generate_root_struct(data_struct: &DataStruct, type_name: &Ident) -> Result<TokenStream> {
     match &data_struct.fields {
-        Fields::Named(_) => {
+        Fields::Named(fields) => {
             let write_model_name =
                 Ident::new(&format!("{}WriteModel", type_name), type_name.span());
             let model_name = Ident::new(&format!("{}Model", type_name), type_name.span());
+            let config_name = Ident::new(&format!("{}Config", type_name), type_name.span());
+            let config_fields = fields
+                .named
+                .iter()
+                .filter(|field| {
+                    field
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path().is_ident("config"))
+                })
+                .map(|field| {
+                    if utils::is_primitive_type(&field.ty) {
+                        Ok(field.ident.as_ref().unwrap())
+                    } else {
+                        Err(Error::new_spanned(
+                            &field.ty,
+                            "#[config] fields must be scalar",
+                        ))
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            // `#[public]` fields are marked at init so other contracts may read
+            // them through `foreign::view-storage`; contracts initialized before
+            // a field was marked keep it private
+            let public_markers = fields
+                .named
+                .iter()
+                .filter(|field| {
+                    field
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path().is_ident("public"))
+                })
+                .map(|field| format!("__public.{}", field.ident.as_ref().unwrap()))
+                .collect::<Vec<_>>();
+            let init = if public_markers.is_empty() {
+                quote! {
+                    stdlib::WriteStorage::__set(&alloc::rc::Rc::new(ctx.storage()),stdlib::DotPathBuf::new(), self)
+                }
+            } else {
+                quote! {
+                    let storage = alloc::rc::Rc::new(ctx.storage());
+                    stdlib::WriteStorage::__set(&storage, stdlib::DotPathBuf::new(), self);
+                    #(stdlib::WriteStorage::__set_void(&storage, #public_markers);)*
+                }
+            };
+            // gathers the `#[config]` fields into the record named after the
+            // root (`<root>-config` in the contract's WIT) so a single view can
+            // return them all, and procs can read them in one go too
+            let config = if config_fields.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    impl #model_name {
+                        pub fn config(&self) -> crate::#config_name {
+                            crate::#config_name {
+                                #(#config_fields: self.#config_fields(),)*
+                            }
+                        }
+                    }
+
+                    impl #write_model_name {
+                        pub fn config(&self) -> crate::#config_name {
+                            crate::#config_name {
+                                #(#config_fields: self.#config_fields(),)*
+                            }
+                        }
+                    }
+                }
+            };
             Ok(quote! {
+                #config
+
                 impl #type_name {
                     pub fn init(self, ctx: &crate::ProcContext) {
-                        stdlib::WriteStorage::__set(&alloc::rc::Rc::new(ctx.storage()),stdlib::DotPathBuf::new(), self)
+                        #init
                     }
                 }
 
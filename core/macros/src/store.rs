@@ -1,43 +1,74 @@
 use crate::utils;
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{DataEnum, DataStruct, Error, Fields, Ident, Result};
+use quote::{ToTokens, quote, quote_spanned};
+use syn::{DataEnum, DataStruct, Error, Fields, Ident, Result, spanned::Spanned};
 
 pub fn generate_struct_body(data_struct: &DataStruct, type_name: &Ident) -> Result<TokenStream> {
-    match &data_struct.fields {
-        Fields::Named(fields) => {
-            let mut field_sets = Vec::new();
-            for field in fields.named.iter() {
-                let field_name = field.ident.as_ref().unwrap();
-                let field_name_str = field_name.to_string();
-                let field_ty = &field.ty;
+    let mut field_sets = Vec::new();
+    for field in utils::struct_fields(&data_struct.fields, type_name, "Store")? {
+        let member = &field.member;
+        let segment = &field.segment;
 
-                if utils::is_result_type(field_ty) {
-                    return Err(Error::new(
-                        type_name.span(),
-                        "Store derive does not support Result field types",
-                    ));
-                } else {
-                    field_sets.push(quote! {
-                        stdlib::WriteStorage::__set(ctx, base_path.push(#field_name_str), value.#field_name);
-                    })
-                }
-            }
-            Ok(quote! { #(#field_sets)* })
+        if utils::is_result_type(field.ty) {
+            return Err(Error::new(
+                type_name.span(),
+                "Store derive does not support Result field types",
+            ));
+        } else {
+            field_sets.push(quote! {
+                stdlib::WriteStorage::__set(ctx, base_path.push(#segment), value.#member);
+            })
         }
-        _ => Err(Error::new(
-            type_name.span(),
-            "Store derive only supports structs with named fields",
-        )),
     }
+    Ok(quote! { #(#field_sets)* })
+}
+
+/// Asserts that every field type implements `Store`, so a missing impl is
+/// reported against the field rather than deep inside the generated `__set`.
+pub fn generate_struct_field_checks(
+    data_struct: &DataStruct,
+    type_name: &Ident,
+) -> Result<TokenStream> {
+    let checks = utils::struct_fields(&data_struct.fields, type_name, "Store")?
+        .into_iter()
+        .map(|field| {
+            let ty = field.ty;
+            let message = format!(
+                "field `{}: {}` does not implement stdlib::Store",
+                field.segment,
+                type_string(ty)
+            );
+            let assertion = quote_spanned! {ty.span()=>
+                let _ = assert_store_field::<#ty>;
+            };
+            quote! {
+                const _: () = {
+                    #[diagnostic::on_unimplemented(message = #message)]
+                    trait StoreField {}
+                    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+                    fn assert_store_field<T: StoreField>() {}
+                    #assertion
+                };
+            }
+        });
+    Ok(quote! { #(#checks)* })
+}
+
+fn type_string(ty: &syn::Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" :: ", "::")
+        .replace("& ", "&")
 }
 
 pub fn generate_enum_body(data_enum: &DataEnum, type_name: &Ident) -> Result<TokenStream> {
-    let mut variant_names = vec![];
-    let arms = data_enum.variants.iter().map(|variant| {
+    let variant_names = utils::variant_segments(data_enum)?;
+    let arms = data_enum.variants.iter().zip(&variant_names).map(|(variant, variant_name)| {
         let variant_ident = &variant.ident;
-        let variant_name = variant_ident.to_string().to_lowercase();
-        variant_names.push(variant_name.clone());
 
         match &variant.fields {
             Fields::Unit => {
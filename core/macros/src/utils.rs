@@ -3,8 +3,9 @@ use heck::ToUpperCamelCase;
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
 use quote::quote;
-use syn::Ident;
 use syn::PathArguments;
+use syn::spanned::Spanned;
+use syn::{DataEnum, Fields, Ident, LitStr, Member};
 use wit_parser::{Handle, Resolve, Type as WitType, TypeDefKind};
 
 pub fn is_option_type(ty: &syn::Type) -> bool {
@@ -51,13 +52,69 @@ pub fn is_primitive_type(ty: &syn::Type) -> bool {
             .replace(" ", "");
         matches!(
             segment.as_deref(),
-            Some("u64" | "i64" | "String" | "bool" | "ContractAddress" | "Integer" | "Decimal")
+            Some(
+                "u64"
+                    | "u32"
+                    | "i64"
+                    | "String"
+                    | "bool"
+                    | "ContractAddress"
+                    | "Integer"
+                    | "Decimal"
+            )
         ) || ["Vec<u8>", "Vec::<u8>"].contains(&generic_segment.as_str())
     } else {
         false
     }
 }
 
+/// Primitive types held in a single storage row, unlike records such as
+/// `Integer`, so map entries of these types can be read in one scan.
+pub fn is_single_row_type(ty: &syn::Type) -> bool {
+    is_primitive_type(ty)
+        && !matches!(
+            ty,
+            syn::Type::Path(type_path)
+                if type_path.path.segments.last().is_some_and(|s| {
+                    matches!(
+                        s.ident.to_string().as_str(),
+                        "ContractAddress" | "Integer" | "Decimal"
+                    )
+                })
+        )
+}
+
+/// Map value types the host can read together with their keys: single-row
+/// primitives, and `Integer`/`Decimal`, which it assembles from their limbs.
+pub fn is_entry_type(ty: &syn::Type) -> bool {
+    is_single_row_type(ty)
+        || matches!(
+            ty,
+            syn::Type::Path(type_path)
+                if type_path.path.segments.last().is_some_and(|s| {
+                    matches!(s.ident.to_string().as_str(), "Integer" | "Decimal")
+                })
+        )
+}
+
+/// `Vec<T>` other than `Vec<u8>`, which is stored natively as bytes.
+pub fn is_list_type(ty: &syn::Type) -> bool {
+    if let syn::Type::Path(type_path) = ty {
+        type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| {
+                segment.ident == "Vec"
+                    && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+            })
+            .unwrap_or(false)
+            && !is_primitive_type(ty)
+    } else {
+        false
+    }
+}
+
 pub fn is_map_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
         type_path
@@ -106,6 +163,14 @@ pub fn wit_type_to_rust_type(
                     };
                     Ok(quote! { Result<#ok_ty, #err_ty> })
                 }
+                TypeDefKind::Tuple(tuple) => {
+                    let tys = tuple
+                        .types
+                        .iter()
+                        .map(|ty| wit_type_to_rust_type(resolve, ty, use_str))
+                        .collect::<anyhow::Result<Vec<_>>>()?;
+                    Ok(quote! { (#(#tys,)*) })
+                }
                 TypeDefKind::Handle(Handle::Borrow(resource_id)) => {
                     let resource_def = &resolve.types[*resource_id];
                     let resource_name = resource_def
@@ -131,3 +196,86 @@ pub fn wit_type_to_rust_type(
         _ => bail!("Unsupported WIT type: {:?}", ty),
     }
 }
+
+/// A struct field as seen by the storage derives. Tuple struct fields are
+/// accessed through `_<index>` and stored under their index.
+pub struct StructField<'a> {
+    pub ident: Ident,
+    pub segment: String,
+    pub member: Member,
+    pub ty: &'a syn::Type,
+}
+
+pub fn struct_fields<'a>(
+    fields: &'a Fields,
+    type_name: &Ident,
+    derive: &str,
+) -> syn::Result<Vec<StructField<'a>>> {
+    match fields {
+        Fields::Named(fields) => Ok(fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.clone().unwrap();
+                StructField {
+                    segment: ident.to_string(),
+                    member: Member::Named(ident.clone()),
+                    ident,
+                    ty: &field.ty,
+                }
+            })
+            .collect()),
+        Fields::Unnamed(fields) => Ok(fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| StructField {
+                ident: Ident::new(&format!("_{}", i), field.ty.span()),
+                segment: i.to_string(),
+                member: Member::Unnamed(i.into()),
+                ty: &field.ty,
+            })
+            .collect()),
+        Fields::Unit => Err(syn::Error::new(
+            type_name.span(),
+            format!("{} derive does not support unit structs", derive),
+        )),
+    }
+}
+
+/// The storage path segment of each enum variant: its lowercased name, or the
+/// value of `#[store(discriminant = "...")]`. Variants are stored by segment
+/// rather than position, so reordering them leaves stored values readable and
+/// the override lets a renamed variant keep its old segment.
+pub fn variant_segments(data_enum: &DataEnum) -> syn::Result<Vec<String>> {
+    let mut segments: Vec<String> = Vec::new();
+    for variant in &data_enum.variants {
+        let mut segment = None;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("store")) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("discriminant") {
+                    return Err(meta.error("expected `discriminant = \"...\"`"));
+                }
+                let value: LitStr = meta.value()?.parse()?;
+                let discriminant = value.value();
+                if discriminant.is_empty() || discriminant.contains('.') {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "discriminant must be non-empty and must not contain '.'",
+                    ));
+                }
+                segment = Some(discriminant);
+                Ok(())
+            })?;
+        }
+        let segment = segment.unwrap_or_else(|| variant.ident.to_string().to_lowercase());
+        if segments.contains(&segment) {
+            return Err(syn::Error::new(
+                variant.ident.span(),
+                format!("duplicate storage discriminant `{}`", segment),
+            ));
+        }
+        segments.push(segment);
+    }
+    Ok(segments)
+}
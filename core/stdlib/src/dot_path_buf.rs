@@ -16,8 +16,15 @@ impl DotPathBuf {
         }
     }
 
+    /// Appends a segment. Map keys become segments as they are, so a key
+    /// containing `.` would alias a nested path and panics instead.
     pub fn push(&self, segment: impl Into<String>) -> Self {
         let segment = segment.into();
+        assert!(
+            !segment.contains('.'),
+            "Storage key {:?} must not contain '.'",
+            segment
+        );
         let mut new_segments = self.segments.clone();
         let mut new_joined = self.joined.clone();
         if !segment.is_empty() {
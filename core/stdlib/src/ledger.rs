@@ -0,0 +1,142 @@
+use core::{fmt, marker::PhantomData};
+
+use alloc::string::{String, ToString};
+
+use crate::CheckedArithmetics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerError {
+    NonPositiveAmount,
+    InsufficientFunds,
+}
+
+impl LedgerError {
+    /// Stable code for the contract error this becomes, shared by every
+    /// contract built on [`Ledger`].
+    pub fn code(&self) -> u32 {
+        match self {
+            LedgerError::NonPositiveAmount => 1,
+            LedgerError::InsufficientFunds => 2,
+        }
+    }
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::NonPositiveAmount => write!(f, "Amount must be positive"),
+            LedgerError::InsufficientFunds => write!(f, "insufficient funds"),
+        }
+    }
+}
+
+/// Balances and total supply backing a [`Ledger`], usually a contract's
+/// storage write model holding a `Map<String, A>` and a supply field.
+pub trait LedgerStore<A> {
+    fn balance(&self, account: &str) -> Option<A>;
+    fn set_balance(&self, account: String, amount: A);
+    fn supply(&self) -> A;
+    fn set_supply(&self, amount: A);
+}
+
+/// Mint/burn/transfer over a [`LedgerStore`] with checked arithmetic. Every
+/// amount must be positive and no balance may go below zero.
+///
+/// With a burner account, burned amounts are credited to it rather than
+/// dropped, while still being removed from the total supply.
+pub struct Ledger<A, S: LedgerStore<A>> {
+    store: S,
+    burner: Option<String>,
+    _marker: PhantomData<A>,
+}
+
+impl<A, S> Ledger<A, S>
+where
+    A: Clone + Default + PartialOrd,
+    S: LedgerStore<A>,
+{
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            burner: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn with_burner(store: S, burner: impl ToString) -> Self {
+        Self {
+            store,
+            burner: Some(burner.to_string()),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn balance(&self, account: &str) -> A {
+        self.store.balance(account).unwrap_or_default()
+    }
+
+    pub fn total_supply(&self) -> A {
+        self.store.supply()
+    }
+
+    pub fn mint<E>(&self, to: &str, amount: A) -> Result<(), E>
+    where
+        A: CheckedArithmetics<E, Output = A>,
+        E: From<LedgerError>,
+    {
+        Self::ensure_positive(&amount)?;
+        let supply = self.store.supply().add(amount.clone())?;
+        let balance = self.balance(to).add(amount)?;
+        self.store.set_supply(supply);
+        self.store.set_balance(to.to_string(), balance);
+        Ok(())
+    }
+
+    pub fn burn<E>(&self, from: &str, amount: A) -> Result<(), E>
+    where
+        A: CheckedArithmetics<E, Output = A>,
+        E: From<LedgerError>,
+    {
+        match self.burner.clone() {
+            Some(burner) => self.transfer(from, &burner, amount.clone())?,
+            None => self.debit(from, amount.clone())?,
+        }
+        let supply = self.store.supply().sub(amount)?;
+        self.store.set_supply(supply);
+        Ok(())
+    }
+
+    pub fn transfer<E>(&self, from: &str, to: &str, amount: A) -> Result<(), E>
+    where
+        A: CheckedArithmetics<E, Output = A>,
+        E: From<LedgerError>,
+    {
+        self.debit(from, amount.clone())?;
+        // read after the debit so a self-transfer nets to zero
+        let balance = self.balance(to).add(amount)?;
+        self.store.set_balance(to.to_string(), balance);
+        Ok(())
+    }
+
+    fn debit<E>(&self, from: &str, amount: A) -> Result<(), E>
+    where
+        A: CheckedArithmetics<E, Output = A>,
+        E: From<LedgerError>,
+    {
+        Self::ensure_positive(&amount)?;
+        let balance = self.balance(from);
+        if balance < amount {
+            return Err(LedgerError::InsufficientFunds.into());
+        }
+        self.store
+            .set_balance(from.to_string(), balance.sub(amount)?);
+        Ok(())
+    }
+
+    fn ensure_positive<E: From<LedgerError>>(amount: &A) -> Result<(), E> {
+        if *amount <= A::default() {
+            return Err(LedgerError::NonPositiveAmount.into());
+        }
+        Ok(())
+    }
+}
@@ -2,13 +2,23 @@
 extern crate alloc;
 
 mod dot_path_buf;
+mod ledger;
+mod nonce;
+mod panic_hook;
+mod require;
 mod storage_interface;
 mod wave_interfaces;
 
 pub use dot_path_buf::*;
+pub use ledger::{Ledger, LedgerError, LedgerStore};
 pub use macros::{
     Model, Root, Storage, StorageRoot, Store, Wavey, contract, contract_address, impls, import,
-    interface,
+    interface, test_only,
+};
+pub use nonce::{NonceError, NonceRegistry, NonceStore};
+pub use panic_hook::set_panic_hook;
+pub use require::{
+    RequireError, require, require_eq, require_ge, require_gt, require_le, require_lt, require_ne,
 };
 pub use storage_interface::*;
 pub use wasm_wave;
@@ -0,0 +1,74 @@
+use core::fmt;
+
+use alloc::string::{String, ToString};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceError {
+    /// The nonce was already used by this signer.
+    Replayed { nonce: u64 },
+    /// The nonce is below one this signer already used.
+    OutOfOrder { nonce: u64, last: u64 },
+}
+
+impl NonceError {
+    /// Stable code for the contract error this becomes, following on from
+    /// the [`LedgerError`](crate::LedgerError) codes.
+    pub fn code(&self) -> u32 {
+        match self {
+            NonceError::Replayed { .. } => 3,
+            NonceError::OutOfOrder { .. } => 4,
+        }
+    }
+}
+
+impl fmt::Display for NonceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonceError::Replayed { nonce } => write!(f, "nonce {} already used", nonce),
+            NonceError::OutOfOrder { nonce, last } => {
+                write!(f, "nonce {} is below the last used nonce {}", nonce, last)
+            }
+        }
+    }
+}
+
+/// The last nonce each signer used, usually a contract's storage write model
+/// holding a `Map<String, u64>`.
+pub trait NonceStore {
+    fn last_nonce(&self, signer: &str) -> Option<u64>;
+    fn set_last_nonce(&self, signer: String, nonce: u64);
+}
+
+/// Replay protection for approvals signed off-chain: each signer's nonces
+/// must strictly increase, so an approval is accepted at most once and never
+/// after a later one. Gaps are allowed.
+pub struct NonceRegistry<S: NonceStore> {
+    store: S,
+}
+
+impl<S: NonceStore> NonceRegistry<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// The last nonce `signer` used, if any.
+    pub fn last(&self, signer: &str) -> Option<u64> {
+        self.store.last_nonce(signer)
+    }
+
+    /// Accepts `nonce` if it is above every nonce `signer` used before and
+    /// records it; a signer's first nonce can be any value.
+    pub fn check_and_increment<E>(&self, signer: &str, nonce: u64) -> Result<(), E>
+    where
+        E: From<NonceError>,
+    {
+        match self.last(signer) {
+            Some(last) if nonce == last => Err(NonceError::Replayed { nonce }.into()),
+            Some(last) if nonce < last => Err(NonceError::OutOfOrder { nonce, last }.into()),
+            _ => {
+                self.store.set_last_nonce(signer.to_string(), nonce);
+                Ok(())
+            }
+        }
+    }
+}
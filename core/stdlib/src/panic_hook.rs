@@ -0,0 +1,11 @@
+extern crate std;
+
+use alloc::{boxed::Box, string::ToString};
+
+/// Forwards the guest's panic message to the host before the module aborts,
+/// so the runtime can report it instead of an opaque `unreachable` trap.
+/// Only the first call installs the hook.
+pub fn set_panic_hook(trap: fn(&str)) {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| std::panic::set_hook(Box::new(move |info| trap(&info.to_string()))));
+}
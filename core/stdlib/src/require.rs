@@ -0,0 +1,120 @@
+use core::fmt;
+
+use alloc::{format, string::String};
+
+/// A failed [`require`] check, carrying the message the contract reverts
+/// with. `?` converts it into the contract's `Error::Message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequireError(pub String);
+
+impl fmt::Display for RequireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Fails with `msg` unless `cond` holds.
+///
+/// ```
+/// use stdlib::{RequireError, require, require_gt};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Error(String);
+///
+/// impl From<RequireError> for Error {
+///     fn from(err: RequireError) -> Self {
+///         Error(err.0)
+///     }
+/// }
+///
+/// fn withdraw(balance: u64, n: u64) -> Result<u64, Error> {
+///     require_gt(n, 0, "amount must be positive")?;
+///     require(balance >= n, "insufficient funds")?;
+///     Ok(balance - n)
+/// }
+///
+/// assert_eq!(withdraw(10, 4), Ok(6));
+/// assert_eq!(withdraw(3, 4), Err(Error("insufficient funds".to_string())));
+/// assert_eq!(
+///     withdraw(3, 0),
+///     Err(Error("amount must be positive: expected 0 > 0".to_string()))
+/// );
+/// ```
+pub fn require(cond: bool, msg: &str) -> Result<(), RequireError> {
+    if cond {
+        Ok(())
+    } else {
+        Err(RequireError(msg.into()))
+    }
+}
+
+fn require_cmp<T: fmt::Display>(
+    cond: bool,
+    left: T,
+    op: &str,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    if cond {
+        Ok(())
+    } else {
+        Err(RequireError(format!(
+            "{}: expected {} {} {}",
+            msg, left, op, right
+        )))
+    }
+}
+
+/// Fails unless `left == right`, naming both values in the message.
+pub fn require_eq<T: PartialEq + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left == right, left, "==", right, msg)
+}
+
+/// Fails unless `left != right`, naming both values in the message.
+pub fn require_ne<T: PartialEq + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left != right, left, "!=", right, msg)
+}
+
+/// Fails unless `left > right`, naming both values in the message.
+pub fn require_gt<T: PartialOrd + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left > right, left, ">", right, msg)
+}
+
+/// Fails unless `left >= right`, naming both values in the message.
+pub fn require_ge<T: PartialOrd + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left >= right, left, ">=", right, msg)
+}
+
+/// Fails unless `left < right`, naming both values in the message.
+pub fn require_lt<T: PartialOrd + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left < right, left, "<", right, msg)
+}
+
+/// Fails unless `left <= right`, naming both values in the message.
+pub fn require_le<T: PartialOrd + fmt::Display>(
+    left: T,
+    right: T,
+    msg: &str,
+) -> Result<(), RequireError> {
+    require_cmp(left <= right, left, "<=", right, msg)
+}
@@ -25,6 +25,32 @@ pub trait ReadStorage {
     where
         <T as FromStr>::Err: Debug;
 
+    fn __get_keys_limited<T: FromStr>(
+        self: &alloc::rc::Rc<Self>,
+        path: &str,
+        max_keys: u64,
+    ) -> LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug;
+
+    /// Up to `max_keys` keys under `path`, starting with the first one
+    /// ordered after the key `after`.
+    fn __get_keys_after<T: FromStr>(
+        self: &alloc::rc::Rc<Self>,
+        path: &str,
+        after: Option<&str>,
+        max_keys: u64,
+    ) -> LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug;
+
+    fn __get_entries<'a, K: FromStr + 'a, V: FromEntry + 'a>(
+        self: &alloc::rc::Rc<Self>,
+        path: &'a str,
+    ) -> impl Iterator<Item = (K, V)> + 'a
+    where
+        <K as FromStr>::Err: Debug;
+
     fn __exists(self: &alloc::rc::Rc<Self>, path: &str) -> bool;
 
     fn __extend_path_with_match(
@@ -46,6 +72,12 @@ impl<T: ReadStorage + ?Sized> Retrieve<T> for u64 {
     }
 }
 
+impl<T: ReadStorage + ?Sized> Retrieve<T> for u32 {
+    fn __get(ctx: &alloc::rc::Rc<T>, path: DotPathBuf) -> Option<Self> {
+        ctx.__get_u64(&path).and_then(|v| u32::try_from(v).ok())
+    }
+}
+
 impl<T: ReadStorage + ?Sized> Retrieve<T> for i64 {
     fn __get(ctx: &alloc::rc::Rc<T>, path: DotPathBuf) -> Option<Self> {
         ctx.__get_s64(&path)
@@ -90,8 +122,11 @@ pub trait WriteStorage {
         base_path: &str,
         variants: &[&str],
     ) -> u64;
+
+    fn __delete_prefix(self: &alloc::rc::Rc<Self>, base_path: &str) -> u64;
 }
 
+#[diagnostic::on_unimplemented(message = "`{Self}` does not implement stdlib::Store")]
 pub trait Store<T: WriteStorage + ?Sized> {
     fn __set(ctx: &alloc::rc::Rc<T>, base_path: DotPathBuf, value: Self);
 }
@@ -102,6 +137,12 @@ impl<T: WriteStorage + ?Sized> Store<T> for u64 {
     }
 }
 
+impl<T: WriteStorage + ?Sized> Store<T> for u32 {
+    fn __set(ctx: &alloc::rc::Rc<T>, path: DotPathBuf, value: u32) {
+        ctx.__set_u64(&path, value as u64);
+    }
+}
+
 impl<T: WriteStorage + ?Sized> Store<T> for i64 {
     fn __set(ctx: &alloc::rc::Rc<T>, path: DotPathBuf, value: i64) {
         ctx.__set_s64(&path, value);
@@ -148,8 +189,26 @@ impl<S: WriteStorage + ?Sized, T: Store<S>> Store<S> for Option<T> {
     }
 }
 
+/// Stores a list element by element under its index, plus a `len` entry.
+/// Used for `Vec<T>` of derived storage types, which have no native list
+/// encoding the way `Vec<u8>` does. Whatever was stored before is removed
+/// first, so a shorter list leaves no elements behind past its length.
+pub fn __set_list<S: WriteStorage + ?Sized, T: Store<S>>(
+    ctx: &alloc::rc::Rc<S>,
+    base_path: DotPathBuf,
+    value: Vec<T>,
+) {
+    ctx.__delete_prefix(&base_path);
+    ctx.__set(base_path.push("len"), value.len() as u64);
+    for (i, item) in value.into_iter().enumerate() {
+        ctx.__set(base_path.push(i.to_string()), item);
+    }
+}
+
 pub trait HasNext {
     fn next(&self) -> Option<String>;
+
+    fn truncated(&self) -> bool;
 }
 
 pub fn make_keys_iterator<K, T>(keys: K) -> impl Iterator<Item = T>
@@ -186,6 +245,166 @@ where
     }
 }
 
+/// Keys of a map, stopping after the limit given when they were requested.
+/// Once exhausted, [`LimitedKeys::truncated`] reports whether keys were left
+/// out.
+pub struct LimitedKeys<T> {
+    keys: alloc::boxed::Box<dyn HasNext>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> LimitedKeys<T> {
+    pub fn new(keys: impl HasNext + 'static) -> Self {
+        Self {
+            keys: alloc::boxed::Box::new(keys),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.keys.truncated()
+    }
+}
+
+impl<T> Iterator for LimitedKeys<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.keys.next().map(|s| T::from_str(&s).unwrap())
+    }
+}
+
+/// Which decoding the host applies to the values of a map entries scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Str,
+    U64,
+    S64,
+    Bool,
+    ListU8,
+    Integer,
+    Decimal,
+}
+
+/// The limbs and sign of an `Integer` or `Decimal` read from a map, which the
+/// contract rebuilds into its own binding of the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberLimbs {
+    pub r0: u64,
+    pub r1: u64,
+    pub r2: u64,
+    pub r3: u64,
+    pub negative: bool,
+}
+
+/// A map value decoded by the host as the [`EntryKind`] it was asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryValue {
+    Str(String),
+    U64(u64),
+    S64(i64),
+    Bool(bool),
+    ListU8(Vec<u8>),
+    Integer(NumberLimbs),
+    Decimal(NumberLimbs),
+}
+
+/// Value types the host can read together with their keys: primitives stored
+/// in a single row, and the numbers it assembles from their limbs.
+pub trait FromEntry: Sized {
+    const KIND: EntryKind;
+
+    fn from_entry(value: EntryValue) -> Option<Self>;
+}
+
+impl FromEntry for u64 {
+    const KIND: EntryKind = EntryKind::U64;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        match value {
+            EntryValue::U64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl FromEntry for u32 {
+    const KIND: EntryKind = EntryKind::U64;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        u64::from_entry(value).and_then(|v| u32::try_from(v).ok())
+    }
+}
+
+impl FromEntry for i64 {
+    const KIND: EntryKind = EntryKind::S64;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        match value {
+            EntryValue::S64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl FromEntry for String {
+    const KIND: EntryKind = EntryKind::Str;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        match value {
+            EntryValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl FromEntry for bool {
+    const KIND: EntryKind = EntryKind::Bool;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        match value {
+            EntryValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl FromEntry for Vec<u8> {
+    const KIND: EntryKind = EntryKind::ListU8;
+
+    fn from_entry(value: EntryValue) -> Option<Self> {
+        match value {
+            EntryValue::ListU8(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+pub trait HasNextEntry {
+    fn next(&self) -> Option<(String, EntryValue)>;
+}
+
+pub fn make_entries_iterator<E, K, V>(entries: E) -> impl Iterator<Item = (K, V)>
+where
+    E: HasNextEntry,
+    K: FromStr,
+    <K as FromStr>::Err: Debug,
+    V: FromEntry,
+{
+    core::iter::from_fn(move || {
+        entries.next().map(|(k, v)| {
+            (
+                K::from_str(&k).unwrap(),
+                V::from_entry(v).expect("Host returned an entry of another kind"),
+            )
+        })
+    })
+}
+
 pub struct StorageMap<K: ToString + FromStr + Clone, V: Store<S> + Clone, S: WriteStorage + ?Sized>
 {
     pub entries: Vec<(K, V)>,
@@ -64,6 +64,21 @@ impl<V: WaveType> WaveType for Result<V, ()> {
     }
 }
 
+macro_rules! impl_wave_type_for_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: WaveType),+> WaveType for ($($t,)+) {
+            fn wave_type() -> wasm_wave::value::Type {
+                wasm_wave::value::Type::tuple(alloc::vec![$($t::wave_type()),+])
+                    .expect("Failed to build tuple type")
+            }
+        }
+    };
+}
+
+impl_wave_type_for_tuple!(A, B);
+impl_wave_type_for_tuple!(A, B, C);
+impl_wave_type_for_tuple!(A, B, C, D);
+
 pub fn wave_type<T: WaveType>() -> wasm_wave::value::Type {
     T::wave_type()
 }
@@ -144,6 +159,23 @@ impl<V: FromWaveValue> FromWaveValue for Result<V, ()> {
     }
 }
 
+macro_rules! impl_from_wave_value_for_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: FromWaveValue),+> FromWaveValue for ($($t,)+) {
+            fn from_wave_value(value: wasm_wave::value::Value) -> Self {
+                let mut values = wasm_wave::wasm::WasmValue::unwrap_tuple(&value);
+                ($($t::from_wave_value(
+                    values.next().expect("Tuple has too few values").into_owned(),
+                ),)+)
+            }
+        }
+    };
+}
+
+impl_from_wave_value_for_tuple!(A, B);
+impl_from_wave_value_for_tuple!(A, B, C);
+impl_from_wave_value_for_tuple!(A, B, C, D);
+
 pub fn from_wave_value<T: FromWaveValue>(value: wasm_wave::value::Value) -> T {
     T::from_wave_value(value)
 }
@@ -71,3 +71,20 @@ fn test_new() {
     assert_eq!(path.segments().collect::<Vec<_>>(), vec![] as Vec<&str>);
     assert_eq!(path.to_string(), "");
 }
+
+#[test]
+fn test_push_keeps_keys_as_is() {
+    let base = DotPathBuf::new().push("pools");
+    for key in ["a%2Eb", "%", "a::b", "plain"] {
+        let path = base.push(key).push("balance_a");
+        assert_eq!(path.to_string(), format!("pools.{}.balance_a", key));
+        assert_eq!(path.num_segments(), 3);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Storage key \"a.b\" must not contain '.'")]
+fn test_push_rejects_dotted_keys() {
+    // "a.b" would read back as key "b" nested under key "a"
+    DotPathBuf::new().push("pools").push("a.b");
+}
@@ -128,6 +128,10 @@ impl FibStorageCacheModel {
         stdlib::ReadStorage::__exists(&self.ctx, &base_path)
             .then(|| FibValueModel::new(self.ctx.clone(), base_path))
     }
+    pub fn contains_key(&self, key: impl ToString) -> bool {
+        let base_path = self.base_path.push(key.to_string());
+        stdlib::ReadStorage::__exists(&self.ctx, &base_path)
+    }
     pub fn load(&self) -> Map<u64, FibValue> {
         Map::new(&[])
     }
@@ -139,6 +143,30 @@ impl FibStorageCacheModel {
     {
         stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
     }
+    pub fn keys_limited<T: ToString + FromStr + Clone>(
+        &self,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_limited(&self.ctx, &self.base_path, max_keys)
+    }
+    pub fn keys_range<T: ToString + FromStr + Clone>(
+        &self,
+        after: Option<T>,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_after(
+            &self.ctx,
+            &self.base_path,
+            after.map(|key| key.to_string()).as_deref(),
+            max_keys,
+        )
+    }
 }
 pub struct FibStorageWriteModel {
     pub base_path: stdlib::DotPathBuf,
@@ -198,6 +226,10 @@ impl FibStorageCacheWriteModel {
         stdlib::ReadStorage::__exists(&self.ctx, &base_path)
             .then(|| FibValueWriteModel::new(self.ctx.clone(), base_path))
     }
+    pub fn contains_key(&self, key: impl ToString) -> bool {
+        let base_path = self.base_path.push(key.to_string());
+        stdlib::ReadStorage::__exists(&self.ctx, &base_path)
+    }
     pub fn set(&self, key: u64, value: FibValue) {
         stdlib::WriteStorage::__set(
             &self.ctx,
@@ -205,6 +237,9 @@ impl FibStorageCacheWriteModel {
             value,
         )
     }
+    pub fn clear(&self) -> u64 {
+        stdlib::WriteStorage::__delete_prefix(&self.ctx, &self.base_path)
+    }
     pub fn load(&self) -> Map<u64, FibValue> {
         Map::new(&[])
     }
@@ -216,4 +251,28 @@ impl FibStorageCacheWriteModel {
     {
         stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
     }
+    pub fn keys_limited<T: ToString + FromStr + Clone>(
+        &self,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_limited(&self.ctx, &self.base_path, max_keys)
+    }
+    pub fn keys_range<T: ToString + FromStr + Clone>(
+        &self,
+        after: Option<T>,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_after(
+            &self.ctx,
+            &self.base_path,
+            after.map(|key| key.to_string()).as_deref(),
+            max_keys,
+        )
+    }
 }
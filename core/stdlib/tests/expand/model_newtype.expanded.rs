@@ -0,0 +1,79 @@
+use stdlib::Model;
+pub struct Balance(pub u64);
+pub struct BalanceModel {
+    pub base_path: stdlib::DotPathBuf,
+    ctx: alloc::rc::Rc<crate::context::ViewStorage>,
+}
+impl BalanceModel {
+    pub fn new(
+        ctx: alloc::rc::Rc<crate::context::ViewStorage>,
+        base_path: stdlib::DotPathBuf,
+    ) -> Self {
+        Self {
+            base_path: base_path.clone(),
+            ctx,
+        }
+    }
+    pub fn _0(&self) -> u64 {
+        stdlib::ReadStorage::__get(&self.ctx, self.base_path.push("0")).unwrap()
+    }
+    pub fn load(&self) -> Balance {
+        Balance { 0: self._0() }
+    }
+}
+pub struct BalanceWriteModel {
+    pub base_path: stdlib::DotPathBuf,
+    ctx: alloc::rc::Rc<crate::context::ProcStorage>,
+    model: BalanceModel,
+}
+impl BalanceWriteModel {
+    pub fn new(
+        ctx: alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+    ) -> Self {
+        let view_storage = ctx.view_storage();
+        Self {
+            base_path: base_path.clone(),
+            ctx,
+            model: BalanceModel::new(
+                alloc::rc::Rc::new(view_storage),
+                base_path.clone(),
+            ),
+        }
+    }
+    pub fn _0(&self) -> u64 {
+        stdlib::ReadStorage::__get(&self.ctx, self.base_path.push("0")).unwrap()
+    }
+    pub fn set_0(&self, value: u64) {
+        stdlib::WriteStorage::__set(&self.ctx, self.base_path.push("0"), value);
+    }
+    pub fn update_0(&self, f: impl Fn(u64) -> u64) {
+        let path = self.base_path.push("0");
+        stdlib::WriteStorage::__set(
+            &self.ctx,
+            path.clone(),
+            f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap()),
+        );
+    }
+    pub fn try_update_0(
+        &self,
+        f: impl Fn(u64) -> Result<u64, crate::error::Error>,
+    ) -> Result<(), crate::error::Error> {
+        let path = self.base_path.push("0");
+        stdlib::WriteStorage::__set(
+            &self.ctx,
+            path.clone(),
+            f(stdlib::ReadStorage::__get(&self.ctx, path).unwrap())?,
+        );
+        Ok(())
+    }
+    pub fn load(&self) -> Balance {
+        Balance { 0: self._0() }
+    }
+}
+impl core::ops::Deref for BalanceWriteModel {
+    type Target = BalanceModel;
+    fn deref(&self) -> &Self::Target {
+        &self.model
+    }
+}
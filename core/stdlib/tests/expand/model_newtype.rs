@@ -0,0 +1,4 @@
+use stdlib::Model;
+
+#[derive(Model)]
+pub struct Balance(pub u64);
@@ -47,6 +47,10 @@ impl TokenStorageLedgerModel {
         let base_path = self.base_path.push(key.to_string());
         stdlib::ReadStorage::__get(&self.ctx, base_path)
     }
+    pub fn contains_key(&self, key: impl ToString) -> bool {
+        let base_path = self.base_path.push(key.to_string());
+        stdlib::ReadStorage::__exists(&self.ctx, &base_path)
+    }
     pub fn load(&self) -> Map<String, u64> {
         Map::new(&[])
     }
@@ -58,6 +62,30 @@ impl TokenStorageLedgerModel {
     {
         stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
     }
+    pub fn keys_limited<T: ToString + FromStr + Clone>(
+        &self,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_limited(&self.ctx, &self.base_path, max_keys)
+    }
+    pub fn keys_range<T: ToString + FromStr + Clone>(
+        &self,
+        after: Option<T>,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_after(
+            &self.ctx,
+            &self.base_path,
+            after.map(|key| key.to_string()).as_deref(),
+            max_keys,
+        )
+    }
 }
 pub struct TokenStorageWriteModel {
     pub base_path: stdlib::DotPathBuf,
@@ -116,6 +144,10 @@ impl TokenStorageLedgerWriteModel {
         let base_path = self.base_path.push(key.to_string());
         stdlib::ReadStorage::__get(&self.ctx, base_path)
     }
+    pub fn contains_key(&self, key: impl ToString) -> bool {
+        let base_path = self.base_path.push(key.to_string());
+        stdlib::ReadStorage::__exists(&self.ctx, &base_path)
+    }
     pub fn set(&self, key: String, value: u64) {
         stdlib::WriteStorage::__set(
             &self.ctx,
@@ -123,6 +155,9 @@ impl TokenStorageLedgerWriteModel {
             value,
         )
     }
+    pub fn clear(&self) -> u64 {
+        stdlib::WriteStorage::__delete_prefix(&self.ctx, &self.base_path)
+    }
     pub fn load(&self) -> Map<String, u64> {
         Map::new(&[])
     }
@@ -134,4 +169,28 @@ impl TokenStorageLedgerWriteModel {
     {
         stdlib::ReadStorage::__get_keys(&self.ctx, &self.base_path)
     }
+    pub fn keys_limited<T: ToString + FromStr + Clone>(
+        &self,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_limited(&self.ctx, &self.base_path, max_keys)
+    }
+    pub fn keys_range<T: ToString + FromStr + Clone>(
+        &self,
+        after: Option<T>,
+        max_keys: u64,
+    ) -> stdlib::LimitedKeys<T>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        stdlib::ReadStorage::__get_keys_after(
+            &self.ctx,
+            &self.base_path,
+            after.map(|key| key.to_string()).as_deref(),
+            max_keys,
+        )
+    }
 }
@@ -16,6 +16,23 @@ impl stdlib::Store<crate::context::ProcStorage> for ProxyStorage {
         );
     }
 }
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<ProxyStorage> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<ProxyStorage>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `contract_address: ContractAddress` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<ContractAddress>;
+};
 pub struct ProxyStorageModel {
     pub base_path: stdlib::DotPathBuf,
     ctx: alloc::rc::Rc<crate::context::ViewStorage>,
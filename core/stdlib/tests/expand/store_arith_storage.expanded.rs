@@ -11,3 +11,20 @@ impl stdlib::Store<crate::context::ProcStorage> for ArithStorage {
         stdlib::WriteStorage::__set(ctx, base_path.push("last_op"), value.last_op);
     }
 }
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<ArithStorage> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<ArithStorage>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `last_op: Option<Op>` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<Option<Op>>;
+};
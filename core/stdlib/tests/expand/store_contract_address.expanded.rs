@@ -15,3 +15,34 @@ impl stdlib::Store<crate::context::ProcStorage> for ContractAddress {
         stdlib::WriteStorage::__set(ctx, base_path.push("tx_index"), value.tx_index);
     }
 }
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<ContractAddress> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<ContractAddress>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `name: String` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<String>;
+};
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `height: i64` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<i64>;
+};
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `tx_index: i64` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<i64>;
+};
@@ -25,3 +25,13 @@ impl stdlib::Store<crate::context::ProcStorage> for Error {
         }
     }
 }
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<Error> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<Error>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
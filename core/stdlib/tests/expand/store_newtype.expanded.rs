@@ -0,0 +1,28 @@
+pub struct Balance(pub u64);
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for Balance {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: Balance,
+    ) {
+        stdlib::WriteStorage::__set(ctx, base_path.push("0"), value.0);
+    }
+}
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<Balance> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<Balance>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `0: u64` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<u64>;
+};
@@ -0,0 +1,2 @@
+#[derive(stdlib::Store)]
+pub struct Balance(pub u64);
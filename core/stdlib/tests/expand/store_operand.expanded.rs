@@ -11,3 +11,20 @@ impl stdlib::Store<crate::context::ProcStorage> for Operand {
         stdlib::WriteStorage::__set(ctx, base_path.push("y"), value.y);
     }
 }
+#[automatically_derived]
+impl stdlib::Store<crate::context::ProcStorage> for alloc::vec::Vec<Operand> {
+    fn __set(
+        ctx: &alloc::rc::Rc<crate::context::ProcStorage>,
+        base_path: stdlib::DotPathBuf,
+        value: alloc::vec::Vec<Operand>,
+    ) {
+        stdlib::__set_list(ctx, base_path, value)
+    }
+}
+const _: () = {
+    #[diagnostic::on_unimplemented(message = "field `y: u64` does not implement stdlib::Store")]
+    trait StoreField {}
+    impl<T: stdlib::Store<crate::context::ProcStorage>> StoreField for T {}
+    fn assert_store_field<T: StoreField>() {}
+    let _ = assert_store_field::<u64>;
+};
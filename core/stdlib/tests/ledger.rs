@@ -0,0 +1,158 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use stdlib::{CheckedArithmetics, Ledger, LedgerError, LedgerStore};
+
+#[derive(Debug, PartialEq, Eq)]
+enum TestError {
+    Ledger(LedgerError),
+    Overflow,
+}
+
+impl From<LedgerError> for TestError {
+    fn from(err: LedgerError) -> Self {
+        TestError::Ledger(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+struct Amount(u8);
+
+impl CheckedArithmetics<TestError> for Amount {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Result<Self, TestError> {
+        self.0
+            .checked_add(other.0)
+            .map(Amount)
+            .ok_or(TestError::Overflow)
+    }
+    fn sub(self, other: Self) -> Result<Self, TestError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Amount)
+            .ok_or(TestError::Overflow)
+    }
+    fn mul(self, other: Self) -> Result<Self, TestError> {
+        self.0
+            .checked_mul(other.0)
+            .map(Amount)
+            .ok_or(TestError::Overflow)
+    }
+    fn div(self, other: Self) -> Result<Self, TestError> {
+        self.0
+            .checked_div(other.0)
+            .map(Amount)
+            .ok_or(TestError::Overflow)
+    }
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    balances: RefCell<HashMap<String, Amount>>,
+    supply: RefCell<Amount>,
+}
+
+impl LedgerStore<Amount> for &MemoryStore {
+    fn balance(&self, account: &str) -> Option<Amount> {
+        self.balances.borrow().get(account).copied()
+    }
+
+    fn set_balance(&self, account: String, amount: Amount) {
+        self.balances.borrow_mut().insert(account, amount);
+    }
+
+    fn supply(&self) -> Amount {
+        *self.supply.borrow()
+    }
+
+    fn set_supply(&self, amount: Amount) {
+        *self.supply.borrow_mut() = amount;
+    }
+}
+
+#[test]
+fn test_ledger_mint_transfer_burn() {
+    let store = MemoryStore::default();
+    let ledger = Ledger::new(&store);
+
+    ledger.mint::<TestError>("alice", Amount(10)).unwrap();
+    ledger
+        .transfer::<TestError>("alice", "bob", Amount(4))
+        .unwrap();
+    assert_eq!(ledger.balance("alice"), Amount(6));
+    assert_eq!(ledger.balance("bob"), Amount(4));
+
+    // a self-transfer leaves the balance unchanged
+    ledger
+        .transfer::<TestError>("bob", "bob", Amount(4))
+        .unwrap();
+    assert_eq!(ledger.balance("bob"), Amount(4));
+
+    ledger.burn::<TestError>("bob", Amount(3)).unwrap();
+    assert_eq!(ledger.balance("bob"), Amount(1));
+    assert_eq!(ledger.total_supply(), Amount(7));
+}
+
+#[test]
+fn test_ledger_burner_account() {
+    let store = MemoryStore::default();
+    let ledger = Ledger::with_burner(&store, "burn");
+
+    ledger.mint::<TestError>("alice", Amount(10)).unwrap();
+    ledger.burn::<TestError>("alice", Amount(3)).unwrap();
+    assert_eq!(ledger.balance("alice"), Amount(7));
+    assert_eq!(ledger.balance("burn"), Amount(3));
+    assert_eq!(ledger.total_supply(), Amount(7));
+}
+
+#[test]
+fn test_ledger_insufficient_funds() {
+    let store = MemoryStore::default();
+    let ledger = Ledger::new(&store);
+    ledger.mint::<TestError>("alice", Amount(5)).unwrap();
+
+    assert_eq!(
+        ledger.transfer("alice", "bob", Amount(6)),
+        Err(TestError::Ledger(LedgerError::InsufficientFunds))
+    );
+    assert_eq!(
+        ledger.burn("bob", Amount(1)),
+        Err(TestError::Ledger(LedgerError::InsufficientFunds))
+    );
+    assert_eq!(ledger.balance("alice"), Amount(5));
+    assert_eq!(ledger.balance("bob"), Amount(0));
+    assert_eq!(ledger.total_supply(), Amount(5));
+}
+
+#[test]
+fn test_ledger_non_positive_amount() {
+    let store = MemoryStore::default();
+    let ledger = Ledger::new(&store);
+
+    assert_eq!(
+        ledger.mint("alice", Amount(0)),
+        Err(TestError::Ledger(LedgerError::NonPositiveAmount))
+    );
+    assert_eq!(
+        ledger.transfer("alice", "bob", Amount(0)),
+        Err(TestError::Ledger(LedgerError::NonPositiveAmount))
+    );
+    assert_eq!(
+        LedgerError::NonPositiveAmount.to_string(),
+        "Amount must be positive"
+    );
+    assert_eq!(LedgerError::NonPositiveAmount.code(), 1);
+    assert_eq!(LedgerError::InsufficientFunds.code(), 2);
+}
+
+#[test]
+fn test_ledger_overflow() {
+    let store = MemoryStore::default();
+    let ledger = Ledger::new(&store);
+    ledger.mint::<TestError>("alice", Amount(200)).unwrap();
+
+    // supply overflow leaves both supply and balance untouched
+    assert_eq!(ledger.mint("bob", Amount(100)), Err(TestError::Overflow));
+    assert_eq!(ledger.balance("bob"), Amount(0));
+    assert_eq!(ledger.total_supply(), Amount(200));
+}
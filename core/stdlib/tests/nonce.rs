@@ -0,0 +1,82 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use stdlib::{NonceError, NonceRegistry, NonceStore};
+
+#[derive(Default)]
+struct MemoryStore {
+    nonces: RefCell<HashMap<String, u64>>,
+}
+
+impl NonceStore for &MemoryStore {
+    fn last_nonce(&self, signer: &str) -> Option<u64> {
+        self.nonces.borrow().get(signer).copied()
+    }
+
+    fn set_last_nonce(&self, signer: String, nonce: u64) {
+        self.nonces.borrow_mut().insert(signer, nonce);
+    }
+}
+
+#[test]
+fn test_nonce_fresh_accepted() {
+    let store = MemoryStore::default();
+    let registry = NonceRegistry::new(&store);
+
+    assert_eq!(registry.last("alice"), None);
+    registry
+        .check_and_increment::<NonceError>("alice", 0)
+        .unwrap();
+    registry
+        .check_and_increment::<NonceError>("alice", 1)
+        .unwrap();
+    // gaps are fine as long as nonces increase
+    registry
+        .check_and_increment::<NonceError>("alice", 7)
+        .unwrap();
+    assert_eq!(registry.last("alice"), Some(7));
+
+    // each signer has its own sequence
+    registry
+        .check_and_increment::<NonceError>("bob", 1)
+        .unwrap();
+    assert_eq!(registry.last("bob"), Some(1));
+}
+
+#[test]
+fn test_nonce_replay_rejected() {
+    let store = MemoryStore::default();
+    let registry = NonceRegistry::new(&store);
+    registry
+        .check_and_increment::<NonceError>("alice", 5)
+        .unwrap();
+
+    assert_eq!(
+        registry.check_and_increment("alice", 5),
+        Err(NonceError::Replayed { nonce: 5 })
+    );
+    assert_eq!(registry.last("alice"), Some(5));
+    assert_eq!(
+        NonceError::Replayed { nonce: 5 }.to_string(),
+        "nonce 5 already used"
+    );
+}
+
+#[test]
+fn test_nonce_out_of_order_rejected() {
+    let store = MemoryStore::default();
+    let registry = NonceRegistry::new(&store);
+    registry
+        .check_and_increment::<NonceError>("alice", 5)
+        .unwrap();
+
+    assert_eq!(
+        registry.check_and_increment("alice", 4),
+        Err(NonceError::OutOfOrder { nonce: 4, last: 5 })
+    );
+    assert_eq!(registry.last("alice"), Some(5));
+    registry
+        .check_and_increment::<NonceError>("alice", 6)
+        .unwrap();
+    assert_eq!(NonceError::Replayed { nonce: 0 }.code(), 3);
+    assert_eq!(NonceError::OutOfOrder { nonce: 0, last: 1 }.code(), 4);
+}
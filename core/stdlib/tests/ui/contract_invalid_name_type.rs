@@ -0,0 +1,5 @@
+use stdlib::contract;
+
+contract!(name = 5);
+
+fn main() {}
@@ -0,0 +1,5 @@
+use stdlib::contract;
+
+contract!(path = "wit");
+
+fn main() {}
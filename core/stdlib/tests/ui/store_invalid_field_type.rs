@@ -0,0 +1,38 @@
+extern crate alloc;
+
+use stdlib::{DotPathBuf, Store, WriteStorage};
+
+mod context {
+    pub struct ProcStorage;
+}
+
+impl WriteStorage for context::ProcStorage {
+    fn __set_str(self: &alloc::rc::Rc<Self>, _path: &str, _value: &str) {}
+    fn __set_u64(self: &alloc::rc::Rc<Self>, _path: &str, _value: u64) {}
+    fn __set_s64(self: &alloc::rc::Rc<Self>, _path: &str, _value: i64) {}
+    fn __set_bool(self: &alloc::rc::Rc<Self>, _path: &str, _value: bool) {}
+    fn __set_list_u8(self: &alloc::rc::Rc<Self>, _path: &str, _value: Vec<u8>) {}
+    fn __set_void(self: &alloc::rc::Rc<Self>, _path: &str) {}
+    fn __set<T: Store<Self>>(self: &alloc::rc::Rc<Self>, path: DotPathBuf, value: T) {
+        T::__set(self, path, value)
+    }
+    fn __delete_matching_paths(
+        self: &alloc::rc::Rc<Self>,
+        _base_path: &str,
+        _variants: &[&str],
+    ) -> u64 {
+        0
+    }
+    fn __delete_prefix(self: &alloc::rc::Rc<Self>, _base_path: &str) -> u64 {
+        0
+    }
+}
+
+struct Bar;
+
+#[derive(Store)]
+struct Foo {
+    foo: Bar,
+}
+
+fn main() {}
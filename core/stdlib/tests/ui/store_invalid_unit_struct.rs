@@ -0,0 +1,4 @@
+use stdlib::Store;
+
+#[derive(Store)]
+struct Unit;
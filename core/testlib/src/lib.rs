@@ -1,21 +1,22 @@
-use anyhow::Context;
+use anyhow::{Context, bail};
 use async_trait::async_trait;
+use bitcoin::BlockHash;
 use bon::Builder;
 use glob::Paths;
 use indexer::{
     database::{
         queries::{
-            contract_has_state, get_checkpoint_latest, get_transaction_by_txid, insert_contract,
-            insert_processed_block, insert_transaction,
+            get_checkpoint_latest, get_transaction_by_txid, insert_contract,
+            insert_processed_block, insert_transaction, select_block_latest,
         },
         types::ContractRow,
     },
     reg_tester::{self, generate_taproot_address},
-    runtime::{ComponentCache, Runtime as IndexerRuntime, Storage},
+    runtime::{ComponentCache, Compression, Runtime as IndexerRuntime, Storage, token},
     test_utils::{new_mock_block_hash, new_mock_transaction, new_test_db},
 };
 pub use indexer::{logging::setup as logging, testlib_exports::*};
-use indexer_types::{BlockRow, Inst, TransactionRow};
+use indexer_types::{BlockRow, ContractSchema, Inst, TransactionRow};
 pub use serial_test;
 use std::{collections::HashMap, path::PathBuf};
 use tempfile::TempDir;
@@ -75,8 +76,21 @@ impl ContractReader {
         })
     }
 
+    /// Reads and decompresses a contract, yielding its wasm module.
+    pub async fn read_module(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(match self.read(name).await? {
+            Some(bytes) => Some(
+                task::spawn_blocking(move || Compression::default().decompress(&bytes)).await??,
+            ),
+            None => None,
+        })
+    }
+
     async fn find_contracts(dir: &str) -> Result<Paths> {
-        let pattern = format!("{}/**/target/wasm32-unknown-unknown/release/*.wasm.br", dir);
+        let pattern = format!(
+            "{}/**/target/wasm32-unknown-unknown/kontor-test/*.wasm.br",
+            dir
+        );
         Ok(
             task::spawn_blocking(move || glob::glob(&pattern).expect("Invalid glob pattern"))
                 .await?,
@@ -99,6 +113,8 @@ pub trait RuntimeImpl: Send {
         contract: &[u8],
     ) -> Result<ContractAddress>;
     async fn wit(&self, contract_address: &ContractAddress) -> Result<String>;
+    async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema>;
+    async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool>;
     async fn execute(
         &mut self,
         signer: Option<&Signer>,
@@ -106,7 +122,10 @@ pub trait RuntimeImpl: Send {
         expr: &str,
     ) -> Result<String>;
     async fn issuance(&mut self, signer: &Signer) -> Result<()>;
+    async fn mock(&mut self, contract_address: &ContractAddress, mock: ContractMock) -> Result<()>;
     async fn checkpoint(&mut self) -> Result<Option<String>>;
+    async fn mine(&mut self, n: u64) -> Result<Vec<BlockHash>>;
+    async fn height(&self) -> Result<i64>;
 }
 
 pub struct RuntimeLocal {
@@ -148,10 +167,11 @@ impl RuntimeLocal {
                     .tx_index(tx_index)
                     .name(name.to_string())
                     .bytes(bytes.to_vec())
+                    .deployer(signer.to_string())
                     .build(),
             )
             .await?;
-            if !contract_has_state(&conn, contract_id).await? {
+            if !self.runtime.storage.has_state(contract_id).await? {
                 self.runtime
                     .execute(
                         Some(signer),
@@ -185,6 +205,7 @@ impl RuntimeLocal {
             BlockRow::builder()
                 .height(1)
                 .hash(new_mock_block_hash(1))
+                .prev_hash(new_mock_block_hash(0))
                 .relevant(true)
                 .build(),
         )
@@ -192,6 +213,9 @@ impl RuntimeLocal {
         let storage = Storage::builder().height(0).tx_index(0).conn(conn).build();
         let component_cache = ComponentCache::new();
         let mut runtime = IndexerRuntime::new(component_cache, storage).await?;
+        // only the local runtime may publish `test-only` exports; the reactor
+        // behind a regtest runtime always rejects them
+        runtime.allow_test_only_exports = true;
         runtime.publish_native_contracts().await?;
         runtime
             .set_context(1, 1, 0, 0, new_mock_transaction(0).txid, None, None)
@@ -234,7 +258,27 @@ impl RuntimeImpl for RuntimeLocal {
             .contract_id(contract_address)
             .await?
             .ok_or(anyhow!("Contract not found"))?;
-        self.runtime.storage.component_wit(contract_id).await
+        Ok(self.runtime.storage.component_wit(contract_id).await?)
+    }
+
+    async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema> {
+        let contract_id = self
+            .runtime
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found"))?;
+        Ok(self.runtime.storage.component_schema(contract_id).await?)
+    }
+
+    async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool> {
+        let contract_id = self
+            .runtime
+            .storage
+            .contract_id(contract_address)
+            .await?
+            .ok_or(anyhow!("Contract not found"))?;
+        self.runtime.storage.has_state(contract_id).await
     }
 
     async fn execute(
@@ -252,11 +296,26 @@ impl RuntimeImpl for RuntimeLocal {
         self.runtime.issuance(signer).await
     }
 
+    async fn mock(&mut self, contract_address: &ContractAddress, mock: ContractMock) -> Result<()> {
+        self.runtime.mock(contract_address, mock);
+        Ok(())
+    }
+
     async fn checkpoint(&mut self) -> Result<Option<String>> {
         Ok(get_checkpoint_latest(&self.runtime.storage.conn)
             .await?
             .map(|r| r.hash))
     }
+
+    async fn mine(&mut self, _n: u64) -> Result<Vec<BlockHash>> {
+        bail!("Mining blocks is only supported in regtest mode")
+    }
+
+    async fn height(&self) -> Result<i64> {
+        Ok(select_block_latest(&self.runtime.storage.conn)
+            .await?
+            .map_or(0, |b| b.height))
+    }
 }
 
 pub struct RuntimeRegtest {
@@ -317,6 +376,14 @@ impl RuntimeImpl for RuntimeRegtest {
         self.reg_tester.wit(contract_address).await
     }
 
+    async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema> {
+        self.reg_tester.schema(contract_address).await
+    }
+
+    async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool> {
+        self.reg_tester.has_state(contract_address).await
+    }
+
     async fn execute(
         &mut self,
         signer: Option<&Signer>,
@@ -359,9 +426,21 @@ impl RuntimeImpl for RuntimeRegtest {
         Ok(())
     }
 
+    async fn mock(&mut self, _: &ContractAddress, _: ContractMock) -> Result<()> {
+        bail!("Mocking contracts is only supported in local mode")
+    }
+
     async fn checkpoint(&mut self) -> Result<Option<String>> {
         self.reg_tester.checkpoint().await
     }
+
+    async fn mine(&mut self, n: u64) -> Result<Vec<BlockHash>> {
+        self.reg_tester.mine(n).await
+    }
+
+    async fn height(&self) -> Result<i64> {
+        self.reg_tester.indexed_height().await
+    }
 }
 
 pub struct Runtime {
@@ -398,6 +477,16 @@ impl Runtime {
         self.runtime.wit(contract_address).await
     }
 
+    /// The contract's exported functions and types, parsed from its WIT.
+    pub async fn schema(&self, contract_address: &ContractAddress) -> Result<ContractSchema> {
+        self.runtime.schema(contract_address).await
+    }
+
+    /// Whether the contract has written storage, i.e. its `init` has run.
+    pub async fn has_state(&self, contract_address: &ContractAddress) -> Result<bool> {
+        self.runtime.has_state(contract_address).await
+    }
+
     pub async fn publish_as(
         &mut self,
         signer: &Signer,
@@ -427,7 +516,46 @@ impl Runtime {
         self.runtime.issuance(signer).await
     }
 
+    /// Replaces `contract_address` with a mock for calls from other contracts.
+    pub async fn mock(
+        &mut self,
+        contract_address: &ContractAddress,
+        mock: ContractMock,
+    ) -> Result<()> {
+        self.runtime.mock(contract_address, mock).await
+    }
+
+    pub async fn identities(&mut self, n: usize) -> Result<Vec<Signer>> {
+        let mut signers = Vec::with_capacity(n);
+        for _ in 0..n {
+            signers.push(self.identity().await?);
+        }
+        Ok(signers)
+    }
+
+    pub async fn fund(&mut self, signer: &Signer, amount: Decimal) -> Result<()> {
+        let result = self
+            .execute(
+                Some(signer),
+                &token::address(),
+                &format!("mint({})", to_wave_expr(amount)),
+            )
+            .await?;
+        if result.starts_with("err(") {
+            bail!("Failed to fund {}: {}", &**signer, result);
+        }
+        Ok(())
+    }
+
     pub async fn checkpoint(&mut self) -> Result<Option<String>> {
         self.runtime.checkpoint().await
     }
+
+    pub async fn mine(&mut self, n: u64) -> Result<Vec<BlockHash>> {
+        self.runtime.mine(n).await
+    }
+
+    pub async fn height(&self) -> Result<i64> {
+        self.runtime.height().await
+    }
 }
@@ -13,6 +13,10 @@ pub struct ValidationError {
     pub message: String,
     /// Where in the WIT the error occurred.
     pub location: Location,
+    /// Stable identifier for errors tooling needs to tell apart.
+    pub code: Option<ErrorCode>,
+    /// Whether this fails validation or is only reported.
+    pub severity: Severity,
 }
 
 impl ValidationError {
@@ -20,8 +24,43 @@ impl ValidationError {
         Self {
             message: message.into(),
             location,
+            code: None,
+            severity: Severity::Error,
         }
     }
+
+    pub fn warning(message: impl Into<String>, location: Location) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(message, location)
+        }
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+}
+
+/// Stable identifiers for specific validation failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The contract declares its own `resource` type.
+    UserResource,
+    /// A record, variant or enum no exported function can reach.
+    UnreachableType,
+    /// An export gated behind the `test-only` feature, which only local
+    /// builds may include.
+    TestOnlyExport,
+}
+
+/// How a validation finding affects the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Fails validation.
+    Error,
+    /// Reported without failing validation.
+    Warning,
 }
 
 impl fmt::Display for ValidationError {
@@ -131,6 +170,8 @@ pub enum LocationKind {
 pub struct ValidationResult {
     /// All validation errors found.
     pub errors: Vec<ValidationError>,
+    /// Findings that don't fail validation.
+    pub warnings: Vec<ValidationError>,
 }
 
 impl ValidationResult {
@@ -143,18 +184,32 @@ impl ValidationResult {
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
+
+    /// Returns true if there are any warnings.
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
 }
 
 impl fmt::Display for ValidationResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_valid() {
-            write!(f, "Validation passed")
+            write!(f, "Validation passed")?;
+            if self.has_warnings() {
+                writeln!(f, " with {} warning(s):", self.warnings.len())?;
+            }
         } else {
             writeln!(f, "Validation failed with {} error(s):", self.errors.len())?;
             for error in &self.errors {
                 writeln!(f, "  - {}", error)?;
             }
-            Ok(())
+            if self.has_warnings() {
+                writeln!(f, "and {} warning(s):", self.warnings.len())?;
+            }
+        }
+        for warning in &self.warnings {
+            writeln!(f, "  - {}", warning)?;
         }
+        Ok(())
     }
 }
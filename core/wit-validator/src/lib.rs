@@ -8,13 +8,14 @@
 extern crate alloc;
 
 use alloc::string::String;
-use alloc::vec::Vec;
 
 mod error;
 mod rules;
+mod schema;
 mod types;
 
-pub use error::{Location, LocationKind, ValidationError, ValidationResult};
+pub use error::{ErrorCode, Location, LocationKind, Severity, ValidationError, ValidationResult};
+pub use types::TEST_ONLY_FEATURE;
 pub use wit_parser::Resolve;
 
 const BUILT_IN_WIT: &str = include_str!("../../indexer/src/runtime/wit/deps/built-in.wit");
@@ -42,6 +43,28 @@ impl Validator {
     /// This automatically includes the Kontor built-in types (context, foreign, etc.)
     /// so that contracts importing from `kontor:built-in` can be validated.
     pub fn validate_str(wit_content: &str) -> Result<ValidationResult, ParseError> {
+        Ok(Self::validate_resolve(&Self::parse(wit_content)?))
+    }
+
+    /// Validate an already-parsed `Resolve` against Kontor rules.
+    pub fn validate_resolve(resolve: &Resolve) -> ValidationResult {
+        let (errors, warnings) = rules::validate_all(resolve)
+            .into_iter()
+            .partition(|e| e.severity == Severity::Error);
+        ValidationResult { errors, warnings }
+    }
+
+    /// Emit a JSON schema of a contract's exported types and functions.
+    pub fn schema_str(wit_content: &str) -> Result<String, ParseError> {
+        Ok(Self::schema_resolve(&Self::parse(wit_content)?))
+    }
+
+    /// Emit a JSON schema from an already-parsed `Resolve`.
+    pub fn schema_resolve(resolve: &Resolve) -> String {
+        schema::export_schema(resolve)
+    }
+
+    fn parse(wit_content: &str) -> Result<Resolve, ParseError> {
         let mut resolve = Resolve::new();
 
         resolve
@@ -56,14 +79,7 @@ impl Validator {
                 message: alloc::format!("Failed to parse contract WIT: {}", e),
             })?;
 
-        Ok(Self::validate_resolve(&resolve))
-    }
-
-    /// Validate an already-parsed `Resolve` against Kontor rules.
-    pub fn validate_resolve(resolve: &Resolve) -> ValidationResult {
-        let mut errors = Vec::new();
-        errors.extend(rules::validate_all(resolve));
-        ValidationResult { errors }
+        Ok(resolve)
     }
 }
 
@@ -164,6 +180,88 @@ world root {{
         );
     }
 
+    #[test]
+    fn test_invalid_unused_resource() {
+        let result = validate(
+            r#"
+    resource foo {}
+
+    export init: async func(ctx: borrow<proc-context>);
+    export get-value: async func(ctx: borrow<view-context>) -> string;
+"#,
+        );
+        assert!(result.has_errors());
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == Some(ErrorCode::UserResource))
+            .expect("Expected a user resource error");
+        assert_eq!(error.location.kind, LocationKind::Type);
+        assert_eq!(error.location.name, "foo");
+    }
+
+    #[test]
+    fn test_builtin_resources_are_not_flagged() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+"#,
+        );
+        assert!(
+            result
+                .errors
+                .iter()
+                .all(|e| e.code != Some(ErrorCode::UserResource)),
+            "Built-in resources should not be flagged, got: {}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_unused_record_warns() {
+        let result = validate(
+            r#"
+    record used { name: string }
+    record unused { name: string }
+    record unused-parent { child: unused }
+
+    export init: async func(ctx: borrow<proc-context>);
+    export get: async func(ctx: borrow<view-context>) -> used;
+"#,
+        );
+        assert!(result.is_valid(), "Warnings should not fail: {}", result);
+        let mut names = result
+            .warnings
+            .iter()
+            .filter(|w| w.code == Some(ErrorCode::UnreachableType))
+            .map(|w| w.location.name.as_str())
+            .collect::<std::vec::Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["unused", "unused-parent"]);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|w| w.severity == Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn test_transitively_used_record_does_not_warn() {
+        let result = validate(
+            r#"
+    enum kind { a, b }
+    record inner { kind: kind }
+    variant outer { wrapped(inner), empty }
+
+    export init: async func(ctx: borrow<proc-context>);
+    export get: async func(ctx: borrow<view-context>) -> option<outer>;
+"#,
+        );
+        assert!(result.is_valid(), "Expected valid, got errors: {}", result);
+        assert!(!result.has_warnings(), "Expected no warnings: {}", result);
+    }
+
     #[test]
     fn test_invalid_empty_record() {
         let result = validate(
@@ -197,6 +295,53 @@ world root {{
         assert!(result.errors.iter().any(|e| e.message.contains("'error'")));
     }
 
+    #[test]
+    fn test_invalid_user_defined_error_type() {
+        let wit = r#"package root:component;
+
+world root {
+    include kontor:built-in/built-in;
+    use kontor:built-in/context.{proc-context};
+
+    variant error { failed(string) }
+
+    export init: async func(ctx: borrow<proc-context>);
+    export bad: async func(ctx: borrow<proc-context>) -> result<string, error>;
+}"#;
+        let result = Validator::validate_str(wit).expect("Failed to parse WIT");
+        assert!(result.has_errors());
+        assert!(result.errors.iter().any(|e| e.message.contains("'error'")));
+    }
+
+    #[test]
+    fn test_test_only_export() {
+        let wit = wrap(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    @unstable(feature = test-only)
+    export reset: async func(ctx: borrow<proc-context>);
+"#,
+        );
+
+        // a release build leaves the export out entirely
+        let result = Validator::validate_str(&wit).expect("Failed to parse WIT");
+        assert!(result.is_valid(), "Expected valid, got errors: {}", result);
+
+        // a local build keeps it, and must not be published
+        let mut resolve = Resolve::new();
+        resolve.features.insert(TEST_ONLY_FEATURE.into());
+        resolve.push_str("built-in.wit", BUILT_IN_WIT).unwrap();
+        resolve.push_str("contract.wit", &wit).unwrap();
+        let result = Validator::validate_resolve(&resolve);
+        let error = result
+            .errors
+            .iter()
+            .find(|e| e.code == Some(ErrorCode::TestOnlyExport))
+            .expect("test-only export should be rejected");
+        assert!(error.message.contains("'reset'"));
+        assert_eq!(result.errors.len(), 1, "{}", result);
+    }
+
     #[test]
     fn test_invalid_nested_list() {
         let result = validate(
@@ -228,6 +373,97 @@ world root {{
         assert!(result.errors.iter().any(|e| e.message.contains("list<T>")));
     }
 
+    #[test]
+    fn test_invalid_nested_option() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    export bad: async func(ctx: borrow<view-context>) -> option<option<string>>;
+"#,
+        );
+        assert!(result.has_errors());
+        assert!(result.errors.iter().any(|e| {
+            e.message.contains("nested option")
+                && e.location.kind == LocationKind::ReturnType
+                && e.location.name == "bad"
+        }));
+    }
+
+    #[test]
+    fn test_invalid_nested_option_in_record() {
+        let result = validate(
+            r#"
+    type maybe = option<u64>;
+    record bad { value: option<maybe> }
+
+    export init: async func(ctx: borrow<proc-context>);
+    export get: async func(ctx: borrow<view-context>) -> bad;
+"#,
+        );
+        assert!(result.has_errors());
+        assert!(result.errors.iter().any(|e| {
+            e.message.contains("nested option")
+                && e.location.kind == LocationKind::Field
+                && e.location.detail.as_deref() == Some("value")
+        }));
+    }
+
+    #[test]
+    fn test_invalid_option_of_result() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    export bad: async func(ctx: borrow<view-context>) -> option<result<string, error>>;
+"#,
+        );
+        assert!(result.has_errors());
+        assert!(
+            result
+                .errors
+                .iter()
+                .any(|e| e.message.contains("option<result<T, error>>"))
+        );
+    }
+
+    #[test]
+    fn test_valid_tuple_return() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    export split: async func(ctx: borrow<view-context>) -> result<tuple<u64, list<string>>, error>;
+"#,
+        );
+        assert!(result.is_valid(), "Expected valid, got errors: {}", result);
+    }
+
+    #[test]
+    fn test_invalid_tuple_return_element() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    export split: async func(ctx: borrow<view-context>) -> tuple<u64, option<option<string>>>;
+"#,
+        );
+        assert!(result.errors.iter().any(|e| {
+            e.message.contains("nested option") && e.location.kind == LocationKind::ReturnType
+        }));
+    }
+
+    #[test]
+    fn test_invalid_tuple_param() {
+        let result = validate(
+            r#"
+    export init: async func(ctx: borrow<proc-context>);
+    export put: async func(ctx: borrow<proc-context>, pair: tuple<u64, string>);
+"#,
+        );
+        assert!(result.errors.iter().any(|e| {
+            e.message
+                == "tuple types are only supported as return values; use a named record instead"
+                && e.location.kind == LocationKind::Parameter
+        }));
+    }
+
     #[test]
     fn test_invalid_result_in_param() {
         let result = validate(
@@ -381,6 +617,59 @@ world root {{
         assert!(result.errors.iter().any(|e| e.message.contains("init")));
     }
 
+    #[test]
+    fn test_schema_record_and_variant() {
+        let schema = Validator::schema_str(&wrap(
+            r#"
+    record entry {
+        key: string,
+        value: option<u64>,
+    }
+
+    variant outcome {
+        found(entry),
+        missing,
+    }
+
+    export init: async func(ctx: borrow<proc-context>);
+    export lookup: async func(ctx: borrow<view-context>, key: string) -> outcome;
+    export put: async func(ctx: borrow<proc-context>, e: entry) -> result<_, error>;
+"#,
+        ))
+        .expect("Failed to parse WIT");
+
+        assert!(schema.starts_with("{\"types\":{"), "{}", schema);
+        assert!(
+            schema.contains(
+                r#""entry":{"kind":"record","fields":[{"name":"key","type":"string"},{"name":"value","type":{"option":"u64"}}]}"#
+            ),
+            "{}",
+            schema
+        );
+        assert!(
+            schema.contains(
+                r#""outcome":{"kind":"variant","cases":[{"name":"found","type":"entry"},{"name":"missing","type":null}]}"#
+            ),
+            "{}",
+            schema
+        );
+        assert!(
+            schema.contains(
+                r#"{"name":"lookup","context":"view-context","params":[{"name":"key","type":"string"}],"result":"outcome"}"#
+            ),
+            "{}",
+            schema
+        );
+        assert!(
+            schema.contains(
+                r#"{"name":"put","context":"proc-context","params":[{"name":"e","type":"entry"}],"result":{"result":{"ok":null,"err":"error"}}}"#
+            ),
+            "{}",
+            schema
+        );
+        assert!(!schema.contains("\"proc-context\":"), "{}", schema);
+    }
+
     #[test]
     fn test_cross_type_cycle_record_variant() {
         let result = Validator::validate_str(&wrap(
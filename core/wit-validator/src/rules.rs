@@ -9,9 +9,11 @@ use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::error::{Location, ValidationError};
-use crate::types::{self, BUILTIN_TYPES, ERROR_TYPE_NAME};
-use wit_parser::{Handle, Resolve, Type, TypeDefKind, TypeId, WorldItem, WorldKey};
+use crate::error::{ErrorCode, Location, ValidationError};
+use crate::types::{self, BUILTIN_TYPES, ERROR_TYPE_NAME, TEST_ONLY_FEATURE};
+use wit_parser::{
+    Handle, Resolve, Stability, Type, TypeDefKind, TypeId, TypeOwner, WorldItem, WorldKey,
+};
 
 /// Run all validation rules and collect errors.
 pub fn validate_all(resolve: &Resolve) -> Vec<ValidationError> {
@@ -19,8 +21,11 @@ pub fn validate_all(resolve: &Resolve) -> Vec<ValidationError> {
 
     errors.extend(validate_function_signatures(resolve));
     errors.extend(validate_required_exports(resolve));
+    errors.extend(validate_test_only_exports(resolve));
     errors.extend(validate_type_definitions(resolve));
+    errors.extend(validate_resources(resolve));
     errors.extend(validate_cycles(resolve));
+    errors.extend(validate_reachability(resolve));
 
     errors
 }
@@ -49,6 +54,41 @@ fn validate_required_exports(resolve: &Resolve) -> Vec<ValidationError> {
     errors
 }
 
+/// A contract built with `test-only` exports enabled carries them in its
+/// WIT; such a build is for local testing and must never be deployed.
+fn validate_test_only_exports(resolve: &Resolve) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (_world_id, world) in resolve.worlds.iter() {
+        if world.name == "built-in" {
+            continue;
+        }
+
+        for (key, item) in world.exports.iter() {
+            if let (WorldKey::Name(name), WorldItem::Function(func)) = (key, item)
+                && matches!(
+                    &func.stability,
+                    Stability::Unstable { feature, .. } if feature == TEST_ONLY_FEATURE
+                )
+            {
+                errors.push(
+                    ValidationError::new(
+                        format!(
+                            "test-only export '{}' cannot be published; build the contract \
+                             without the kontor_test cfg",
+                            name
+                        ),
+                        Location::function(name),
+                    )
+                    .with_code(ErrorCode::TestOnlyExport),
+                );
+            }
+        }
+    }
+
+    errors
+}
+
 fn validate_function_signatures(resolve: &Resolve) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
@@ -300,6 +340,43 @@ fn validate_type_definitions(resolve: &Resolve) -> Vec<ValidationError> {
     errors
 }
 
+/// Contracts may only use the built-in context resources, so any resource
+/// declared outside `kontor:built-in` is rejected, whether or not it's used.
+fn validate_resources(resolve: &Resolve) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (_id, type_def) in resolve.types.iter() {
+        if !matches!(type_def.kind, TypeDefKind::Resource)
+            || is_builtin_owner(resolve, type_def.owner)
+        {
+            continue;
+        }
+        if let Some(name) = &type_def.name {
+            errors.push(
+                ValidationError::new(
+                    "contracts cannot declare resources; only the built-in context \
+                     resources are supported",
+                    Location::type_def(name),
+                )
+                .with_code(ErrorCode::UserResource),
+            );
+        }
+    }
+
+    errors
+}
+
+fn is_builtin_owner(resolve: &Resolve, owner: TypeOwner) -> bool {
+    match owner {
+        TypeOwner::World(id) => resolve.worlds[id].name == "built-in",
+        TypeOwner::Interface(id) => resolve.interfaces[id].package.is_some_and(|package| {
+            let name = &resolve.packages[package].name;
+            name.namespace == "kontor" && name.name == "built-in"
+        }),
+        TypeOwner::None => false,
+    }
+}
+
 fn validate_cycles(resolve: &Resolve) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
@@ -335,6 +412,61 @@ fn validate_cycles(resolve: &Resolve) -> Vec<ValidationError> {
     errors
 }
 
+/// Warns about records, variants and enums that no exported function's
+/// params or result reach, directly or through other reachable types.
+fn validate_reachability(resolve: &Resolve) -> Vec<ValidationError> {
+    let mut reachable = BTreeSet::new();
+    let mut pending = Vec::new();
+
+    for (_world_id, world) in resolve.worlds.iter() {
+        if world.name == "built-in" {
+            continue;
+        }
+        for item in world.exports.values() {
+            if let WorldItem::Function(func) = item {
+                for (_, ty) in &func.params {
+                    collect_type_refs(resolve, ty, &mut pending);
+                }
+                if let Some(ty) = &func.result {
+                    collect_type_refs(resolve, ty, &mut pending);
+                }
+            }
+        }
+    }
+
+    while let Some(id) = pending.pop() {
+        if reachable.insert(id) {
+            collect_type_dependencies(resolve, &resolve.types[id].kind, &mut pending);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (id, type_def) in resolve.types.iter() {
+        let Some(name) = &type_def.name else {
+            continue;
+        };
+        if BUILTIN_TYPES.contains(&name.as_str())
+            || is_builtin_owner(resolve, type_def.owner)
+            || reachable.contains(&id)
+            || !matches!(
+                type_def.kind,
+                TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Enum(_)
+            )
+        {
+            continue;
+        }
+        warnings.push(
+            ValidationError::warning(
+                "type is not reachable from any exported function",
+                Location::type_def(name),
+            )
+            .with_code(ErrorCode::UnreachableType),
+        );
+    }
+
+    warnings
+}
+
 fn collect_type_dependencies(resolve: &Resolve, kind: &TypeDefKind, deps: &mut Vec<TypeId>) {
     match kind {
         TypeDefKind::Record(record) => {
@@ -461,7 +593,7 @@ fn validate_type_in_context(
                         if !is_error_type(resolve, err_type) {
                             errors.push(ValidationError::new(
                                 format!(
-                                    "result error type must be 'error', found '{}'",
+                                    "result error type must be the built-in 'error', found '{}'",
                                     type_name(resolve, err_type)
                                 ),
                                 location.clone(),
@@ -519,7 +651,16 @@ fn validate_type_in_context(
                 TypeDefKind::Option(inner) => {
                     if is_option_type(resolve, inner) {
                         errors.push(ValidationError::new(
-                            "nested option types are not allowed",
+                            "nested option types are not allowed; use a variant to tell \
+                             the absent cases apart",
+                            location.clone(),
+                        ));
+                    }
+
+                    if is_result_type(resolve, inner) {
+                        errors.push(ValidationError::new(
+                            "option<result<T, error>> is not allowed; return \
+                             result<option<T>, error> instead",
                             location.clone(),
                         ));
                     }
@@ -548,9 +689,17 @@ fn validate_type_in_context(
                     ));
                 }
 
+                // WIT functions return a single value, so a tuple is how
+                // multiple return values get spelled
+                TypeDefKind::Tuple(tuple) if ctx == TypeContext::FunctionReturn => {
+                    for ty in &tuple.types {
+                        errors.extend(validate_type_in_context(resolve, ty, ctx, location));
+                    }
+                }
+
                 TypeDefKind::Tuple(_) => {
                     errors.push(ValidationError::new(
-                        "tuple types are not supported; use a named record instead",
+                        "tuple types are only supported as return values; use a named record instead",
                         location.clone(),
                     ));
                 }
@@ -590,11 +739,19 @@ fn is_inline_record(resolve: &Resolve, ty: &Type) -> bool {
     false
 }
 
+/// Only the built-in `error` counts, reached directly or through a `use`; a
+/// contract's own type named `error` would hide its variants from clients.
 fn is_error_type(resolve: &Resolve, ty: &Type) -> bool {
-    if let Type::Id(id) = ty
-        && let Some(name) = &resolve.types[*id].name
-    {
-        return name == ERROR_TYPE_NAME;
+    let mut ty = ty;
+    while let Type::Id(id) = ty {
+        let type_def = &resolve.types[*id];
+        match &type_def.kind {
+            TypeDefKind::Type(inner) => ty = inner,
+            _ => {
+                return type_def.name.as_deref() == Some(ERROR_TYPE_NAME)
+                    && is_builtin_owner(resolve, type_def.owner);
+            }
+        }
     }
     false
 }
@@ -613,9 +770,15 @@ fn is_list_type(resolve: &Resolve, ty: &Type) -> bool {
     false
 }
 
+/// Sees through `type` aliases so `option<maybe>` with
+/// `type maybe = option<T>` still counts as nested.
 fn is_option_type(resolve: &Resolve, ty: &Type) -> bool {
     if let Type::Id(id) = ty {
-        return matches!(resolve.types[*id].kind, TypeDefKind::Option(_));
+        return match &resolve.types[*id].kind {
+            TypeDefKind::Option(_) => true,
+            TypeDefKind::Type(inner) => is_option_type(resolve, inner),
+            _ => false,
+        };
     }
     false
 }
@@ -0,0 +1,211 @@
+//! JSON schema export for contract types.
+//!
+//! Describes the records, variants and enums a contract defines along with
+//! its exported function signatures, so tooling doesn't have to read WIT.
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::types::BUILTIN_TYPES;
+use wit_parser::{Handle, Resolve, Type, TypeDefKind, TypeOwner, WorldItem, WorldKey};
+
+/// Emit the JSON schema of every contract world in `resolve`.
+///
+/// Types are keyed by name and functions sorted by name so the output is
+/// canonical for a given WIT. The context parameter is reported as the
+/// function's `context` rather than as a parameter.
+pub fn export_schema(resolve: &Resolve) -> String {
+    let mut types = BTreeMap::new();
+    let mut functions = BTreeMap::new();
+
+    for (world_id, world) in resolve.worlds.iter() {
+        if world.name == "built-in" {
+            continue;
+        }
+
+        for (_id, type_def) in resolve.types.iter() {
+            let Some(name) = &type_def.name else {
+                continue;
+            };
+            if !matches!(type_def.owner, TypeOwner::World(owner) if owner == world_id)
+                || BUILTIN_TYPES.contains(&name.as_str())
+            {
+                continue;
+            }
+            if let Some(schema) = type_def_schema(resolve, &type_def.kind) {
+                types.insert(name.clone(), schema);
+            }
+        }
+
+        for (key, item) in world.exports.iter() {
+            if let (WorldKey::Name(name), WorldItem::Function(func)) = (key, item) {
+                functions.insert(name.clone(), function_schema(resolve, func));
+            }
+        }
+    }
+
+    format!(
+        "{{\"types\":{{{}}},\"functions\":[{}]}}",
+        types
+            .iter()
+            .map(|(name, schema)| format!("{}:{}", quote(name), schema))
+            .collect::<Vec<_>>()
+            .join(","),
+        functions.into_values().collect::<Vec<_>>().join(",")
+    )
+}
+
+fn type_def_schema(resolve: &Resolve, kind: &TypeDefKind) -> Option<String> {
+    Some(match kind {
+        TypeDefKind::Record(record) => format!(
+            "{{\"kind\":\"record\",\"fields\":[{}]}}",
+            record
+                .fields
+                .iter()
+                .map(|field| named(&field.name, Some(type_ref(resolve, &field.ty))))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        TypeDefKind::Variant(variant) => format!(
+            "{{\"kind\":\"variant\",\"cases\":[{}]}}",
+            variant
+                .cases
+                .iter()
+                .map(|case| named(&case.name, case.ty.as_ref().map(|ty| type_ref(resolve, ty))))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        TypeDefKind::Enum(enum_) => format!(
+            "{{\"kind\":\"enum\",\"cases\":[{}]}}",
+            enum_
+                .cases
+                .iter()
+                .map(|case| quote(&case.name))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        TypeDefKind::Type(inner) => {
+            format!(
+                "{{\"kind\":\"alias\",\"type\":{}}}",
+                type_ref(resolve, inner)
+            )
+        }
+        _ => return None,
+    })
+}
+
+fn function_schema(resolve: &Resolve, func: &wit_parser::Function) -> String {
+    let (context, params) = match func.params.split_first() {
+        Some(((_, ty), rest)) if context_name(resolve, ty).is_some() => {
+            (context_name(resolve, ty), rest)
+        }
+        _ => (None, func.params.as_slice()),
+    };
+    format!(
+        "{{\"name\":{},\"context\":{},\"params\":[{}],\"result\":{}}}",
+        quote(&func.name),
+        context.map_or(String::from("null"), |name| quote(&name)),
+        params
+            .iter()
+            .map(|(name, ty)| named(name, Some(type_ref(resolve, ty))))
+            .collect::<Vec<_>>()
+            .join(","),
+        func.result
+            .as_ref()
+            .map_or(String::from("null"), |ty| type_ref(resolve, ty))
+    )
+}
+
+fn context_name(resolve: &Resolve, ty: &Type) -> Option<String> {
+    if let Type::Id(id) = ty
+        && let TypeDefKind::Handle(Handle::Borrow(resource_id)) = &resolve.types[*id].kind
+    {
+        return resolve.types[*resource_id].name.clone();
+    }
+    None
+}
+
+/// Named types are referenced by name; anonymous ones are spelled out.
+fn type_ref(resolve: &Resolve, ty: &Type) -> String {
+    let Type::Id(id) = ty else {
+        return quote(&primitive_name(ty));
+    };
+    let type_def = &resolve.types[*id];
+    if let Some(name) = &type_def.name {
+        return quote(name);
+    }
+    let opt = |ty: &Option<Type>| {
+        ty.as_ref()
+            .map_or(String::from("null"), |ty| type_ref(resolve, ty))
+    };
+    match &type_def.kind {
+        TypeDefKind::Option(inner) => format!("{{\"option\":{}}}", type_ref(resolve, inner)),
+        TypeDefKind::List(inner) => format!("{{\"list\":{}}}", type_ref(resolve, inner)),
+        TypeDefKind::Result(result) => format!(
+            "{{\"result\":{{\"ok\":{},\"err\":{}}}}}",
+            opt(&result.ok),
+            opt(&result.err)
+        ),
+        TypeDefKind::Tuple(tuple) => format!(
+            "{{\"tuple\":[{}]}}",
+            tuple
+                .types
+                .iter()
+                .map(|ty| type_ref(resolve, ty))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        TypeDefKind::Type(inner) => type_ref(resolve, inner),
+        _ => quote("<unsupported>"),
+    }
+}
+
+fn primitive_name(ty: &Type) -> String {
+    String::from(match ty {
+        Type::Bool => "bool",
+        Type::U8 => "u8",
+        Type::U16 => "u16",
+        Type::U32 => "u32",
+        Type::U64 => "u64",
+        Type::S8 => "s8",
+        Type::S16 => "s16",
+        Type::S32 => "s32",
+        Type::S64 => "s64",
+        Type::F32 => "f32",
+        Type::F64 => "f64",
+        Type::Char => "char",
+        Type::String => "string",
+        Type::ErrorContext => "error-context",
+        Type::Id(_) => "<anonymous>",
+    })
+}
+
+fn named(name: &str, ty: Option<String>) -> String {
+    format!(
+        "{{\"name\":{},\"type\":{}}}",
+        quote(name),
+        ty.unwrap_or_else(|| String::from("null"))
+    )
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -19,6 +19,10 @@ pub const KONTOR_PRIMITIVES: &[&str] = &[
     "contract-address", // Kontor contract address
 ];
 
+/// The WIT feature gating exports that exist only for local testing, as in
+/// `@unstable(feature = test-only)`.
+pub const TEST_ONLY_FEATURE: &str = "test-only";
+
 /// The required error type name for result types.
 pub const ERROR_TYPE_NAME: &str = "error";
 
@@ -37,6 +41,7 @@ pub const BUILTIN_TYPES: &[&str] = &[
     "file-descriptor",
     "raw-file-descriptor",
     "error",
+    "contract-error",
     "keys",
     "integer",
     "decimal",
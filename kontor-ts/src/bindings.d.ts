@@ -4,10 +4,17 @@ export type Block = {
   height: number;
   hash: string;
   prev_hash: string;
+  time: number;
   transactions: Array<Transaction>;
 };
 
-export type BlockRow = { height: number; hash: string; relevant: boolean };
+export type BlockRow = {
+  height: number;
+  hash: string;
+  prev_hash: string | null;
+  time: number;
+  relevant: boolean;
+};
 
 export type CommitOutputs = {
   commit_transaction: string;
@@ -42,6 +49,13 @@ export type ContractListRow = {
 
 export type ContractResponse = { wit: string };
 
+export type ContractSchema = {
+  types: Record<string, WitTypeDef>;
+  functions: Array<WitFunction>;
+};
+
+export type ContractStateResponse = { has_state: boolean };
+
 export type ErrorResponse = { error: string };
 
 export type Event = { "type": "Processed"; block: BlockRow } | {
@@ -49,6 +63,8 @@ export type Event = { "type": "Processed"; block: BlockRow } | {
   height: number;
 };
 
+export type GasStats = { total_consumed: number; call_count: number };
+
 export type Info = {
   version: string;
   target: string;
@@ -71,6 +87,11 @@ export type InstructionQuery = {
   chained_instruction: Inst | null;
 };
 
+export type InstructionSimulation = {
+  x_only_public_key: string;
+  instruction: Inst;
+};
+
 export type Op = {
   "Publish": {
     metadata: OpMetadata;
@@ -126,6 +147,7 @@ export type ResultRow = {
   func: string;
   gas: number;
   value: string | null;
+  error_trace: string | null;
   contract: string;
 };
 
@@ -197,13 +219,37 @@ export type TransactionRow = {
 
 export type TxOutSchema = { value: number; script_pubkey: string };
 
-export type ViewExpr = { expr: string };
+export type ViewExpr = { expr: string; gas_limit: number | null };
 
 export type ViewResult = { "type": "Ok"; value: string } | {
   "type": "Err";
   message: string;
 };
 
+export type WitFunction = {
+  name: string;
+  context: string | null;
+  params: Array<WitNamedType>;
+  result: WitTypeRef | null;
+};
+
+export type WitNamedType = { name: string; type: WitTypeRef | null };
+
+export type WitResultRef = { ok: WitTypeRef | null; err: WitTypeRef | null };
+
+export type WitTypeDef =
+  | { "kind": "record"; fields: Array<WitNamedType> }
+  | { "kind": "variant"; cases: Array<WitNamedType> }
+  | { "kind": "enum"; cases: Array<string> }
+  | { "kind": "alias"; type: WitTypeRef };
+
+export type WitTypeRef =
+  | string
+  | { option: WitTypeRef }
+  | { list: WitTypeRef }
+  | { result: WitResultRef }
+  | { tuple: Array<WitTypeRef> };
+
 export type WsRequest = never;
 
 export type WsResponse = { "type": "Event"; event: Event } | {
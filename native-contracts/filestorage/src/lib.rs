@@ -1,7 +1,7 @@
 #![no_std]
 contract!(name = "filestorage");
 
-use alloc::collections::BTreeSet;
+use alloc::{boxed::Box, collections::BTreeSet};
 use stdlib::*;
 
 // ─────────────────────────────────────────────────────────────────
@@ -23,6 +23,16 @@ const DEFAULT_BLOCKS_PER_YEAR: u64 = 52560;
 /// Number of sectors/symbols sampled per challenge
 const DEFAULT_S_CHAL: u64 = 100;
 
+/// Challenge selection strategy, one of the `SELECTOR_*` values. Also used
+/// when the stored selector is unset, as on deployments that predate it.
+const DEFAULT_CHALLENGE_SELECTOR: u64 = SELECTOR_DEFAULT;
+
+/// [`DefaultSelector`]: θ(t) rate with uniformly sampled agreements
+const SELECTOR_DEFAULT: u64 = 0;
+
+/// [`FirstEligibleSelector`]: always the first eligible agreement
+const SELECTOR_FIRST_ELIGIBLE: u64 = 1;
+
 // ─────────────────────────────────────────────────────────────────
 // State Types
 // ─────────────────────────────────────────────────────────────────
@@ -36,11 +46,18 @@ struct AgreementNodes {
 
 #[derive(Clone, Default, StorageRoot)]
 struct ProtocolState {
+    #[config]
     pub min_nodes: u64,
+    #[config]
     pub challenge_deadline_blocks: u64,
+    #[config]
     pub c_target: u64,
+    #[config]
     pub s_chal: u64,
+    #[config]
     pub blocks_per_year: u64,
+    #[config]
+    pub challenge_selector: Option<u64>,
     pub agreements: Map<String, AgreementData>,
     pub agreement_nodes: Map<String, AgreementNodes>,
     pub agreement_count: u64,
@@ -59,6 +76,7 @@ impl Guest for Filestorage {
             c_target: DEFAULT_C_TARGET,
             s_chal: DEFAULT_S_CHAL,
             blocks_per_year: DEFAULT_BLOCKS_PER_YEAR,
+            challenge_selector: Some(DEFAULT_CHALLENGE_SELECTOR),
             agreements: Map::default(),
             agreement_nodes: Map::default(),
             agreement_count: 0,
@@ -85,7 +103,7 @@ impl Guest for Filestorage {
 
         // Check for duplicate agreement
         let agreement_id = descriptor.file_id.clone();
-        if model.agreements().get(&agreement_id).is_some() {
+        if model.agreements().contains_key(&agreement_id) {
             return Err(Error::Message(format!(
                 "agreement already exists for file_id: {}",
                 agreement_id
@@ -309,11 +327,8 @@ impl Guest for Filestorage {
     // Challenge Generation
     // ─────────────────────────────────────────────────────────────────
 
-    fn generate_challenges_for_block(
-        ctx: &ProcContext,
-        block_height: u64,
-        prev_block_hash: Vec<u8>,
-    ) -> Vec<ChallengeData> {
+    fn generate_challenges_for_block(ctx: &ProcContext, block_height: u64) -> Vec<ChallengeData> {
+        let prev_block_hash = ctx.prev_block_hash();
         let model = ctx.model();
         let mut new_challenges = Vec::new();
 
@@ -339,51 +354,21 @@ impl Guest for Filestorage {
             })
             .collect();
 
-        let total_files = eligible_agreement_ids.len();
-        if total_files == 0 {
+        if eligible_agreement_ids.is_empty() {
             return new_challenges;
         }
 
         // Derive deterministic seed from block hash for agreement selection
         let agreement_seed = derive_seed(&prev_block_hash, b"agreement_selection");
         let mut rng_counter: u64 = 0;
-
-        // Calculate expected number of challenges: θ(t) = (C_target * |F|) / B
-        let c_target = model.c_target();
-        let blocks_per_year = model.blocks_per_year();
-
-        // Stochastic component: add one more challenge with probability (expected - base)
-        let roll = uniform_index(
-            &agreement_seed,
-            &mut rng_counter,
-            b"roll",
-            blocks_per_year as usize,
-        ) as u64;
-        let num_to_challenge =
-            compute_num_to_challenge(c_target, total_files, blocks_per_year, roll);
-
-        if num_to_challenge == 0 {
+        let selected_indices = challenge_selector(&model.config())
+            .select(&eligible_agreement_ids, &mut |info: &[u8], n| {
+                uniform_index(&agreement_seed, &mut rng_counter, info, n)
+            });
+        if selected_indices.is_empty() {
             return new_challenges;
         }
 
-        // Don't try to challenge more agreements than exist
-        let num_to_challenge = core::cmp::min(num_to_challenge, total_files);
-
-        // Select random unique agreement indices (rejection sampling avoids modulo bias)
-        let mut selected_indices = BTreeSet::new();
-        let eligible_len = eligible_agreement_ids.len();
-        if num_to_challenge == eligible_len {
-            for i in 0..eligible_len {
-                selected_indices.insert(i);
-            }
-        } else {
-            while selected_indices.len() < num_to_challenge {
-                let index =
-                    uniform_index(&agreement_seed, &mut rng_counter, b"select", eligible_len);
-                selected_indices.insert(index);
-            }
-        }
-
         // Derive batch seed for all challenges in this block
         let batch_seed = derive_seed(&prev_block_hash, b"batch_seed");
         let seed: Vec<u8> = batch_seed.to_vec();
@@ -559,6 +544,23 @@ impl Guest for Filestorage {
         ctx.model().s_chal()
     }
 
+    fn config(ctx: &ViewContext) -> ProtocolStateConfig {
+        ctx.model().config()
+    }
+
+    fn set_challenge_selector(ctx: &CoreContext, selector: u64) -> Result<(), Error> {
+        if ![SELECTOR_DEFAULT, SELECTOR_FIRST_ELIGIBLE].contains(&selector) {
+            return Err(Error::Message(format!(
+                "unknown challenge selector {}",
+                selector
+            )));
+        }
+        ctx.proc_context()
+            .model()
+            .set_challenge_selector(Some(selector));
+        Ok(())
+    }
+
     // ─────────────────────────────────────────────────────────────────
     // Proof Verification
     // ─────────────────────────────────────────────────────────────────
@@ -575,20 +577,39 @@ impl Guest for Filestorage {
             return Err(Error::Message("Proof contains no challenges".to_string()));
         }
 
-        // 3. Build challenge inputs from contract storage
-        let mut challenge_inputs = Vec::new();
+        // 3. Build challenge inputs from contract storage. An aggregated proof
+        // comes from a single prover, so mixing in another node's challenges
+        // is rejected before any status changes.
+        let mut challenge_inputs: Vec<file_registry::ChallengeInput> = Vec::new();
+        let mut expired = Vec::new();
         for cid in &challenge_ids {
             let challenge = model
                 .challenges()
                 .get(cid)
                 .ok_or(Error::Message(format!("Challenge not found: {}", cid)))?;
 
-            // Only accept proofs for active challenges
-            if challenge.status().load() != ChallengeStatus::Active {
+            // Only accept proofs for active challenges. Challenges that expired
+            // before the proof landed are still part of the aggregate, so they
+            // are checked along with the rest but reported as rejected.
+            match challenge.status().load() {
+                ChallengeStatus::Active => {}
+                ChallengeStatus::Expired => expired.push(cid.clone()),
+                status => {
+                    return Err(Error::Message(format!(
+                        "Challenge {} is not active (status: {:?})",
+                        cid, status
+                    )));
+                }
+            }
+
+            if let Some(first) = challenge_inputs.first()
+                && first.prover_id != challenge.prover_id()
+            {
                 return Err(Error::Message(format!(
-                    "Challenge {} is not active (status: {:?})",
+                    "Challenge {} belongs to prover {}, expected {}",
                     cid,
-                    challenge.status().load()
+                    challenge.prover_id(),
+                    first.prover_id
                 )));
             }
 
@@ -622,18 +643,117 @@ impl Guest for Filestorage {
             file_registry::VerifyResult::Invalid => ChallengeStatus::Invalid,
         };
 
-        for cid in &challenge_ids {
-            if let Some(c) = model.challenges().get(cid) {
+        let mut outcomes = Vec::new();
+        for cid in challenge_ids {
+            if expired.contains(&cid) {
+                outcomes.push(ChallengeOutcome {
+                    challenge_id: cid,
+                    status: ChallengeStatus::Expired,
+                });
+            } else if let Some(c) = model.challenges().get(&cid) {
                 c.set_status(new_status);
+                outcomes.push(ChallengeOutcome {
+                    challenge_id: cid,
+                    status: new_status,
+                });
             }
         }
 
         Ok(VerifyProofResult {
-            verified_count: challenge_ids.len() as u64,
+            verified_count: outcomes
+                .iter()
+                .filter(|o| o.status == ChallengeStatus::Proven)
+                .count() as u64,
+            outcomes,
         })
     }
 }
 
+// ─────────────────────────────────────────────────────────────────
+// Challenge Selection
+// ─────────────────────────────────────────────────────────────────
+
+/// Picks which eligible agreements are challenged in a block.
+///
+/// `draw(info, n)` returns an index in `[0, n)` from the block's deterministic
+/// agreement-selection stream, with `info` separating unrelated draws.
+pub trait ChallengeSelector {
+    /// Indices into `eligible` of the agreements to challenge.
+    fn select(
+        &self,
+        eligible: &[String],
+        draw: &mut dyn FnMut(&[u8], usize) -> usize,
+    ) -> BTreeSet<usize>;
+}
+
+/// Challenges θ(t) agreements per block (see [`compute_num_to_challenge`]),
+/// sampled uniformly without replacement.
+pub struct DefaultSelector {
+    pub c_target: u64,
+    pub blocks_per_year: u64,
+}
+
+impl ChallengeSelector for DefaultSelector {
+    fn select(
+        &self,
+        eligible: &[String],
+        draw: &mut dyn FnMut(&[u8], usize) -> usize,
+    ) -> BTreeSet<usize> {
+        let total_files = eligible.len();
+        let mut selected = BTreeSet::new();
+        if total_files == 0 {
+            return selected;
+        }
+
+        // Stochastic component: add one more challenge with probability (expected - base)
+        let roll = draw(b"roll", self.blocks_per_year as usize) as u64;
+        let num_to_challenge =
+            compute_num_to_challenge(self.c_target, total_files, self.blocks_per_year, roll);
+
+        // Don't try to challenge more agreements than exist
+        let num_to_challenge = core::cmp::min(num_to_challenge, total_files);
+
+        // Select random unique agreement indices (rejection sampling avoids modulo bias)
+        if num_to_challenge == total_files {
+            selected.extend(0..total_files);
+        } else {
+            while selected.len() < num_to_challenge {
+                selected.insert(draw(b"select", total_files));
+            }
+        }
+        selected
+    }
+}
+
+/// Challenges the first eligible agreement every block, for deterministic
+/// experiments.
+pub struct FirstEligibleSelector;
+
+impl ChallengeSelector for FirstEligibleSelector {
+    fn select(
+        &self,
+        eligible: &[String],
+        _draw: &mut dyn FnMut(&[u8], usize) -> usize,
+    ) -> BTreeSet<usize> {
+        (0..eligible.len().min(1)).collect()
+    }
+}
+
+/// The selector configured by `challenge_selector`, falling back to
+/// [`DefaultSelector`] for unknown values.
+fn challenge_selector(config: &ProtocolStateConfig) -> Box<dyn ChallengeSelector> {
+    match config
+        .challenge_selector
+        .unwrap_or(DEFAULT_CHALLENGE_SELECTOR)
+    {
+        SELECTOR_FIRST_ELIGIBLE => Box::new(FirstEligibleSelector),
+        _ => Box::new(DefaultSelector {
+            c_target: config.c_target,
+            blocks_per_year: config.blocks_per_year,
+        }),
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────
 // Helper Functions
 // ─────────────────────────────────────────────────────────────────
@@ -734,7 +854,15 @@ fn register_file_descriptor(descriptor: &RawFileDescriptor) -> Result<(), Error>
 
 #[cfg(test)]
 mod tests {
-    use super::{compute_num_to_challenge, uniform_index_from_u64};
+    use super::{
+        ChallengeSelector, DefaultSelector, FirstEligibleSelector, compute_num_to_challenge,
+        uniform_index_from_u64,
+    };
+    use alloc::{string::String, vec::Vec};
+
+    fn agreements(n: usize) -> Vec<String> {
+        (0..n).map(|i| alloc::format!("agreement_{}", i)).collect()
+    }
 
     #[test]
     fn theta_total_files_zero() {
@@ -821,4 +949,38 @@ mod tests {
         assert_eq!(uniform_index_from_u64(n, &mut next), 7);
         assert_eq!(calls, 2);
     }
+
+    #[test]
+    fn first_eligible_selector_picks_first_agreement() {
+        let mut draw = |_: &[u8], _: usize| -> usize { panic!("no randomness needed") };
+        let selected = FirstEligibleSelector.select(&agreements(5), &mut draw);
+        assert_eq!(selected.into_iter().collect::<Vec<_>>(), [0]);
+        assert!(
+            FirstEligibleSelector
+                .select(&agreements(0), &mut draw)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn default_selector_follows_theta() {
+        let selector = DefaultSelector {
+            c_target: 12,
+            blocks_per_year: 52560,
+        };
+
+        // roll >= remainder => nothing selected, and no further draws
+        let mut draws = Vec::new();
+        let mut draw = |info: &[u8], _: usize| {
+            draws.push(info.to_vec());
+            48
+        };
+        assert!(selector.select(&agreements(4), &mut draw).is_empty());
+        assert_eq!(draws, [b"roll".to_vec()]);
+
+        // roll < remainder => one agreement, drawn from the select stream
+        let mut draw = |info: &[u8], _: usize| if info == b"roll" { 0 } else { 2 };
+        let selected = selector.select(&agreements(4), &mut draw);
+        assert_eq!(selected.into_iter().collect::<Vec<_>>(), [2]);
+    }
 }
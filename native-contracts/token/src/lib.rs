@@ -1,14 +1,32 @@
 #![no_std]
 contract!(name = "token");
 
+use alloc::collections::BTreeSet;
 use stdlib::*;
 
+/// Account that burned tokens are transferred to.
 const BURNER: &str = "burn";
+/// Ledger key of the core signer, which holds escrowed gas.
+const CORE: &str = "core";
+/// Accounts that hold tokens on behalf of the protocol rather than a user.
+const SYSTEM_ACCOUNTS: [&str; 2] = [BURNER, CORE];
+const DECIMALS: u32 = 8;
+
+// stable error codes; amount and balance failures share the `Ledger` codes
+const MINT_LIMIT_EXCEEDED: u32 = 10;
+const NO_SOURCE_BALANCE: u32 = 11;
 
 #[derive(Clone, Default, StorageRoot)]
 struct TokenStorage {
     pub ledger: Map<String, Decimal>,
     pub total_supply: Decimal,
+    /// Decimal places used to display amounts; fixed at init. Tokens
+    /// deployed before this field existed read back `None`.
+    pub decimals: Option<u32>,
+}
+
+fn system_accounts() -> BTreeSet<String> {
+    SYSTEM_ACCOUNTS.iter().map(|acc| acc.to_string()).collect()
 }
 
 fn make_utxo_id(txid: String, vout: u64) -> String {
@@ -17,7 +35,7 @@ fn make_utxo_id(txid: String, vout: u64) -> String {
 
 fn assert_gt_zero(n: Decimal) -> Result<(), Error> {
     if n <= 0.into() {
-        return Err(Error::Message("Amount must be positive".to_string()));
+        return Err(LedgerError::NonPositiveAmount.into());
     }
 
     Ok(())
@@ -26,7 +44,7 @@ fn assert_gt_zero(n: Decimal) -> Result<(), Error> {
 fn mint(model: &TokenStorageWriteModel, dst: String, amt: Decimal) -> Result<Mint, Error> {
     assert_gt_zero(amt)?;
     if amt > 1000.into() {
-        return Err(Error::Message("Amount exceeds limit".to_string()));
+        return Err(Error::contract(MINT_LIMIT_EXCEEDED, "Amount exceeds limit"));
     }
     let ledger = model.ledger();
     let new_amt = ledger.get(&dst).unwrap_or_default().add(amt)?;
@@ -43,7 +61,7 @@ fn transfer(ctx: &ProcContext, src: String, dst: String, amt: Decimal) -> Result
     let dst_amt = ledger.get(&dst).unwrap_or_default();
 
     if src_amt < amt {
-        return Err(Error::Message("insufficient funds".to_string()));
+        return Err(LedgerError::InsufficientFunds.into());
     }
 
     ledger.set(src.clone(), src_amt.sub(amt)?);
@@ -53,7 +71,11 @@ fn transfer(ctx: &ProcContext, src: String, dst: String, amt: Decimal) -> Result
 
 impl Guest for Token {
     fn init(ctx: &ProcContext) {
-        TokenStorage::default().init(ctx);
+        TokenStorage {
+            decimals: Some(DECIMALS),
+            ..Default::default()
+        }
+        .init(ctx);
     }
 
     fn issuance(ctx: &CoreContext, amt: Decimal) -> Result<Mint, Error> {
@@ -119,7 +141,7 @@ impl Guest for Token {
             .model()
             .ledger()
             .get(&src)
-            .ok_or(Error::Message("Source has no balance".to_string()))?;
+            .ok_or(Error::contract(NO_SOURCE_BALANCE, "Source has no balance"))?;
         let dst =
             if let Some(context::OpReturnData::PubKey(dst)) = ctx.transaction().op_return_data() {
                 dst
@@ -134,23 +156,55 @@ impl Guest for Token {
     }
 
     fn balances(ctx: &ViewContext) -> Vec<Balance> {
+        let system_accounts = system_accounts();
         ctx.model()
             .ledger()
-            .keys()
-            .filter_map(|acc| {
-                if [BURNER.to_string(), "core".to_string()].contains(&acc) {
-                    None
-                } else {
-                    Some(Balance {
-                        amt: ctx.model().ledger().get(&acc).unwrap_or_default(),
-                        acc,
-                    })
-                }
+            .entries()
+            .filter(|(acc, _): &(String, Decimal)| !system_accounts.contains(acc))
+            .map(|(acc, amt)| Balance { acc, amt })
+            .collect()
+    }
+
+    fn balances_page(ctx: &ViewContext, after: Option<String>, limit: u64) -> BalancePage {
+        let system_accounts = system_accounts();
+        let ledger = ctx.model().ledger();
+        let mut keys = ledger.keys_range::<String>(after, limit);
+        let mut entries = Vec::new();
+        let mut last = None;
+        for acc in keys.by_ref() {
+            last = Some(acc.clone());
+            if !system_accounts.contains(&acc) {
+                entries.push(Balance {
+                    amt: ledger.get(&acc).unwrap_or_default(),
+                    acc,
+                });
+            }
+        }
+        // system accounts still advance the cursor, so a page made up of only
+        // system accounts doesn't end the scan early
+        BalancePage {
+            entries,
+            next_cursor: last.filter(|_| keys.truncated()),
+        }
+    }
+
+    fn balances_above(ctx: &ViewContext, threshold: Decimal) -> Vec<Balance> {
+        let system_accounts = system_accounts();
+        ctx.model()
+            .ledger()
+            .entries()
+            .filter(|(acc, amt): &(String, Decimal)| {
+                !system_accounts.contains(acc) && *amt > threshold
             })
+            .map(|(acc, amt)| Balance { acc, amt })
             .collect()
     }
 
     fn total_supply(ctx: &ViewContext) -> Decimal {
         ctx.model().total_supply()
     }
+
+    fn decimals(ctx: &ViewContext) -> u32 {
+        ctx.model().decimals().unwrap_or(DECIMALS)
+    }
 }
@@ -23,6 +23,17 @@ struct AMMStorage {
     pub custodian: String,
 }
 
+// stable error codes; failures from the tokens themselves keep their own
+const TOKEN_NOT_IN_PAIR: u32 = 200;
+const INVALID_PAIR: u32 = 201;
+const BAD_AMOUNT: u32 = 202;
+const POOL_NOT_FOUND: u32 = 203;
+const EMPTY_POOL: u32 = 204;
+const POOL_EXISTS: u32 = 205;
+const INSUFFICIENT_TOTAL_SUPPLY: u32 = 206;
+const INSUFFICIENT_SHARE_BALANCE: u32 = 207;
+const BELOW_MINIMUM: u32 = 208;
+
 fn pair_id(pair: &TokenPair) -> String {
     format!("{}::{}", pair.a, pair.b)
 }
@@ -36,20 +47,25 @@ fn pair_other_token(
     } else if token_in == &pair.b {
         Ok(pair.a.clone())
     } else {
-        Err(Error::Message(format!("token {} not in pair", token_in)))
+        Err(Error::contract(
+            TOKEN_NOT_IN_PAIR,
+            format!("token {} not in pair", token_in),
+        ))
     }
 }
 
 fn validate_pair(pair: &TokenPair) -> Result<(), Error> {
     if pair.a.name.is_empty() || pair.b.name.is_empty() {
-        return Err(Error::Message(
-            "Token addresses must not be empty".to_string(),
+        return Err(Error::contract(
+            INVALID_PAIR,
+            "Token addresses must not be empty",
         ));
     }
 
     if pair.a.to_string() >= pair.b.to_string() {
-        return Err(Error::Message(
-            "Token pair must be ordered A < B".to_string(),
+        return Err(Error::contract(
+            INVALID_PAIR,
+            "Token pair must be ordered A < B",
         ));
     }
 
@@ -59,7 +75,7 @@ fn validate_pair(pair: &TokenPair) -> Result<(), Error> {
 fn validate_amount(amount: Integer) -> Result<(), Error> {
     // 0 < amount < sqrt(MAX_INT)
     if amount <= Integer::default() || amount > "340_282_366_920_938_463_463_374_607_431".into() {
-        return Err(Error::Message("bad amount".to_string()));
+        return Err(Error::contract(BAD_AMOUNT, "bad amount"));
     }
     Ok(())
 }
@@ -75,8 +91,7 @@ fn calc_swap_result(
     validate_amount(bal_out)?;
 
     // input amount less fee, round down
-    let bps_in_100pct = 10000.into();
-    let in_less_fee = amount_in * (bps_in_100pct - fee_bps) / bps_in_100pct;
+    let in_less_fee = amount_in.apply_fee(fee_bps)?;
 
     let new_bal_in = bal_in + in_less_fee;
     validate_amount(new_bal_in)?;
@@ -87,7 +102,11 @@ fn calc_swap_result(
 }
 
 fn pool_not_found() -> Error {
-    Error::Message("Pool not found".to_string())
+    Error::contract(POOL_NOT_FOUND, "Pool not found")
+}
+
+fn empty_pool() -> Error {
+    Error::contract(EMPTY_POOL, "empty pool")
 }
 
 impl Guest for Amm {
@@ -114,12 +133,12 @@ impl Guest for Amm {
 
         let pools = ctx.model().pools();
 
-        match pools.get(pair_id(&pair)).ok_or(pool_not_found()) {
-            Ok(_) => Err(Error::Message(
-                "pool for this pair already exists".to_string(),
-            )),
-            Err(_) => Ok(()),
-        }?;
+        if pools.contains_key(pair_id(&pair)) {
+            return Err(Error::contract(
+                POOL_EXISTS,
+                "pool for this pair already exists",
+            ));
+        }
 
         let lp_shares = (amount_a * amount_b).sqrt()?;
 
@@ -153,6 +172,15 @@ impl Guest for Amm {
             .fee_bps())
     }
 
+    fn lp_total_supply(ctx: &ViewContext, pair: TokenPair) -> Result<Integer, Error> {
+        Ok(ctx
+            .model()
+            .pools()
+            .get(pair_id(&pair))
+            .ok_or(pool_not_found())?
+            .lp_total_supply())
+    }
+
     fn balance(ctx: &ViewContext, pair: TokenPair, acc: String) -> Option<Integer> {
         ctx.model()
             .pools()
@@ -198,7 +226,13 @@ impl Guest for Amm {
         let lp_supply = pool.lp_total_supply();
         let balance_a = pool.balance_a();
         let balance_b = pool.balance_b();
-        let lp_shares = if amount_a * balance_b < amount_b * balance_a {
+        if lp_supply == 0.into() || balance_a == 0.into() || balance_b == 0.into() {
+            return Err(empty_pool());
+        }
+        // Shares follow the scarcer side relative to the pool, the excess of
+        // the other token is not deposited. An exactly proportional deposit
+        // gives the same shares from either side and uses side a.
+        let lp_shares = if amount_a * balance_b <= amount_b * balance_a {
             amount_a * lp_supply / balance_a
         } else {
             amount_b * lp_supply / balance_b
@@ -250,6 +284,9 @@ impl Guest for Amm {
             .ok_or(pool_not_found())?;
 
         let lp_total_supply = pool.lp_total_supply();
+        if lp_total_supply == 0.into() {
+            return Err(empty_pool());
+        }
         Ok(WithdrawResult {
             amount_a: shares * pool.balance_a() / lp_total_supply,
             amount_b: shares * pool.balance_b() / lp_total_supply,
@@ -274,10 +311,16 @@ impl Guest for Amm {
         let bal = ledger.get(&user).unwrap_or_default();
 
         if total < shares {
-            return Err(Error::Message("insufficient total supply".to_string()));
+            return Err(Error::contract(
+                INSUFFICIENT_TOTAL_SUPPLY,
+                "insufficient total supply",
+            ));
         }
         if bal < shares {
-            return Err(Error::Message("insufficient share balance".to_string()));
+            return Err(Error::contract(
+                INSUFFICIENT_SHARE_BALANCE,
+                "insufficient share balance",
+            ));
         }
 
         ledger.set(user.clone(), bal - shares);
@@ -297,6 +340,15 @@ impl Guest for Amm {
         token_in: ContractAddress,
         amount_in: Integer,
     ) -> Result<Integer, Error> {
+        Self::quote_swap_exact(ctx, pair, token_in, amount_in).map(|quote| quote.amount_out)
+    }
+
+    fn quote_swap_exact(
+        ctx: &ViewContext,
+        pair: TokenPair,
+        token_in: ContractAddress,
+        amount_in: Integer,
+    ) -> Result<SwapQuote, Error> {
         let pool = ctx
             .model()
             .pools()
@@ -307,7 +359,15 @@ impl Guest for Amm {
         } else {
             (pool.balance_b(), pool.balance_a())
         };
-        calc_swap_result(amount_in, bal_in, bal_out, pool.fee_bps())
+        if bal_in == 0.into() || bal_out == 0.into() {
+            return Err(empty_pool());
+        }
+        let amount_out = calc_swap_result(amount_in, bal_in, bal_out, pool.fee_bps())?;
+        Ok(SwapQuote {
+            amount_out,
+            balance_in: bal_in + amount_in,
+            balance_out: bal_out - amount_out,
+        })
     }
 
     fn swap(
@@ -318,28 +378,29 @@ impl Guest for Amm {
         min_out: Integer,
     ) -> Result<Integer, Error> {
         let token_out = pair_other_token(&pair, &token_in)?;
-        let amount_out = Self::quote_swap(
+        let quote = Self::quote_swap_exact(
             &ctx.view_context(),
             pair.clone(),
             token_in.clone(),
             amount_in,
         )?;
+        let amount_out = quote.amount_out;
 
         if amount_out < min_out {
-            return Err(Error::Message(format!(
-                "amount out ({}) below minimum",
-                amount_out
-            )));
+            return Err(Error::contract(
+                BELOW_MINIMUM,
+                format!("amount out ({}) below minimum", amount_out),
+            ));
         }
 
         let model = ctx.model();
         let pool = model.pools().get(pair_id(&pair)).ok_or(pool_not_found())?;
         if token_in == pair.a {
-            pool.update_balance_a(|b| b + amount_in);
-            pool.update_balance_b(|b| b - amount_out);
+            pool.set_balance_a(quote.balance_in);
+            pool.set_balance_b(quote.balance_out);
         } else {
-            pool.update_balance_a(|b| b - amount_out);
-            pool.update_balance_b(|b| b + amount_in);
+            pool.set_balance_a(quote.balance_out);
+            pool.set_balance_b(quote.balance_in);
         }
 
         token_dyn::transfer(&token_in, ctx.signer(), &model.custodian(), amount_in)?;
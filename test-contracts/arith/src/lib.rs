@@ -8,32 +8,87 @@ interface!(name = "fib", path = "../fib/wit");
 #[derive(Clone, Default, StorageRoot)]
 struct ArithStorage {
     pub last_op: Option<Op>,
+    pub last_operand: Option<Operand>,
+    pub history: Vec<Op>,
 }
 
 impl Guest for Arith {
     fn init(ctx: &ProcContext) {
         ArithStorage {
             last_op: Some(Op::Id),
+            last_operand: None,
+            history: Vec::new(),
         }
         .init(ctx)
     }
 
-    fn eval(ctx: &ProcContext, x: u64, op: Op) -> ArithReturn {
-        ctx.model().set_last_op(Some(op));
-        ArithReturn {
-            value: match op {
-                Op::Id => x,
-                Op::Sum(operand) => x + operand.y,
-                Op::Mul(operand) => x * operand.y,
-                Op::Div(operand) => x / operand.y,
-            },
-        }
+    fn eval(ctx: &ProcContext, x: u64, op: Op) -> Result<ArithReturn, Error> {
+        let model = ctx.model();
+        model.set_last_op(Some(op));
+        model.set_last_operand(match op {
+            Op::Id => None,
+            Op::Sum(operand) | Op::Mul(operand) | Op::Div(operand) => Some(operand),
+        });
+        model.history().push(op);
+        let value = match op {
+            Op::Id => x,
+            Op::Sum(operand) => x
+                .checked_add(operand.y)
+                .ok_or(Error::Message("overflow".to_string()))?,
+            Op::Mul(operand) => x
+                .checked_mul(operand.y)
+                .ok_or(Error::Message("overflow".to_string()))?,
+            Op::Div(operand) => x
+                .checked_div(operand.y)
+                .ok_or(Error::Message("division by zero".to_string()))?,
+        };
+        Ok(ArithReturn { value })
     }
 
     fn last_op(ctx: &ViewContext) -> Option<Op> {
         ctx.model().last_op().map(|op| op.load())
     }
 
+    fn last_operand(ctx: &ViewContext) -> Option<Operand> {
+        ctx.model().last_operand().map(|operand| operand.load())
+    }
+
+    fn history(ctx: &ViewContext) -> Vec<Op> {
+        ctx.model().history().load()
+    }
+
+    fn set_history(ctx: &ProcContext, ops: Vec<Op>) {
+        ctx.model().set_history(ops)
+    }
+
+    // whether anything is stored at `index`, regardless of the list's length
+    fn history_stored(ctx: &ViewContext, index: u64) -> bool {
+        ctx.storage().exists(&format!("history.{}", index))
+    }
+
+    // for nested call budget tests
+    fn burn(ctx: &ProcContext, n: u64) -> u64 {
+        let model = ctx.model();
+        for y in 0..n {
+            model.set_last_op(Some(Op::Sum(Operand { y })));
+        }
+        n
+    }
+
+    // for proc view context tests: reads back through a view context within
+    // the same call
+    fn record_op(ctx: &ProcContext, op: Op) -> Option<Op> {
+        ctx.model().set_last_op(Some(op));
+        Self::last_op(&ctx.view_context())
+    }
+
+    fn divmod(_: &ViewContext, x: u64, y: u64) -> Result<(u64, u64), Error> {
+        if y == 0 {
+            return Err(Error::DivByZero("division by zero".to_string()));
+        }
+        Ok((x / y, x % y))
+    }
+
     fn checked_sub(_: &ViewContext, x: String, y: String) -> Result<u64, Error> {
         let x = x.parse::<u64>()?;
         let y = y.parse::<u64>()?;
@@ -45,4 +100,14 @@ impl Guest for Arith {
     fn fib(ctx: &ProcContext, contract_address: ContractAddress, n: u64) -> u64 {
         fib::fib(&contract_address, ctx.signer(), get_contract_address(), n)
     }
+
+    // three-way branch on a single host comparison
+    fn compare(_: &ViewContext, a: Integer, b: Integer) -> String {
+        match a.cmp(&b) {
+            core::cmp::Ordering::Less => "less",
+            core::cmp::Ordering::Equal => "equal",
+            core::cmp::Ordering::Greater => "greater",
+        }
+        .to_string()
+    }
 }
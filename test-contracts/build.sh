@@ -1,10 +1,13 @@
 #!/bin/bash
 set -e
 
-cargo build --release
+# Only the test profile gets the `kontor_test` cfg, so release builds never
+# carry `#[test_only]` exports
+cargo build --profile kontor-test \
+    --config 'target.wasm32-unknown-unknown.rustflags = ["--cfg", "kontor_test"]'
 
 # Hardcoded target directory
-TARGET_DIR="target/wasm32-unknown-unknown/release"
+TARGET_DIR="target/wasm32-unknown-unknown/kontor-test"
 
 # Check if directory exists
 if [ ! -d "$TARGET_DIR" ]; then
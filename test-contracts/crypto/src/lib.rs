@@ -29,6 +29,10 @@ impl Guest for Crypto {
         ctx.generate_id()
     }
 
+    fn generate_id_with(ctx: &ProcContext, domain: String) -> String {
+        ctx.generate_id_with(&domain)
+    }
+
     fn set_hash(ctx: &ProcContext, input: String) -> Vec<u8> {
         let hash = crypto::hash(&input).1;
         ctx.model().set_bytes(Some(hash.clone()));
@@ -38,4 +42,30 @@ impl Guest for Crypto {
     fn get_hash(ctx: &ViewContext) -> Option<Vec<u8>> {
         ctx.model().bytes()
     }
+
+    fn hash_many(_ctx: &ViewContext, inputs: Vec<String>) -> Vec<Digest> {
+        let inputs: Vec<Vec<u8>> = inputs.into_iter().map(String::into_bytes).collect();
+        crypto::hash_many(&inputs)
+            .into_iter()
+            .map(|bytes| Digest { bytes })
+            .collect()
+    }
+
+    fn hash_each(_ctx: &ViewContext, inputs: Vec<String>) -> Vec<Digest> {
+        inputs
+            .iter()
+            .map(|input| Digest {
+                bytes: crypto::hash(input).1,
+            })
+            .collect()
+    }
+
+    fn merkle_root(_ctx: &ViewContext, leaves: Vec<String>) -> Vec<u8> {
+        let leaves: Vec<Vec<u8>> = leaves.into_iter().map(String::into_bytes).collect();
+        crypto::merkle_root(&leaves)
+    }
+
+    fn ct_eq(_ctx: &ViewContext, a: Vec<u8>, b: Vec<u8>) -> bool {
+        crypto::ct_eq(&a, &b)
+    }
 }
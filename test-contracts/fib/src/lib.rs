@@ -6,9 +6,7 @@ use stdlib::*;
 interface!(name = "arith", path = "../arith/wit");
 
 #[derive(Clone, Default, Storage)]
-struct FibValue {
-    pub value: u64,
-}
+struct FibValue(pub u64);
 
 #[derive(Clone, Default, StorageRoot)]
 struct FibStorage {
@@ -18,7 +16,7 @@ struct FibStorage {
 impl Fib {
     fn raw_fib(ctx: &ProcContext, arith_address: ContractAddress, n: u64) -> u64 {
         let cache = ctx.model().cache();
-        if let Some(v) = cache.get(n).map(|v| v.value()) {
+        if let Some(v) = cache.get(n).map(|v| v._0()) {
             return v;
         }
 
@@ -33,10 +31,11 @@ impl Fib {
                         y: Self::raw_fib(ctx, arith_address.clone(), n - 2),
                     }),
                 )
+                .expect("fib addition failed")
                 .value
             }
         };
-        cache.set(n, FibValue { value });
+        cache.set(n, FibValue(value));
         value
     }
 }
@@ -44,7 +43,7 @@ impl Fib {
 impl Guest for Fib {
     fn init(ctx: &ProcContext) {
         FibStorage {
-            cache: Map::new(&[(0, FibValue { value: 0 })]),
+            cache: Map::new(&[(0, FibValue(0))]),
         }
         .init(ctx);
     }
@@ -63,6 +62,16 @@ impl Guest for Fib {
         Ok(Self::fib(ctx, arith_address, n))
     }
 
+    fn burn_pair(
+        ctx: &ProcContext,
+        arith_address: ContractAddress,
+        first: u64,
+        second: u64,
+    ) -> u64 {
+        arith::burn(&arith_address, ctx.signer(), first)
+            + arith::burn(&arith_address, ctx.signer(), second)
+    }
+
     fn cached_values(ctx: &ViewContext) -> Vec<u64> {
         ctx.model().cache().keys().collect()
     }
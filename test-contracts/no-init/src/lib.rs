@@ -0,0 +1,20 @@
+#![no_std]
+extern crate alloc;
+
+stdlib::wit_bindgen::generate!({
+    world: "root",
+    path: "wit",
+    generate_all,
+    runtime_path: "stdlib::wit_bindgen::rt",
+    async: false,
+});
+
+struct NoInit;
+
+impl Guest for NoInit {
+    fn answer(_ctx: &ViewContext) -> u64 {
+        42
+    }
+}
+
+export!(NoInit);
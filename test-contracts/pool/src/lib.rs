@@ -1,5 +1,5 @@
 #![no_std]
-contract!(name = "pool");
+contract!(name = "pool", read_foreign = true);
 
 use stdlib::*;
 
@@ -61,6 +61,10 @@ fn token_out(
     }
 }
 
+fn empty_pool() -> Error {
+    Error::Message("empty pool".to_string())
+}
+
 fn validate_amount(amount: Integer) -> Result<(), Error> {
     // 0 < amount < sqrt(MAX_INT)
     if amount <= Integer::default() || amount > "340_282_366_920_938_463_463_374_607_431".into() {
@@ -80,8 +84,7 @@ fn calc_swap_result(
     validate_amount(bal_out)?;
 
     // input amount less fee, round down
-    let bps_in_100pct = 10000.into();
-    let in_less_fee = amount_in * (bps_in_100pct - fee_bps) / bps_in_100pct;
+    let in_less_fee = amount_in.apply_fee(fee_bps)?;
 
     let new_bal_in = bal_in + in_less_fee;
     validate_amount(new_bal_in)?;
@@ -114,7 +117,7 @@ impl Guest for Pool {
     }
 
     // This represents the production init function.
-    // Only for local testing purposes.
+    #[test_only]
     fn re_init(
         ctx: &ProcContext,
         token_a: ContractAddress,
@@ -156,6 +159,12 @@ impl Guest for Pool {
         Ok(token_dyn::balance(&token, &model.custodian()).unwrap_or_default())
     }
 
+    fn token_balance_direct(ctx: &ViewContext, token: ContractAddress) -> Result<Integer, Error> {
+        let model = ctx.model();
+        token_out(&model.token_a(), &model.token_b(), &token)?;
+        Ok(read_foreign(&token, &format!("ledger.{}", model.custodian())).unwrap_or_default())
+    }
+
     fn quote_deposit(
         ctx: &ViewContext,
         amount_a: Integer,
@@ -172,8 +181,14 @@ impl Guest for Pool {
         let custodian = model.custodian();
         let bal_a = token_dyn::balance(&token_a, &custodian).unwrap_or_default();
         let bal_b = token_dyn::balance(&token_b, &custodian).unwrap_or_default();
+        if lp_supply == 0.into() || bal_a == 0.into() || bal_b == 0.into() {
+            return Err(empty_pool());
+        }
 
-        let lp_shares = if amount_a * bal_b < amount_b * bal_a {
+        // Shares follow the scarcer side relative to the pool, the excess of
+        // the other token is not deposited. An exactly proportional deposit
+        // gives the same shares from either side and uses side a.
+        let lp_shares = if amount_a * bal_b <= amount_b * bal_a {
             amount_a * lp_supply / bal_a
         } else {
             amount_b * lp_supply / bal_b
@@ -219,6 +234,9 @@ impl Guest for Pool {
         let custodian = model.custodian();
         let bal_a = token_dyn::balance(&token_a, &custodian).unwrap_or_default();
         let bal_b = token_dyn::balance(&token_b, &custodian).unwrap_or_default();
+        if lp_supply == 0.into() {
+            return Err(empty_pool());
+        }
 
         Ok(WithdrawResult {
             amount_a: shares * bal_a / lp_supply,
@@ -265,6 +283,9 @@ impl Guest for Pool {
             &custodian,
         )
         .unwrap_or_default();
+        if bal_in == 0.into() || bal_out == 0.into() {
+            return Err(empty_pool());
+        }
         calc_swap_result(amount_in, bal_in, bal_out, model.fee_bps())
     }
 
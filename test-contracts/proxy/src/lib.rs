@@ -6,6 +6,7 @@ use stdlib::*;
 #[derive(Clone, StorageRoot, Default)]
 struct ProxyStorage {
     contract_address: Option<ContractAddress>,
+    deployer: Option<String>,
 }
 
 impl Guest for Proxy {
@@ -19,13 +20,25 @@ impl Guest for Proxy {
     }
 
     fn init(ctx: &ProcContext) {
-        ProxyStorage::default().init(ctx)
+        ProxyStorage {
+            deployer: ctx.deployer(),
+            ..Default::default()
+        }
+        .init(ctx)
     }
 
     fn get_contract_address(ctx: &ViewContext) -> Option<ContractAddress> {
         ctx.model().contract_address()
     }
 
+    fn get_deployer(ctx: &ViewContext) -> Option<String> {
+        ctx.model().deployer()
+    }
+
+    fn self_address(ctx: &ProcContext) -> ContractAddress {
+        ctx.contract_address()
+    }
+
     fn set_contract_address(ctx: &ProcContext, contract_address: ContractAddress) {
         ctx.model().set_contract_address(Some(contract_address));
     }
@@ -0,0 +1,44 @@
+#![no_std]
+contract!(name = "shapes-v2");
+
+use stdlib::*;
+
+// The shapes contract's enum with its variants reordered and `Square` renamed,
+// for checking that values stored by the original still load.
+#[derive(Clone, Storage)]
+enum Shape {
+    #[store(discriminant = "square")]
+    Quad(u64),
+    Circle(u64),
+    Empty,
+}
+
+#[derive(Clone, StorageRoot)]
+struct ShapesStorage {
+    pub shape: Shape,
+}
+
+impl Guest for ShapesV2 {
+    fn init(ctx: &ProcContext) {
+        ShapesStorage {
+            shape: Shape::Empty,
+        }
+        .init(ctx)
+    }
+
+    fn set_circle(ctx: &ProcContext, radius: u64) {
+        ctx.model().set_shape(Shape::Circle(radius));
+    }
+
+    fn set_quad(ctx: &ProcContext, side: u64) {
+        ctx.model().set_shape(Shape::Quad(side));
+    }
+
+    fn describe(ctx: &ViewContext) -> String {
+        match ctx.model().shape().load() {
+            Shape::Empty => "empty".to_string(),
+            Shape::Circle(radius) => format!("circle({})", radius),
+            Shape::Quad(side) => format!("quad({})", side),
+        }
+    }
+}
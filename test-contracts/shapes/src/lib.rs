@@ -0,0 +1,41 @@
+#![no_std]
+contract!(name = "shapes");
+
+use stdlib::*;
+
+#[derive(Clone, Storage)]
+enum Shape {
+    Empty,
+    Circle(u64),
+    Square(u64),
+}
+
+#[derive(Clone, StorageRoot)]
+struct ShapesStorage {
+    pub shape: Shape,
+}
+
+impl Guest for Shapes {
+    fn init(ctx: &ProcContext) {
+        ShapesStorage {
+            shape: Shape::Empty,
+        }
+        .init(ctx)
+    }
+
+    fn set_circle(ctx: &ProcContext, radius: u64) {
+        ctx.model().set_shape(Shape::Circle(radius));
+    }
+
+    fn set_square(ctx: &ProcContext, side: u64) {
+        ctx.model().set_shape(Shape::Square(side));
+    }
+
+    fn describe(ctx: &ViewContext) -> String {
+        match ctx.model().shape().load() {
+            Shape::Empty => "empty".to_string(),
+            Shape::Circle(radius) => format!("circle({})", radius),
+            Shape::Square(side) => format!("square({})", side),
+        }
+    }
+}
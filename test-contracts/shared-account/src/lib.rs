@@ -25,16 +25,21 @@ fn authorized(signer: &Signer, account: &AccountModel) -> bool {
             .is_some_and(|b| b)
 }
 
+// stable error codes; the token's own ledger errors use 1 and 2
+const INSUFFICIENT_BALANCE: u32 = 100;
+const UNAUTHORIZED: u32 = 101;
+const UNKNOWN_ACCOUNT: u32 = 102;
+
 fn insufficient_balance_error() -> Error {
-    Error::Message("insufficient balance".to_string())
+    Error::contract(INSUFFICIENT_BALANCE, "insufficient balance")
 }
 
 fn unauthorized_error() -> Error {
-    Error::Message("unauthorized".to_string())
+    Error::contract(UNAUTHORIZED, "unauthorized")
 }
 
 fn unknown_error() -> Error {
-    Error::Message("unknown account".to_string())
+    Error::contract(UNKNOWN_ACCOUNT, "unknown account")
 }
 
 impl Guest for SharedAccount {
@@ -54,7 +59,7 @@ impl Guest for SharedAccount {
         if balance < n {
             return Err(insufficient_balance_error());
         }
-        let account_id = ctx.generate_id();
+        let account_id = ctx.generate_id_with("account");
         ctx.model().accounts().set(
             account_id.clone(),
             Account {
@@ -119,6 +124,29 @@ impl Guest for SharedAccount {
         token::transfer(&token, ctx.contract_signer(), &signer.to_string(), n)
     }
 
+    fn sweep(
+        ctx: &ProcContext,
+        token: ContractAddress,
+        account_id: String,
+    ) -> Result<Integer, Error> {
+        let account = ctx
+            .model()
+            .accounts()
+            .get(account_id)
+            .ok_or(unknown_error())?;
+        if !authorized(&ctx.signer(), &account) {
+            return Err(unauthorized_error());
+        }
+        // the transfer is a nested call made between reading and writing the
+        // balance
+        account.try_update_balance(|b| {
+            let n = token::balance(&token, &ctx.signer().to_string()).unwrap_or_default();
+            token::transfer(&token, ctx.signer(), &ctx.contract_signer().to_string(), n)?;
+            Ok(b + n)
+        })?;
+        Ok(account.balance())
+    }
+
     fn balance(ctx: &ViewContext, account_id: String) -> Option<Integer> {
         ctx.model().accounts().get(account_id).map(|a| a.balance())
     }
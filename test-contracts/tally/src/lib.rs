@@ -0,0 +1,64 @@
+#![no_std]
+contract!(name = "tally");
+
+use stdlib::*;
+
+#[derive(Clone, Default, StorageRoot)]
+struct TallyStorage {
+    pub votes: Map<String, u64>,
+    pub weights: Map<String, Integer>,
+}
+
+impl Guest for Tally {
+    fn init(ctx: &ProcContext) {
+        TallyStorage::default().init(ctx);
+    }
+
+    fn vote(ctx: &ProcContext, name: String) {
+        let votes = ctx.model().votes();
+        let n = votes.get(&name).unwrap_or_default();
+        votes.set(name, n + 1);
+    }
+
+    fn weigh(ctx: &ProcContext, name: String, weight: Integer) {
+        ctx.model().weights().set(name, weight);
+    }
+
+    fn reset(ctx: &ProcContext) -> u64 {
+        ctx.model().votes().clear()
+    }
+
+    fn tallies(ctx: &ViewContext) -> Vec<VoteCount> {
+        let votes = ctx.model().votes();
+        votes
+            .keys()
+            .map(|name: String| VoteCount {
+                votes: votes.get(&name).unwrap_or_default(),
+                name,
+            })
+            .collect()
+    }
+
+    fn tallies_streamed(ctx: &ViewContext) -> Vec<VoteCount> {
+        ctx.model()
+            .votes()
+            .entries()
+            .map(|(name, votes)| VoteCount { name, votes })
+            .collect()
+    }
+
+    fn weights(ctx: &ViewContext) -> Vec<(String, Integer)> {
+        let weights = ctx.model().weights();
+        weights
+            .keys()
+            .map(|name: String| {
+                let weight = weights.get(&name).unwrap_or_default();
+                (name, weight)
+            })
+            .collect()
+    }
+
+    fn weights_streamed(ctx: &ViewContext) -> Vec<(String, Integer)> {
+        ctx.model().weights().entries().collect()
+    }
+}
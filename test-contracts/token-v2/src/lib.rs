@@ -0,0 +1,70 @@
+#![no_std]
+contract!(name = "token-v2");
+
+use stdlib::*;
+
+const BURNER: &str = "burn";
+
+// Same layout as the token contract, so it can replace token's code in place.
+#[derive(Clone, Default, StorageRoot)]
+struct TokenStorage {
+    #[public]
+    pub ledger: Map<String, Integer>,
+    pub total_supply: Integer,
+}
+
+impl LedgerStore<Integer> for TokenStorageWriteModel {
+    fn balance(&self, account: &str) -> Option<Integer> {
+        self.ledger().get(account)
+    }
+
+    fn set_balance(&self, account: String, amount: Integer) {
+        self.ledger().set(account, amount)
+    }
+
+    fn supply(&self) -> Integer {
+        self.total_supply()
+    }
+
+    fn set_supply(&self, amount: Integer) {
+        self.set_total_supply(amount)
+    }
+}
+
+fn ledger(ctx: &ProcContext) -> Ledger<Integer, TokenStorageWriteModel> {
+    Ledger::with_burner(ctx.model(), BURNER)
+}
+
+impl Guest for TokenV2 {
+    fn init(ctx: &ProcContext) {
+        TokenStorage::default().init(ctx);
+    }
+
+    fn mint(ctx: &ProcContext, n: Integer) -> Result<(), Error> {
+        ledger(ctx).mint(&ctx.signer().to_string(), n)
+    }
+
+    fn burn(ctx: &ProcContext, n: Integer) -> Result<(), Error> {
+        ledger(ctx).burn(&ctx.signer().to_string(), n)
+    }
+
+    fn transfer(ctx: &ProcContext, to: String, n: Integer) -> Result<(), Error> {
+        ledger(ctx).transfer(&ctx.signer().to_string(), &to, n)
+    }
+
+    fn balance(ctx: &ViewContext, acc: String) -> Option<Integer> {
+        ctx.model().ledger().get(acc)
+    }
+
+    fn total_supply(ctx: &ViewContext) -> Integer {
+        ctx.model().total_supply()
+    }
+
+    fn holders(ctx: &ViewContext) -> u64 {
+        ctx.model()
+            .ledger()
+            .keys::<String>()
+            .filter(|k| k != BURNER)
+            .count() as u64
+    }
+}
@@ -7,25 +7,31 @@ const BURNER: &str = "burn";
 
 #[derive(Clone, Default, StorageRoot)]
 struct TokenStorage {
+    #[public]
     pub ledger: Map<String, Integer>,
     pub total_supply: Integer,
 }
 
-fn assert_gt_zero(n: Integer) -> Result<(), Error> {
-    if n <= 0.into() {
-        return Err(Error::Message("Amount must be positive".to_string()));
+impl LedgerStore<Integer> for TokenStorageWriteModel {
+    fn balance(&self, account: &str) -> Option<Integer> {
+        self.ledger().get(account)
     }
 
-    Ok(())
+    fn set_balance(&self, account: String, amount: Integer) {
+        self.ledger().set(account, amount)
+    }
+
+    fn supply(&self) -> Integer {
+        self.total_supply()
+    }
+
+    fn set_supply(&self, amount: Integer) {
+        self.set_total_supply(amount)
+    }
 }
 
-fn mint(model: &TokenStorageWriteModel, to: String, n: Integer) -> Result<(), Error> {
-    assert_gt_zero(n)?;
-    let ledger = model.ledger();
-    let balance = ledger.get(&to).unwrap_or_default();
-    ledger.set(to, balance.add(n)?);
-    model.try_update_total_supply(|t| t.add(n))?;
-    Ok(())
+fn ledger(ctx: &ProcContext) -> Ledger<Integer, TokenStorageWriteModel> {
+    Ledger::with_burner(ctx.model(), BURNER)
 }
 
 impl Guest for Token {
@@ -34,54 +40,67 @@ impl Guest for Token {
     }
 
     fn mint(ctx: &ProcContext, n: Integer) -> Result<(), Error> {
-        mint(&ctx.model(), ctx.signer().to_string(), n)
+        ledger(ctx).mint(&ctx.signer().to_string(), n)
     }
 
     fn burn(ctx: &ProcContext, n: Integer) -> Result<(), Error> {
-        Self::transfer(ctx, BURNER.to_string(), n)?;
-        ctx.model().try_update_total_supply(|t| t.sub(n))?;
-        Ok(())
+        ledger(ctx).burn(&ctx.signer().to_string(), n)
     }
 
     fn transfer(ctx: &ProcContext, to: String, n: Integer) -> Result<(), Error> {
-        assert_gt_zero(n)?;
-        let from = ctx.signer().to_string();
-        let ledger = ctx.model().ledger();
-
-        let from_balance = ledger.get(&from).unwrap_or_default();
-        let to_balance = ledger.get(&to).unwrap_or_default();
-
-        if from_balance < n {
-            return Err(Error::Message("insufficient funds".to_string()));
-        }
-
-        ledger.set(from, from_balance.sub(n)?);
-        ledger.set(to, to_balance.add(n)?);
-        Ok(())
+        ledger(ctx).transfer(&ctx.signer().to_string(), &to, n)
     }
 
     fn balance(ctx: &ViewContext, acc: String) -> Option<Integer> {
         ctx.model().ledger().get(acc)
     }
 
+    fn has_balance(ctx: &ViewContext, acc: String) -> bool {
+        ctx.model().ledger().contains_key(acc)
+    }
+
     fn balances(ctx: &ViewContext) -> Vec<Balance> {
         ctx.model()
             .ledger()
-            .keys()
-            .filter_map(|k| {
-                if [BURNER.to_string()].contains(&k) {
-                    None
-                } else {
-                    Some(Balance {
-                        value: ctx.model().ledger().get(&k).unwrap_or_default(),
-                        key: k,
-                    })
-                }
-            })
+            .entries()
+            .filter(|(key, _): &(String, Integer)| key != BURNER)
+            .map(|(key, value)| Balance { key, value })
             .collect()
     }
 
+    fn balances_limited(ctx: &ViewContext, max_keys: u64) -> BalancesPage {
+        let ledger = ctx.model().ledger();
+        let mut keys = ledger.keys_limited::<String>(max_keys);
+        let entries = keys
+            .by_ref()
+            .map(|k| Balance {
+                value: ledger.get(&k).unwrap_or_default(),
+                key: k,
+            })
+            .collect();
+        BalancesPage {
+            entries,
+            truncated: keys.truncated(),
+        }
+    }
+
     fn total_supply(ctx: &ViewContext) -> Integer {
         ctx.model().total_supply()
     }
+
+    fn audit_supply(ctx: &ViewContext) -> Result<(), Error> {
+        let ledger = ctx.model().ledger();
+        let sum = ledger
+            .keys::<String>()
+            .filter(|k| k != BURNER)
+            .try_fold(Integer::default(), |acc, k| {
+                acc.add(ledger.get(&k).unwrap_or_default())
+            })?;
+        require_eq(
+            sum,
+            ctx.model().total_supply(),
+            "ledger balances must sum to the total supply",
+        )?;
+        Ok(())
+    }
 }